@@ -0,0 +1,26 @@
+// Convert a variant name such as CXXRecordDecl to snake_case, cxx_record_decl.
+pub fn snake(name: &str) -> String {
+    let chars: Vec<char> = name.chars().collect();
+    let mut snake = String::new();
+    for (i, &ch) in chars.iter().enumerate() {
+        if ch.is_ascii_uppercase() {
+            let prev = if i > 0 { Some(chars[i - 1]) } else { None };
+            let next = chars.get(i + 1).copied();
+            let boundary = match prev {
+                None | Some('_') => false,
+                Some(prev) => {
+                    prev.is_ascii_lowercase()
+                        || prev.is_ascii_digit()
+                        || next.map_or(false, |next| next.is_ascii_lowercase())
+                }
+            };
+            if boundary {
+                snake.push('_');
+            }
+            snake.push(ch.to_ascii_lowercase());
+        } else {
+            snake.push(ch);
+        }
+    }
+    snake
+}
@@ -0,0 +1,239 @@
+use proc_macro2::{Literal, Span, TokenStream};
+use quote::{quote, quote_spanned};
+use syn::{Attribute, Data, DeriveInput, Error, Fields, Ident, Result};
+
+enum Style {
+    Unit,
+    Newtype,
+}
+
+struct Variant<'a> {
+    ident: &'a Ident,
+    style: Style,
+    attrs: Attrs,
+}
+
+#[derive(Default)]
+struct Attrs {
+    other: bool,
+    unchecked: bool,
+}
+
+pub fn derive(input: DeriveInput) -> Result<TokenStream> {
+    let data = match &input.data {
+        Data::Enum(data) => data,
+        Data::Struct(_) | Data::Union(_) => {
+            return Err(Error::new(
+                Span::call_site(),
+                "ClangKind can only be derived for enums",
+            ));
+        }
+    };
+    if !input.generics.params.is_empty() {
+        return Err(Error::new_spanned(
+            &input.generics,
+            "ClangKind does not support generic enums",
+        ));
+    }
+
+    let mut variants = Vec::new();
+    for variant in &data.variants {
+        let style = match &variant.fields {
+            Fields::Unit => Style::Unit,
+            Fields::Unnamed(fields) if fields.unnamed.len() == 1 => Style::Newtype,
+            Fields::Named(_) | Fields::Unnamed(_) => {
+                return Err(Error::new_spanned(
+                    variant,
+                    "ClangKind supports unit variants and newtype variants only",
+                ));
+            }
+        };
+        variants.push(Variant {
+            ident: &variant.ident,
+            style,
+            attrs: parse_attrs(&variant.attrs)?,
+        });
+    }
+
+    // The fallback is whichever variant is marked #[clang(other)], or else one
+    // named Other or Unknown.
+    let mut fallback = None;
+    for (i, variant) in variants.iter().enumerate() {
+        if variant.attrs.other {
+            if fallback.is_some() {
+                return Err(Error::new_spanned(
+                    variant.ident,
+                    "duplicate #[clang(other)] variant",
+                ));
+            }
+            fallback = Some(i);
+        }
+    }
+    if fallback.is_none() {
+        fallback = variants
+            .iter()
+            .position(|variant| variant.ident == "Other" || variant.ident == "Unknown");
+    }
+    let fallback = fallback.map(|i| variants.remove(i));
+    if variants.is_empty() {
+        return Err(Error::new(
+            Span::call_site(),
+            "ClangKind requires at least one variant for a node kind",
+        ));
+    }
+
+    let ident = &input.ident;
+    let name = ident.to_string();
+
+    let checks = variants.iter().filter(|v| !v.attrs.unchecked).map(|v| {
+        let kind = v.ident;
+        quote_spanned!(kind.span()=> ::clang_ast::Kind::#kind)
+    });
+
+    let kinds: Vec<String> = variants.iter().map(|v| v.ident.to_string()).collect();
+    let mut names = kinds.clone();
+    if fallback.is_some() {
+        // Recognized by clang_ast's node deserializer as the variant to use
+        // for kinds not in the list.
+        names.push("Other".to_owned());
+    }
+    let indices = (0..variants.len()).map(Literal::usize_unsuffixed);
+
+    let mut arms = Vec::new();
+    for (i, variant) in variants.iter().enumerate() {
+        // Without a fallback, the last variant's arm is also the catch-all.
+        let pat = if fallback.is_none() && i + 1 == variants.len() {
+            quote!(_)
+        } else {
+            let index = Literal::usize_unsuffixed(i);
+            quote!(#index)
+        };
+        let var = variant.ident;
+        let body = match variant.style {
+            Style::Unit => quote! {{
+                ::clang_ast::__private::serde::de::VariantAccess::unit_variant(__variant)?;
+                ::std::result::Result::Ok(#ident::#var)
+            }},
+            Style::Newtype => quote! {
+                ::clang_ast::__private::serde::de::VariantAccess::newtype_variant(__variant)
+                    .map(#ident::#var)
+            },
+        };
+        arms.push(quote!(#pat => #body,));
+    }
+    if let Some(variant) = &fallback {
+        let var = variant.ident;
+        let body = match variant.style {
+            Style::Unit => quote! {{
+                ::clang_ast::__private::serde::de::VariantAccess::unit_variant(__variant)?;
+                ::std::result::Result::Ok(#ident::#var)
+            }},
+            Style::Newtype => quote! {
+                ::clang_ast::__private::fallback_kind(__variant)
+                    .map(|__kind| #ident::#var(::std::convert::From::from(__kind)))
+            },
+        };
+        arms.push(quote!(_ => #body,));
+    }
+
+    let unknown = if fallback.is_some() {
+        let index = Literal::usize_unsuffixed(variants.len());
+        quote!("Other" => ::std::result::Result::Ok(#index),)
+    } else {
+        quote!()
+    };
+
+    Ok(quote! {
+        const _: &[::clang_ast::Kind] = &[#(#checks,)*];
+
+        impl<'de> ::clang_ast::__private::serde::Deserialize<'de> for #ident {
+            fn deserialize<__D>(__deserializer: __D) -> ::std::result::Result<Self, __D::Error>
+            where
+                __D: ::clang_ast::__private::serde::Deserializer<'de>,
+            {
+                const __VARIANTS: &[&str] = &[#(#names),*];
+
+                struct __Variant;
+
+                impl<'de> ::clang_ast::__private::serde::de::DeserializeSeed<'de> for __Variant {
+                    type Value = usize;
+
+                    fn deserialize<__D>(self, __deserializer: __D) -> ::std::result::Result<Self::Value, __D::Error>
+                    where
+                        __D: ::clang_ast::__private::serde::Deserializer<'de>,
+                    {
+                        ::clang_ast::__private::serde::Deserializer::deserialize_identifier(__deserializer, self)
+                    }
+                }
+
+                impl<'de> ::clang_ast::__private::serde::de::Visitor<'de> for __Variant {
+                    type Value = usize;
+
+                    fn expecting(&self, __formatter: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+                        ::std::fmt::Formatter::write_str(__formatter, "node kind")
+                    }
+
+                    fn visit_str<__E>(self, __value: &str) -> ::std::result::Result<Self::Value, __E>
+                    where
+                        __E: ::clang_ast::__private::serde::de::Error,
+                    {
+                        match __value {
+                            #(#kinds => ::std::result::Result::Ok(#indices),)*
+                            #unknown
+                            _ => ::std::result::Result::Err(__E::unknown_variant(__value, __VARIANTS)),
+                        }
+                    }
+                }
+
+                struct __Visitor;
+
+                impl<'de> ::clang_ast::__private::serde::de::Visitor<'de> for __Visitor {
+                    type Value = #ident;
+
+                    fn expecting(&self, __formatter: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+                        ::std::fmt::Formatter::write_str(__formatter, "clang syntax tree node")
+                    }
+
+                    fn visit_enum<__A>(self, __data: __A) -> ::std::result::Result<Self::Value, __A::Error>
+                    where
+                        __A: ::clang_ast::__private::serde::de::EnumAccess<'de>,
+                    {
+                        let (__index, __variant) =
+                            ::clang_ast::__private::serde::de::EnumAccess::variant_seed(__data, __Variant)?;
+                        match __index {
+                            #(#arms)*
+                        }
+                    }
+                }
+
+                ::clang_ast::__private::serde::Deserializer::deserialize_enum(
+                    __deserializer,
+                    #name,
+                    __VARIANTS,
+                    __Visitor,
+                )
+            }
+        }
+    })
+}
+
+fn parse_attrs(attrs: &[Attribute]) -> Result<Attrs> {
+    let mut result = Attrs::default();
+    for attr in attrs {
+        if !attr.path().is_ident("clang") {
+            continue;
+        }
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("other") {
+                result.other = true;
+                Ok(())
+            } else if meta.path.is_ident("unchecked") {
+                result.unchecked = true;
+                Ok(())
+            } else {
+                Err(meta.error("unsupported clang attribute, expected `other` or `unchecked`"))
+            }
+        })?;
+    }
+    Ok(result)
+}
@@ -0,0 +1,30 @@
+#![allow(
+    clippy::needless_pass_by_value,
+    clippy::too_many_lines,
+    clippy::uninlined_format_args
+)]
+
+extern crate proc_macro;
+
+mod case;
+mod kind;
+mod visitor;
+
+use proc_macro::TokenStream;
+use syn::{parse_macro_input, DeriveInput, Error};
+
+#[proc_macro_derive(ClangKind, attributes(clang))]
+pub fn derive_clang_kind(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    kind::derive(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+#[proc_macro_derive(ClangVisitor)]
+pub fn derive_clang_visitor(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    visitor::derive(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
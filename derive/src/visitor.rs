@@ -0,0 +1,112 @@
+use crate::case;
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote};
+use syn::{Data, DeriveInput, Error, Fields, Result};
+
+pub fn derive(input: DeriveInput) -> Result<TokenStream> {
+    let data = match &input.data {
+        Data::Enum(data) => data,
+        Data::Struct(_) | Data::Union(_) => {
+            return Err(Error::new(
+                Span::call_site(),
+                "ClangVisitor can only be derived for enums",
+            ));
+        }
+    };
+    if !input.generics.params.is_empty() {
+        return Err(Error::new_spanned(
+            &input.generics,
+            "ClangVisitor does not support generic enums",
+        ));
+    }
+
+    let vis = &input.vis;
+    let ident = &input.ident;
+    let visitor = format_ident!("{}Visitor", ident);
+    let node = quote!(::clang_ast::Node<#ident>);
+    let trait_doc = format!(
+        "Callbacks for each kind of node in a syntax tree of [`{}`], called by `dispatch` and `walk`.",
+        ident,
+    );
+
+    let mut methods = Vec::new();
+    let mut arms = Vec::new();
+    for variant in &data.variants {
+        let var = &variant.ident;
+        let method = format_ident!("visit_{}", case::snake(&var.to_string()));
+        let doc = format!("Called for nodes of kind `{}`.", var);
+        match &variant.fields {
+            Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+                let ty = &fields.unnamed[0].ty;
+                methods.push(quote! {
+                    #[doc = #doc]
+                    fn #method(&mut self, node: &'ast #node, kind: &'ast #ty) -> ::clang_ast::Control {
+                        let _ = node;
+                        let _ = kind;
+                        ::clang_ast::Control::Continue
+                    }
+                });
+                arms.push(quote! {
+                    #ident::#var(kind) => self.#method(node, kind),
+                });
+            }
+            Fields::Unit | Fields::Named(_) | Fields::Unnamed(_) => {
+                methods.push(quote! {
+                    #[doc = #doc]
+                    fn #method(&mut self, node: &'ast #node) -> ::clang_ast::Control {
+                        let _ = node;
+                        ::clang_ast::Control::Continue
+                    }
+                });
+                let pat = match &variant.fields {
+                    Fields::Unit => quote!(#ident::#var),
+                    Fields::Named(_) => quote!(#ident::#var { .. }),
+                    Fields::Unnamed(_) => quote!(#ident::#var(..)),
+                };
+                arms.push(quote! {
+                    #pat => self.#method(node),
+                });
+            }
+        }
+    }
+
+    Ok(quote! {
+        #[doc = #trait_doc]
+        #vis trait #visitor<'ast> {
+            #(#methods)*
+
+            /// Called after all of a node's children have been walked.
+            fn exit_node(&mut self, node: &'ast #node) {
+                let _ = node;
+            }
+
+            /// Call the method for the kind of `node`.
+            fn dispatch(&mut self, node: &'ast #node) -> ::clang_ast::Control {
+                match &node.kind {
+                    #(#arms)*
+                }
+            }
+
+            /// Walk `node` and everything beneath it, calling the method for
+            /// each node's kind on the way down and `exit_node` on the way up.
+            fn walk(&mut self, node: &'ast #node) {
+                struct __Walk<'a, __V: ?::std::marker::Sized>(&'a mut __V);
+
+                impl<'a, 'ast, __V> ::clang_ast::Visit<'ast, #ident> for __Walk<'a, __V>
+                where
+                    __V: ?::std::marker::Sized + #visitor<'ast>,
+                {
+                    fn enter_node(&mut self, node: &'ast #node) -> ::clang_ast::Control {
+                        self.0.dispatch(node)
+                    }
+
+                    fn exit_node(&mut self, node: &'ast #node) {
+                        self.0.exit_node(node);
+                    }
+                }
+
+                node.visit(&mut __Walk(self));
+            }
+        }
+    })
+}
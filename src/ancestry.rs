@@ -0,0 +1,256 @@
+use crate::context;
+use crate::intern::Shared;
+use crate::Id;
+use serde::de::{Deserialize, Deserializer};
+use serde::ser::{Serialize, Serializer};
+
+#[derive(Default)]
+pub(crate) struct State {
+    // The nodes currently being deserialized, outermost first.
+    stack: Vec<Frame>,
+    // Number of wrappers and seeds currently relying on `stack`. While this
+    // is zero, nodes are not pushed onto it.
+    refcount: usize,
+    // Id of the innermost node entered while `stack` was not in use, for a
+    // kind wrapper which turns it on partway through that node.
+    untracked: Id,
+}
+
+struct Frame {
+    id: Id,
+    // File of the first source location deserialized as part of this node,
+    // which is the node's own "loc" or else the beginning of its "range".
+    file: Option<Shared<str>>,
+    implicit: bool,
+}
+
+impl Frame {
+    fn new(id: Id) -> Self {
+        Frame {
+            id,
+            file: None,
+            implicit: false,
+        }
+    }
+}
+
+pub(crate) struct Guard {
+    tracked: bool,
+}
+
+pub(crate) fn enter(id: Id) -> Guard {
+    let tracked = context::with(|context| {
+        let state = &mut context.ancestry;
+        let tracked = state.refcount > 0;
+        if tracked {
+            state.stack.push(Frame::new(id));
+        } else {
+            state.untracked = id;
+        }
+        tracked
+    });
+    Guard { tracked }
+}
+
+impl Drop for Guard {
+    fn drop(&mut self) {
+        if self.tracked {
+            context::with(|context| context.ancestry.stack.pop());
+        }
+    }
+}
+
+pub(crate) struct Activation {
+    // Whether this activation pushed the frame of the node it began in.
+    pushed: bool,
+}
+
+// Keep track of ancestry for nodes entered from now on. For seeds which wrap
+// the deserialization of a whole tree.
+pub(crate) fn activate() -> Activation {
+    context::with(|context| context.ancestry.refcount += 1);
+    Activation { pushed: false }
+}
+
+// Keep track of ancestry for the node currently being deserialized and the
+// ones nested in it. For kind wrappers, which begin after their own node has
+// already been entered.
+pub(crate) fn activate_in_node() -> Activation {
+    let pushed = context::with(|context| {
+        let state = &mut context.ancestry;
+        let pushed = state.refcount == 0;
+        if pushed {
+            state.stack.push(Frame::new(state.untracked));
+        }
+        state.refcount += 1;
+        pushed
+    });
+    Activation { pushed }
+}
+
+impl Drop for Activation {
+    fn drop(&mut self) {
+        context::with(|context| {
+            let state = &mut context.ancestry;
+            if self.pushed {
+                state.stack.pop();
+            }
+            state.refcount -= 1;
+        });
+    }
+}
+
+// The innermost node currently being deserialized, and how many nodes
+// enclose it including itself.
+pub(crate) fn current() -> (Option<Id>, usize) {
+    context::with(|context| {
+        let stack = &context.ancestry.stack;
+        (stack.last().map(|frame| frame.id), stack.len())
+    })
+}
+
+pub(crate) fn record_file(file: &Shared<str>) {
+    context::with(|context| {
+        let state = &mut context.ancestry;
+        if state.refcount == 0 {
+            return;
+        }
+        if let Some(frame) = state.stack.last_mut() {
+            if frame.file.is_none() {
+                frame.file = Some(Shared::clone(file));
+            }
+        }
+    });
+}
+
+pub(crate) fn current_file() -> Option<Shared<str>> {
+    context::with(|context| {
+        let stack = &context.ancestry.stack;
+        stack.last().and_then(|frame| frame.file.clone())
+    })
+}
+
+pub(crate) fn record_implicit() {
+    context::with(|context| {
+        if let Some(frame) = context.ancestry.stack.last_mut() {
+            frame.implicit = true;
+        }
+    });
+}
+
+pub(crate) fn current_is_implicit() -> bool {
+    context::with(|context| {
+        let stack = &context.ancestry.stack;
+        stack.last().map_or(false, |frame| frame.implicit)
+    })
+}
+
+fn parent() -> Option<Id> {
+    context::with(|context| {
+        let stack = &context.ancestry.stack;
+        let len = stack.len();
+        if len >= 2 {
+            Some(stack[len - 2].id)
+        } else {
+            None
+        }
+    })
+}
+
+fn depth() -> usize {
+    context::with(|context| context.ancestry.stack.len().saturating_sub(1))
+}
+
+/// Kind wrapper which records the id of each node's parent as the tree is
+/// deserialized.
+///
+/// Use `Node<WithParent<Clang>>` in place of `Node<Clang>`. The root node has
+/// no parent.
+///
+/// ```
+/// use clang_ast::WithParent;
+/// use serde::Deserialize;
+///
+/// pub type Node = clang_ast::Node<WithParent<Clang>>;
+///
+/// #[derive(Deserialize)]
+/// pub enum Clang {
+///     EnumConstantDecl { name: String },
+///     Other,
+/// }
+/// #
+/// # let json = r#"{"id":"0x1","kind":"EnumDecl","inner":[{"id":"0x2","kind":"EnumConstantDecl","name":"A"}]}"#;
+/// # let node: Node = serde_json::from_str(json).unwrap();
+/// # assert_eq!(node.kind.parent, None);
+/// # assert_eq!(node.inner[0].kind.parent, Some(node.id));
+/// ```
+#[derive(Debug)]
+pub struct WithParent<T> {
+    pub parent: Option<Id>,
+    pub kind: T,
+}
+
+impl<'de, T> Deserialize<'de> for WithParent<T>
+where
+    T: Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let _ancestry = activate_in_node();
+        let parent = parent();
+        let kind = T::deserialize(deserializer)?;
+        Ok(WithParent { parent, kind })
+    }
+}
+
+impl<T> Serialize for WithParent<T>
+where
+    T: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.kind.serialize(serializer)
+    }
+}
+
+/// Kind wrapper which records how deeply each node is nested as the tree is
+/// deserialized.
+///
+/// Use `Node<WithDepth<Clang>>` in place of `Node<Clang>`. The root node has
+/// depth 0, its children depth 1, and so on.
+#[derive(Debug)]
+pub struct WithDepth<T> {
+    pub depth: usize,
+    pub kind: T,
+}
+
+impl<'de, T> Deserialize<'de> for WithDepth<T>
+where
+    T: Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let _ancestry = activate_in_node();
+        let depth = depth();
+        let kind = T::deserialize(deserializer)?;
+        Ok(WithDepth { depth, kind })
+    }
+}
+
+impl<T> Serialize for WithDepth<T>
+where
+    T: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.kind.serialize(serializer)
+    }
+}
@@ -0,0 +1,454 @@
+use crate::deserializer::{self, Inner};
+use crate::serializer::NodeSerializer;
+use crate::{dedup, intern, Id, InnerIntoIter, Node};
+use serde::de::{Deserialize, DeserializeSeed, Deserializer, Error, MapAccess, SeqAccess, Visitor};
+use serde::ser::{Serialize, SerializeMap, SerializeSeq, Serializer};
+use std::convert::TryFrom;
+use std::fmt::{self, Debug};
+use std::ops::{Index, IndexMut};
+
+/// Syntax tree stored as a flat arena of nodes.
+///
+/// This is an alternative to the nested `Vec<Node<T>>` layout of [`Node`].
+/// All nodes live in one contiguous allocation and refer to one another by
+/// [`NodeId`], which gives constant time access to a node's parent and
+/// children, and lets the whole tree be dropped without recursion.
+///
+/// An `Ast` can be deserialized directly from an AST dump, in which case no
+/// intermediate `Node` tree is materialized, or converted from an existing
+/// `Node` through `From`.
+///
+/// ```
+/// use clang_ast::Ast;
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// pub enum Clang {
+///     FunctionDecl { name: String },
+///     Other,
+/// }
+///
+/// # fn main() -> serde_json::Result<()> {
+/// # let json = r#"{"id":"0x1","kind":"TranslationUnitDecl","inner":[{"id":"0x2","kind":"FunctionDecl","name":"f"}]}"#;
+/// let ast: Ast<Clang> = serde_json::from_str(json)?;
+/// for (id, node) in ast.iter() {
+///     if let Clang::FunctionDecl { name } = &node.kind {
+///         let parent = ast.parent(id).unwrap();
+///         println!("{} declared in {}", name, ast[parent].id);
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+///
+/// Nodes are stored in post-order: every node comes after all of its
+/// descendants, and the root is last.
+///
+/// Besides the id and kind, each node takes 12 bytes: 4 for its parent, 4 to
+/// locate its children, and 4 for the reference to it from its parent's list
+/// of children. By comparison, every [`Node`] carries a 24-byte `Vec` for its
+/// children even though most nodes in real dumps are leaves, or an 8-byte
+/// `ThinVec` with the `"thin-inner"` feature, and the spare capacity left in
+/// those vectors by deserialization comes on top.
+pub struct Ast<T> {
+    nodes: Vec<AstNode<T>>,
+    edges: Vec<NodeId>,
+}
+
+/// Index of a node within an [`Ast`].
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct NodeId {
+    index: u32,
+}
+
+/// One node of an [`Ast`].
+pub struct AstNode<T> {
+    pub id: Id,
+    pub kind: T,
+    // NO_PARENT for the root.
+    parent: u32,
+    // Start of this node's children in `edges`. They end where the next
+    // node's begin.
+    edges: u32,
+}
+
+const NO_PARENT: u32 = u32::MAX;
+
+impl<T> Ast<T> {
+    #[allow(clippy::cast_possible_truncation)] // len is checked in Builder::finish
+    pub fn root(&self) -> NodeId {
+        NodeId {
+            index: self.nodes.len() as u32 - 1,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Always false; an `Ast` contains at least its root node.
+    pub fn is_empty(&self) -> bool {
+        false
+    }
+
+    pub fn get(&self, id: NodeId) -> Option<&AstNode<T>> {
+        self.nodes.get(id.index())
+    }
+
+    pub fn get_mut(&mut self, id: NodeId) -> Option<&mut AstNode<T>> {
+        self.nodes.get_mut(id.index())
+    }
+
+    pub fn parent(&self, id: NodeId) -> Option<NodeId> {
+        self[id].parent()
+    }
+
+    pub fn children(&self, id: NodeId) -> &[NodeId] {
+        let start = self[id].edges as usize;
+        let end = match self.nodes.get(id.index() + 1) {
+            Some(next) => next.edges as usize,
+            None => self.edges.len(),
+        };
+        &self.edges[start..end]
+    }
+
+    /// Iterate over the nodes enclosing the given one, starting with its
+    /// immediate parent and ending with the root.
+    pub fn ancestors(&self, id: NodeId) -> impl Iterator<Item = NodeId> + '_ {
+        let mut next = self.parent(id);
+        std::iter::from_fn(move || {
+            let id = next?;
+            next = self.parent(id);
+            Some(id)
+        })
+    }
+
+    /// Iterate over all nodes in storage order, which is post-order.
+    #[allow(clippy::cast_possible_truncation)] // len is checked in Builder::finish
+    pub fn iter(&self) -> impl Iterator<Item = (NodeId, &AstNode<T>)> + '_ {
+        self.nodes.iter().enumerate().map(|(index, node)| {
+            let index = index as u32;
+            (NodeId { index }, node)
+        })
+    }
+}
+
+impl<T> AstNode<T> {
+    pub fn parent(&self) -> Option<NodeId> {
+        if self.parent == NO_PARENT {
+            None
+        } else {
+            Some(NodeId { index: self.parent })
+        }
+    }
+}
+
+impl NodeId {
+    pub fn index(self) -> usize {
+        self.index as usize
+    }
+}
+
+impl<T> Index<NodeId> for Ast<T> {
+    type Output = AstNode<T>;
+
+    fn index(&self, id: NodeId) -> &Self::Output {
+        &self.nodes[id.index()]
+    }
+}
+
+impl<T> IndexMut<NodeId> for Ast<T> {
+    fn index_mut(&mut self, id: NodeId) -> &mut Self::Output {
+        &mut self.nodes[id.index()]
+    }
+}
+
+struct Builder<T> {
+    nodes: Vec<AstNode<T>>,
+    edges: Vec<NodeId>,
+    // Ids of completed nodes whose parent has not been completed yet.
+    pending: Vec<NodeId>,
+}
+
+impl<T> Builder<T> {
+    fn new() -> Self {
+        Builder {
+            nodes: Vec::new(),
+            edges: Vec::new(),
+            pending: Vec::new(),
+        }
+    }
+
+    // Complete a node whose children are pending[mark..]. There are fewer
+    // edges than nodes, so once the node index fits in u32 so do the edges.
+    #[allow(clippy::cast_possible_truncation)]
+    fn finish(&mut self, id: Id, kind: T, mark: usize) -> Option<NodeId> {
+        let index = u32::try_from(self.nodes.len()).ok()?;
+        if index == NO_PARENT {
+            return None;
+        }
+        let node_id = NodeId { index };
+        let edges = self.edges.len() as u32;
+        for child in self.pending.drain(mark..) {
+            self.nodes[child.index()].parent = index;
+            self.edges.push(child);
+        }
+        self.nodes.push(AstNode {
+            id,
+            kind,
+            parent: NO_PARENT,
+            edges,
+        });
+        self.pending.push(node_id);
+        Some(node_id)
+    }
+
+    fn build(self) -> Ast<T> {
+        Ast {
+            nodes: self.nodes,
+            edges: self.edges,
+        }
+    }
+}
+
+const TOO_LARGE: &str = "syntax tree has too many nodes for Ast";
+
+impl<T> From<Node<T>> for Ast<T> {
+    fn from(node: Node<T>) -> Self {
+        struct Frame<T> {
+            id: Id,
+            kind: T,
+            children: InnerIntoIter<Node<T>>,
+            mark: usize,
+        }
+
+        let mut builder = Builder::new();
+        let mut stack = vec![Frame {
+            id: node.id,
+            kind: node.kind,
+            children: node.inner.into_iter(),
+            mark: 0,
+        }];
+        while let Some(frame) = stack.last_mut() {
+            if let Some(child) = frame.children.next() {
+                stack.push(Frame {
+                    id: child.id,
+                    kind: child.kind,
+                    children: child.inner.into_iter(),
+                    mark: builder.pending.len(),
+                });
+            } else {
+                let frame = stack.pop().unwrap();
+                builder
+                    .finish(frame.id, frame.kind, frame.mark)
+                    .expect(TOO_LARGE);
+            }
+        }
+        builder.build()
+    }
+}
+
+impl<'de, T> Deserialize<'de> for Ast<T>
+where
+    T: Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let _intern = intern::activate();
+        let mut builder = Builder::new();
+        deserializer.deserialize_map(AstNodeVisitor {
+            builder: &mut builder,
+        })?;
+        Ok(builder.build())
+    }
+}
+
+struct AstNodeVisitor<'a, T> {
+    builder: &'a mut Builder<T>,
+}
+
+impl<'de, 'a, T> Visitor<'de> for AstNodeVisitor<'a, T>
+where
+    T: Deserialize<'de>,
+{
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("clang syntax tree node")
+    }
+
+    fn visit_map<M>(self, map: M) -> Result<Self::Value, M::Error>
+    where
+        M: MapAccess<'de>,
+    {
+        let mark = self.builder.pending.len();
+        let mut inner = AstInner {
+            builder: &mut *self.builder,
+        };
+        let (id, kind) = deserializer::visit_node(map, &mut inner)?;
+        match self.builder.finish(id, kind, mark) {
+            Some(_node_id) => Ok(()),
+            None => Err(M::Error::custom(TOO_LARGE)),
+        }
+    }
+}
+
+impl<'de, 'a, T> DeserializeSeed<'de> for AstNodeVisitor<'a, T>
+where
+    T: Deserialize<'de>,
+{
+    type Value = ();
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_map(self)
+    }
+}
+
+struct AstInner<'a, T> {
+    builder: &'a mut Builder<T>,
+}
+
+impl<'de, 'a, T> Inner<'de> for AstInner<'a, T>
+where
+    T: Deserialize<'de>,
+{
+    fn deserialize_inner<D>(&mut self, deserializer: D) -> Result<(), D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_seq(AstInnerVisitor {
+            builder: &mut *self.builder,
+        })
+    }
+}
+
+struct AstInnerVisitor<'a, T> {
+    builder: &'a mut Builder<T>,
+}
+
+impl<'de, 'a, T> Visitor<'de> for AstInnerVisitor<'a, T>
+where
+    T: Deserialize<'de>,
+{
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("array of clang syntax tree nodes")
+    }
+
+    fn visit_seq<S>(self, mut seq: S) -> Result<Self::Value, S::Error>
+    where
+        S: SeqAccess<'de>,
+    {
+        while let Some(()) = seq.next_element_seed(AstNodeVisitor {
+            builder: &mut *self.builder,
+        })? {}
+        Ok(())
+    }
+}
+
+// Serializes as the same nested JSON as the equivalent Node.
+impl<T> Serialize for Ast<T>
+where
+    T: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let _dedup = dedup::activate();
+        AstSubtree {
+            ast: self,
+            id: self.root(),
+        }
+        .serialize(serializer)
+    }
+}
+
+struct AstSubtree<'a, T> {
+    ast: &'a Ast<T>,
+    id: NodeId,
+}
+
+impl<'a, T> Serialize for AstSubtree<'a, T>
+where
+    T: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let node = &self.ast[self.id];
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry("id", &node.id)?;
+        T::serialize(&node.kind, NodeSerializer::new(&mut map))?;
+        let children = self.ast.children(self.id);
+        if !children.is_empty() {
+            map.serialize_entry(
+                "inner",
+                &AstChildren {
+                    ast: self.ast,
+                    children,
+                },
+            )?;
+        }
+        map.end()
+    }
+}
+
+struct AstChildren<'a, T> {
+    ast: &'a Ast<T>,
+    children: &'a [NodeId],
+}
+
+impl<'a, T> Serialize for AstChildren<'a, T>
+where
+    T: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut seq = serializer.serialize_seq(Some(self.children.len()))?;
+        for &id in self.children {
+            seq.serialize_element(&AstSubtree { ast: self.ast, id })?;
+        }
+        seq.end()
+    }
+}
+
+impl<T> Debug for Ast<T>
+where
+    T: Debug,
+{
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.debug_list().entries(&self.nodes).finish()
+    }
+}
+
+// Children are left out; they are only meaningful relative to the Ast.
+#[allow(clippy::missing_fields_in_debug)]
+impl<T> Debug for AstNode<T>
+where
+    T: Debug,
+{
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter
+            .debug_struct("AstNode")
+            .field("id", &self.id)
+            .field("kind", &self.kind)
+            .field("parent", &self.parent())
+            .finish()
+    }
+}
+
+impl Debug for NodeId {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "NodeId({})", self.index)
+    }
+}
@@ -0,0 +1,282 @@
+use crate::Type;
+use serde::de::{Deserialize, Deserializer, Error, IgnoredAny, MapAccess, Visitor};
+use serde::ser::{Serialize, SerializeMap, Serializer};
+use std::fmt;
+
+/// One element of the `"bases"` of a `CXXRecordDecl`.
+///
+/// ```
+/// use clang_ast::Base;
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// pub enum Clang {
+///     CXXRecordDecl {
+///         name: Option<String>,
+///         #[serde(default)]
+///         bases: Vec<Base>,
+///     },
+///     Other,
+/// }
+/// #
+/// # let json = r#"{"id":"0x1","kind":"CXXRecordDecl","name":"D","bases":[{"access":"private","type":{"qualType":"B"},"writtenAccess":"none","isVirtual":true}]}"#;
+/// # let node: clang_ast::Node<Clang> = serde_json::from_str(json).unwrap();
+/// # match node.kind {
+/// #     Clang::CXXRecordDecl { bases, .. } => {
+/// #         assert_eq!(bases[0].access, clang_ast::Access::Private);
+/// #         assert_eq!(bases[0].written_access, None);
+/// #         assert!(bases[0].is_virtual);
+/// #     }
+/// #     Clang::Other => panic!(),
+/// # }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Base {
+    /// The access of the base, as written or else the default for the kind
+    /// of class: private for `class`, public for `struct`.
+    pub access: Access,
+    /// The access specifier as written, if any.
+    pub written_access: Option<Access>,
+    pub is_virtual: bool,
+    /// Whether the base is a pack expansion, like `Ts...`.
+    pub is_pack_expansion: bool,
+    pub ty: Type,
+}
+
+/// C++ access specifier.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Access {
+    Public,
+    Protected,
+    Private,
+}
+
+impl Access {
+    fn from_str(access: &str) -> Option<Self> {
+        match access {
+            "public" => Some(Access::Public),
+            "protected" => Some(Access::Protected),
+            "private" => Some(Access::Private),
+            _ => None,
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            Access::Public => "public",
+            Access::Protected => "protected",
+            Access::Private => "private",
+        }
+    }
+}
+
+const ACCESS: &[&str] = &["public", "protected", "private"];
+
+impl<'de> Deserialize<'de> for Access {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct AccessVisitor;
+
+        impl<'de> Visitor<'de> for AccessVisitor {
+            type Value = Access;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("access specifier")
+            }
+
+            fn visit_str<E>(self, access: &str) -> Result<Self::Value, E>
+            where
+                E: Error,
+            {
+                Access::from_str(access).ok_or_else(|| E::unknown_variant(access, ACCESS))
+            }
+        }
+
+        deserializer.deserialize_str(AccessVisitor)
+    }
+}
+
+impl Serialize for Access {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+// Clang writes "none" for a base without an access specifier.
+struct WrittenAccess(Option<Access>);
+
+impl<'de> Deserialize<'de> for WrittenAccess {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct WrittenAccessVisitor;
+
+        impl<'de> Visitor<'de> for WrittenAccessVisitor {
+            type Value = WrittenAccess;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("access specifier or \"none\"")
+            }
+
+            fn visit_str<E>(self, access: &str) -> Result<Self::Value, E>
+            where
+                E: Error,
+            {
+                if access == "none" {
+                    return Ok(WrittenAccess(None));
+                }
+                match Access::from_str(access) {
+                    Some(access) => Ok(WrittenAccess(Some(access))),
+                    None => Err(E::unknown_variant(
+                        access,
+                        &["public", "protected", "private", "none"],
+                    )),
+                }
+            }
+        }
+
+        deserializer.deserialize_str(WrittenAccessVisitor)
+    }
+}
+
+enum BaseField {
+    Access,
+    WrittenAccess,
+    IsVirtual,
+    IsPackExpansion,
+    Type,
+    Other,
+}
+
+impl<'de> Deserialize<'de> for BaseField {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct BaseFieldVisitor;
+
+        impl<'de> Visitor<'de> for BaseFieldVisitor {
+            type Value = BaseField;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("field identifier")
+            }
+
+            fn visit_str<E>(self, field: &str) -> Result<Self::Value, E> {
+                Ok(match field {
+                    "access" => BaseField::Access,
+                    "writtenAccess" => BaseField::WrittenAccess,
+                    "isVirtual" => BaseField::IsVirtual,
+                    "isPackExpansion" => BaseField::IsPackExpansion,
+                    "type" => BaseField::Type,
+                    _ => BaseField::Other,
+                })
+            }
+        }
+
+        deserializer.deserialize_identifier(BaseFieldVisitor)
+    }
+}
+
+impl<'de> Deserialize<'de> for Base {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct BaseVisitor;
+
+        impl<'de> Visitor<'de> for BaseVisitor {
+            type Value = Base;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("base specifier")
+            }
+
+            fn visit_map<M>(self, mut map: M) -> Result<Self::Value, M::Error>
+            where
+                M: MapAccess<'de>,
+            {
+                let mut access = None;
+                let mut written_access = None;
+                let mut is_virtual = None;
+                let mut is_pack_expansion = None;
+                let mut ty = None;
+                while let Some(field) = map.next_key()? {
+                    match field {
+                        BaseField::Access => {
+                            if access.is_some() {
+                                return Err(Error::duplicate_field("access"));
+                            }
+                            access = Some(map.next_value()?);
+                        }
+                        BaseField::WrittenAccess => {
+                            if written_access.is_some() {
+                                return Err(Error::duplicate_field("writtenAccess"));
+                            }
+                            let WrittenAccess(access) = map.next_value()?;
+                            written_access = Some(access);
+                        }
+                        BaseField::IsVirtual => {
+                            if is_virtual.is_some() {
+                                return Err(Error::duplicate_field("isVirtual"));
+                            }
+                            is_virtual = Some(map.next_value()?);
+                        }
+                        BaseField::IsPackExpansion => {
+                            if is_pack_expansion.is_some() {
+                                return Err(Error::duplicate_field("isPackExpansion"));
+                            }
+                            is_pack_expansion = Some(map.next_value()?);
+                        }
+                        BaseField::Type => {
+                            if ty.is_some() {
+                                return Err(Error::duplicate_field("type"));
+                            }
+                            ty = Some(map.next_value()?);
+                        }
+                        BaseField::Other => {
+                            let IgnoredAny = map.next_value()?;
+                        }
+                    }
+                }
+                Ok(Base {
+                    access: access.ok_or_else(|| Error::missing_field("access"))?,
+                    written_access: written_access.unwrap_or(None),
+                    is_virtual: is_virtual.unwrap_or(false),
+                    is_pack_expansion: is_pack_expansion.unwrap_or(false),
+                    ty: ty.ok_or_else(|| Error::missing_field("type"))?,
+                })
+            }
+        }
+
+        deserializer.deserialize_map(BaseVisitor)
+    }
+}
+
+impl Serialize for Base {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry("access", &self.access)?;
+        if self.is_virtual {
+            map.serialize_entry("isVirtual", &true)?;
+        }
+        if self.is_pack_expansion {
+            map.serialize_entry("isPackExpansion", &true)?;
+        }
+        map.serialize_entry("type", &self.ty)?;
+        match self.written_access {
+            Some(access) => map.serialize_entry("writtenAccess", &access)?,
+            None => map.serialize_entry("writtenAccess", "none")?,
+        }
+        map.end()
+    }
+}
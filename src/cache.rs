@@ -0,0 +1,874 @@
+//! Compact binary encoding of a syntax tree, for caching.
+//!
+//! Tools which process the same AST dumps over and over can save the parsed
+//! tree once with [`to_vec`] and later load it with [`from_slice`], which is
+//! much cheaper than parsing the JSON again. Every distinct string, such as a
+//! kind, a field name or a filepath, is written out only the first time it
+//! occurs and referred to by index after that, and strings are borrowed from
+//! the input when loading.
+//!
+//! ```
+//! use clang_ast::{Kind, Node};
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Deserialize, Serialize)]
+//! pub struct Clang<'a> {
+//!     pub kind: Kind,
+//!     pub name: Option<&'a str>,
+//! }
+//!
+//! # fn main() -> Result<(), clang_ast::cache::Error> {
+//! # let json = r#"{"id":"0x1","kind":"TranslationUnitDecl","inner":[{"id":"0x2","kind":"FunctionDecl","name":"f"}]}"#;
+//! let node: Node<Clang> = serde_json::from_str(json).unwrap();
+//! let bytes = clang_ast::cache::to_vec(&node)?;
+//!
+//! let node: Node<Clang> = clang_ast::cache::from_slice(&bytes)?;
+//! assert_eq!(node.inner[0].kind.name, Some("f"));
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! The encoding begins with a header recording the version of the format.
+//! Data written by a different version of this crate with a different format
+//! version is rejected by `from_slice` rather than misinterpreted, so a cache
+//! should be keyed in a way that tolerates being regenerated.
+//!
+//! The kind type `T` must deserialize from what it serializes to, which is
+//! true of anything using serde's derives. The encoding is self-describing,
+//! so `T` can also ignore fields or kinds exactly as it would when reading
+//! JSON.
+
+use crate::Node;
+use serde::de::value::BorrowedStrDeserializer;
+use serde::de::{
+    self, Deserialize, DeserializeSeed, EnumAccess, MapAccess, SeqAccess, VariantAccess, Visitor,
+};
+use serde::forward_to_deserialize_any;
+use serde::ser::{self, Serialize};
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::fmt::{self, Debug, Display};
+use std::str;
+
+const MAGIC: &[u8; 8] = b"clangast";
+const VERSION: u32 = 1;
+
+mod tag {
+    pub const NULL: u8 = 0;
+    pub const FALSE: u8 = 1;
+    pub const TRUE: u8 = 2;
+    pub const UINT: u8 = 3;
+    // Stored as the bitwise complement, which is nonnegative.
+    pub const NEG_INT: u8 = 4;
+    pub const FLOAT: u8 = 5;
+    pub const STR: u8 = 6;
+    pub const STR_REF: u8 = 7;
+    pub const BYTES: u8 = 8;
+    pub const SEQ: u8 = 9;
+    pub const MAP: u8 = 10;
+    pub const END: u8 = 11;
+    pub const UINT128: u8 = 12;
+    pub const INT128: u8 = 13;
+}
+
+/// Error from encoding or decoding a cached syntax tree.
+pub struct Error {
+    msg: Box<str>,
+}
+
+impl Error {
+    fn new(msg: impl Display) -> Self {
+        Error {
+            msg: msg.to_string().into_boxed_str(),
+        }
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str(&self.msg)
+    }
+}
+
+impl Debug for Error {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.debug_tuple("Error").field(&self.msg).finish()
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl ser::Error for Error {
+    fn custom<T: Display>(msg: T) -> Self {
+        Error::new(msg)
+    }
+}
+
+impl de::Error for Error {
+    fn custom<T: Display>(msg: T) -> Self {
+        Error::new(msg)
+    }
+}
+
+/// Encode a syntax tree, header included.
+pub fn to_vec<T>(node: &Node<T>) -> Result<Vec<u8>, Error>
+where
+    T: Serialize,
+{
+    let mut encoder = Encoder {
+        out: Vec::new(),
+        strings: HashMap::new(),
+    };
+    encoder.out.extend_from_slice(MAGIC);
+    encoder.out.extend_from_slice(&VERSION.to_le_bytes());
+    node.serialize(&mut encoder)?;
+    Ok(encoder.out)
+}
+
+/// Decode a syntax tree previously encoded by [`to_vec`].
+pub fn from_slice<'de, T>(bytes: &'de [u8]) -> Result<Node<T>, Error>
+where
+    T: Deserialize<'de>,
+{
+    if !bytes.starts_with(MAGIC) {
+        return Err(Error::new("not a clang-ast cache"));
+    }
+    let input = &bytes[MAGIC.len()..];
+    if input.len() < 4 {
+        return Err(eof());
+    }
+    let (version, input) = input.split_at(4);
+    let version = u32::from_le_bytes([version[0], version[1], version[2], version[3]]);
+    if version != VERSION {
+        return Err(Error::new(format_args!(
+            "cache format version {} is not supported by this version of clang-ast, which uses version {}",
+            version, VERSION,
+        )));
+    }
+    let mut decoder = Decoder {
+        input,
+        strings: Vec::new(),
+    };
+    let node = Node::deserialize(&mut decoder)?;
+    if decoder.input.is_empty() {
+        Ok(node)
+    } else {
+        Err(Error::new("trailing bytes after cached syntax tree"))
+    }
+}
+
+struct Encoder {
+    out: Vec<u8>,
+    strings: HashMap<Box<str>, u64>,
+}
+
+impl Encoder {
+    #[allow(clippy::cast_possible_truncation)]
+    fn varint(&mut self, mut value: u64) {
+        while value >= 0x80 {
+            self.out.push((value & 0x7f) as u8 | 0x80);
+            value >>= 7;
+        }
+        self.out.push((value & 0x7f) as u8);
+    }
+
+    fn len(&mut self, len: usize) {
+        self.varint(u64::try_from(len).unwrap());
+    }
+
+    fn str(&mut self, string: &str) {
+        if let Some(&index) = self.strings.get(string) {
+            self.out.push(tag::STR_REF);
+            self.varint(index);
+        } else {
+            let index = u64::try_from(self.strings.len()).unwrap();
+            self.strings.insert(Box::from(string), index);
+            self.out.push(tag::STR);
+            self.len(string.len());
+            self.out.extend_from_slice(string.as_bytes());
+        }
+    }
+}
+
+impl<'a> ser::Serializer for &'a mut Encoder {
+    type Ok = ();
+    type Error = Error;
+    type SerializeSeq = Compound<'a>;
+    type SerializeTuple = Compound<'a>;
+    type SerializeTupleStruct = Compound<'a>;
+    type SerializeTupleVariant = Compound<'a>;
+    type SerializeMap = Compound<'a>;
+    type SerializeStruct = Compound<'a>;
+    type SerializeStructVariant = Compound<'a>;
+
+    fn serialize_bool(self, v: bool) -> Result<(), Error> {
+        self.out.push(if v { tag::TRUE } else { tag::FALSE });
+        Ok(())
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<(), Error> {
+        self.serialize_i64(i64::from(v))
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<(), Error> {
+        self.serialize_i64(i64::from(v))
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<(), Error> {
+        self.serialize_i64(i64::from(v))
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<(), Error> {
+        if let Ok(v) = u64::try_from(v) {
+            self.out.push(tag::UINT);
+            self.varint(v);
+        } else {
+            self.out.push(tag::NEG_INT);
+            self.varint(u64::try_from(!v).unwrap());
+        }
+        Ok(())
+    }
+
+    fn serialize_i128(self, v: i128) -> Result<(), Error> {
+        if let Ok(v) = i64::try_from(v) {
+            self.serialize_i64(v)
+        } else {
+            self.out.push(tag::INT128);
+            self.out.extend_from_slice(&v.to_le_bytes());
+            Ok(())
+        }
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<(), Error> {
+        self.serialize_u64(u64::from(v))
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<(), Error> {
+        self.serialize_u64(u64::from(v))
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<(), Error> {
+        self.serialize_u64(u64::from(v))
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<(), Error> {
+        self.out.push(tag::UINT);
+        self.varint(v);
+        Ok(())
+    }
+
+    fn serialize_u128(self, v: u128) -> Result<(), Error> {
+        if let Ok(v) = u64::try_from(v) {
+            self.serialize_u64(v)
+        } else {
+            self.out.push(tag::UINT128);
+            self.out.extend_from_slice(&v.to_le_bytes());
+            Ok(())
+        }
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<(), Error> {
+        self.serialize_f64(f64::from(v))
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<(), Error> {
+        self.out.push(tag::FLOAT);
+        self.out.extend_from_slice(&v.to_le_bytes());
+        Ok(())
+    }
+
+    fn serialize_char(self, v: char) -> Result<(), Error> {
+        self.str(v.encode_utf8(&mut [0; 4]));
+        Ok(())
+    }
+
+    fn serialize_str(self, v: &str) -> Result<(), Error> {
+        self.str(v);
+        Ok(())
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<(), Error> {
+        self.out.push(tag::BYTES);
+        self.len(v.len());
+        self.out.extend_from_slice(v);
+        Ok(())
+    }
+
+    fn serialize_none(self) -> Result<(), Error> {
+        self.serialize_unit()
+    }
+
+    fn serialize_some<T>(self, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<(), Error> {
+        self.out.push(tag::NULL);
+        Ok(())
+    }
+
+    fn serialize_unit_struct(self, name: &'static str) -> Result<(), Error> {
+        let _ = name;
+        self.serialize_unit()
+    }
+
+    fn serialize_unit_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+    ) -> Result<(), Error> {
+        let _ = name;
+        let _ = variant_index;
+        self.serialize_str(variant)
+    }
+
+    fn serialize_newtype_struct<T>(self, name: &'static str, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        let _ = name;
+        value.serialize(self)
+    }
+
+    // Variants with data are encoded as a map with one entry, the same as in
+    // JSON.
+    fn serialize_newtype_variant<T>(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<(), Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        let _ = name;
+        let _ = variant_index;
+        self.out.push(tag::MAP);
+        self.str(variant);
+        value.serialize(&mut *self)?;
+        self.out.push(tag::END);
+        Ok(())
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Compound<'a>, Error> {
+        let _ = len;
+        self.out.push(tag::SEQ);
+        Ok(Compound {
+            encoder: self,
+            variant: false,
+        })
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Compound<'a>, Error> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(self, name: &'static str, len: usize) -> Result<Compound<'a>, Error> {
+        let _ = name;
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Compound<'a>, Error> {
+        let _ = name;
+        let _ = variant_index;
+        let _ = len;
+        self.out.push(tag::MAP);
+        self.str(variant);
+        self.out.push(tag::SEQ);
+        Ok(Compound {
+            encoder: self,
+            variant: true,
+        })
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<Compound<'a>, Error> {
+        let _ = len;
+        self.out.push(tag::MAP);
+        Ok(Compound {
+            encoder: self,
+            variant: false,
+        })
+    }
+
+    fn serialize_struct(self, name: &'static str, len: usize) -> Result<Compound<'a>, Error> {
+        let _ = name;
+        self.serialize_map(Some(len))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Compound<'a>, Error> {
+        let _ = name;
+        let _ = variant_index;
+        let _ = len;
+        self.out.push(tag::MAP);
+        self.str(variant);
+        self.out.push(tag::MAP);
+        Ok(Compound {
+            encoder: self,
+            variant: true,
+        })
+    }
+}
+
+struct Compound<'a> {
+    encoder: &'a mut Encoder,
+    // Whether the sequence or map is wrapped in the one-entry map of a
+    // variant, which needs closing too.
+    variant: bool,
+}
+
+impl<'a> Compound<'a> {
+    fn element<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        value.serialize(&mut *self.encoder)
+    }
+
+    fn finish(self) {
+        self.encoder.out.push(tag::END);
+        if self.variant {
+            self.encoder.out.push(tag::END);
+        }
+    }
+}
+
+impl<'a> ser::SerializeSeq for Compound<'a> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        self.finish();
+        Ok(())
+    }
+}
+
+impl<'a> ser::SerializeTuple for Compound<'a> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_element<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        self.finish();
+        Ok(())
+    }
+}
+
+impl<'a> ser::SerializeTupleStruct for Compound<'a> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        self.finish();
+        Ok(())
+    }
+}
+
+impl<'a> ser::SerializeTupleVariant for Compound<'a> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        self.finish();
+        Ok(())
+    }
+}
+
+impl<'a> ser::SerializeMap for Compound<'a> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_key<T>(&mut self, key: &T) -> Result<(), Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.element(key)
+    }
+
+    fn serialize_value<T>(&mut self, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        self.finish();
+        Ok(())
+    }
+}
+
+impl<'a> ser::SerializeStruct for Compound<'a> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.encoder.str(key);
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        self.finish();
+        Ok(())
+    }
+}
+
+impl<'a> ser::SerializeStructVariant for Compound<'a> {
+    type Ok = ();
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        self.encoder.str(key);
+        self.element(value)
+    }
+
+    fn end(self) -> Result<(), Error> {
+        self.finish();
+        Ok(())
+    }
+}
+
+struct Decoder<'de> {
+    input: &'de [u8],
+    strings: Vec<&'de str>,
+}
+
+fn eof() -> Error {
+    Error::new("unexpected end of cache")
+}
+
+impl<'de> Decoder<'de> {
+    fn peek(&self) -> Result<u8, Error> {
+        self.input.first().copied().ok_or_else(eof)
+    }
+
+    fn byte(&mut self) -> Result<u8, Error> {
+        let byte = self.peek()?;
+        self.input = &self.input[1..];
+        Ok(byte)
+    }
+
+    fn bytes(&mut self, len: usize) -> Result<&'de [u8], Error> {
+        if len > self.input.len() {
+            return Err(eof());
+        }
+        let (bytes, rest) = self.input.split_at(len);
+        self.input = rest;
+        Ok(bytes)
+    }
+
+    fn array<A>(&mut self) -> Result<A, Error>
+    where
+        A: Default + AsMut<[u8]>,
+    {
+        let mut array = A::default();
+        let len = array.as_mut().len();
+        array.as_mut().copy_from_slice(self.bytes(len)?);
+        Ok(array)
+    }
+
+    fn varint(&mut self) -> Result<u64, Error> {
+        let mut value = 0u64;
+        let mut shift = 0;
+        loop {
+            let byte = self.byte()?;
+            if shift == 63 && byte > 1 {
+                return Err(Error::new("integer overflow in cache"));
+            }
+            value |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+            shift += 7;
+            if shift > 63 {
+                return Err(Error::new("integer overflow in cache"));
+            }
+        }
+    }
+
+    fn len(&mut self) -> Result<usize, Error> {
+        usize::try_from(self.varint()?).map_err(|_| Error::new("length out of range in cache"))
+    }
+
+    // The string following a STR or STR_REF tag.
+    fn str(&mut self, tag: u8) -> Result<&'de str, Error> {
+        if tag == tag::STR {
+            let len = self.len()?;
+            let string = str::from_utf8(self.bytes(len)?).map_err(Error::new)?;
+            self.strings.push(string);
+            Ok(string)
+        } else {
+            let index = usize::try_from(self.varint()?).ok();
+            match index.and_then(|index| self.strings.get(index)) {
+                Some(string) => Ok(string),
+                None => Err(Error::new("invalid string reference in cache")),
+            }
+        }
+    }
+
+    fn end(&mut self) -> Result<(), Error> {
+        if self.byte()? == tag::END {
+            Ok(())
+        } else {
+            Err(Error::new("expected end of sequence or map in cache"))
+        }
+    }
+}
+
+impl<'de> de::Deserializer<'de> for &mut Decoder<'de> {
+    type Error = Error;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self.byte()? {
+            tag::NULL => visitor.visit_unit(),
+            tag::FALSE => visitor.visit_bool(false),
+            tag::TRUE => visitor.visit_bool(true),
+            tag::UINT => visitor.visit_u64(self.varint()?),
+            tag::NEG_INT => match i64::try_from(self.varint()?) {
+                Ok(complement) => visitor.visit_i64(!complement),
+                Err(_) => Err(Error::new("integer overflow in cache")),
+            },
+            tag::FLOAT => visitor.visit_f64(f64::from_le_bytes(self.array()?)),
+            tag @ (tag::STR | tag::STR_REF) => visitor.visit_borrowed_str(self.str(tag)?),
+            tag::BYTES => {
+                let len = self.len()?;
+                visitor.visit_borrowed_bytes(self.bytes(len)?)
+            }
+            tag::SEQ => {
+                let mut access = Access {
+                    decoder: self,
+                    done: false,
+                };
+                let value = visitor.visit_seq(&mut access)?;
+                if !access.done {
+                    access.decoder.end()?;
+                }
+                Ok(value)
+            }
+            tag::MAP => {
+                let mut access = Access {
+                    decoder: self,
+                    done: false,
+                };
+                let value = visitor.visit_map(&mut access)?;
+                if !access.done {
+                    access.decoder.end()?;
+                }
+                Ok(value)
+            }
+            tag::UINT128 => visitor.visit_u128(u128::from_le_bytes(self.array()?)),
+            tag::INT128 => visitor.visit_i128(i128::from_le_bytes(self.array()?)),
+            _ => Err(Error::new("invalid tag in cache")),
+        }
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        if self.peek()? == tag::NULL {
+            self.input = &self.input[1..];
+            visitor.visit_none()
+        } else {
+            visitor.visit_some(self)
+        }
+    }
+
+    fn deserialize_newtype_struct<V>(
+        self,
+        name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        let _ = name;
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        let _ = name;
+        let _ = variants;
+        match self.peek()? {
+            tag @ (tag::STR | tag::STR_REF) => {
+                self.input = &self.input[1..];
+                let variant = self.str(tag)?;
+                visitor.visit_enum(BorrowedStrDeserializer::new(variant))
+            }
+            tag::MAP => {
+                self.input = &self.input[1..];
+                let value = visitor.visit_enum(&mut *self)?;
+                self.end()?;
+                Ok(value)
+            }
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple tuple_struct map struct
+        identifier ignored_any
+    }
+}
+
+struct Access<'a, 'de> {
+    decoder: &'a mut Decoder<'de>,
+    // Whether the END tag has been consumed.
+    done: bool,
+}
+
+impl<'a, 'de> Access<'a, 'de> {
+    fn at_end(&mut self) -> Result<bool, Error> {
+        if !self.done && self.decoder.peek()? == tag::END {
+            self.decoder.input = &self.decoder.input[1..];
+            self.done = true;
+        }
+        Ok(self.done)
+    }
+}
+
+impl<'de, 'a, 'b> SeqAccess<'de> for &'b mut Access<'a, 'de> {
+    type Error = Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        if self.at_end()? {
+            Ok(None)
+        } else {
+            seed.deserialize(&mut *self.decoder).map(Some)
+        }
+    }
+}
+
+impl<'de, 'a, 'b> MapAccess<'de> for &'b mut Access<'a, 'de> {
+    type Error = Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Error>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        if self.at_end()? {
+            Ok(None)
+        } else {
+            seed.deserialize(&mut *self.decoder).map(Some)
+        }
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Error>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        seed.deserialize(&mut *self.decoder)
+    }
+}
+
+impl<'de> EnumAccess<'de> for &mut Decoder<'de> {
+    type Error = Error;
+    type Variant = Self;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self), Error>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let variant = seed.deserialize(&mut *self)?;
+        Ok((variant, self))
+    }
+}
+
+impl<'de> VariantAccess<'de> for &mut Decoder<'de> {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<(), Error> {
+        de::Deserialize::deserialize(self)
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value, Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        seed.deserialize(self)
+    }
+
+    fn tuple_variant<V>(self, len: usize, visitor: V) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        let _ = len;
+        de::Deserializer::deserialize_any(self, visitor)
+    }
+
+    fn struct_variant<V>(
+        self,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error>
+    where
+        V: Visitor<'de>,
+    {
+        let _ = fields;
+        de::Deserializer::deserialize_any(self, visitor)
+    }
+}
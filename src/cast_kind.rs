@@ -0,0 +1,178 @@
+use serde::de::{Deserialize, Deserializer, Error, Visitor};
+use serde::ser::{Serialize, Serializer};
+use std::fmt::{self, Display};
+
+macro_rules! cast_kind {
+    ($($kind:ident,)*) => {
+        /// The `"castKind"` of a cast expression, such as `ImplicitCastExpr`,
+        /// `CStyleCastExpr` or `CXXStaticCastExpr`.
+        ///
+        /// ```
+        /// use clang_ast::CastKind;
+        /// use serde::Deserialize;
+        ///
+        /// #[derive(Deserialize)]
+        /// pub enum Clang {
+        ///     ImplicitCastExpr {
+        ///         #[serde(rename = "castKind")]
+        ///         cast_kind: CastKind,
+        ///     },
+        ///     Other,
+        /// }
+        /// #
+        /// # let json = r#"{"id":"0x1","kind":"ImplicitCastExpr","castKind":"LValueToRValue"}"#;
+        /// # let node: clang_ast::Node<Clang> = serde_json::from_str(json).unwrap();
+        /// # match node.kind {
+        /// #     Clang::ImplicitCastExpr { cast_kind } => {
+        /// #         assert_eq!(cast_kind, CastKind::LValueToRValue);
+        /// #     }
+        /// #     Clang::Other => panic!(),
+        /// # }
+        /// ```
+        #[derive(Clone, Debug, PartialEq, Eq, Hash)]
+        #[non_exhaustive]
+        pub enum CastKind {
+            $(
+                $kind,
+            )*
+            /// A cast kind not known to this version of this crate, such as
+            /// one introduced by a newer Clang release.
+            Other(Box<str>),
+        }
+
+        impl CastKind {
+            /// Name of the cast kind as it appears in the JSON, which is
+            /// also its `Display` representation.
+            pub fn as_str(&self) -> &str {
+                match self {
+                    $(
+                        CastKind::$kind => stringify!($kind),
+                    )*
+                    CastKind::Other(kind) => kind,
+                }
+            }
+
+            fn from_name(kind: &str) -> Self {
+                match kind {
+                    $(
+                        stringify!($kind) => CastKind::$kind,
+                    )*
+                    other => CastKind::Other(Box::from(other)),
+                }
+            }
+        }
+    };
+}
+
+cast_kind! {
+    Dependent,
+    BitCast,
+    LValueBitCast,
+    LValueToRValueBitCast,
+    LValueToRValue,
+    NoOp,
+    BaseToDerived,
+    DerivedToBase,
+    UncheckedDerivedToBase,
+    Dynamic,
+    ToUnion,
+    ArrayToPointerDecay,
+    FunctionToPointerDecay,
+    NullToPointer,
+    NullToMemberPointer,
+    BaseToDerivedMemberPointer,
+    DerivedToBaseMemberPointer,
+    MemberPointerToBoolean,
+    ReinterpretMemberPointer,
+    UserDefinedConversion,
+    ConstructorConversion,
+    IntegralToPointer,
+    PointerToIntegral,
+    PointerToBoolean,
+    ToVoid,
+    MatrixCast,
+    VectorSplat,
+    IntegralCast,
+    IntegralToBoolean,
+    IntegralToFloating,
+    FloatingToFixedPoint,
+    FixedPointToFloating,
+    FixedPointCast,
+    FixedPointToIntegral,
+    IntegralToFixedPoint,
+    FixedPointToBoolean,
+    FloatingToIntegral,
+    FloatingToBoolean,
+    BooleanToSignedIntegral,
+    FloatingCast,
+    CPointerToObjCPointerCast,
+    BlockPointerToObjCPointerCast,
+    AnyPointerToBlockPointerCast,
+    ObjCObjectLValueCast,
+    FloatingRealToComplex,
+    FloatingComplexToReal,
+    FloatingComplexToBoolean,
+    FloatingComplexCast,
+    FloatingComplexToIntegralComplex,
+    IntegralRealToComplex,
+    IntegralComplexToReal,
+    IntegralComplexToBoolean,
+    IntegralComplexCast,
+    IntegralComplexToFloatingComplex,
+    ARCProduceObject,
+    ARCConsumeObject,
+    ARCReclaimReturnedObject,
+    ARCExtendBlockObject,
+    AtomicToNonAtomic,
+    NonAtomicToAtomic,
+    CopyAndAutoreleaseBlockObject,
+    BuiltinFnToFnPtr,
+    ZeroToOCLOpaqueType,
+    AddressSpaceConversion,
+    IntToOCLSampler,
+    HLSLVectorTruncation,
+    HLSLArrayRValue,
+    HLSLElementwiseCast,
+    HLSLAggregateSplatCast,
+}
+
+impl Display for CastKind {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for CastKind {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct CastKindVisitor;
+
+        impl<'de> Visitor<'de> for CastKindVisitor {
+            type Value = CastKind;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("cast kind")
+            }
+
+            fn visit_str<E>(self, kind: &str) -> Result<Self::Value, E>
+            where
+                E: Error,
+            {
+                Ok(CastKind::from_name(kind))
+            }
+        }
+
+        deserializer.deserialize_str(CastKindVisitor)
+    }
+}
+
+impl Serialize for CastKind {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(self.as_str())
+    }
+}
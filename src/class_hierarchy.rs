@@ -0,0 +1,489 @@
+use crate::{Access, Base, Id, Type};
+use serde::de::{
+    Deserialize, DeserializeSeed, Deserializer, IgnoredAny, MapAccess, SeqAccess, Visitor,
+};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt;
+
+/// Inheritance relationships among the C++ classes defined in an AST dump.
+///
+/// Deserializing a `ClassHierarchy` walks the entire dump without building
+/// the tree, collecting every class, struct and union definition along with
+/// its `"bases"`.
+///
+/// Clang identifies a base class only by its type, so bases are matched up
+/// with their definitions by fully qualified name. This is best-effort: a
+/// base which is a template specialization is matched to the first
+/// definition of the template's name, and a base whose definition is not in
+/// the dump, or is a class local to a function, stays unresolved.
+///
+/// ```
+/// use clang_ast::ClassHierarchy;
+///
+/// let json = r#"
+///     {
+///       "id": "0x1",
+///       "kind": "TranslationUnitDecl",
+///       "inner": [
+///         {
+///           "id": "0x2",
+///           "kind": "CXXRecordDecl",
+///           "name": "Shape",
+///           "tagUsed": "struct",
+///           "completeDefinition": true
+///         },
+///         {
+///           "id": "0x3",
+///           "kind": "CXXRecordDecl",
+///           "name": "Circle",
+///           "tagUsed": "struct",
+///           "completeDefinition": true,
+///           "bases": [
+///             {
+///               "access": "public",
+///               "type": { "qualType": "Shape" },
+///               "writtenAccess": "none"
+///             }
+///           ]
+///         }
+///       ]
+///     }
+/// "#;
+/// let hierarchy: ClassHierarchy = serde_json::from_str(json).unwrap();
+/// let shape = hierarchy.find("Shape").unwrap();
+/// let derived: Vec<&str> = hierarchy.derived_of(shape.id).map(|class| &*class.name).collect();
+/// assert_eq!(derived, ["Circle"]);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct ClassHierarchy {
+    classes: Vec<Class>,
+    indices: HashMap<Id, usize>,
+    names: HashMap<String, usize>,
+    derived: Vec<Vec<usize>>,
+}
+
+/// A class definition in a [`ClassHierarchy`].
+#[derive(Clone, Debug)]
+pub struct Class {
+    pub id: Id,
+    /// Fully qualified name, such as `ns::Outer::Inner`, leaving out inline
+    /// namespaces. Unnamed classes are called `(anonymous)`.
+    pub name: String,
+    pub bases: Vec<Inheritance>,
+}
+
+/// One base class of a [`Class`].
+#[derive(Clone, Debug)]
+pub struct Inheritance {
+    /// The base's definition, if it could be found.
+    pub class: Option<Id>,
+    pub ty: Type,
+    pub access: Access,
+    pub is_virtual: bool,
+}
+
+impl ClassHierarchy {
+    /// Every class, in the order their definitions appear in the dump.
+    pub fn classes(&self) -> &[Class] {
+        &self.classes
+    }
+
+    pub fn get(&self, id: Id) -> Option<&Class> {
+        self.indices.get(&id).map(|&index| &self.classes[index])
+    }
+
+    /// Look up a class by its fully qualified name.
+    pub fn find(&self, name: &str) -> Option<&Class> {
+        let name = name.strip_prefix("::").unwrap_or(name);
+        self.names.get(name).map(|&index| &self.classes[index])
+    }
+
+    /// Direct base classes of the given class.
+    pub fn bases(&self, id: Id) -> &[Inheritance] {
+        self.get(id).map_or(&[], |class| &class.bases)
+    }
+
+    /// Classes which directly derive from the given class.
+    pub fn derived_of(&self, id: Id) -> impl Iterator<Item = &Class> {
+        let derived = match self.indices.get(&id) {
+            Some(&index) => self.derived[index].as_slice(),
+            None => &[],
+        };
+        derived.iter().map(move |&index| &self.classes[index])
+    }
+
+    /// Every class which the given class derives from, directly or
+    /// indirectly, nearest first. A class inherited along several paths is
+    /// listed once.
+    pub fn all_bases(&self, id: Id) -> Vec<&Class> {
+        self.breadth_first(id, |class| {
+            class
+                .bases
+                .iter()
+                .filter_map(|base| base.class)
+                .filter_map(|id| self.indices.get(&id).copied())
+                .collect()
+        })
+    }
+
+    /// Every class which derives from the given class, directly or
+    /// indirectly, nearest first.
+    pub fn all_subclasses(&self, id: Id) -> Vec<&Class> {
+        self.breadth_first(id, |class| {
+            let index = self.indices[&class.id];
+            self.derived[index].clone()
+        })
+    }
+
+    fn breadth_first<F>(&self, id: Id, next: F) -> Vec<&Class>
+    where
+        F: Fn(&Class) -> Vec<usize>,
+    {
+        let mut result = Vec::new();
+        let start = match self.indices.get(&id) {
+            Some(&index) => index,
+            None => return result,
+        };
+        let mut seen = HashSet::new();
+        seen.insert(start);
+        let mut queue = VecDeque::new();
+        queue.push_back(start);
+        while let Some(index) = queue.pop_front() {
+            for next in next(&self.classes[index]) {
+                if seen.insert(next) {
+                    result.push(&self.classes[next]);
+                    queue.push_back(next);
+                }
+            }
+        }
+        result
+    }
+
+    fn insert(&mut self, id: Id, name: String, qualified: bool) -> usize {
+        let index = self.classes.len();
+        if qualified && !self.names.contains_key(&name) {
+            self.names.insert(name.clone(), index);
+        }
+        self.indices.insert(id, index);
+        self.classes.push(Class {
+            id,
+            name,
+            bases: Vec::new(),
+        });
+        self.derived.push(Vec::new());
+        index
+    }
+
+    fn resolve(&mut self) {
+        for derived in 0..self.classes.len() {
+            for i in 0..self.classes[derived].bases.len() {
+                let base = &self.classes[derived].bases[i];
+                let mut candidates = base
+                    .ty
+                    .desugared_qual_type
+                    .iter()
+                    .chain(Some(&base.ty.qual_type));
+                let found = candidates.find_map(|qual_type| self.lookup(qual_type));
+                if let Some(index) = found {
+                    self.classes[derived].bases[i].class = Some(self.classes[index].id);
+                    if !self.derived[index].contains(&derived) {
+                        self.derived[index].push(derived);
+                    }
+                }
+            }
+        }
+    }
+
+    fn lookup(&self, qual_type: &str) -> Option<usize> {
+        let mut name = qual_type.trim();
+        for keyword in &["struct ", "class ", "union "] {
+            name = name.strip_prefix(keyword).unwrap_or(name);
+        }
+        name = name.strip_prefix("::").unwrap_or(name);
+        if let Some(&index) = self.names.get(name) {
+            return Some(index);
+        }
+        self.names.get(strip_template_args(name)?).copied()
+    }
+}
+
+// `ns::Foo<int, Bar<char>>` -> `ns::Foo`.
+fn strip_template_args(name: &str) -> Option<&str> {
+    if !name.ends_with('>') {
+        return None;
+    }
+    let mut depth = 0;
+    for (i, ch) in name.char_indices().rev() {
+        match ch {
+            '>' => depth += 1,
+            '<' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(name[..i].trim_end());
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+impl<'de> Deserialize<'de> for ClassHierarchy {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let mut hierarchy = ClassHierarchy::default();
+        deserializer.deserialize_map(NodeVisitor {
+            hierarchy: &mut hierarchy,
+            scope: Some(""),
+        })?;
+        hierarchy.resolve();
+        Ok(hierarchy)
+    }
+}
+
+#[derive(Copy, Clone, PartialEq)]
+enum NodeKind {
+    Namespace,
+    Record,
+    // Nodes which do not contribute to qualified names, such as a
+    // ClassTemplateDecl whose name is repeated by the CXXRecordDecl inside.
+    Transparent,
+    Other,
+}
+
+impl<'de> Deserialize<'de> for NodeKind {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct NodeKindVisitor;
+
+        impl<'de> Visitor<'de> for NodeKindVisitor {
+            type Value = NodeKind;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("node kind")
+            }
+
+            fn visit_str<E>(self, kind: &str) -> Result<Self::Value, E> {
+                Ok(match kind {
+                    "NamespaceDecl" => NodeKind::Namespace,
+                    "CXXRecordDecl"
+                    | "ClassTemplateSpecializationDecl"
+                    | "ClassTemplatePartialSpecializationDecl" => NodeKind::Record,
+                    "TranslationUnitDecl"
+                    | "LinkageSpecDecl"
+                    | "ExportDecl"
+                    | "ClassTemplateDecl" => NodeKind::Transparent,
+                    _ => NodeKind::Other,
+                })
+            }
+        }
+
+        deserializer.deserialize_str(NodeKindVisitor)
+    }
+}
+
+enum NodeField {
+    Id,
+    Kind,
+    Name,
+    IsInline,
+    CompleteDefinition,
+    Bases,
+    Inner,
+    Other,
+}
+
+impl<'de> Deserialize<'de> for NodeField {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct NodeFieldVisitor;
+
+        impl<'de> Visitor<'de> for NodeFieldVisitor {
+            type Value = NodeField;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("field identifier")
+            }
+
+            fn visit_str<E>(self, field: &str) -> Result<Self::Value, E> {
+                Ok(match field {
+                    "id" => NodeField::Id,
+                    "kind" => NodeField::Kind,
+                    "name" => NodeField::Name,
+                    "isInline" => NodeField::IsInline,
+                    "completeDefinition" => NodeField::CompleteDefinition,
+                    "bases" => NodeField::Bases,
+                    "inner" => NodeField::Inner,
+                    _ => NodeField::Other,
+                })
+            }
+        }
+
+        deserializer.deserialize_identifier(NodeFieldVisitor)
+    }
+}
+
+struct NodeVisitor<'a> {
+    hierarchy: &'a mut ClassHierarchy,
+    // Qualified name of the enclosing scope, or None inside of a function
+    // or anything else whose classes cannot be named from outside.
+    scope: Option<&'a str>,
+}
+
+impl<'de, 'a> Visitor<'de> for NodeVisitor<'a> {
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("clang syntax tree node")
+    }
+
+    fn visit_map<M>(mut self, mut map: M) -> Result<Self::Value, M::Error>
+    where
+        M: MapAccess<'de>,
+    {
+        let mut id = None;
+        let mut kind = NodeKind::Other;
+        let mut name: Option<String> = None;
+        let mut is_inline = false;
+        let mut complete_definition = false;
+        let mut bases = Vec::new();
+        let mut index = None;
+        while let Some(field) = map.next_key()? {
+            match field {
+                NodeField::Id => id = Some(map.next_value::<Id>()?),
+                NodeField::Kind => kind = map.next_value()?,
+                NodeField::Name => name = Some(map.next_value()?),
+                NodeField::IsInline => is_inline = map.next_value()?,
+                NodeField::CompleteDefinition => complete_definition = map.next_value()?,
+                NodeField::Bases => bases = map.next_value::<Vec<Base>>()?,
+                NodeField::Inner => {
+                    // Clang writes "inner" last, so the name is known by now.
+                    let scope = match kind {
+                        NodeKind::Namespace if is_inline => self.scope.map(str::to_owned),
+                        NodeKind::Namespace => self.scope.map(|scope| {
+                            join(scope, name.as_deref().unwrap_or("(anonymous namespace)"))
+                        }),
+                        NodeKind::Record => {
+                            if complete_definition && index.is_none() {
+                                index = self.insert(id, name.as_deref());
+                            }
+                            match (self.scope, &name) {
+                                (Some(scope), Some(name)) => Some(join(scope, name)),
+                                _ => None,
+                            }
+                        }
+                        NodeKind::Transparent => self.scope.map(str::to_owned),
+                        NodeKind::Other => None,
+                    };
+                    map.next_value_seed(InnerSeed {
+                        hierarchy: &mut *self.hierarchy,
+                        scope: scope.as_deref(),
+                    })?;
+                }
+                NodeField::Other => {
+                    let IgnoredAny = map.next_value()?;
+                }
+            }
+        }
+        if kind == NodeKind::Record && complete_definition && index.is_none() {
+            index = self.insert(id, name.as_deref());
+        }
+        if let Some(index) = index {
+            self.hierarchy.classes[index].bases = bases
+                .into_iter()
+                .map(|base| Inheritance {
+                    class: None,
+                    ty: base.ty,
+                    access: base.access,
+                    is_virtual: base.is_virtual,
+                })
+                .collect();
+        }
+        Ok(())
+    }
+}
+
+impl<'a> NodeVisitor<'a> {
+    fn insert(&mut self, id: Option<Id>, name: Option<&str>) -> Option<usize> {
+        let id = id?;
+        let qualified = match (self.scope, name) {
+            (Some(scope), Some(name)) => Some(join(scope, name)),
+            _ => None,
+        };
+        let index = if let Some(qualified) = qualified {
+            self.hierarchy.insert(id, qualified, true)
+        } else {
+            let name = name.unwrap_or("(anonymous)").to_owned();
+            self.hierarchy.insert(id, name, false)
+        };
+        Some(index)
+    }
+}
+
+fn join(scope: &str, name: &str) -> String {
+    if scope.is_empty() {
+        name.to_owned()
+    } else {
+        format!("{}::{}", scope, name)
+    }
+}
+
+struct InnerSeed<'a> {
+    hierarchy: &'a mut ClassHierarchy,
+    scope: Option<&'a str>,
+}
+
+impl<'de, 'a> DeserializeSeed<'de> for InnerSeed<'a> {
+    type Value = ();
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+impl<'de, 'a> Visitor<'de> for InnerSeed<'a> {
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("array of clang syntax tree nodes")
+    }
+
+    fn visit_seq<S>(self, mut seq: S) -> Result<Self::Value, S::Error>
+    where
+        S: SeqAccess<'de>,
+    {
+        while let Some(()) = seq.next_element_seed(NodeSeed {
+            hierarchy: &mut *self.hierarchy,
+            scope: self.scope,
+        })? {}
+        Ok(())
+    }
+}
+
+struct NodeSeed<'a> {
+    hierarchy: &'a mut ClassHierarchy,
+    scope: Option<&'a str>,
+}
+
+impl<'de, 'a> DeserializeSeed<'de> for NodeSeed<'a> {
+    type Value = ();
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_map(NodeVisitor {
+            hierarchy: self.hierarchy,
+            scope: self.scope,
+        })
+    }
+}
@@ -0,0 +1,145 @@
+use crate::schema::{FieldSchema, KindSchema, Schema, ValueType};
+use std::fmt::Write;
+
+pub(crate) fn to_rust(schema: &Schema) -> String {
+    let mut out = String::new();
+    out.push_str("use serde::Deserialize;\n");
+    out.push('\n');
+    out.push_str("pub type Node = clang_ast::Node<Clang>;\n");
+    out.push('\n');
+    out.push_str("#[derive(Deserialize, Debug)]\n");
+    out.push_str("pub enum Clang {\n");
+    for (name, kind) in kinds(schema) {
+        if has_fields(kind) {
+            let _ = writeln!(out, "    {}({}),", name, name);
+        } else {
+            let _ = writeln!(out, "    {},", name);
+        }
+    }
+    out.push_str("    Other,\n");
+    out.push_str("}\n");
+
+    for (name, kind) in kinds(schema) {
+        if !has_fields(kind) {
+            continue;
+        }
+        out.push('\n');
+        out.push_str("#[derive(Deserialize, Debug)]\n");
+        let _ = writeln!(out, "pub struct {} {{", name);
+        for (field_name, field) in fields(kind) {
+            let ident = field_ident(field_name);
+            if ident.trim_start_matches("r#") != field_name {
+                let _ = writeln!(out, "    #[serde(rename = \"{}\")]", field_name);
+            }
+            let ty = field_type(field_name, field);
+            if field.is_required(kind) {
+                let _ = writeln!(out, "    pub {}: {},", ident, ty);
+            } else if ty == "bool" {
+                out.push_str("    #[serde(default)]\n");
+                let _ = writeln!(out, "    pub {}: bool,", ident);
+            } else if ty.starts_with("Option<") {
+                let _ = writeln!(out, "    pub {}: {},", ident, ty);
+            } else {
+                let _ = writeln!(out, "    pub {}: Option<{}>,", ident, ty);
+            }
+        }
+        out.push_str("}\n");
+    }
+    out
+}
+
+// Kinds which can be variant names. Objects without a "kind" are left to the
+// fallback variant.
+fn kinds(schema: &Schema) -> impl Iterator<Item = (&String, &KindSchema)> {
+    schema
+        .kinds
+        .iter()
+        .filter(|(name, _kind)| is_ident(name) && name.as_str() != "Other")
+}
+
+// Fields other than "inner", whose contents end up in the Node.
+fn fields(kind: &KindSchema) -> impl Iterator<Item = (&String, &FieldSchema)> {
+    kind.fields
+        .iter()
+        .filter(|(name, _field)| name.as_str() != "inner" && is_ident(name))
+}
+
+fn has_fields(kind: &KindSchema) -> bool {
+    fields(kind).next().is_some()
+}
+
+fn is_ident(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .map_or(false, |ch| ch.is_ascii_alphabetic() || ch == '_')
+        && chars.all(|ch| ch.is_ascii_alphanumeric() || ch == '_')
+}
+
+fn field_type(name: &str, field: &FieldSchema) -> String {
+    let types = &field.types;
+    let nullable = types.contains(&ValueType::Null);
+    let mut non_null = types.iter().filter(|&&ty| ty != ValueType::Null);
+    let ty = match (non_null.next(), non_null.next(), non_null.next()) {
+        (Some(ValueType::Object), None, None) if name == "loc" => "clang_ast::SourceLocation",
+        (Some(ValueType::Object), None, None) if name == "range" => "clang_ast::SourceRange",
+        (Some(ValueType::Object), None, None) if name == "type" => "clang_ast::Type",
+        (Some(ValueType::Array), None, None) if name == "bases" => "Vec<clang_ast::Base>",
+        (Some(ValueType::Object), None, None) if name == "definitionData" => {
+            "clang_ast::DefinitionData"
+        }
+        (Some(ValueType::String), None, None) if name == "valueCategory" => {
+            "clang_ast::ValueCategory"
+        }
+        (Some(ValueType::String), None, None) if name == "storageClass" => {
+            "clang_ast::StorageClass"
+        }
+        (Some(ValueType::String), None, None) if name == "tagUsed" => "clang_ast::TagKind",
+        (Some(ValueType::String), None, None) if name == "init" => "clang_ast::InitStyle",
+        (Some(ValueType::Bool), None, None) => "bool",
+        (Some(ValueType::Integer), None, None) => "i64",
+        (Some(ValueType::Integer), Some(ValueType::Float), None)
+        | (Some(ValueType::Float), None, None) => "f64",
+        (Some(ValueType::String), None, None) => "String",
+        _ => return "serde_json::Value".to_owned(),
+    };
+    if nullable {
+        format!("Option<{}>", ty)
+    } else {
+        ty.to_owned()
+    }
+}
+
+// Convert a camelCase field name to snake_case.
+fn field_ident(name: &str) -> String {
+    let mut ident = String::new();
+    let mut prev_lower = false;
+    for ch in name.chars() {
+        if ch.is_ascii_uppercase() {
+            if prev_lower {
+                ident.push('_');
+            }
+            ident.push(ch.to_ascii_lowercase());
+            prev_lower = false;
+        } else {
+            ident.push(ch);
+            prev_lower = ch.is_ascii_lowercase() || ch.is_ascii_digit();
+        }
+    }
+    if is_keyword(&ident) {
+        ident.insert_str(0, "r#");
+    }
+    ident
+}
+
+fn is_keyword(ident: &str) -> bool {
+    match ident {
+        "as" | "async" | "await" | "break" | "const" | "continue" | "crate" | "dyn" | "else"
+        | "enum" | "extern" | "false" | "fn" | "for" | "if" | "impl" | "in" | "let" | "loop"
+        | "match" | "mod" | "move" | "mut" | "pub" | "ref" | "return" | "self" | "static"
+        | "struct" | "super" | "trait" | "true" | "type" | "unsafe" | "use" | "where" | "while"
+        | "abstract" | "become" | "box" | "do" | "final" | "macro" | "override" | "priv"
+        | "try" | "typeof" | "unsized" | "virtual" | "yield" => true,
+        _ => false,
+    }
+}
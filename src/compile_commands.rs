@@ -0,0 +1,258 @@
+//! Dumping every translation unit of a build from its
+//! `compile_commands.json`.
+//!
+//! A [compilation database] lists the exact compiler invocation for each
+//! source file of a project, and can be exported by most build systems.
+//! [`Driver`] reruns each of those commands with `-Xclang -ast-dump=json
+//! -fsyntax-only` appended, and deserializes the dump that clang writes to
+//! its standard output.
+//!
+//! [compilation database]: https://clang.llvm.org/docs/JSONCompilationDatabase.html
+//!
+//! ```no_run
+//! use clang_ast::compile_commands::{self, Driver};
+//! use clang_ast::Kind;
+//! use serde::Deserialize;
+//!
+//! #[derive(Deserialize)]
+//! pub struct Clang {
+//!     pub kind: Kind,
+//! }
+//!
+//! fn main() -> Result<(), compile_commands::Error> {
+//!     let commands = compile_commands::read("build/compile_commands.json")?;
+//!     let mut driver = Driver::new();
+//!     driver.jobs(8);
+//!     for (i, result) in driver.run::<Clang>(commands.clone()) {
+//!         let node = result?;
+//!         println!("{}: {} decls", commands[i].file.display(), node.inner.len());
+//!     }
+//!     Ok(())
+//! }
+//! ```
+
+use crate::{exec, Node};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{mpsc, Arc};
+use std::thread;
+
+pub use crate::exec::Error;
+
+/// One entry of a compilation database.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CompileCommand {
+    /// Working directory in which the command runs.
+    pub directory: PathBuf,
+    /// Source file being compiled, possibly relative to `directory`.
+    pub file: PathBuf,
+    /// The command line, beginning with the compiler. Entries which give
+    /// `"command"` as a single string are split into arguments using shell
+    /// quoting rules.
+    pub arguments: Vec<String>,
+    pub output: Option<PathBuf>,
+}
+
+/// Read the entries of a `compile_commands.json` file.
+pub fn read<P: AsRef<Path>>(path: P) -> Result<Vec<CompileCommand>, Error> {
+    let json = fs::read(path).map_err(Error::Io)?;
+    parse(&json)
+}
+
+/// Parse the entries of a compilation database from its JSON contents.
+pub fn parse(json: &[u8]) -> Result<Vec<CompileCommand>, Error> {
+    let entries: Vec<Value> = serde_json::from_slice(json).map_err(Error::Json)?;
+    entries.iter().map(parse_entry).collect()
+}
+
+fn parse_entry(entry: &Value) -> Result<CompileCommand, Error> {
+    let field = |name: &str| entry.get(name).and_then(Value::as_str);
+    let missing = |name: &str| {
+        let msg = format!("compile command is missing \"{}\"", name);
+        Error::Json(serde::de::Error::custom(msg))
+    };
+
+    let directory = PathBuf::from(field("directory").ok_or_else(|| missing("directory"))?);
+    let file = PathBuf::from(field("file").ok_or_else(|| missing("file"))?);
+    let arguments = if let Some(arguments) = entry.get("arguments").and_then(Value::as_array) {
+        arguments
+            .iter()
+            .filter_map(Value::as_str)
+            .map(str::to_owned)
+            .collect()
+    } else if let Some(command) = field("command") {
+        split_command(command)
+    } else {
+        return Err(missing("arguments"));
+    };
+    let output = field("output").map(PathBuf::from);
+    Ok(CompileCommand {
+        directory,
+        file,
+        arguments,
+        output,
+    })
+}
+
+// Split a command line the way a POSIX shell would, handling single quotes,
+// double quotes, and backslash escapes.
+fn split_command(command: &str) -> Vec<String> {
+    let mut arguments = Vec::new();
+    let mut current = String::new();
+    let mut in_argument = false;
+    let mut chars = command.chars();
+    while let Some(ch) = chars.next() {
+        match ch {
+            ' ' | '\t' | '\n' => {
+                if in_argument {
+                    arguments.push(std::mem::take(&mut current));
+                    in_argument = false;
+                }
+            }
+            '\'' => {
+                in_argument = true;
+                for ch in &mut chars {
+                    if ch == '\'' {
+                        break;
+                    }
+                    current.push(ch);
+                }
+            }
+            '"' => {
+                in_argument = true;
+                while let Some(ch) = chars.next() {
+                    match ch {
+                        '"' => break,
+                        '\\' => match chars.next() {
+                            Some(ch) if "\"\\$`".contains(ch) => current.push(ch),
+                            Some(ch) => {
+                                current.push('\\');
+                                current.push(ch);
+                            }
+                            None => current.push('\\'),
+                        },
+                        _ => current.push(ch),
+                    }
+                }
+            }
+            '\\' => {
+                in_argument = true;
+                if let Some(ch) = chars.next() {
+                    current.push(ch);
+                }
+            }
+            _ => {
+                in_argument = true;
+                current.push(ch);
+            }
+        }
+    }
+    if in_argument {
+        arguments.push(current);
+    }
+    arguments
+}
+
+/// Runs the commands of a compilation database to produce their AST dumps.
+#[derive(Clone, Debug)]
+pub struct Driver {
+    clang: Option<PathBuf>,
+    jobs: usize,
+}
+
+impl Driver {
+    pub fn new() -> Self {
+        Driver {
+            clang: None,
+            jobs: 1,
+        }
+    }
+
+    /// Run this compiler in place of the one named by each command, for
+    /// databases produced by a build that used gcc or a compiler wrapper.
+    pub fn clang(&mut self, clang: impl Into<PathBuf>) -> &mut Self {
+        self.clang = Some(clang.into());
+        self
+    }
+
+    /// Run up to `jobs` commands at the same time. The default is 1.
+    pub fn jobs(&mut self, jobs: usize) -> &mut Self {
+        self.jobs = jobs.max(1);
+        self
+    }
+
+    /// Run every command, yielding each one's index in `commands` together
+    /// with its syntax tree, in the order that they finish.
+    pub fn run<T>(&self, commands: Vec<CompileCommand>) -> Dumps<T>
+    where
+        T: DeserializeOwned + Send + 'static,
+    {
+        let commands = Arc::new(commands);
+        let next = Arc::new(AtomicUsize::new(0));
+        let (sender, receiver) = mpsc::channel();
+        for _ in 0..self.jobs.min(commands.len()) {
+            let commands = Arc::clone(&commands);
+            let next = Arc::clone(&next);
+            let sender = sender.clone();
+            let clang = self.clang.clone();
+            thread::spawn(move || loop {
+                let i = next.fetch_add(1, Ordering::Relaxed);
+                let command = match commands.get(i) {
+                    Some(command) => command,
+                    None => return,
+                };
+                let result = dump(clang.as_deref(), command);
+                if sender.send((i, result)).is_err() {
+                    return;
+                }
+            });
+        }
+        Dumps { receiver }
+    }
+}
+
+impl Default for Driver {
+    fn default() -> Self {
+        Driver::new()
+    }
+}
+
+/// Iterator returned by [`Driver::run`].
+#[derive(Debug)]
+pub struct Dumps<T> {
+    receiver: mpsc::Receiver<(usize, Result<Node<T>, Error>)>,
+}
+
+impl<T> Iterator for Dumps<T> {
+    type Item = (usize, Result<Node<T>, Error>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.receiver.recv().ok()
+    }
+}
+
+fn dump<T>(clang: Option<&Path>, command: &CompileCommand) -> Result<Node<T>, Error>
+where
+    T: DeserializeOwned,
+{
+    let mut arguments = command.arguments.iter();
+    let program = match (clang, arguments.next()) {
+        (Some(clang), _) => clang.as_os_str().to_owned(),
+        (None, Some(program)) => program.into(),
+        (None, None) => {
+            let msg = "compile command has no arguments";
+            return Err(Error::Io(io::Error::new(io::ErrorKind::InvalidInput, msg)));
+        }
+    };
+    let mut clang = Command::new(program);
+    clang
+        .args(arguments)
+        .args(exec::DUMP_FLAGS)
+        .current_dir(&command.directory);
+    exec::run(&mut clang)
+}
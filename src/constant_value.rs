@@ -0,0 +1,265 @@
+use crate::{FloatValue, LiteralValue};
+use serde::de::{Deserialize, Deserializer, Error, Visitor};
+use serde::ser::{Serialize, Serializer};
+use std::fmt::{self, Display};
+
+/// The `"value"` Clang writes for the result of evaluating a `ConstantExpr`,
+/// such as an enumerator's initializer, a case label, or a call to a
+/// `consteval` function.
+///
+/// Clang writes the value pretty-printed as C++, like `42`, `true`,
+/// `1.500000e+00`, `{1, {2, 3}}` for a struct or array, or `{.x = 1}` for a
+/// union. Those forms are broken down here; any other, like `&global` for a
+/// pointer, is kept as text in [`Other`][ConstantValue::Other].
+///
+/// ```
+/// use clang_ast::ConstantValue;
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// pub enum Clang {
+///     ConstantExpr { value: Option<ConstantValue> },
+///     Other,
+/// }
+/// #
+/// # let json = r#"{"id":"0x1","kind":"ConstantExpr","value":"{1, {2, 3}}"}"#;
+/// # let node: clang_ast::Node<Clang> = serde_json::from_str(json).unwrap();
+/// # match node.kind {
+/// #     Clang::ConstantExpr { value: Some(value) } => {
+/// #         let elements = value.elements().unwrap();
+/// #         assert_eq!(elements[0].as_integer().unwrap().as_i64(), Some(1));
+/// #         assert_eq!(elements[1].elements().unwrap().len(), 2);
+/// #     }
+/// #     _ => panic!(),
+/// # }
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub enum ConstantValue {
+    Integer(LiteralValue),
+    Bool(bool),
+    Float(FloatValue),
+    /// The bases and then fields of a struct, in declaration order, or the
+    /// elements of an array or vector.
+    Aggregate(Vec<ConstantValue>),
+    /// The active field of a union, by name, and its value.
+    Union(String, Box<ConstantValue>),
+    /// `...`, in place of the remaining elements of a long array, which
+    /// Clang truncates.
+    Elided,
+    /// Anything else, as Clang printed it.
+    Other(String),
+}
+
+// Same as serde_json's default recursion limit.
+const RECURSION_LIMIT: usize = 128;
+
+impl ConstantValue {
+    /// Break down a value as printed by Clang.
+    ///
+    /// Text in an unrecognized form becomes `ConstantValue::Other`. This
+    /// returns `None` only for aggregates nested more than 128 levels deep,
+    /// which are rejected rather than risk overflowing the stack.
+    pub fn parse(text: &str) -> Option<Self> {
+        parse_at_depth(text, 0)
+    }
+
+    pub fn as_integer(&self) -> Option<&LiteralValue> {
+        match self {
+            ConstantValue::Integer(integer) => Some(integer),
+            _ => None,
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            ConstantValue::Bool(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            ConstantValue::Float(float) => Some(float.value()),
+            _ => None,
+        }
+    }
+
+    /// The elements of an `Aggregate`.
+    pub fn elements(&self) -> Option<&[ConstantValue]> {
+        match self {
+            ConstantValue::Aggregate(elements) => Some(elements),
+            _ => None,
+        }
+    }
+}
+
+// The text between a pair of braces enclosing all of the text.
+fn braced(text: &str) -> Option<&str> {
+    let inner = text.strip_prefix('{')?.strip_suffix('}')?;
+    if split_top_level(inner, '}').len() == 1 {
+        Some(inner)
+    } else {
+        None
+    }
+}
+
+fn parse_at_depth(text: &str, depth: usize) -> Option<ConstantValue> {
+    let text = text.trim();
+    if let Some(inner) = braced(text) {
+        if depth == RECURSION_LIMIT {
+            return None;
+        }
+        return match parse_braced(inner.trim(), depth + 1) {
+            Braced::Value(value) => Some(value),
+            Braced::NotAggregate => Some(ConstantValue::Other(text.to_owned())),
+            Braced::TooDeep => None,
+        };
+    }
+    Some(match text {
+        "true" => ConstantValue::Bool(true),
+        "false" => ConstantValue::Bool(false),
+        "..." => ConstantValue::Elided,
+        _ => {
+            if let Some(integer) = LiteralValue::parse(text) {
+                ConstantValue::Integer(integer)
+            } else if let Some(float) = FloatValue::parse(text) {
+                ConstantValue::Float(float)
+            } else {
+                ConstantValue::Other(text.to_owned())
+            }
+        }
+    })
+}
+
+enum Braced {
+    Value(ConstantValue),
+    // Not in the form of an aggregate or union, and kept as text.
+    NotAggregate,
+    TooDeep,
+}
+
+fn parse_braced(inner: &str, depth: usize) -> Braced {
+    if inner.is_empty() {
+        return Braced::Value(ConstantValue::Aggregate(Vec::new()));
+    }
+    if let Some(designated) = inner.strip_prefix('.') {
+        let eq = match designated.find(" = ") {
+            Some(eq) => eq,
+            None => return Braced::NotAggregate,
+        };
+        let field = &designated[..eq];
+        if field.is_empty() || !field.chars().all(|ch| ch.is_alphanumeric() || ch == '_') {
+            return Braced::NotAggregate;
+        }
+        return match parse_at_depth(&designated[eq + 3..], depth) {
+            Some(value) => Braced::Value(ConstantValue::Union(field.to_owned(), Box::new(value))),
+            None => Braced::TooDeep,
+        };
+    }
+    let elements = split_top_level(inner, ',');
+    if elements.iter().any(|element| element.trim().is_empty()) {
+        return Braced::NotAggregate;
+    }
+    let mut values = Vec::with_capacity(elements.len());
+    for element in elements {
+        match parse_at_depth(element, depth) {
+            Some(value) => values.push(value),
+            None => return Braced::TooDeep,
+        }
+    }
+    Braced::Value(ConstantValue::Aggregate(values))
+}
+
+// Splits on the separator where it is outside of any brackets and quotes.
+// A closing bracket with nothing open also counts as a separator, which
+// makes an unbalanced '}' detectable to the caller.
+fn split_top_level(text: &str, separator: char) -> Vec<&str> {
+    let mut pieces = Vec::new();
+    let mut depth = 0usize;
+    let mut start = 0;
+    let mut chars = text.char_indices();
+    while let Some((i, ch)) = chars.next() {
+        match ch {
+            '\'' | '"' => {
+                let mut escaped = false;
+                for (_, next) in &mut chars {
+                    if escaped {
+                        escaped = false;
+                    } else if next == '\\' {
+                        escaped = true;
+                    } else if next == ch {
+                        break;
+                    }
+                }
+            }
+            '{' | '(' | '[' | '<' => depth += 1,
+            '}' | ')' | ']' | '>' if depth > 0 => depth -= 1,
+            _ if depth == 0 && ch == separator => {
+                pieces.push(&text[start..i]);
+                start = i + ch.len_utf8();
+            }
+            _ => {}
+        }
+    }
+    pieces.push(&text[start..]);
+    pieces
+}
+
+impl Display for ConstantValue {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConstantValue::Integer(integer) => Display::fmt(integer, formatter),
+            ConstantValue::Bool(value) => Display::fmt(value, formatter),
+            ConstantValue::Float(float) => Display::fmt(float, formatter),
+            ConstantValue::Aggregate(elements) => {
+                formatter.write_str("{")?;
+                for (i, element) in elements.iter().enumerate() {
+                    if i > 0 {
+                        formatter.write_str(", ")?;
+                    }
+                    Display::fmt(element, formatter)?;
+                }
+                formatter.write_str("}")
+            }
+            ConstantValue::Union(field, value) => write!(formatter, "{{.{} = {}}}", field, value),
+            ConstantValue::Elided => formatter.write_str("..."),
+            ConstantValue::Other(text) => formatter.write_str(text),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for ConstantValue {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct ConstantValueVisitor;
+
+        impl<'de> Visitor<'de> for ConstantValueVisitor {
+            type Value = ConstantValue;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("constant value")
+            }
+
+            fn visit_str<E>(self, text: &str) -> Result<Self::Value, E>
+            where
+                E: Error,
+            {
+                ConstantValue::parse(text)
+                    .ok_or_else(|| E::custom("constant value nested more than 128 levels deep"))
+            }
+        }
+
+        deserializer.deserialize_str(ConstantValueVisitor)
+    }
+}
+
+impl Serialize for ConstantValue {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
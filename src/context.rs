@@ -0,0 +1,39 @@
+use crate::loc::LocState;
+use crate::path_map::PathMap;
+use crate::{ancestry, intern, normalize};
+use std::cell::RefCell;
+
+thread_local! {
+    static CONTEXT: RefCell<Context> = RefCell::new(Context::default());
+}
+
+// Everything that deserializing or serializing a syntax tree keeps track of
+// on the current thread, in one place.
+//
+// Source locations and the other types which need this state are
+// deserialized by the Deserialize impl of the caller's kind type, and serde
+// has no way to hand them anything besides the deserializer, so the state
+// cannot be passed down to them as an argument. Instead it sits here for the
+// duration of a parse. Whatever needs to outlive one parse, such as the
+// location state of a Session, is owned by that value in between parses and
+// moved in and back out around each one.
+#[derive(Default)]
+pub(crate) struct Context {
+    pub(crate) locs: LocState,
+    pub(crate) intern: intern::State,
+    pub(crate) ancestry: ancestry::State,
+    pub(crate) strict: bool,
+    pub(crate) path_map: Option<PathMap>,
+    pub(crate) str_arena: Option<String>,
+    // Number of serializations in progress, which share the location state
+    // for abbreviating the locations they write.
+    pub(crate) dedup: usize,
+    pub(crate) normalize: Option<normalize::State>,
+    pub(crate) include_chains: bool,
+}
+
+// Access the current thread's context. Must not be called reentrantly, so
+// `f` must not call out to code that could deserialize or serialize anything.
+pub(crate) fn with<R>(f: impl FnOnce(&mut Context) -> R) -> R {
+    CONTEXT.with(|context| f(&mut context.borrow_mut()))
+}
@@ -0,0 +1,152 @@
+use crate::Node;
+use std::fmt::{self, Debug};
+use std::iter::FusedIterator;
+
+/// A position within a syntax tree which can move up to its parent and
+/// sideways to its siblings.
+///
+/// `Node` does not store parent pointers, so instead a cursor remembers the
+/// path of ancestors by which it was reached from the node it was created
+/// from. Navigation never leaves the subtree of that original node.
+///
+/// ```
+/// # use clang_ast::Node;
+/// #
+/// # fn example<T>(root: &Node<T>) {
+/// let cursor = root.cursor();
+/// if let Some(first) = cursor.children().next() {
+///     assert_eq!(first.parent().unwrap().node().id, root.id);
+///     let _ = first.next_sibling();
+/// }
+/// # }
+/// ```
+pub struct Cursor<'a, T> {
+    node: &'a Node<T>,
+    // For each ancestor, the index within its `inner` of the next node along
+    // the path down to `node`.
+    ancestors: Vec<(&'a Node<T>, usize)>,
+}
+
+impl<'a, T> Cursor<'a, T> {
+    pub(crate) fn new(node: &'a Node<T>) -> Self {
+        Cursor {
+            node,
+            ancestors: Vec::new(),
+        }
+    }
+
+    /// The node at the current position.
+    pub fn node(&self) -> &'a Node<T> {
+        self.node
+    }
+
+    /// Number of ancestors between this position and the node the cursor was
+    /// created from. The original node has depth 0.
+    pub fn depth(&self) -> usize {
+        self.ancestors.len()
+    }
+
+    /// Iterate over the nodes enclosing this position, starting with the
+    /// immediate parent.
+    pub fn ancestors(&self) -> impl Iterator<Item = &'a Node<T>> + '_ {
+        self.ancestors
+            .iter()
+            .rev()
+            .map(|&(ancestor, _index)| ancestor)
+    }
+
+    pub fn parent(&self) -> Option<Self> {
+        let mut ancestors = self.ancestors.clone();
+        let (node, _index) = ancestors.pop()?;
+        Some(Cursor { node, ancestors })
+    }
+
+    pub fn next_sibling(&self) -> Option<Self> {
+        let &(parent, index) = self.ancestors.last()?;
+        self.sibling(parent, index.checked_add(1)?)
+    }
+
+    pub fn prev_sibling(&self) -> Option<Self> {
+        let &(parent, index) = self.ancestors.last()?;
+        self.sibling(parent, index.checked_sub(1)?)
+    }
+
+    pub fn first_child(&self) -> Option<Self> {
+        self.child(0)
+    }
+
+    pub fn last_child(&self) -> Option<Self> {
+        self.child(self.node.inner.len().checked_sub(1)?)
+    }
+
+    pub fn children(&self) -> Children<'a, T> {
+        Children {
+            parent: self.clone(),
+            range: 0..self.node.inner.len(),
+        }
+    }
+
+    fn child(&self, index: usize) -> Option<Self> {
+        let node = self.node.inner.get(index)?;
+        let mut ancestors = Vec::with_capacity(self.ancestors.len() + 1);
+        ancestors.extend_from_slice(&self.ancestors);
+        ancestors.push((self.node, index));
+        Some(Cursor { node, ancestors })
+    }
+
+    fn sibling(&self, parent: &'a Node<T>, index: usize) -> Option<Self> {
+        let node = parent.inner.get(index)?;
+        let mut ancestors = self.ancestors.clone();
+        ancestors.last_mut().unwrap().1 = index;
+        Some(Cursor { node, ancestors })
+    }
+}
+
+impl<'a, T> Clone for Cursor<'a, T> {
+    fn clone(&self) -> Self {
+        Cursor {
+            node: self.node,
+            ancestors: self.ancestors.clone(),
+        }
+    }
+}
+
+impl<'a, T> Debug for Cursor<'a, T> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter
+            .debug_struct("Cursor")
+            .field("id", &self.node.id)
+            .field("depth", &self.depth())
+            .finish()
+    }
+}
+
+/// Iterator over the children of a [`Cursor`] position, as cursors.
+pub struct Children<'a, T> {
+    parent: Cursor<'a, T>,
+    range: std::ops::Range<usize>,
+}
+
+impl<'a, T> Iterator for Children<'a, T> {
+    type Item = Cursor<'a, T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let index = self.range.next()?;
+        self.parent.child(index)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.range.size_hint()
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for Children<'a, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let index = self.range.next_back()?;
+        self.parent.child(index)
+    }
+}
+
+impl<'a, T> ExactSizeIterator for Children<'a, T> {}
+
+impl<'a, T> FusedIterator for Children<'a, T> {}
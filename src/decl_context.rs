@@ -0,0 +1,177 @@
+use crate::{Control, Id, Node, Visit};
+use std::collections::HashMap;
+
+/// What [`DeclContexts`] needs to know about each node.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct DeclInfo {
+    /// Whether the declarations nested within this node belong to it, as
+    /// for a namespace, record, function or block.
+    pub is_context: bool,
+    /// The node's `"parentDeclContextId"`, which Clang writes when the
+    /// semantic context of a declaration differs from the one it is nested
+    /// in, as for an out-of-line definition of a member.
+    pub parent_decl_context_id: Option<Id>,
+}
+
+/// Semantic declaration contexts of the nodes of a syntax tree.
+///
+/// Every node belongs to the nearest enclosing node which is a context,
+/// unless it says otherwise by way of `"parentDeclContextId"`. This puts
+/// the definition `void Outer::method() {}`, and everything within it,
+/// inside of `Outer` even though the definition is nested directly in the
+/// translation unit.
+///
+/// ```
+/// use clang_ast::{DeclContexts, DeclInfo, Id, Node};
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// pub enum Clang {
+///     TranslationUnitDecl,
+///     CXXRecordDecl,
+///     CXXMethodDecl {
+///         #[serde(rename = "parentDeclContextId")]
+///         parent_decl_context_id: Option<Id>,
+///     },
+///     Other,
+/// }
+///
+/// fn decl_info(kind: &Clang) -> DeclInfo {
+///     match kind {
+///         Clang::TranslationUnitDecl | Clang::CXXRecordDecl => DeclInfo {
+///             is_context: true,
+///             parent_decl_context_id: None,
+///         },
+///         Clang::CXXMethodDecl { parent_decl_context_id } => DeclInfo {
+///             is_context: true,
+///             parent_decl_context_id: *parent_decl_context_id,
+///         },
+///         Clang::Other => DeclInfo::default(),
+///     }
+/// }
+///
+/// let json = r#"
+///     {
+///       "id": "0x1",
+///       "kind": "TranslationUnitDecl",
+///       "inner": [
+///         {
+///           "id": "0x2",
+///           "kind": "CXXRecordDecl",
+///           "inner": [
+///             { "id": "0x3", "kind": "CXXMethodDecl" }
+///           ]
+///         },
+///         {
+///           "id": "0x4",
+///           "kind": "CXXMethodDecl",
+///           "parentDeclContextId": "0x2",
+///           "inner": [
+///             { "id": "0x5", "kind": "CompoundStmt" }
+///           ]
+///         }
+///       ]
+///     }
+/// "#;
+/// let node: Node<Clang> = serde_json::from_str(json).unwrap();
+/// let contexts = DeclContexts::new(&node, decl_info);
+/// let id = |id| Id::from_u64(id).unwrap();
+/// let body = id(0x5);
+/// let enclosing: Vec<Id> = contexts.enclosing(body).collect();
+/// assert_eq!(enclosing, [id(0x4), id(0x2), id(0x1)]);
+/// assert_eq!(contexts.children(id(0x2)), [id(0x3), id(0x4)]);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct DeclContexts {
+    parents: HashMap<Id, Id>,
+    children: HashMap<Id, Vec<Id>>,
+}
+
+impl DeclContexts {
+    /// Determine the context of every node in the tree, using the
+    /// information retrieved from each node's kind by the callback.
+    pub fn new<T, F>(root: &Node<T>, decl_info: F) -> Self
+    where
+        F: Fn(&T) -> DeclInfo,
+    {
+        let mut builder = Builder {
+            decl_info,
+            contexts: DeclContexts::default(),
+            stack: Vec::new(),
+            pushed: Vec::new(),
+        };
+        root.visit(&mut builder);
+        builder.contexts
+    }
+
+    /// The context which the node belongs to, or `None` if no enclosing
+    /// node is a context.
+    pub fn parent(&self, id: Id) -> Option<Id> {
+        self.parents.get(&id).copied()
+    }
+
+    /// The contexts enclosing the node, innermost first.
+    pub fn enclosing(&self, id: Id) -> impl Iterator<Item = Id> + '_ {
+        // Bounded in case the parentDeclContextIds form a cycle.
+        let mut remaining = self.children.len();
+        let mut id = id;
+        std::iter::from_fn(move || {
+            remaining = remaining.checked_sub(1)?;
+            id = self.parent(id)?;
+            Some(id)
+        })
+    }
+
+    /// The contexts which belong directly to the given context, in the
+    /// order of a depth-first pre-order walk of the tree.
+    pub fn children(&self, id: Id) -> &[Id] {
+        self.children.get(&id).map_or(&[], Vec::as_slice)
+    }
+
+    /// Whether the node is a context in the tree.
+    pub fn is_context(&self, id: Id) -> bool {
+        self.children.contains_key(&id)
+    }
+}
+
+struct Builder<F> {
+    decl_info: F,
+    contexts: DeclContexts,
+    // Lexically enclosing contexts, innermost last.
+    stack: Vec<Id>,
+    // Whether each node on the current path is one of those contexts.
+    pushed: Vec<bool>,
+}
+
+impl<'ast, T, F> Visit<'ast, T> for Builder<F>
+where
+    F: Fn(&T) -> DeclInfo,
+{
+    fn enter_node(&mut self, node: &'ast Node<T>) -> Control {
+        let info = (self.decl_info)(&node.kind);
+        let lexical = self.stack.last().copied();
+        let parent = info.parent_decl_context_id.or(lexical);
+        if let Some(parent) = parent {
+            self.contexts.parents.insert(node.id, parent);
+        }
+        if info.is_context {
+            if let Some(parent) = parent {
+                self.contexts
+                    .children
+                    .entry(parent)
+                    .or_default()
+                    .push(node.id);
+            }
+            self.contexts.children.entry(node.id).or_default();
+            self.stack.push(node.id);
+        }
+        self.pushed.push(info.is_context);
+        Control::Continue
+    }
+
+    fn exit_node(&mut self, _node: &'ast Node<T>) {
+        if let Some(true) = self.pushed.pop() {
+            self.stack.pop();
+        }
+    }
+}
@@ -0,0 +1,158 @@
+use serde::de::{Deserialize, Deserializer, Error, Visitor};
+use serde::ser::{Serialize, Serializer};
+use std::fmt::{self, Display};
+
+macro_rules! decl_spec {
+    (
+        $(#[$attr:meta])*
+        pub enum $name:ident {
+            $(
+                $(#[$variant_attr:meta])*
+                $variant:ident = $spelling:literal,
+            )*
+        }
+    ) => {
+        $(#[$attr])*
+        #[derive(Clone, Debug, PartialEq, Eq, Hash)]
+        #[non_exhaustive]
+        pub enum $name {
+            $(
+                $(#[$variant_attr])*
+                $variant,
+            )*
+            /// A value not known to this version of this crate, such as one
+            /// introduced by a newer Clang release.
+            Other(Box<str>),
+        }
+
+        impl $name {
+            /// The value as it appears in the JSON, which is also its
+            /// `Display` representation.
+            pub fn as_str(&self) -> &str {
+                match self {
+                    $(
+                        $name::$variant => $spelling,
+                    )*
+                    $name::Other(other) => other,
+                }
+            }
+
+            fn from_str(value: &str) -> Self {
+                match value {
+                    $(
+                        $spelling => $name::$variant,
+                    )*
+                    other => $name::Other(Box::from(other)),
+                }
+            }
+        }
+
+        impl Display for $name {
+            fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str(self.as_str())
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                struct DeclSpecVisitor;
+
+                impl<'de> Visitor<'de> for DeclSpecVisitor {
+                    type Value = $name;
+
+                    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                        formatter.write_str(stringify!($name))
+                    }
+
+                    fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+                    where
+                        E: Error,
+                    {
+                        Ok($name::from_str(value))
+                    }
+                }
+
+                deserializer.deserialize_str(DeclSpecVisitor)
+            }
+        }
+
+        impl Serialize for $name {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: Serializer,
+            {
+                serializer.serialize_str(self.as_str())
+            }
+        }
+    };
+}
+
+decl_spec! {
+    /// The `"storageClass"` of a `VarDecl` or `FunctionDecl`.
+    ///
+    /// Clang omits the field for a declaration without a storage class
+    /// specifier, so it is typically deserialized as an `Option`.
+    ///
+    /// ```
+    /// use clang_ast::StorageClass;
+    /// use serde::Deserialize;
+    ///
+    /// #[derive(Deserialize)]
+    /// pub enum Clang {
+    ///     VarDecl {
+    ///         #[serde(rename = "storageClass")]
+    ///         storage_class: Option<StorageClass>,
+    ///     },
+    ///     Other,
+    /// }
+    /// #
+    /// # let json = r#"{"id":"0x1","kind":"VarDecl","storageClass":"static"}"#;
+    /// # let node: clang_ast::Node<Clang> = serde_json::from_str(json).unwrap();
+    /// # match node.kind {
+    /// #     Clang::VarDecl { storage_class } => {
+    /// #         assert_eq!(storage_class, Some(StorageClass::Static));
+    /// #     }
+    /// #     Clang::Other => panic!(),
+    /// # }
+    /// ```
+    pub enum StorageClass {
+        Extern = "extern",
+        Static = "static",
+        /// `__private_extern__`
+        PrivateExtern = "__private_extern__",
+        /// `auto`, as a storage class in C.
+        Auto = "auto",
+        Register = "register",
+    }
+}
+
+decl_spec! {
+    /// The `"tagUsed"` of a `CXXRecordDecl`, `RecordDecl` or `EnumDecl`,
+    /// meaning the keyword it was declared with.
+    pub enum TagKind {
+        Struct = "struct",
+        /// `__interface`
+        Interface = "__interface",
+        Union = "union",
+        Class = "class",
+        Enum = "enum",
+    }
+}
+
+decl_spec! {
+    /// The `"init"` of a `VarDecl` with an initializer, meaning the syntax
+    /// of its initialization.
+    pub enum InitStyle {
+        /// `int x = 0;`
+        C = "c",
+        /// `int x(0);`
+        Call = "call",
+        /// `int x{0};`
+        List = "list",
+        /// `S s(0, 1);` for an aggregate `S`, since C++20.
+        ParenList = "paren-list",
+    }
+}
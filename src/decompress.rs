@@ -0,0 +1,231 @@
+use crate::Node;
+use serde::de::DeserializeOwned;
+use std::fmt::{self, Debug, Display};
+use std::io::{self, BufRead, BufReader, Cursor, Read};
+
+const CAPACITY: usize = 64 * 1024;
+
+type Decoder = Box<dyn Fn(Box<dyn BufRead>) -> io::Result<Box<dyn Read>>>;
+
+/// Compression format of an AST dump.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Compression {
+    None,
+    /// `.json.gz`
+    Gzip,
+    /// `.json.zst`
+    Zstd,
+}
+
+impl Compression {
+    /// Recognize the format from the first 4 bytes of a file, or fewer if
+    /// the file is shorter than that.
+    pub fn detect(prefix: &[u8]) -> Self {
+        if prefix.starts_with(&[0x1f, 0x8b]) {
+            Compression::Gzip
+        } else if prefix.starts_with(&[0x28, 0xb5, 0x2f, 0xfd]) {
+            Compression::Zstd
+        } else {
+            Compression::None
+        }
+    }
+}
+
+impl Display for Compression {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str(match self {
+            Compression::None => "uncompressed",
+            Compression::Gzip => "gzip",
+            Compression::Zstd => "zstd",
+        })
+    }
+}
+
+/// Decompressors for reading compressed AST dumps.
+///
+/// AST dumps compress about tenfold, so they are often stored compressed.
+/// This crate does not implement any decompression itself. Register a
+/// decoder from the compression library of your choice for each format, and
+/// `Decoders` takes care of recognizing which one an input uses and of
+/// buffering on both sides of the decoder.
+///
+/// ```no_run
+/// use clang_ast::Decoders;
+/// use serde::Deserialize;
+/// use std::fs::File;
+///
+/// #[derive(Deserialize)]
+/// pub enum Clang {
+///     FunctionDecl { name: String },
+///     Other,
+/// }
+///
+/// # mod flate2 {
+/// #     pub mod bufread {
+/// #         pub struct MultiGzDecoder<R>(R);
+/// #         impl<R> MultiGzDecoder<R> {
+/// #             pub fn new(r: R) -> Self {
+/// #                 MultiGzDecoder(r)
+/// #             }
+/// #         }
+/// #         impl<R> std::io::Read for MultiGzDecoder<R> {
+/// #             fn read(&mut self, _: &mut [u8]) -> std::io::Result<usize> {
+/// #                 unimplemented!()
+/// #             }
+/// #         }
+/// #     }
+/// # }
+/// #
+/// # mod zstd {
+/// #     pub struct Decoder<R>(R);
+/// #     impl<R> Decoder<R> {
+/// #         pub fn with_buffer(r: R) -> std::io::Result<Self> {
+/// #             Ok(Decoder(r))
+/// #         }
+/// #     }
+/// #     impl<R> std::io::Read for Decoder<R> {
+/// #         fn read(&mut self, _: &mut [u8]) -> std::io::Result<usize> {
+/// #             unimplemented!()
+/// #         }
+/// #     }
+/// # }
+/// #
+/// fn main() -> serde_json::Result<()> {
+///     let mut decoders = Decoders::new();
+///     decoders.gzip(|input| Ok(flate2::bufread::MultiGzDecoder::new(input)));
+///     decoders.zstd(|input| zstd::Decoder::with_buffer(input));
+///
+///     let file = File::open("ast.json.zst").map_err(serde_json::Error::io)?;
+///     let node: clang_ast::Node<Clang> = decoders.from_reader(file)?;
+///     println!("{} top-level decls", node.inner.len());
+///     Ok(())
+/// }
+/// ```
+pub struct Decoders {
+    gzip: Option<Decoder>,
+    zstd: Option<Decoder>,
+}
+
+impl Decoders {
+    /// Decoders for no compressed format. Only uncompressed input can be
+    /// read.
+    pub fn new() -> Self {
+        Decoders {
+            gzip: None,
+            zstd: None,
+        }
+    }
+
+    /// Decode gzip input with the given decoder, which receives the
+    /// compressed input already buffered.
+    pub fn gzip<F, D>(&mut self, decoder: F) -> &mut Self
+    where
+        F: Fn(Box<dyn BufRead>) -> io::Result<D> + 'static,
+        D: Read + 'static,
+    {
+        self.gzip = Some(boxed(decoder));
+        self
+    }
+
+    /// Decode zstd input with the given decoder, which receives the
+    /// compressed input already buffered.
+    pub fn zstd<F, D>(&mut self, decoder: F) -> &mut Self
+    where
+        F: Fn(Box<dyn BufRead>) -> io::Result<D> + 'static,
+        D: Read + 'static,
+    {
+        self.zstd = Some(boxed(decoder));
+        self
+    }
+
+    /// Wrap `reader` in the decoder for its compression format, producing
+    /// buffered JSON.
+    ///
+    /// The result can be passed to [`stream::from_reader`] or to any other
+    /// deserializer taking `io::Read`. Compressed input for which no decoder
+    /// is registered is an error of kind `InvalidData`.
+    ///
+    /// [`stream::from_reader`]: crate::stream::from_reader
+    pub fn decode<R>(&self, mut reader: R) -> io::Result<Box<dyn BufRead>>
+    where
+        R: Read + 'static,
+    {
+        // A single read may return fewer bytes than are needed to recognize
+        // the format, for example from a pipe.
+        let mut magic = [0; 4];
+        let mut len = 0;
+        while len < magic.len() {
+            match reader.read(&mut magic[len..]) {
+                Ok(0) => break,
+                Ok(n) => len += n,
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+                Err(err) => return Err(err),
+            }
+        }
+
+        let compression = Compression::detect(&magic[..len]);
+        let input = Cursor::new(magic).take(len as u64).chain(reader);
+        let input = BufReader::with_capacity(CAPACITY, input);
+        let decoder = match compression {
+            Compression::None => return Ok(Box::new(input)),
+            Compression::Gzip => &self.gzip,
+            Compression::Zstd => &self.zstd,
+        };
+
+        match decoder {
+            Some(decoder) => {
+                let output = decoder(Box::new(input))?;
+                Ok(Box::new(BufReader::with_capacity(CAPACITY, output)))
+            }
+            None => Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("{} compressed input, but no {0} decoder", compression),
+            )),
+        }
+    }
+
+    /// Deserialize a syntax tree from a possibly compressed AST dump.
+    pub fn from_reader<T, R>(&self, reader: R) -> serde_json::Result<Node<T>>
+    where
+        T: DeserializeOwned,
+        R: Read + 'static,
+    {
+        let input = self.decode(reader).map_err(serde_json::Error::io)?;
+        serde_json::from_reader(input)
+    }
+}
+
+fn boxed<F, D>(decoder: F) -> Decoder
+where
+    F: Fn(Box<dyn BufRead>) -> io::Result<D> + 'static,
+    D: Read + 'static,
+{
+    Box::new(move |input| Ok(Box::new(decoder(input)?)))
+}
+
+impl Default for Decoders {
+    fn default() -> Self {
+        Decoders::new()
+    }
+}
+
+impl Debug for Decoders {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter
+            .debug_struct("Decoders")
+            .field("gzip", &self.gzip.is_some())
+            .field("zstd", &self.zstd.is_some())
+            .finish()
+    }
+}
+
+/// Deserialize a syntax tree from an uncompressed AST dump, with buffering.
+///
+/// For compressed dumps, use [`Decoders::from_reader`].
+pub fn from_reader<T, R>(reader: R) -> serde_json::Result<Node<T>>
+where
+    T: DeserializeOwned,
+    R: Read + 'static,
+{
+    Decoders::new().from_reader(reader)
+}
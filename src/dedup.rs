@@ -1,22 +1,21 @@
-use std::cell::Cell;
-
-thread_local! {
-    static REFCOUNT: Cell<usize> = Cell::new(0);
-}
+use crate::context;
 
 pub(crate) struct Guard {
     _private: (),
 }
 
 pub(crate) fn activate() -> Guard {
-    REFCOUNT.with(|refcount| refcount.set(refcount.get() + 1));
+    context::with(|context| context.dedup += 1);
     Guard { _private: () }
 }
 
 impl Drop for Guard {
     fn drop(&mut self) {
-        let prev = REFCOUNT.with(|refcount| refcount.replace(refcount.get() - 1));
-        if prev == 1 {
+        let refcount = context::with(|context| {
+            context.dedup -= 1;
+            context.dedup
+        });
+        if refcount == 0 {
             crate::loc::thread_local_reset();
         }
     }
@@ -0,0 +1,233 @@
+use serde::de::{Deserialize, Deserializer, Error, IgnoredAny, MapAccess, Visitor};
+use serde::ser::{Serialize, SerializeMap, Serializer};
+use std::fmt;
+
+// Clang writes each flag only when it is true, so every field is optional
+// and a missing one reads as false.
+macro_rules! flags {
+    (
+        $(#[$attr:meta])*
+        pub struct $name:ident {
+            $(
+                $(#[$flag_attr:meta])*
+                $flag:ident: $flag_json:literal,
+            )*
+            $(
+                ;
+                $(
+                    $(#[$member_attr:meta])*
+                    $member:ident: $member_json:literal,
+                )*
+            )?
+        }
+    ) => {
+        $(#[$attr])*
+        #[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+        pub struct $name {
+            $(
+                $(#[$flag_attr])*
+                pub $flag: bool,
+            )*
+            $($(
+                $(#[$member_attr])*
+                pub $member: Option<SpecialMember>,
+            )*)?
+        }
+
+        const _: () = {
+            #[allow(non_camel_case_types)]
+            enum Field {
+                $($flag,)*
+                $($($member,)*)?
+                __other,
+            }
+
+            impl<'de> Deserialize<'de> for Field {
+                fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+                where
+                    D: Deserializer<'de>,
+                {
+                    struct FieldVisitor;
+
+                    impl<'de> Visitor<'de> for FieldVisitor {
+                        type Value = Field;
+
+                        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                            formatter.write_str("field identifier")
+                        }
+
+                        fn visit_str<E>(self, field: &str) -> Result<Self::Value, E> {
+                            Ok(match field {
+                                $($flag_json => Field::$flag,)*
+                                $($($member_json => Field::$member,)*)?
+                                _ => Field::__other,
+                            })
+                        }
+                    }
+
+                    deserializer.deserialize_identifier(FieldVisitor)
+                }
+            }
+
+            impl<'de> Deserialize<'de> for $name {
+                fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+                where
+                    D: Deserializer<'de>,
+                {
+                    struct FlagsVisitor;
+
+                    impl<'de> Visitor<'de> for FlagsVisitor {
+                        type Value = $name;
+
+                        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                            formatter.write_str(stringify!($name))
+                        }
+
+                        fn visit_map<M>(self, mut map: M) -> Result<Self::Value, M::Error>
+                        where
+                            M: MapAccess<'de>,
+                        {
+                            $(let mut $flag: Option<bool> = None;)*
+                            $($(let mut $member: Option<SpecialMember> = None;)*)?
+                            while let Some(field) = map.next_key()? {
+                                match field {
+                                    $(
+                                        Field::$flag => {
+                                            if $flag.is_some() {
+                                                return Err(Error::duplicate_field($flag_json));
+                                            }
+                                            $flag = Some(map.next_value()?);
+                                        }
+                                    )*
+                                    $($(
+                                        Field::$member => {
+                                            if $member.is_some() {
+                                                return Err(Error::duplicate_field($member_json));
+                                            }
+                                            $member = Some(map.next_value()?);
+                                        }
+                                    )*)?
+                                    Field::__other => {
+                                        let IgnoredAny = map.next_value()?;
+                                    }
+                                }
+                            }
+                            Ok($name {
+                                $($flag: $flag.unwrap_or(false),)*
+                                $($($member,)*)?
+                            })
+                        }
+                    }
+
+                    deserializer.deserialize_map(FlagsVisitor)
+                }
+            }
+
+            impl Serialize for $name {
+                fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+                where
+                    S: Serializer,
+                {
+                    let mut map = serializer.serialize_map(None)?;
+                    $(
+                        if self.$flag {
+                            map.serialize_entry($flag_json, &true)?;
+                        }
+                    )*
+                    $($(
+                        if let Some($member) = &self.$member {
+                            map.serialize_entry($member_json, $member)?;
+                        }
+                    )*)?
+                    map.end()
+                }
+            }
+        };
+    };
+}
+
+flags! {
+    /// The `"definitionData"` of a `CXXRecordDecl` which is a definition,
+    /// describing properties of the class which the language rules derive
+    /// from its members and bases.
+    ///
+    /// ```
+    /// use clang_ast::DefinitionData;
+    /// use serde::Deserialize;
+    ///
+    /// #[derive(Deserialize)]
+    /// pub enum Clang {
+    ///     CXXRecordDecl {
+    ///         name: Option<String>,
+    ///         #[serde(rename = "definitionData")]
+    ///         definition_data: Option<DefinitionData>,
+    ///     },
+    ///     Other,
+    /// }
+    /// #
+    /// # let json = r#"{"id":"0x1","kind":"CXXRecordDecl","name":"S","definitionData":{"isPOD":true,"copyCtor":{"simple":true,"trivial":true}}}"#;
+    /// # let node: clang_ast::Node<Clang> = serde_json::from_str(json).unwrap();
+    /// # match node.kind {
+    /// #     Clang::CXXRecordDecl { definition_data: Some(data), .. } => {
+    /// #         assert!(data.is_pod);
+    /// #         assert!(data.copy_ctor.unwrap().trivial);
+    /// #     }
+    /// #     _ => panic!(),
+    /// # }
+    /// ```
+    ///
+    /// Clang writes only the flags which are true, so a flag missing from
+    /// the dump, including one from a Clang version newer or older than
+    /// this crate knows about, is false here.
+    pub struct DefinitionData {
+        is_generic_lambda: "isGenericLambda",
+        is_lambda: "isLambda",
+        is_empty: "isEmpty",
+        is_aggregate: "isAggregate",
+        is_standard_layout: "isStandardLayout",
+        is_trivially_copyable: "isTriviallyCopyable",
+        is_pod: "isPOD",
+        is_trivial: "isTrivial",
+        is_polymorphic: "isPolymorphic",
+        is_abstract: "isAbstract",
+        is_literal: "isLiteral",
+        can_pass_in_registers: "canPassInRegisters",
+        has_user_declared_constructor: "hasUserDeclaredConstructor",
+        has_constexpr_non_copy_move_constructor: "hasConstexprNonCopyMoveConstructor",
+        has_mutable_fields: "hasMutableFields",
+        has_variant_members: "hasVariantMembers",
+        can_const_default_init: "canConstDefaultInit",
+        ;
+        default_ctor: "defaultCtor",
+        copy_ctor: "copyCtor",
+        move_ctor: "moveCtor",
+        copy_assign: "copyAssign",
+        move_assign: "moveAssign",
+        dtor: "dtor",
+    }
+}
+
+flags! {
+    /// Properties of one of the special member functions of a class, within
+    /// its [`DefinitionData`].
+    ///
+    /// Not every flag applies to every special member; Clang writes
+    /// `"irrelevant"` only for destructors, for example.
+    pub struct SpecialMember {
+        exists: "exists",
+        simple: "simple",
+        trivial: "trivial",
+        non_trivial: "nonTrivial",
+        user_provided: "userProvided",
+        user_declared: "userDeclared",
+        is_constexpr: "isConstexpr",
+        has_const_param: "hasConstParam",
+        implicit_has_const_param: "implicitHasConstParam",
+        needs_implicit: "needsImplicit",
+        needs_overload_resolution: "needsOverloadResolution",
+        defaulted_is_constexpr: "defaultedIsConstexpr",
+        defaulted_is_deleted: "defaultedIsDeleted",
+        /// The destructor is trivial and there is no need to call it.
+        irrelevant: "irrelevant",
+    }
+}
@@ -0,0 +1,3017 @@
+use std::convert::TryFrom;
+use std::fmt::Display;
+
+/// Demangle an Itanium C++ ABI symbol name, such as the `"mangledName"` of a
+/// declaration in the dump of a C++ translation unit for Linux or macOS.
+///
+/// The result is in the same form as printed by GNU `c++filt`, so it can be
+/// compared with the demangled symbols of a linked binary, or shown to a user
+/// in place of the mangled name. This includes the names of entities whose
+/// signatures involve expressions, such as in `decltype` or in non-type
+/// template arguments.
+///
+/// ```
+/// let name = "_ZNKSt17integral_constantIbLb1EEcvbEv";
+/// assert_eq!(
+///     clang_ast::demangle(name).as_deref(),
+///     Some("std::integral_constant<bool, true>::operator bool() const"),
+/// );
+/// ```
+///
+/// Returns `None` if the name is not mangled, which is the case for the
+/// `"mangledName"` of declarations with C linkage, or if it is not valid
+/// according to the mangling grammar, or uses vendor extensions of it that
+/// `c++filt` does not know either. The MSVC mangling used by dumps targeting
+/// Windows is not supported.
+pub fn demangle(mangled: &str) -> Option<String> {
+    // Darwin symbols have an extra leading underscore.
+    let mangled = mangled
+        .strip_prefix('_')
+        .filter(|rest| rest.starts_with("_Z"))
+        .unwrap_or(mangled);
+    if !mangled.starts_with("_Z") {
+        return None;
+    }
+
+    let mut parser = Parser::new(mangled, Unresolved::New);
+    let mut root = parser.whole();
+    if root.is_none() && parser.unresolved == Unresolved::Ambiguous {
+        // See Parser::unresolved_name.
+        parser = Parser::new(mangled, Unresolved::Old);
+        root = parser.whole();
+    }
+    Printer::new(&parser.nodes).print(root?)
+}
+
+// Nesting of types, encodings or expressions beyond which the input is
+// rejected rather than risk overflowing the stack, and likewise for the
+// nesting of components while printing.
+const MAX_DEPTH: usize = 256;
+
+// Length of output beyond which the input is rejected. Each substitution can
+// double the length of the output, so a short input could otherwise demangle
+// to gigabytes.
+const MAX_LEN: usize = 1 << 20;
+
+// Number of components visited while printing beyond which the input is
+// rejected, for the same reason as MAX_LEN but for components that print
+// nothing, such as empty packs.
+const MAX_STEPS: usize = 1 << 22;
+
+// The symbol is parsed into a tree of components, which substitutions turn
+// into a DAG, and then printed. The form of the tree and of the printed
+// result follow the demangler in libiberty that c++filt is built on, so that
+// the output agrees with c++filt down to the spaces, including where the
+// printed form is not what a C++ programmer would write.
+type NodeId = usize;
+
+#[derive(Copy, Clone)]
+struct Node<'a> {
+    kind: Kind<'a>,
+    left: Option<NodeId>,
+    right: Option<NodeId>,
+}
+
+#[derive(Copy, Clone, PartialEq)]
+enum Kind<'a> {
+    // An identifier, or the digits of a literal or array dimension.
+    Name(&'a str),
+    // Scope on the left, name on the right.
+    QualName,
+    // Function on the left, entity local to it on the right.
+    LocalName,
+    // Name on the left, function type on the right.
+    TypedName,
+    // Template on the left, TemplateArgList on the right.
+    Template,
+    TemplateParam(usize),
+    // 0 for `this`, otherwise the 1-based index of a parameter.
+    FunctionParam(usize),
+    // Class name on the left.
+    Ctor,
+    Dtor,
+    // Text such as "vtable for " before the operand on the left.
+    Special(&'static str),
+    // Base on the left, derived on the right.
+    ConstructionVtable,
+    // Name on the left, Number on the right.
+    RefTemp,
+    SubStd(&'static str),
+    // Qualifiers of the type on the left.
+    Restrict,
+    Volatile,
+    Const,
+    // Qualifiers of the function type on the left.
+    RestrictThis,
+    VolatileThis,
+    ConstThis,
+    ReferenceThis,
+    RvalueReferenceThis,
+    TransactionSafe,
+    // Expression on the right, if any.
+    Noexcept,
+    // ArgList on the right.
+    ThrowSpec,
+    // Type on the left, qualifier on the right.
+    VendorTypeQual,
+    Pointer,
+    Reference,
+    RvalueReference,
+    Complex,
+    Imaginary,
+    Builtin(&'static Builtin),
+    // N of _FloatN, and whether it is _FloatNx.
+    FloatN(i32, bool),
+    VendorType,
+    // Return type on the left if any, ArgList of parameters on the right.
+    FunctionType,
+    // Dimension on the left if any, element type on the right.
+    ArrayType,
+    // Class on the left, member type on the right.
+    PtrMemType,
+    // Dimension on the left, element type on the right.
+    VectorType,
+    // Element on the left, rest of the list on the right.
+    ArgList,
+    TemplateArgList,
+    Operator(&'static Operator),
+    // Number of operands, and the name on the left.
+    ExtendedOperator(usize),
+    // Type on the left.
+    Conversion,
+    Cast,
+    // Operator on the left, operands on the right.
+    Nullary,
+    Unary,
+    Binary,
+    BinaryArgs,
+    Trinary,
+    TrinaryArg1,
+    TrinaryArg2,
+    // Type on the left, Name of the value on the right.
+    Literal,
+    LiteralNeg,
+    Number(i32),
+    Decltype,
+    PackExpansion,
+    // Number of the lambda, and the parameters on the left.
+    Lambda(usize),
+    UnnamedType(usize),
+    // Number of the argument, and the name on the left.
+    DefaultArg(usize),
+    // Name on the left, ABI tag on the right.
+    TaggedName,
+    // Encoding on the left, suffix on the right.
+    Clone,
+    // Type on the left if any, ArgList on the right.
+    InitializerList,
+    // ArgList of names on the left.
+    StructuredBinding,
+}
+
+#[derive(PartialEq)]
+struct Builtin {
+    name: &'static str,
+    // How a literal of this type is printed.
+    print: Print,
+}
+
+#[derive(Copy, Clone, PartialEq)]
+enum Print {
+    Default,
+    Int,
+    Unsigned,
+    Long,
+    UnsignedLong,
+    LongLong,
+    UnsignedLongLong,
+    Bool,
+    Float,
+    Void,
+}
+
+#[derive(PartialEq)]
+struct Operator {
+    code: &'static str,
+    name: &'static str,
+    args: usize,
+}
+
+// How qualified names in expressions, which are mangled starting with "sr",
+// are read. GCC before version 8 mangled them in a form that is ambiguous
+// with the current one, so a symbol that fails to demangle in the current
+// form is tried again in the old one.
+#[derive(Copy, Clone, PartialEq)]
+enum Unresolved {
+    New,
+    // Like New, and a name in the current form was encountered.
+    Ambiguous,
+    Old,
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    pos: usize,
+    nodes: Vec<Node<'a>>,
+    // Components that later substitutions S_, S0_, ... refer to.
+    subs: Vec<NodeId>,
+    // Most recent source name, which constructors and destructors name.
+    last_name: Option<NodeId>,
+    // Whether an expression is being parsed, in which "cv" is a cast rather
+    // than a conversion operator.
+    is_expression: bool,
+    // Whether the type of a conversion operator is being parsed, in which
+    // template arguments after a template parameter may instead belong to
+    // the operator.
+    is_conversion: bool,
+    unresolved: Unresolved,
+    depth: usize,
+}
+
+struct Checkpoint {
+    pos: usize,
+    nodes: usize,
+    subs: usize,
+    last_name: Option<NodeId>,
+}
+
+impl<'a> Parser<'a> {
+    fn new(input: &'a str, unresolved: Unresolved) -> Self {
+        Parser {
+            input,
+            pos: 0,
+            nodes: Vec::new(),
+            subs: Vec::new(),
+            last_name: None,
+            is_expression: false,
+            is_conversion: false,
+            unresolved,
+            depth: 0,
+        }
+    }
+
+    fn whole(&mut self) -> Option<NodeId> {
+        let root = self.mangled_name(true)?;
+        if self.pos < self.input.len() {
+            return None;
+        }
+        Some(root)
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.input.as_bytes().get(self.pos).copied()
+    }
+
+    fn peek_at(&self, ahead: usize) -> Option<u8> {
+        self.input.as_bytes().get(self.pos + ahead).copied()
+    }
+
+    fn peek2(&self) -> (Option<u8>, Option<u8>) {
+        (self.peek(), self.peek_at(1))
+    }
+
+    fn advance(&mut self, n: usize) {
+        self.pos += n;
+    }
+
+    fn eat(&mut self, byte: u8) -> bool {
+        let eaten = self.peek() == Some(byte);
+        if eaten {
+            self.pos += 1;
+        }
+        eaten
+    }
+
+    fn expect(&mut self, byte: u8) -> Option<()> {
+        if self.eat(byte) {
+            Some(())
+        } else {
+            None
+        }
+    }
+
+    fn next(&mut self) -> Option<u8> {
+        let byte = self.peek()?;
+        self.pos += 1;
+        Some(byte)
+    }
+
+    fn nested<T>(&mut self, f: impl FnOnce(&mut Self) -> Option<T>) -> Option<T> {
+        if self.depth == MAX_DEPTH {
+            return None;
+        }
+        self.depth += 1;
+        let result = f(self);
+        self.depth -= 1;
+        result
+    }
+
+    fn add(&mut self, kind: Kind<'a>, left: Option<NodeId>, right: Option<NodeId>) -> NodeId {
+        self.nodes.push(Node { kind, left, right });
+        self.nodes.len() - 1
+    }
+
+    fn leaf(&mut self, kind: Kind<'a>) -> NodeId {
+        self.add(kind, None, None)
+    }
+
+    fn kind(&self, id: NodeId) -> Kind<'a> {
+        self.nodes[id].kind
+    }
+
+    // Linked list of the items, through the right of each node.
+    fn list(&mut self, kind: Kind<'a>, items: &[NodeId]) -> NodeId {
+        let mut list = None;
+        for &item in items.iter().rev() {
+            list = Some(self.add(kind, Some(item), list));
+        }
+        match list {
+            Some(list) => list,
+            None => self.leaf(kind),
+        }
+    }
+
+    fn add_sub(&mut self, id: NodeId) {
+        self.subs.push(id);
+    }
+
+    fn checkpoint(&self) -> Checkpoint {
+        Checkpoint {
+            pos: self.pos,
+            nodes: self.nodes.len(),
+            subs: self.subs.len(),
+            last_name: self.last_name,
+        }
+    }
+
+    fn backtrack(&mut self, checkpoint: &Checkpoint) {
+        self.pos = checkpoint.pos;
+        self.nodes.truncate(checkpoint.nodes);
+        self.subs.truncate(checkpoint.subs);
+        self.last_name = checkpoint.last_name;
+    }
+
+    // Decimal number, negative if preceded by n. Returns -1 on overflow.
+    fn number(&mut self) -> i32 {
+        let negative = self.eat(b'n');
+        let mut number: i32 = 0;
+        while let Some(digit @ b'0'..=b'9') = self.peek() {
+            number = match number
+                .checked_mul(10)
+                .and_then(|n| n.checked_add(i32::from(digit - b'0')))
+            {
+                Some(number) => number,
+                None => return -1,
+            };
+            self.advance(1);
+        }
+        if negative {
+            -number
+        } else {
+            number
+        }
+    }
+
+    // [<number>] _, as the index of a template parameter or the number of a
+    // lambda, where _ alone is 0 and the number is one less than the value.
+    fn compact_number(&mut self) -> Option<usize> {
+        let number = match self.peek() {
+            Some(b'_') => 0,
+            Some(b'n') => return None,
+            _ => i64::from(self.number()) + 1,
+        };
+        let number = usize::try_from(number)
+            .ok()
+            .filter(|&number| number < i32::MAX as usize)?;
+        self.expect(b'_')?;
+        Some(number)
+    }
+
+    // <mangled-name> ::= _Z <encoding> [<clone-suffix>]*
+    fn mangled_name(&mut self, top_level: bool) -> Option<NodeId> {
+        if !self.eat(b'_') && top_level {
+            return None;
+        }
+        self.expect(b'Z')?;
+        let mut encoding = self.encoding(top_level)?;
+        if top_level {
+            while self.peek() == Some(b'.')
+                && matches!(self.peek_at(1), Some(b'a'..=b'z' | b'_' | b'0'..=b'9'))
+            {
+                encoding = self.clone_suffix(encoding);
+            }
+        }
+        Some(encoding)
+    }
+
+    // <clone-suffix> ::= . [a-z_]+ [. <number>]*
+    //                ::= . <number> [. <number>]*
+    //
+    // Suffix of a clone made by the optimizer, such as ".cold".
+    fn clone_suffix(&mut self, encoding: NodeId) -> NodeId {
+        let bytes = self.input.as_bytes();
+        let start = self.pos;
+        let mut end = start + 1;
+        while let Some(b'a'..=b'z' | b'_') = bytes.get(end) {
+            end += 1;
+        }
+        while bytes.get(end) == Some(&b'.') && matches!(bytes.get(end + 1), Some(b'0'..=b'9')) {
+            end += 2;
+            while let Some(b'0'..=b'9') = bytes.get(end) {
+                end += 1;
+            }
+        }
+        self.pos = end;
+        let suffix = self.leaf(Kind::Name(&self.input[start..end]));
+        self.add(Kind::Clone, Some(encoding), Some(suffix))
+    }
+
+    // <encoding> ::= <name> <bare-function-type>
+    //            ::= <name>
+    //            ::= <special-name>
+    fn encoding(&mut self, top_level: bool) -> Option<NodeId> {
+        self.nested(|p| {
+            if let Some(b'G' | b'T') = p.peek() {
+                return p.special_name();
+            }
+            let name = p.name(false)?;
+            if let None | Some(b'E') = p.peek() {
+                return Some(name);
+            }
+            let has_return_type = p.has_return_type(name);
+            let function = p.bare_function_type(has_return_type)?;
+            // Not to be mistaken for the return type of whatever this is
+            // local to.
+            if !top_level && p.kind(name) == Kind::LocalName {
+                p.nodes[function].left = None;
+            }
+            Some(p.add(Kind::TypedName, Some(name), Some(function)))
+        })
+    }
+
+    // Function templates other than constructors, destructors and conversion
+    // functions mangle their return type.
+    fn has_return_type(&self, name: NodeId) -> bool {
+        let node = self.nodes[name];
+        match node.kind {
+            Kind::LocalName => node
+                .right
+                .map_or(false, |right| self.has_return_type(right)),
+            Kind::Template => !node
+                .left
+                .map_or(false, |left| self.is_ctor_dtor_or_conversion(left)),
+            kind if is_fnqual(kind) => node.left.map_or(false, |left| self.has_return_type(left)),
+            _ => false,
+        }
+    }
+
+    fn is_ctor_dtor_or_conversion(&self, name: NodeId) -> bool {
+        let node = self.nodes[name];
+        match node.kind {
+            Kind::QualName | Kind::LocalName => node
+                .right
+                .map_or(false, |right| self.is_ctor_dtor_or_conversion(right)),
+            Kind::Ctor | Kind::Dtor | Kind::Conversion => true,
+            _ => false,
+        }
+    }
+
+    // <special-name> ::= TV <type>
+    //                ::= TT <type>
+    //                ::= TI <type>
+    //                ::= TS <type>
+    //                ::= TF <type>
+    //                ::= TJ <type>
+    //                ::= TH <name>
+    //                ::= TW <name>
+    //                ::= TA <template-arg>
+    //                ::= Th <call-offset> <encoding>
+    //                ::= Tv <call-offset> <encoding>
+    //                ::= Tc <call-offset> <call-offset> <encoding>
+    //                ::= TC <type> <number> _ <type>
+    //                ::= GV <name>
+    //                ::= GR <name> [<number>]
+    //                ::= GA <encoding>
+    //                ::= GTt <encoding>
+    //                ::= GTn <encoding>
+    fn special_name(&mut self) -> Option<NodeId> {
+        let (text, operand) = match (self.next()?, self.next()?) {
+            (b'T', b'V') => ("vtable for ", self.ty()?),
+            (b'T', b'T') => ("VTT for ", self.ty()?),
+            (b'T', b'I') => ("typeinfo for ", self.ty()?),
+            (b'T', b'S') => ("typeinfo name for ", self.ty()?),
+            (b'T', b'F') => ("typeinfo fn for ", self.ty()?),
+            (b'T', b'J') => ("java Class for ", self.ty()?),
+            (b'T', b'H') => ("TLS init function for ", self.name(false)?),
+            (b'T', b'W') => ("TLS wrapper function for ", self.name(false)?),
+            (b'T', b'A') => ("template parameter object for ", self.template_arg()?),
+            (b'T', b'h') => {
+                self.call_offset(b'h')?;
+                ("non-virtual thunk to ", self.encoding(false)?)
+            }
+            (b'T', b'v') => {
+                self.call_offset(b'v')?;
+                ("virtual thunk to ", self.encoding(false)?)
+            }
+            (b'T', b'c') => {
+                let kind = self.next()?;
+                self.call_offset(kind)?;
+                let kind = self.next()?;
+                self.call_offset(kind)?;
+                ("covariant return thunk to ", self.encoding(false)?)
+            }
+            (b'T', b'C') => {
+                let derived = self.ty()?;
+                if self.number() < 0 {
+                    return None;
+                }
+                self.expect(b'_')?;
+                let base = self.ty()?;
+                return Some(self.add(Kind::ConstructionVtable, Some(base), Some(derived)));
+            }
+            (b'G', b'V') => ("guard variable for ", self.name(false)?),
+            (b'G', b'R') => {
+                let name = self.name(false)?;
+                let number = self.number();
+                let number = self.leaf(Kind::Number(number));
+                return Some(self.add(Kind::RefTemp, Some(name), Some(number)));
+            }
+            (b'G', b'A') => ("hidden alias for ", self.encoding(false)?),
+            (b'G', b'T') => match self.next()? {
+                b'n' => ("non-transaction clone for ", self.encoding(false)?),
+                _ => ("transaction clone for ", self.encoding(false)?),
+            },
+            _ => return None,
+        };
+        Some(self.add(Kind::Special(text), Some(operand), None))
+    }
+
+    // <call-offset> ::= h <nv-offset> _
+    //               ::= v <v-offset> _
+    //
+    // The offsets are not printed.
+    fn call_offset(&mut self, kind: u8) -> Option<()> {
+        match kind {
+            b'h' => {
+                self.number();
+            }
+            b'v' => {
+                self.number();
+                self.expect(b'_')?;
+                self.number();
+            }
+            _ => return None,
+        }
+        self.expect(b'_')
+    }
+
+    // <name> ::= <nested-name>
+    //        ::= <unscoped-name>
+    //        ::= <unscoped-template-name> <template-args>
+    //        ::= <local-name>
+    //
+    // <unscoped-name> ::= <unqualified-name>
+    //                 ::= St <unqualified-name>
+    //
+    // <unscoped-template-name> ::= <unscoped-name>
+    //                          ::= <substitution>
+    fn name(&mut self, substable: bool) -> Option<NodeId> {
+        let mut subst = false;
+        let name = match self.peek()? {
+            b'N' => self.nested_name()?,
+            b'Z' => self.local_name()?,
+            b'U' => self.unqualified_name(None)?,
+            peek => {
+                let mut scope = None;
+                let mut sub = None;
+                if peek == b'S' {
+                    if self.peek_at(1) == Some(b't') {
+                        self.advance(2);
+                        scope = Some(self.leaf(Kind::Name("std")));
+                    }
+                    if self.peek() == Some(b'S') {
+                        if scope.is_some() {
+                            return None;
+                        }
+                        sub = Some(self.substitution()?);
+                        subst = true;
+                    }
+                }
+                let mut name = match sub {
+                    Some(sub) => sub,
+                    None => self.unqualified_name(scope)?,
+                };
+                if self.peek() == Some(b'I') {
+                    if !subst {
+                        self.add_sub(name);
+                    }
+                    let args = self.template_args()?;
+                    name = self.add(Kind::Template, Some(name), Some(args));
+                    subst = false;
+                }
+                name
+            }
+        };
+        if substable && !subst {
+            self.add_sub(name);
+        }
+        Some(name)
+    }
+
+    // <nested-name> ::= N [<CV-qualifiers>] [<ref-qualifier>] <prefix> <unqualified-name> E
+    //               ::= N [<CV-qualifiers>] [<ref-qualifier>] <template-prefix> <template-args> E
+    fn nested_name(&mut self) -> Option<NodeId> {
+        self.expect(b'N')?;
+        let qualifiers = self.cv_qualifiers(true)?;
+        let ref_qualifier = self.ref_qualifier();
+        let prefix = self.prefix(true)?;
+        let mut name = self.wrap(&qualifiers, prefix);
+        if let Some(kind) = ref_qualifier {
+            name = self.add(kind, Some(name), None);
+        }
+        self.expect(b'E')?;
+        Some(name)
+    }
+
+    // <prefix> ::= <prefix> <unqualified-name>
+    //          ::= <template-prefix> <template-args>
+    //          ::= <template-param>
+    //          ::= <decltype>
+    //          ::= <substitution>
+    //
+    // <template-prefix> ::= <prefix> <template unqualified-name>
+    //                   ::= <template-param>
+    //                   ::= <substitution>
+    fn prefix(&mut self, substable: bool) -> Option<NodeId> {
+        let mut prefix: Option<NodeId> = None;
+        loop {
+            let component = match self.peek2() {
+                (Some(b'D'), Some(b'T' | b't')) => {
+                    if prefix.is_some() {
+                        return None;
+                    }
+                    self.ty()?
+                }
+                (Some(b'I'), _) => {
+                    let template = prefix?;
+                    let args = self.template_args()?;
+                    self.add(Kind::Template, Some(template), Some(args))
+                }
+                (Some(b'T'), _) => {
+                    if prefix.is_some() {
+                        return None;
+                    }
+                    self.template_param()?
+                }
+                (Some(b'M'), _) => {
+                    // Initializer scope of a data member, which is not
+                    // printed.
+                    self.advance(1);
+                    continue;
+                }
+                (Some(b'S'), _) => {
+                    let sub = self.substitution()?;
+                    if prefix.is_some() {
+                        return None;
+                    }
+                    prefix = Some(sub);
+                    continue;
+                }
+                _ => self.unqualified_name(prefix)?,
+            };
+            prefix = Some(component);
+            if self.peek() == Some(b'E') {
+                return prefix;
+            }
+            if substable {
+                self.add_sub(component);
+            }
+        }
+    }
+
+    // <unqualified-name> ::= <operator-name> [<abi-tags>]
+    //                    ::= <ctor-dtor-name> [<abi-tags>]
+    //                    ::= <source-name> [<abi-tags>]
+    //                    ::= <local-source-name> [<abi-tags>]
+    //                    ::= <unnamed-type-name> [<abi-tags>]
+    //                    ::= DC <source-name>+ E
+    //
+    // <local-source-name> ::= L <source-name> [<discriminator>]
+    fn unqualified_name(&mut self, scope: Option<NodeId>) -> Option<NodeId> {
+        let mut name = match self.peek2() {
+            (Some(b'0'..=b'9'), _) => self.source_name()?,
+            (Some(peek @ b'a'..=b'z'), next) => {
+                let was_expression = self.is_expression;
+                if (peek, next) == (b'o', Some(b'n')) {
+                    self.advance(2);
+                    self.is_expression = false;
+                }
+                let op = self.operator_name();
+                self.is_expression = was_expression;
+                let op = op?;
+                if let Kind::Operator(Operator { code: "li", .. }) = self.kind(op) {
+                    let suffix = self.source_name()?;
+                    self.add(Kind::Unary, Some(op), Some(suffix))
+                } else {
+                    op
+                }
+            }
+            (Some(b'D'), Some(b'C')) => {
+                self.advance(2);
+                let mut names = Vec::new();
+                loop {
+                    names.push(self.source_name()?);
+                    if self.eat(b'E') {
+                        break;
+                    }
+                }
+                let names = self.list(Kind::ArgList, &names);
+                self.add(Kind::StructuredBinding, Some(names), None)
+            }
+            (Some(b'C' | b'D'), _) => self.ctor_dtor_name()?,
+            (Some(b'L'), _) => {
+                self.advance(1);
+                let name = self.source_name()?;
+                self.discriminator()?;
+                name
+            }
+            (Some(b'U'), Some(b'l')) => self.lambda()?,
+            (Some(b'U'), Some(b't')) => self.unnamed_type()?,
+            _ => return None,
+        };
+        if self.peek() == Some(b'B') {
+            name = self.abi_tags(name)?;
+        }
+        if let Some(scope) = scope {
+            name = self.add(Kind::QualName, Some(scope), Some(name));
+        }
+        Some(name)
+    }
+
+    // <abi-tags> ::= <abi-tag> [<abi-tags>]
+    // <abi-tag> ::= B <source-name>
+    fn abi_tags(&mut self, mut name: NodeId) -> Option<NodeId> {
+        // The tag is not what a constructor is named after.
+        let hold = self.last_name;
+        while self.eat(b'B') {
+            let tag = self.source_name()?;
+            name = self.add(Kind::TaggedName, Some(name), Some(tag));
+        }
+        self.last_name = hold;
+        Some(name)
+    }
+
+    // <source-name> ::= <positive length number> <identifier>
+    fn source_name(&mut self) -> Option<NodeId> {
+        let len = usize::try_from(self.number()).ok().filter(|&len| len > 0)?;
+        let name = self.identifier(len)?;
+        self.last_name = Some(name);
+        Some(name)
+    }
+
+    fn identifier(&mut self, len: usize) -> Option<NodeId> {
+        let start = self.pos;
+        let identifier = self.input.get(start..start.checked_add(len)?)?;
+        self.pos += len;
+        // GCC names anonymous namespaces _GLOBAL_ followed by one of . _ $
+        // and then N.
+        let bytes = identifier.as_bytes();
+        if len >= 10
+            && identifier.starts_with("_GLOBAL_")
+            && matches!(bytes[8], b'.' | b'_' | b'$')
+            && bytes[9] == b'N'
+        {
+            return Some(self.leaf(Kind::Name("(anonymous namespace)")));
+        }
+        Some(self.leaf(Kind::Name(identifier)))
+    }
+
+    // <operator-name> ::= <two lowercase letters>
+    //                 ::= cv <type>
+    //                 ::= v <digit> <source-name>
+    fn operator_name(&mut self) -> Option<NodeId> {
+        let c1 = self.next()?;
+        let c2 = self.next()?;
+        if c1 == b'v' && c2.is_ascii_digit() {
+            let name = self.source_name()?;
+            let args = usize::from(c2 - b'0');
+            return Some(self.add(Kind::ExtendedOperator(args), Some(name), None));
+        }
+        if (c1, c2) == (b'c', b'v') {
+            let was_conversion = self.is_conversion;
+            self.is_conversion = !self.is_expression;
+            let ty = self.ty();
+            let kind = if self.is_conversion {
+                Kind::Conversion
+            } else {
+                Kind::Cast
+            };
+            self.is_conversion = was_conversion;
+            return Some(self.add(kind, Some(ty?), None));
+        }
+        let code = [c1, c2];
+        let op = OPERATORS.iter().find(|op| op.code.as_bytes() == code)?;
+        Some(self.leaf(Kind::Operator(op)))
+    }
+
+    // <ctor-dtor-name> ::= C1 | C2 | C3 | C4 | C5
+    //                  ::= CI1 <type> | CI2 <type>
+    //                  ::= D0 | D1 | D2 | D4 | D5
+    fn ctor_dtor_name(&mut self) -> Option<NodeId> {
+        let kind = if self.peek() == Some(b'C') {
+            let inheriting = self.peek_at(1) == Some(b'I');
+            if inheriting {
+                self.advance(1);
+            }
+            if !matches!(self.peek_at(1), Some(b'1'..=b'5')) {
+                return None;
+            }
+            self.advance(2);
+            if inheriting {
+                // Named after the base whose constructor is inherited, as
+                // c++filt does.
+                let _ = self.ty();
+            }
+            Kind::Ctor
+        } else {
+            if !matches!(self.peek_at(1), Some(b'0' | b'1' | b'2' | b'4' | b'5')) {
+                return None;
+            }
+            self.advance(2);
+            Kind::Dtor
+        };
+        let name = self.last_name?;
+        Some(self.add(kind, Some(name), None))
+    }
+
+    // <closure-type-name> ::= Ul <lambda-sig> E [<nonnegative number>] _
+    // <lambda-sig> ::= <parameter type>+
+    fn lambda(&mut self) -> Option<NodeId> {
+        self.advance(2);
+        let params = self.parmlist()?;
+        self.expect(b'E')?;
+        let number = self.compact_number()?;
+        Some(self.add(Kind::Lambda(number), Some(params), None))
+    }
+
+    // <unnamed-type-name> ::= Ut [<nonnegative number>] _
+    fn unnamed_type(&mut self) -> Option<NodeId> {
+        self.advance(2);
+        let number = self.compact_number()?;
+        let unnamed = self.leaf(Kind::UnnamedType(number));
+        self.add_sub(unnamed);
+        Some(unnamed)
+    }
+
+    // <discriminator> ::= _ <digit>
+    //                 ::= __ <number> _
+    //
+    // The discriminator is not printed.
+    fn discriminator(&mut self) -> Option<()> {
+        if !self.eat(b'_') {
+            return Some(());
+        }
+        let long = self.eat(b'_');
+        let discriminator = self.number();
+        if discriminator < 0 {
+            return None;
+        }
+        if long && discriminator >= 10 {
+            self.expect(b'_')?;
+        }
+        Some(())
+    }
+
+    // <local-name> ::= Z <function encoding> E <entity name> [<discriminator>]
+    //              ::= Z <function encoding> E s [<discriminator>]
+    //              ::= Z <function encoding> E d [<parameter number>] _ <entity name>
+    fn local_name(&mut self) -> Option<NodeId> {
+        self.expect(b'Z')?;
+        let function = self.encoding(false)?;
+        self.expect(b'E')?;
+        let name = if self.eat(b's') {
+            self.discriminator()?;
+            self.leaf(Kind::Name("string literal"))
+        } else {
+            let default_arg = if self.eat(b'd') {
+                Some(self.compact_number()?)
+            } else {
+                None
+            };
+            let mut name = self.name(false)?;
+            // Lambdas and unnamed types have their discriminator in their
+            // number.
+            if !matches!(self.kind(name), Kind::Lambda(_) | Kind::UnnamedType(_)) {
+                self.discriminator()?;
+            }
+            if let Some(number) = default_arg {
+                name = self.add(Kind::DefaultArg(number), Some(name), None);
+            }
+            name
+        };
+        // Not to be mistaken for the return type of the local entity.
+        if self.kind(function) == Kind::TypedName {
+            if let Some(ty) = self.nodes[function].right {
+                if self.kind(ty) == Kind::FunctionType {
+                    self.nodes[ty].left = None;
+                }
+            }
+        }
+        Some(self.add(Kind::LocalName, Some(function), Some(name)))
+    }
+
+    // <CV-qualifiers> ::= [r] [V] [K] [Dx] [<exception-spec>]
+    //
+    // <exception-spec> ::= Do
+    //                  ::= DO <expression> E
+    //                  ::= Dw <type>+ E
+    //
+    // Returned from the outermost in, with the left of each to be filled in
+    // by wrap. The exception specification is only allowed on function
+    // types, which are qualified like their member functions are.
+    fn cv_qualifiers(&mut self, member_fn: bool) -> Option<Vec<NodeId>> {
+        let mut qualifiers = Vec::new();
+        while self.next_is_type_qual() {
+            let mut right = None;
+            let kind = match self.next()? {
+                b'r' => Kind::Restrict,
+                b'V' => Kind::Volatile,
+                b'K' => Kind::Const,
+                _ => match self.next()? {
+                    b'x' => Kind::TransactionSafe,
+                    b'o' => Kind::Noexcept,
+                    b'O' => {
+                        right = Some(self.expression()?);
+                        self.expect(b'E')?;
+                        Kind::Noexcept
+                    }
+                    _ => {
+                        right = Some(self.parmlist()?);
+                        self.expect(b'E')?;
+                        Kind::ThrowSpec
+                    }
+                },
+            };
+            qualifiers.push(self.add(kind, None, right));
+        }
+        if member_fn || self.peek() == Some(b'F') {
+            for &qualifier in &qualifiers {
+                let node = &mut self.nodes[qualifier];
+                node.kind = match node.kind {
+                    Kind::Restrict => Kind::RestrictThis,
+                    Kind::Volatile => Kind::VolatileThis,
+                    Kind::Const => Kind::ConstThis,
+                    kind => kind,
+                };
+            }
+        }
+        Some(qualifiers)
+    }
+
+    // Fill in the chain of qualifiers from cv_qualifiers around the inner
+    // component.
+    fn wrap(&mut self, qualifiers: &[NodeId], inner: NodeId) -> NodeId {
+        let mut inner = inner;
+        for &qualifier in qualifiers.iter().rev() {
+            self.nodes[qualifier].left = Some(inner);
+            inner = qualifier;
+        }
+        inner
+    }
+
+    fn next_is_type_qual(&self) -> bool {
+        match self.peek2() {
+            (Some(b'r' | b'V' | b'K'), _) => true,
+            (Some(b'D'), next) => matches!(next, Some(b'x' | b'o' | b'O' | b'w')),
+            _ => false,
+        }
+    }
+
+    // <ref-qualifier> ::= R
+    //                 ::= O
+    fn ref_qualifier(&mut self) -> Option<Kind<'a>> {
+        let kind = match self.peek() {
+            Some(b'R') => Kind::ReferenceThis,
+            Some(b'O') => Kind::RvalueReferenceThis,
+            _ => return None,
+        };
+        self.advance(1);
+        Some(kind)
+    }
+
+    // <type> ::= <builtin-type>
+    //        ::= <qualified-type>
+    //        ::= <function-type>
+    //        ::= <class-enum-type>
+    //        ::= <array-type>
+    //        ::= <pointer-to-member-type>
+    //        ::= <template-param>
+    //        ::= <template-template-param> <template-args>
+    //        ::= <decltype>
+    //        ::= P <type>
+    //        ::= R <type>
+    //        ::= O <type>
+    //        ::= C <type>
+    //        ::= G <type>
+    //        ::= Dp <type>
+    //        ::= U <source-name> [<template-args>] <type>
+    //        ::= <substitution>
+    fn ty(&mut self) -> Option<NodeId> {
+        self.nested(Parser::ty_inner)
+    }
+
+    #[allow(clippy::too_many_lines)]
+    fn ty_inner(&mut self) -> Option<NodeId> {
+        if self.next_is_type_qual() {
+            return self.qualified_type();
+        }
+
+        let mut can_subst = true;
+        let ty = match self.peek()? {
+            b'u' => {
+                self.advance(1);
+                let name = self.source_name()?;
+                self.add(Kind::VendorType, Some(name), None)
+            }
+            peek @ b'a'..=b'z' => match builtin_type(peek) {
+                Some(builtin) => {
+                    self.advance(1);
+                    can_subst = false;
+                    self.leaf(Kind::Builtin(builtin))
+                }
+                // Such as an operator name, which c++filt accepts as a type.
+                None => self.name(false)?,
+            },
+            b'F' => self.function_type()?,
+            b'A' => self.array_type()?,
+            b'M' => self.pointer_to_member_type()?,
+            b'T' => self.template_template_param()?,
+            b'O' => self.modified(Kind::RvalueReference)?,
+            b'P' => self.modified(Kind::Pointer)?,
+            b'R' => self.modified(Kind::Reference)?,
+            b'C' => self.modified(Kind::Complex)?,
+            b'G' => self.modified(Kind::Imaginary)?,
+            b'U' => {
+                self.advance(1);
+                let mut qualifier = self.source_name()?;
+                if self.peek() == Some(b'I') {
+                    let args = self.template_args()?;
+                    qualifier = self.add(Kind::Template, Some(qualifier), Some(args));
+                }
+                let ty = self.ty()?;
+                self.add(Kind::VendorTypeQual, Some(ty), Some(qualifier))
+            }
+            b'D' => {
+                self.advance(1);
+                can_subst = false;
+                match self.next()? {
+                    b'T' | b't' => {
+                        // <decltype> ::= Dt <expression> E
+                        //            ::= DT <expression> E
+                        let expression = self.expression()?;
+                        let ty = self.add(Kind::Decltype, Some(expression), None);
+                        self.expect(b'E')?;
+                        can_subst = true;
+                        ty
+                    }
+                    b'p' => {
+                        let pattern = self.ty()?;
+                        can_subst = true;
+                        self.add(Kind::PackExpansion, Some(pattern), None)
+                    }
+                    b'a' => self.leaf(Kind::Name("auto")),
+                    b'c' => self.leaf(Kind::Name("decltype(auto)")),
+                    b'f' => self.leaf(Kind::Builtin(&DECIMAL32)),
+                    b'd' => self.leaf(Kind::Builtin(&DECIMAL64)),
+                    b'e' => self.leaf(Kind::Builtin(&DECIMAL128)),
+                    b'h' => self.leaf(Kind::Builtin(&HALF)),
+                    b'u' => self.leaf(Kind::Builtin(&CHAR8)),
+                    b's' => self.leaf(Kind::Builtin(&CHAR16)),
+                    b'i' => self.leaf(Kind::Builtin(&CHAR32)),
+                    b'n' => self.leaf(Kind::Builtin(&NULLPTR)),
+                    b'F' => {
+                        // DF <number> _ is _FloatN, DF <number> x is
+                        // _FloatNx, and DF16b is bfloat16.
+                        let bits = self.number();
+                        match self.next()? {
+                            b'b' if bits == 16 => self.leaf(Kind::Builtin(&BFLOAT16)),
+                            b'_' => self.leaf(Kind::FloatN(bits, false)),
+                            b'x' => self.leaf(Kind::FloatN(bits, true)),
+                            _ => return None,
+                        }
+                    }
+                    b'v' => {
+                        can_subst = true;
+                        self.vector_type()?
+                    }
+                    _ => return None,
+                }
+            }
+            b'S' => {
+                if let Some(b'0'..=b'9' | b'_' | b'A'..=b'Z') = self.peek_at(1) {
+                    let sub = self.substitution()?;
+                    if self.peek() == Some(b'I') {
+                        let args = self.template_args()?;
+                        self.add(Kind::Template, Some(sub), Some(args))
+                    } else {
+                        can_subst = false;
+                        sub
+                    }
+                } else {
+                    // A standard substitution is not a new one, unless it is
+                    // followed by template arguments.
+                    let name = self.name(false)?;
+                    if let Kind::SubStd(_) = self.kind(name) {
+                        can_subst = false;
+                    }
+                    name
+                }
+            }
+            b'N' | b'Z' | b'L' | b'0'..=b'9' => self.name(false)?,
+            _ => return None,
+        };
+        if can_subst {
+            self.add_sub(ty);
+        }
+        Some(ty)
+    }
+
+    // <qualified-type> ::= <CV-qualifiers> <type>
+    fn qualified_type(&mut self) -> Option<NodeId> {
+        let qualifiers = self.cv_qualifiers(false)?;
+        // Qualifiers of a function type apply to this, so the function type
+        // without them is not a substitution.
+        let inner = if self.peek() == Some(b'F') {
+            self.function_type()?
+        } else {
+            self.ty()?
+        };
+        let ty = match self.kind(inner) {
+            // The ref-qualifier of a function type goes outside its other
+            // qualifiers.
+            Kind::ReferenceThis | Kind::RvalueReferenceThis => {
+                let function = self.nodes[inner].left?;
+                let qualified = self.wrap(&qualifiers, function);
+                self.nodes[inner].left = Some(qualified);
+                inner
+            }
+            _ => self.wrap(&qualifiers, inner),
+        };
+        self.add_sub(ty);
+        Some(ty)
+    }
+
+    fn modified(&mut self, kind: Kind<'a>) -> Option<NodeId> {
+        self.advance(1);
+        let ty = self.ty()?;
+        Some(self.add(kind, Some(ty), None))
+    }
+
+    // <template-template-param> ::= <template-param>
+    //                           ::= <substitution>
+    fn template_template_param(&mut self) -> Option<NodeId> {
+        let mut ty = self.template_param()?;
+        if self.peek() != Some(b'I') {
+            return Some(ty);
+        }
+        if !self.is_conversion {
+            self.add_sub(ty);
+            let args = self.template_args()?;
+            return Some(self.add(Kind::Template, Some(ty), Some(args)));
+        }
+        // In the type of a conversion operator, the template arguments might
+        // belong to the operator instead, which is the case unless they are
+        // followed by another set that does.
+        let checkpoint = self.checkpoint();
+        let args = self.template_args();
+        if self.peek() == Some(b'I') {
+            let args = args?;
+            self.add_sub(ty);
+            ty = self.add(Kind::Template, Some(ty), Some(args));
+        } else {
+            self.backtrack(&checkpoint);
+        }
+        Some(ty)
+    }
+
+    // <function-type> ::= F [Y] <bare-function-type> [<ref-qualifier>] E
+    fn function_type(&mut self) -> Option<NodeId> {
+        self.nested(|p| {
+            p.expect(b'F')?;
+            // extern "C" is not printed.
+            p.eat(b'Y');
+            let mut function = p.bare_function_type(true)?;
+            if let Some(kind) = p.ref_qualifier() {
+                function = p.add(kind, Some(function), None);
+            }
+            p.expect(b'E')?;
+            Some(function)
+        })
+    }
+
+    // <bare-function-type> ::= [J] <type>+
+    fn bare_function_type(&mut self, has_return_type: bool) -> Option<NodeId> {
+        let has_return_type = self.eat(b'J') || has_return_type;
+        let return_type = if has_return_type {
+            Some(self.ty()?)
+        } else {
+            None
+        };
+        let params = self.parmlist()?;
+        Some(self.add(Kind::FunctionType, return_type, Some(params)))
+    }
+
+    fn parmlist(&mut self) -> Option<NodeId> {
+        let mut params = Vec::new();
+        loop {
+            match self.peek2() {
+                (None | Some(b'E' | b'.' | b'Q'), _) | (Some(b'R' | b'O'), Some(b'E')) => break,
+                _ => params.push(self.ty()?),
+            }
+        }
+        if params.is_empty() {
+            return None;
+        }
+        let list = self.list(Kind::ArgList, &params);
+        // A lone void means no parameters.
+        if let [param] = params[..] {
+            if let Kind::Builtin(Builtin {
+                print: Print::Void, ..
+            }) = self.kind(param)
+            {
+                self.nodes[list].left = None;
+            }
+        }
+        Some(list)
+    }
+
+    // <array-type> ::= A <positive dimension number> _ <element type>
+    //              ::= A [<dimension expression>] _ <element type>
+    fn array_type(&mut self) -> Option<NodeId> {
+        self.expect(b'A')?;
+        let dimension = match self.peek()? {
+            b'_' => None,
+            b'0'..=b'9' => {
+                let start = self.pos;
+                while let Some(b'0'..=b'9') = self.peek() {
+                    self.advance(1);
+                }
+                Some(self.leaf(Kind::Name(&self.input[start..self.pos])))
+            }
+            _ => Some(self.expression()?),
+        };
+        self.expect(b'_')?;
+        let element = self.ty()?;
+        Some(self.add(Kind::ArrayType, dimension, Some(element)))
+    }
+
+    // <vector-type> ::= Dv <number> _ <type>
+    //               ::= Dv _ <expression> _ <type>
+    fn vector_type(&mut self) -> Option<NodeId> {
+        let dimension = if self.eat(b'_') {
+            self.expression()?
+        } else {
+            let number = self.number();
+            self.leaf(Kind::Number(number))
+        };
+        self.expect(b'_')?;
+        let element = self.ty()?;
+        Some(self.add(Kind::VectorType, Some(dimension), Some(element)))
+    }
+
+    // <pointer-to-member-type> ::= M <class type> <member type>
+    fn pointer_to_member_type(&mut self) -> Option<NodeId> {
+        self.expect(b'M')?;
+        let class = self.ty()?;
+        let member = self.ty()?;
+        Some(self.add(Kind::PtrMemType, Some(class), Some(member)))
+    }
+
+    // <template-param> ::= T_
+    //                  ::= T <parameter-2 non-negative number> _
+    fn template_param(&mut self) -> Option<NodeId> {
+        self.expect(b'T')?;
+        let index = self.compact_number()?;
+        Some(self.leaf(Kind::TemplateParam(index)))
+    }
+
+    // <template-args> ::= I <template-arg>+ E
+    fn template_args(&mut self) -> Option<NodeId> {
+        match self.peek()? {
+            b'I' | b'J' => self.advance(1),
+            _ => return None,
+        }
+        self.template_args_rest()
+    }
+
+    fn template_args_rest(&mut self) -> Option<NodeId> {
+        // The arguments are not what a constructor is named after.
+        let hold = self.last_name;
+        if self.eat(b'E') {
+            // An empty pack.
+            return Some(self.leaf(Kind::TemplateArgList));
+        }
+        let mut args = Vec::new();
+        loop {
+            args.push(self.template_arg()?);
+            if self.eat(b'E') {
+                break;
+            }
+        }
+        self.last_name = hold;
+        Some(self.list(Kind::TemplateArgList, &args))
+    }
+
+    // <template-arg> ::= <type>
+    //                ::= X <expression> E
+    //                ::= <expr-primary>
+    //                ::= J <template-arg>* E
+    fn template_arg(&mut self) -> Option<NodeId> {
+        match self.peek()? {
+            b'X' => {
+                self.advance(1);
+                let expression = self.expression()?;
+                self.expect(b'E')?;
+                Some(expression)
+            }
+            b'L' => self.expr_primary(),
+            b'I' | b'J' => self.template_args(),
+            _ => self.ty(),
+        }
+    }
+
+    // <expr-primary> ::= L <type> <value number> E
+    //                ::= L <type> <value float> E
+    //                ::= L <mangled-name> E
+    fn expr_primary(&mut self) -> Option<NodeId> {
+        self.expect(b'L')?;
+        let primary = if let Some(b'_' | b'Z') = self.peek() {
+            self.mangled_name(false)?
+        } else {
+            let ty = self.ty()?;
+            // nullptr is mangled as a literal of its type with no value.
+            if self.kind(ty) == Kind::Builtin(&NULLPTR) && self.eat(b'E') {
+                return Some(ty);
+            }
+            let kind = if self.eat(b'n') {
+                Kind::LiteralNeg
+            } else {
+                Kind::Literal
+            };
+            let start = self.pos;
+            while self.peek()? != b'E' {
+                self.advance(1);
+            }
+            if self.pos == start {
+                return None;
+            }
+            let value = self.input.get(start..self.pos)?;
+            let value = self.leaf(Kind::Name(value));
+            self.add(kind, Some(ty), Some(value))
+        };
+        self.expect(b'E')?;
+        Some(primary)
+    }
+
+    fn expression(&mut self) -> Option<NodeId> {
+        let was_expression = self.is_expression;
+        self.is_expression = true;
+        let expression = self.expression_1();
+        self.is_expression = was_expression;
+        expression
+    }
+
+    // <expression> ::= <(unary) operator-name> <expression>
+    //              ::= <(binary) operator-name> <expression> <expression>
+    //              ::= <(trinary) operator-name> <expression> <expression> <expression>
+    //              ::= cl <expression>+ E
+    //              ::= cv <type> <expression>
+    //              ::= cv <type> _ <expression>* E
+    //              ::= [gs] nw <expression>* _ <type> [<initializer>] E
+    //              ::= st <type>
+    //              ::= <template-param>
+    //              ::= <function-param>
+    //              ::= sp <expression>
+    //              ::= il <braced-expression>* E
+    //              ::= tl <type> <braced-expression>* E
+    //              ::= <unresolved-name>
+    //              ::= <expr-primary>
+    fn expression_1(&mut self) -> Option<NodeId> {
+        self.nested(|p| match p.peek2() {
+            (Some(b'L'), _) => p.expr_primary(),
+            (Some(b'T'), _) => p.template_param(),
+            (Some(b's'), Some(b'r')) => p.unresolved_name(),
+            (Some(b's'), Some(b'p')) => {
+                p.advance(2);
+                let pattern = p.expression_1()?;
+                Some(p.add(Kind::PackExpansion, Some(pattern), None))
+            }
+            (Some(b'f'), Some(b'p')) => {
+                // <function-param> ::= fp [<CV-qualifiers>] [<number>] _
+                //                  ::= fpT
+                p.advance(2);
+                let index = if p.eat(b'T') {
+                    0
+                } else {
+                    p.compact_number()? + 1
+                };
+                Some(p.leaf(Kind::FunctionParam(index)))
+            }
+            (Some(peek @ (b'0'..=b'9' | b'o')), next) if peek != b'o' || next == Some(b'n') => {
+                // Name of a function in a dependent call, or of an operator
+                // if preceded by on.
+                if peek == b'o' {
+                    p.advance(2);
+                }
+                let mut name = p.unqualified_name(None)?;
+                if p.peek() == Some(b'I') {
+                    let args = p.template_args()?;
+                    name = p.add(Kind::Template, Some(name), Some(args));
+                }
+                Some(name)
+            }
+            (Some(peek @ (b'i' | b't')), Some(b'l')) => {
+                p.advance(2);
+                let ty = if peek == b't' { Some(p.ty()?) } else { None };
+                let elements = p.exprlist(b'E')?;
+                Some(p.add(Kind::InitializerList, ty, Some(elements)))
+            }
+            (Some(b'u'), _) => None,
+            _ => p.operator_expression(),
+        })
+    }
+
+    fn operator_expression(&mut self) -> Option<NodeId> {
+        let op = self.operator_name()?;
+        let (code, args) = match self.kind(op) {
+            Kind::Operator(info) => {
+                if info.code == "st" {
+                    let ty = self.ty()?;
+                    return Some(self.add(Kind::Unary, Some(op), Some(ty)));
+                }
+                (info.code, info.args)
+            }
+            Kind::ExtendedOperator(args) => ("", args),
+            Kind::Cast => ("", 1),
+            _ => return None,
+        };
+        match args {
+            0 => Some(self.add(Kind::Nullary, Some(op), None)),
+            1 => {
+                // The prefix increment and decrement operators are mangled
+                // with a _ that the postfix ones are not.
+                let postfix = (code == "pp" || code == "mm") && !self.eat(b'_');
+                let operand = if self.kind(op) == Kind::Cast && self.eat(b'_') {
+                    self.exprlist(b'E')?
+                } else if code == "sP" {
+                    self.template_args_rest()?
+                } else {
+                    self.expression_1()?
+                };
+                let operand = if postfix {
+                    self.add(Kind::BinaryArgs, Some(operand), Some(operand))
+                } else {
+                    operand
+                };
+                Some(self.add(Kind::Unary, Some(op), Some(operand)))
+            }
+            2 => {
+                if code.is_empty() {
+                    return None;
+                }
+                let left = if is_new_cast(code) {
+                    self.ty()?
+                } else if code.starts_with('f') {
+                    // The operator of a fold expression.
+                    self.operator_name()?
+                } else if code == "di" {
+                    self.unqualified_name(None)?
+                } else {
+                    self.expression_1()?
+                };
+                let right = if code == "cl" {
+                    self.exprlist(b'E')?
+                } else if (code == "dt" || code == "pt")
+                    && !matches!(
+                        self.peek2(),
+                        (Some(b'g'), Some(b's')) | (Some(b's'), Some(b'r'))
+                    )
+                {
+                    let mut member = self.unqualified_name(None)?;
+                    if self.peek() == Some(b'I') {
+                        let args = self.template_args()?;
+                        member = self.add(Kind::Template, Some(member), Some(args));
+                    }
+                    member
+                } else {
+                    self.expression_1()?
+                };
+                let args = self.add(Kind::BinaryArgs, Some(left), Some(right));
+                Some(self.add(Kind::Binary, Some(op), Some(args)))
+            }
+            3 => {
+                let (first, second, third) = if code == "qu" || code == "dX" {
+                    let first = self.expression_1()?;
+                    let second = self.expression_1()?;
+                    (first, second, Some(self.expression_1()?))
+                } else if code.starts_with('f') {
+                    let first = self.operator_name()?;
+                    let second = self.expression_1()?;
+                    (first, second, Some(self.expression_1()?))
+                } else if code == "nw" || code == "na" {
+                    // <initializer> ::= pi <expression>* E
+                    let placement = self.exprlist(b'_')?;
+                    let ty = self.ty()?;
+                    let init = match self.peek2() {
+                        (Some(b'E'), _) => {
+                            self.advance(1);
+                            None
+                        }
+                        (Some(b'p'), Some(b'i')) => {
+                            self.advance(2);
+                            Some(self.exprlist(b'E')?)
+                        }
+                        (Some(b'i'), Some(b'l')) => Some(self.expression_1()?),
+                        _ => return None,
+                    };
+                    (placement, ty, init)
+                } else {
+                    return None;
+                };
+                let rest = self.add(Kind::TrinaryArg2, Some(second), third);
+                let operands = self.add(Kind::TrinaryArg1, Some(first), Some(rest));
+                Some(self.add(Kind::Trinary, Some(op), Some(operands)))
+            }
+            _ => None,
+        }
+    }
+
+    // <expression>* followed by the terminator.
+    fn exprlist(&mut self, terminator: u8) -> Option<NodeId> {
+        let mut expressions = Vec::new();
+        while !self.eat(terminator) {
+            expressions.push(self.expression_1()?);
+        }
+        Some(self.list(Kind::ArgList, &expressions))
+    }
+
+    // <unresolved-name> ::= [gs] <base-unresolved-name>
+    //                   ::= sr <unresolved-type> <base-unresolved-name>
+    //                   ::= srN <unresolved-type> <unresolved-qualifier-level>+ E <base-unresolved-name>
+    //                   ::= [gs] sr <unresolved-qualifier-level>+ E <base-unresolved-name>
+    //
+    // GCC before version 8 mangled the last form without the E, which is
+    // ambiguous with the second form when the qualifier is a source name.
+    fn unresolved_name(&mut self) -> Option<NodeId> {
+        self.advance(2);
+        let scope = if self.unresolved != Unresolved::Old
+            && matches!(
+                self.peek(),
+                Some(b'0'..=b'9' | b'a'..=b'z' | b'C' | b'U' | b'L')
+            ) {
+            self.unresolved = Unresolved::Ambiguous;
+            let scope = self.prefix(false)?;
+            self.eat(b'E');
+            scope
+        } else {
+            self.ty()?
+        };
+        let mut name = self.unqualified_name(Some(scope))?;
+        if self.peek() == Some(b'I') {
+            let args = self.template_args()?;
+            name = self.add(Kind::Template, Some(name), Some(args));
+        }
+        Some(name)
+    }
+
+    // <substitution> ::= S <seq-id> _
+    //                ::= S_
+    //                ::= St
+    //                ::= Sa
+    //                ::= Sb
+    //                ::= Ss
+    //                ::= Si
+    //                ::= So
+    //                ::= Sd
+    fn substitution(&mut self) -> Option<NodeId> {
+        self.expect(b'S')?;
+        let mut c = self.next()?;
+        if c == b'_' || c.is_ascii_digit() || c.is_ascii_uppercase() {
+            let mut id: usize = 0;
+            if c != b'_' {
+                loop {
+                    let digit = match c {
+                        b'0'..=b'9' => c - b'0',
+                        b'A'..=b'Z' => c - b'A' + 10,
+                        _ => return None,
+                    };
+                    id = id.checked_mul(36)?.checked_add(usize::from(digit))?;
+                    c = self.next()?;
+                    if c == b'_' {
+                        break;
+                    }
+                }
+                id += 1;
+            }
+            return self.subs.get(id).copied();
+        }
+
+        // Abbreviations in the standard library, which are printed in full.
+        let (full, name) = match c {
+            b't' => ("std", None),
+            b'a' => ("std::allocator", Some("allocator")),
+            b'b' => ("std::basic_string", Some("basic_string")),
+            b's' => (
+                "std::basic_string<char, std::char_traits<char>, std::allocator<char> >",
+                Some("basic_string"),
+            ),
+            b'i' => (
+                "std::basic_istream<char, std::char_traits<char> >",
+                Some("basic_istream"),
+            ),
+            b'o' => (
+                "std::basic_ostream<char, std::char_traits<char> >",
+                Some("basic_ostream"),
+            ),
+            b'd' => (
+                "std::basic_iostream<char, std::char_traits<char> >",
+                Some("basic_iostream"),
+            ),
+            _ => return None,
+        };
+        if let Some(name) = name {
+            self.last_name = Some(self.leaf(Kind::Name(name)));
+        }
+        let mut sub = self.leaf(Kind::SubStd(full));
+        if self.peek() == Some(b'B') {
+            sub = self.abi_tags(sub)?;
+            self.add_sub(sub);
+        }
+        Some(sub)
+    }
+}
+
+fn is_fnqual(kind: Kind) -> bool {
+    matches!(
+        kind,
+        Kind::RestrictThis
+            | Kind::VolatileThis
+            | Kind::ConstThis
+            | Kind::ReferenceThis
+            | Kind::RvalueReferenceThis
+            | Kind::TransactionSafe
+            | Kind::Noexcept
+            | Kind::ThrowSpec
+    )
+}
+
+fn is_new_cast(code: &str) -> bool {
+    matches!(code, "dc" | "sc" | "cc" | "rc")
+}
+
+macro_rules! builtins {
+    ($($code:literal $name:literal $print:ident,)*) => {
+        fn builtin_type(code: u8) -> Option<&'static Builtin> {
+            match code {
+                $($code => Some(&Builtin { name: $name, print: Print::$print }),)*
+                _ => None,
+            }
+        }
+    };
+}
+
+builtins! {
+    b'a' "signed char" Default,
+    b'b' "bool" Bool,
+    b'c' "char" Default,
+    b'd' "double" Float,
+    b'e' "long double" Float,
+    b'f' "float" Float,
+    b'g' "__float128" Float,
+    b'h' "unsigned char" Default,
+    b'i' "int" Int,
+    b'j' "unsigned int" Unsigned,
+    b'l' "long" Long,
+    b'm' "unsigned long" UnsignedLong,
+    b'n' "__int128" Default,
+    b'o' "unsigned __int128" Default,
+    b's' "short" Default,
+    b't' "unsigned short" Default,
+    b'v' "void" Void,
+    b'w' "wchar_t" Default,
+    b'x' "long long" LongLong,
+    b'y' "unsigned long long" UnsignedLongLong,
+    b'z' "..." Default,
+}
+
+static DECIMAL32: Builtin = Builtin {
+    name: "decimal32",
+    print: Print::Default,
+};
+static DECIMAL64: Builtin = Builtin {
+    name: "decimal64",
+    print: Print::Default,
+};
+static DECIMAL128: Builtin = Builtin {
+    name: "decimal128",
+    print: Print::Default,
+};
+static HALF: Builtin = Builtin {
+    name: "half",
+    print: Print::Float,
+};
+static CHAR8: Builtin = Builtin {
+    name: "char8_t",
+    print: Print::Default,
+};
+static CHAR16: Builtin = Builtin {
+    name: "char16_t",
+    print: Print::Default,
+};
+static CHAR32: Builtin = Builtin {
+    name: "char32_t",
+    print: Print::Default,
+};
+static NULLPTR: Builtin = Builtin {
+    name: "decltype(nullptr)",
+    print: Print::Default,
+};
+static BFLOAT16: Builtin = Builtin {
+    name: "std::bfloat16_t",
+    print: Print::Float,
+};
+
+macro_rules! operators {
+    ($($code:literal $name:literal $args:literal,)*) => {
+        &[$(Operator { code: $code, name: $name, args: $args },)*]
+    };
+}
+
+// Operators by their mangled code, with how they are printed in expressions
+// and the number of operands.
+static OPERATORS: &[Operator] = operators! {
+    "aN" "&=" 2,
+    "aS" "=" 2,
+    "aa" "&&" 2,
+    "ad" "&" 1,
+    "an" "&" 2,
+    "at" "alignof " 1,
+    "aw" "co_await " 1,
+    "az" "alignof " 1,
+    "cc" "const_cast" 2,
+    "cl" "()" 2,
+    "cm" "," 2,
+    "co" "~" 1,
+    "dV" "/=" 2,
+    "dX" "[...]=" 3,
+    "da" "delete[] " 1,
+    "dc" "dynamic_cast" 2,
+    "de" "*" 1,
+    "di" "=" 2,
+    "dl" "delete " 1,
+    "ds" ".*" 2,
+    "dt" "." 2,
+    "dv" "/" 2,
+    "dx" "]=" 2,
+    "eO" "^=" 2,
+    "eo" "^" 2,
+    "eq" "==" 2,
+    "fL" "..." 3,
+    "fR" "..." 3,
+    "fl" "..." 2,
+    "fr" "..." 2,
+    "ge" ">=" 2,
+    "gs" "::" 1,
+    "gt" ">" 2,
+    "ix" "[]" 2,
+    "lS" "<<=" 2,
+    "le" "<=" 2,
+    "li" "operator\"\" " 1,
+    "ls" "<<" 2,
+    "lt" "<" 2,
+    "mI" "-=" 2,
+    "mL" "*=" 2,
+    "mi" "-" 2,
+    "ml" "*" 2,
+    "mm" "--" 1,
+    "na" "new[]" 3,
+    "ne" "!=" 2,
+    "ng" "-" 1,
+    "nt" "!" 1,
+    "nw" "new" 3,
+    "oR" "|=" 2,
+    "oo" "||" 2,
+    "or" "|" 2,
+    "pL" "+=" 2,
+    "pl" "+" 2,
+    "pm" "->*" 2,
+    "pp" "++" 1,
+    "ps" "+" 1,
+    "pt" "->" 2,
+    "qu" "?" 3,
+    "rM" "%=" 2,
+    "rS" ">>=" 2,
+    "rc" "reinterpret_cast" 2,
+    "rm" "%" 2,
+    "rs" ">>" 2,
+    "sP" "sizeof..." 1,
+    "sZ" "sizeof..." 1,
+    "sc" "static_cast" 2,
+    "ss" "<=>" 2,
+    "st" "sizeof " 1,
+    "sz" "sizeof " 1,
+    "tr" "throw" 0,
+    "tw" "throw " 1,
+};
+
+// A type that is printed around whatever it applies to, such as the "*" in
+// "void (*)(int)", is pushed as a modifier while printing what it applies
+// to, and printed by whichever component ends up printing the declarator,
+// or else after what it applies to.
+#[derive(Copy, Clone)]
+struct Modifier {
+    node: NodeId,
+    printed: bool,
+    // Templates in scope of the modifier.
+    templates: Option<usize>,
+    next: Option<usize>,
+}
+
+struct Printer<'n, 'a> {
+    nodes: &'n [Node<'a>],
+    out: String,
+    last_char: u8,
+    // Modifiers pushed by the components being printed, which truncate it
+    // back on the way out, and the head of the list of the current ones.
+    mods: Vec<Modifier>,
+    modifiers: Option<usize>,
+    // Templates whose arguments template parameters refer to, as linked
+    // lists through the second of each, and the head of the current one.
+    templates: Vec<(NodeId, Option<usize>)>,
+    template: Option<usize>,
+    // Innermost template being printed, for a conversion operator template.
+    current_template: Option<NodeId>,
+    // Element of a template argument pack being printed by a pack expansion,
+    // or None for the whole pack.
+    pack_index: Option<usize>,
+    is_lambda_arg: usize,
+    // Number of times each component is being printed, to break cycles.
+    printing: Vec<u8>,
+    stack: Vec<NodeId>,
+    // Templates in scope the first time a reference to each template
+    // parameter was printed.
+    scopes: Vec<(NodeId, Option<usize>)>,
+    steps: usize,
+    overflow: bool,
+}
+
+impl<'n, 'a> Printer<'n, 'a> {
+    fn new(nodes: &'n [Node<'a>]) -> Self {
+        Printer {
+            nodes,
+            out: String::new(),
+            last_char: 0,
+            mods: Vec::new(),
+            modifiers: None,
+            templates: Vec::new(),
+            template: None,
+            current_template: None,
+            pack_index: Some(0),
+            is_lambda_arg: 0,
+            printing: vec![0; nodes.len()],
+            stack: Vec::new(),
+            scopes: Vec::new(),
+            steps: 0,
+            overflow: false,
+        }
+    }
+
+    fn print(mut self, root: NodeId) -> Option<String> {
+        self.comp(root)?;
+        if self.overflow {
+            return None;
+        }
+        Some(self.out)
+    }
+
+    fn kind(&self, id: NodeId) -> Kind<'a> {
+        self.nodes[id].kind
+    }
+
+    fn left(&self, id: NodeId) -> Option<NodeId> {
+        self.nodes[id].left
+    }
+
+    fn right(&self, id: NodeId) -> Option<NodeId> {
+        self.nodes[id].right
+    }
+
+    fn push(&mut self, s: &str) {
+        if let Some(&last) = s.as_bytes().last() {
+            if self.out.len() + s.len() > MAX_LEN {
+                self.overflow = true;
+                return;
+            }
+            self.out.push_str(s);
+            self.last_char = last;
+        }
+    }
+
+    fn push_num(&mut self, n: impl Display) {
+        self.push(&n.to_string());
+    }
+
+    fn push_mod(&mut self, node: NodeId) -> usize {
+        self.mods.push(Modifier {
+            node,
+            printed: false,
+            templates: self.template,
+            next: self.modifiers,
+        });
+        self.mods.len() - 1
+    }
+
+    fn push_template(&mut self, node: NodeId) -> usize {
+        self.templates.push((node, self.template));
+        self.templates.len() - 1
+    }
+
+    fn comp(&mut self, dc: NodeId) -> Option<()> {
+        self.steps += 1;
+        if self.overflow
+            || self.printing[dc] > 1
+            || self.stack.len() >= MAX_DEPTH
+            || self.steps > MAX_STEPS
+        {
+            return None;
+        }
+        self.printing[dc] += 1;
+        self.stack.push(dc);
+        let result = self.comp_inner(dc);
+        self.stack.pop();
+        self.printing[dc] -= 1;
+        result
+    }
+
+    #[allow(clippy::too_many_lines)]
+    fn comp_inner(&mut self, dc: NodeId) -> Option<()> {
+        match self.kind(dc) {
+            Kind::Name(name) => self.push(name),
+            Kind::TaggedName => {
+                self.comp(self.left(dc)?)?;
+                self.push("[abi:");
+                self.comp(self.right(dc)?)?;
+                self.push("]");
+            }
+            Kind::QualName | Kind::LocalName => {
+                self.comp(self.left(dc)?)?;
+                self.push("::");
+                let mut name = self.right(dc)?;
+                if let Kind::DefaultArg(number) = self.kind(name) {
+                    self.default_arg(number);
+                    name = self.left(name)?;
+                }
+                self.comp(name)?;
+            }
+            Kind::TypedName => self.typed_name(dc)?,
+            Kind::Template => self.template(dc)?,
+            Kind::TemplateParam(index) => self.template_param(index)?,
+            Kind::FunctionParam(0) => self.push("this"),
+            Kind::FunctionParam(index) => {
+                self.push("{parm#");
+                self.push_num(index);
+                self.push("}");
+            }
+            Kind::Ctor | Kind::VendorType => self.comp(self.left(dc)?)?,
+            Kind::Dtor => {
+                self.push("~");
+                self.comp(self.left(dc)?)?;
+            }
+            Kind::Special(text) => {
+                self.push(text);
+                self.comp(self.left(dc)?)?;
+            }
+            Kind::ConstructionVtable => {
+                self.push("construction vtable for ");
+                self.comp(self.left(dc)?)?;
+                self.push("-in-");
+                self.comp(self.right(dc)?)?;
+            }
+            Kind::RefTemp => {
+                self.push("reference temporary #");
+                self.comp(self.right(dc)?)?;
+                self.push(" for ");
+                self.comp(self.left(dc)?)?;
+            }
+            Kind::SubStd(text) => self.push(text),
+            Kind::Restrict | Kind::Volatile | Kind::Const => self.cv_qualifier(dc)?,
+            Kind::Reference | Kind::RvalueReference => self.reference(dc)?,
+            Kind::RestrictThis
+            | Kind::VolatileThis
+            | Kind::ConstThis
+            | Kind::ReferenceThis
+            | Kind::RvalueReferenceThis
+            | Kind::TransactionSafe
+            | Kind::Noexcept
+            | Kind::ThrowSpec
+            | Kind::VendorTypeQual
+            | Kind::Pointer
+            | Kind::Complex
+            | Kind::Imaginary => self.modifier_comp(dc, self.left(dc)?)?,
+            Kind::Builtin(builtin) => self.push(builtin.name),
+            Kind::FloatN(bits, x) => {
+                self.push("_Float");
+                self.push_num(bits);
+                if x {
+                    self.push("x");
+                }
+            }
+            Kind::FunctionType => self.function(dc)?,
+            Kind::ArrayType => self.array(dc)?,
+            Kind::PtrMemType | Kind::VectorType => self.modifier_comp(dc, self.right(dc)?)?,
+            Kind::ArgList | Kind::TemplateArgList => self.list(dc)?,
+            Kind::Operator(op) => {
+                self.push("operator");
+                if op.name.as_bytes()[0].is_ascii_lowercase() {
+                    self.push(" ");
+                }
+                self.push(op.name.trim_end_matches(' '));
+            }
+            Kind::ExtendedOperator(_) => {
+                self.push("operator ");
+                self.comp(self.left(dc)?)?;
+            }
+            Kind::Conversion => {
+                self.push("operator ");
+                self.conversion(dc)?;
+            }
+            Kind::Nullary => self.expr_op(self.left(dc)?)?,
+            Kind::Unary => self.unary(dc)?,
+            Kind::Binary => self.binary(dc)?,
+            Kind::Trinary => self.trinary(dc)?,
+            Kind::Literal | Kind::LiteralNeg => self.literal(dc)?,
+            Kind::Number(number) => self.push_num(number),
+            Kind::Decltype => {
+                self.push("decltype (");
+                self.comp(self.left(dc)?)?;
+                self.push(")");
+            }
+            Kind::PackExpansion => self.pack_expansion(dc)?,
+            Kind::Lambda(number) => {
+                self.push("{lambda(");
+                self.is_lambda_arg += 1;
+                let params = self.comp(self.left(dc)?);
+                self.is_lambda_arg -= 1;
+                params?;
+                self.push(")#");
+                self.push_num(number + 1);
+                self.push("}");
+            }
+            Kind::UnnamedType(number) => {
+                self.push("{unnamed type#");
+                self.push_num(number + 1);
+                self.push("}");
+            }
+            Kind::Clone => {
+                self.comp(self.left(dc)?)?;
+                self.push(" [clone ");
+                self.comp(self.right(dc)?)?;
+                self.push("]");
+            }
+            Kind::InitializerList => {
+                if let Some(ty) = self.left(dc) {
+                    self.comp(ty)?;
+                }
+                self.push("{");
+                self.comp(self.right(dc)?)?;
+                self.push("}");
+            }
+            Kind::StructuredBinding => {
+                self.push("[");
+                self.comp(self.left(dc)?)?;
+                self.push("]");
+            }
+            Kind::Cast
+            | Kind::BinaryArgs
+            | Kind::TrinaryArg1
+            | Kind::TrinaryArg2
+            | Kind::DefaultArg(_) => return None,
+        }
+        Some(())
+    }
+
+    fn list(&mut self, dc: NodeId) -> Option<()> {
+        if let Some(left) = self.left(dc) {
+            self.comp(left)?;
+        }
+        if let Some(right) = self.right(dc) {
+            self.push(", ");
+            let len = self.out.len();
+            self.comp(right)?;
+            // An empty pack prints nothing, and neither does the comma
+            // before it.
+            if self.out.len() == len {
+                self.out.truncate(len - 2);
+            }
+        }
+        Some(())
+    }
+
+    fn default_arg(&mut self, number: usize) {
+        self.push("{default arg#");
+        self.push_num(number + 1);
+        self.push("}::");
+    }
+
+    // Print the inner component with the modifier pending, and then the
+    // modifier unless the inner component printed it.
+    fn modifier_comp(&mut self, dc: NodeId, inner: NodeId) -> Option<()> {
+        let mark = self.mods.len();
+        let hold = self.modifiers;
+        let m = self.push_mod(dc);
+        self.modifiers = Some(m);
+        self.comp(inner)?;
+        if !self.mods[m].printed {
+            self.modifier(dc)?;
+        }
+        self.modifiers = hold;
+        self.mods.truncate(mark);
+        Some(())
+    }
+
+    fn cv_qualifier(&mut self, dc: NodeId) -> Option<()> {
+        // A qualifier already pending on the same type, as for a
+        // substitution of a const type that is qualified const again, is
+        // printed once.
+        let mut pending = self.modifiers;
+        while let Some(i) = pending {
+            let m = self.mods[i];
+            if !m.printed {
+                let kind = self.kind(m.node);
+                if !matches!(kind, Kind::Restrict | Kind::Volatile | Kind::Const) {
+                    break;
+                }
+                if kind == self.kind(dc) {
+                    return self.comp(self.left(dc)?);
+                }
+            }
+            pending = m.next;
+        }
+        self.modifier_comp(dc, self.left(dc)?)
+    }
+
+    fn typed_name(&mut self, dc: NodeId) -> Option<()> {
+        // The name, and the qualifiers of a member function, are printed as
+        // modifiers of the function type.
+        let hold = self.modifiers;
+        let mark = self.mods.len();
+        self.modifiers = None;
+        let mut pushed = Vec::new();
+        let mut name = self.left(dc);
+        while let Some(node) = name {
+            let m = self.push_mod(node);
+            self.modifiers = Some(m);
+            pushed.push(m);
+            if !is_fnqual(self.kind(node)) {
+                break;
+            }
+            name = self.left(node);
+        }
+        let mut name = name?;
+
+        // For a local entity, the qualifiers of the function it is local to
+        // are printed by the modifier for the local name, and those of the
+        // entity itself after its parameters.
+        if self.kind(name) == Kind::LocalName {
+            name = self.right(name)?;
+            if let Kind::DefaultArg(_) = self.kind(name) {
+                name = self.left(name)?;
+            }
+            while is_fnqual(self.kind(name)) {
+                let m = self.push_mod(name);
+                self.modifiers = Some(m);
+                pushed.push(m);
+                name = self.left(name)?;
+            }
+        }
+        if pushed.len() > 4 {
+            return None;
+        }
+
+        let hold_template = self.template;
+        if self.kind(name) == Kind::Template {
+            self.template = Some(self.push_template(name));
+        }
+        self.comp(self.right(dc)?)?;
+        self.template = hold_template;
+
+        for &m in pushed.iter().rev() {
+            if !self.mods[m].printed {
+                self.push(" ");
+                self.modifier(self.mods[m].node)?;
+            }
+        }
+        self.modifiers = hold;
+        self.mods.truncate(mark);
+        Some(())
+    }
+
+    fn template(&mut self, dc: NodeId) -> Option<()> {
+        let hold_current = self.current_template;
+        self.current_template = Some(dc);
+        // Modifiers do not apply to the template arguments.
+        let hold = self.modifiers;
+        self.modifiers = None;
+        self.comp(self.left(dc)?)?;
+        self.template_arguments(self.right(dc)?)?;
+        self.modifiers = hold;
+        self.current_template = hold_current;
+        Some(())
+    }
+
+    fn template_arguments(&mut self, args: NodeId) -> Option<()> {
+        // Avoid the tokens "<<" and ">>".
+        if self.last_char == b'<' {
+            self.push(" ");
+        }
+        self.push("<");
+        self.comp(args)?;
+        if self.last_char == b'>' {
+            self.push(" ");
+        }
+        self.push(">");
+        Some(())
+    }
+
+    fn lookup_template_argument(&self, index: usize) -> Option<NodeId> {
+        let template = self.templates[self.template?].0;
+        self.index_template_argument(self.right(template), Some(index))
+    }
+
+    fn index_template_argument(
+        &self,
+        args: Option<NodeId>,
+        index: Option<usize>,
+    ) -> Option<NodeId> {
+        let mut index = match index {
+            Some(index) => index,
+            None => return args,
+        };
+        let mut args = args;
+        while let Some(list) = args {
+            if self.kind(list) != Kind::TemplateArgList {
+                return None;
+            }
+            if index == 0 {
+                break;
+            }
+            index -= 1;
+            args = self.right(list);
+        }
+        if index != 0 {
+            return None;
+        }
+        self.left(args?)
+    }
+
+    // The argument of a template parameter, or the current element of it if
+    // it is a pack.
+    fn template_argument(&self, index: usize) -> Option<NodeId> {
+        let mut arg = self.lookup_template_argument(index)?;
+        if self.kind(arg) == Kind::TemplateArgList {
+            arg = self.index_template_argument(Some(arg), self.pack_index)?;
+        }
+        Some(arg)
+    }
+
+    fn template_param(&mut self, index: usize) -> Option<()> {
+        if self.is_lambda_arg > 0 {
+            // Parameter of a generic lambda.
+            self.push("auto:");
+            self.push_num(index + 1);
+            return Some(());
+        }
+        let arg = self.template_argument(index)?;
+        // The argument is in the scope of the template's parent.
+        let hold = self.template;
+        self.template = self.templates[hold?].1;
+        self.comp(arg)?;
+        self.template = hold;
+        Some(())
+    }
+
+    // References to references collapse, as for a template parameter whose
+    // argument is a reference.
+    fn reference(&mut self, dc: NodeId) -> Option<()> {
+        let mut sub = self.left(dc)?;
+        let mut restore = None;
+        if let (0, Kind::TemplateParam(index)) = (self.is_lambda_arg, self.kind(sub)) {
+            match self.scopes.iter().find(|scope| scope.0 == sub) {
+                None => self.scopes.push((sub, self.template)),
+                Some(&(_, template)) => {
+                    // Print it in the scope it was first printed in, unless
+                    // this is beneath it or the reference itself.
+                    let top = self.stack.len() - 1;
+                    let beneath = self
+                        .stack
+                        .iter()
+                        .enumerate()
+                        .any(|(i, &node)| node == sub || (node == dc && i != top));
+                    if !beneath {
+                        restore = Some(self.template);
+                        self.template = template;
+                    }
+                }
+            }
+            sub = self.template_argument(index)?;
+        }
+
+        let (dc, inner) = match self.kind(sub) {
+            kind if kind == Kind::Reference || kind == self.kind(dc) => (sub, self.left(sub)?),
+            Kind::RvalueReference => (dc, self.left(sub)?),
+            _ => (dc, self.left(dc)?),
+        };
+        self.modifier_comp(dc, inner)?;
+        if let Some(template) = restore {
+            self.template = template;
+        }
+        Some(())
+    }
+
+    fn function(&mut self, dc: NodeId) -> Option<()> {
+        if let Some(return_type) = self.left(dc) {
+            // The declarator goes after the return type, unless the return
+            // type is itself a function or array type which prints it.
+            let mark = self.mods.len();
+            let hold = self.modifiers;
+            let m = self.push_mod(dc);
+            self.modifiers = Some(m);
+            self.comp(return_type)?;
+            self.modifiers = hold;
+            let printed = self.mods[m].printed;
+            self.mods.truncate(mark);
+            if printed {
+                return Some(());
+            }
+            self.push(" ");
+        }
+        self.function_type(dc, self.modifiers)
+    }
+
+    fn function_type(&mut self, dc: NodeId, mods: Option<usize>) -> Option<()> {
+        let mut need_paren = false;
+        let mut need_space = false;
+        let mut pending = mods;
+        while let Some(i) = pending {
+            let m = self.mods[i];
+            if m.printed {
+                break;
+            }
+            match self.kind(m.node) {
+                Kind::Pointer | Kind::Reference | Kind::RvalueReference => need_paren = true,
+                Kind::Restrict
+                | Kind::Volatile
+                | Kind::Const
+                | Kind::VendorTypeQual
+                | Kind::Complex
+                | Kind::Imaginary
+                | Kind::PtrMemType => {
+                    need_space = true;
+                    need_paren = true;
+                }
+                _ => {}
+            }
+            if need_paren {
+                break;
+            }
+            pending = m.next;
+        }
+
+        if need_paren {
+            if !need_space && self.last_char != b'(' && self.last_char != b'*' {
+                need_space = true;
+            }
+            if need_space && self.last_char != b' ' {
+                self.push(" ");
+            }
+            self.push("(");
+        }
+        let hold = self.modifiers;
+        self.modifiers = None;
+        self.mod_list(mods, false)?;
+        if need_paren {
+            self.push(")");
+        }
+        self.push("(");
+        if let Some(params) = self.right(dc) {
+            self.comp(params)?;
+        }
+        self.push(")");
+        self.mod_list(mods, true)?;
+        self.modifiers = hold;
+        Some(())
+    }
+
+    fn array(&mut self, dc: NodeId) -> Option<()> {
+        // Qualifiers of an array apply to its elements, so they are printed
+        // after the element type, before the declarator.
+        let hold = self.modifiers;
+        let mark = self.mods.len();
+        let first = self.push_mod(dc);
+        self.modifiers = Some(first);
+        let mut copies = Vec::new();
+        let mut pending = hold;
+        while let Some(i) = pending {
+            let m = self.mods[i];
+            if !matches!(
+                self.kind(m.node),
+                Kind::Restrict | Kind::Volatile | Kind::Const
+            ) {
+                break;
+            }
+            if !m.printed {
+                if copies.len() == 3 {
+                    return None;
+                }
+                self.mods.push(Modifier {
+                    next: self.modifiers,
+                    ..m
+                });
+                let copy = self.mods.len() - 1;
+                self.modifiers = Some(copy);
+                self.mods[i].printed = true;
+                copies.push(copy);
+            }
+            pending = m.next;
+        }
+
+        self.comp(self.right(dc)?)?;
+        self.modifiers = hold;
+        if !self.mods[first].printed {
+            for &copy in copies.iter().rev() {
+                self.modifier(self.mods[copy].node)?;
+            }
+            self.array_type(dc, self.modifiers)?;
+        }
+        self.mods.truncate(mark);
+        Some(())
+    }
+
+    fn array_type(&mut self, dc: NodeId, mods: Option<usize>) -> Option<()> {
+        let mut need_space = true;
+        if mods.is_some() {
+            let mut need_paren = false;
+            let mut pending = mods;
+            while let Some(i) = pending {
+                let m = self.mods[i];
+                if !m.printed {
+                    if self.kind(m.node) == Kind::ArrayType {
+                        need_space = false;
+                    } else {
+                        need_paren = true;
+                        need_space = true;
+                    }
+                    break;
+                }
+                pending = m.next;
+            }
+            if need_paren {
+                self.push(" (");
+            }
+            self.mod_list(mods, false)?;
+            if need_paren {
+                self.push(")");
+            }
+        }
+        if need_space {
+            self.push(" ");
+        }
+        self.push("[");
+        if let Some(dimension) = self.left(dc) {
+            self.comp(dimension)?;
+        }
+        self.push("]");
+        Some(())
+    }
+
+    // Print the modifiers not printed yet, either those that go before the
+    // declarator or, if suffix, those that go after it.
+    fn mod_list(&mut self, mods: Option<usize>, suffix: bool) -> Option<()> {
+        let mut pending = mods;
+        while let Some(i) = pending {
+            let m = self.mods[i];
+            pending = m.next;
+            if m.printed || (!suffix && is_fnqual(self.kind(m.node))) {
+                continue;
+            }
+            self.mods[i].printed = true;
+            let hold = self.template;
+            self.template = m.templates;
+            let kind = self.kind(m.node);
+            match kind {
+                Kind::FunctionType => self.function_type(m.node, m.next)?,
+                Kind::ArrayType => self.array_type(m.node, m.next)?,
+                Kind::LocalName => self.local_name_modifier(m.node)?,
+                _ => self.modifier(m.node)?,
+            }
+            self.template = hold;
+            if let Kind::FunctionType | Kind::ArrayType | Kind::LocalName = kind {
+                break;
+            }
+        }
+        Some(())
+    }
+
+    // The name of a local entity, whose function's qualifiers are not those
+    // of the entity.
+    fn local_name_modifier(&mut self, dc: NodeId) -> Option<()> {
+        let hold = self.modifiers;
+        self.modifiers = None;
+        self.comp(self.left(dc)?)?;
+        self.modifiers = hold;
+        self.push("::");
+        let mut name = self.right(dc)?;
+        if let Kind::DefaultArg(number) = self.kind(name) {
+            self.default_arg(number);
+            name = self.left(name)?;
+        }
+        while is_fnqual(self.kind(name)) {
+            name = self.left(name)?;
+        }
+        self.comp(name)
+    }
+
+    fn modifier(&mut self, m: NodeId) -> Option<()> {
+        match self.kind(m) {
+            Kind::Restrict | Kind::RestrictThis => self.push(" restrict"),
+            Kind::Volatile | Kind::VolatileThis => self.push(" volatile"),
+            Kind::Const | Kind::ConstThis => self.push(" const"),
+            Kind::TransactionSafe => self.push(" transaction_safe"),
+            Kind::Noexcept => {
+                self.push(" noexcept");
+                if let Some(condition) = self.right(m) {
+                    self.push("(");
+                    self.comp(condition)?;
+                    self.push(")");
+                }
+            }
+            Kind::ThrowSpec => {
+                self.push(" throw");
+                if let Some(types) = self.right(m) {
+                    self.push("(");
+                    self.comp(types)?;
+                    self.push(")");
+                }
+            }
+            Kind::VendorTypeQual => {
+                self.push(" ");
+                self.comp(self.right(m)?)?;
+            }
+            Kind::Pointer => self.push("*"),
+            Kind::ReferenceThis => self.push(" &"),
+            Kind::Reference => self.push("&"),
+            Kind::RvalueReferenceThis => self.push(" &&"),
+            Kind::RvalueReference => self.push("&&"),
+            Kind::Complex => self.push(" _Complex"),
+            Kind::Imaginary => self.push(" _Imaginary"),
+            Kind::PtrMemType => {
+                if self.last_char != b'(' {
+                    self.push(" ");
+                }
+                self.comp(self.left(m)?)?;
+                self.push("::*");
+            }
+            Kind::TypedName => self.comp(self.left(m)?)?,
+            Kind::VectorType => {
+                self.push(" __vector(");
+                self.comp(self.left(m)?)?;
+                self.push(")");
+            }
+            _ => self.comp(m)?,
+        }
+        Some(())
+    }
+
+    fn conversion(&mut self, dc: NodeId) -> Option<()> {
+        // Template parameters in the type of a conversion operator template
+        // refer to the operator's own arguments.
+        let hold = self.template;
+        if let Some(current) = self.current_template {
+            self.template = Some(self.push_template(current));
+        }
+        let ty = self.left(dc)?;
+        if self.kind(ty) == Kind::Template {
+            self.comp(self.left(ty)?)?;
+            self.template = hold;
+            self.template_arguments(self.right(ty)?)?;
+        } else {
+            self.comp(ty)?;
+            self.template = hold;
+        }
+        Some(())
+    }
+
+    // The template argument pack that a pack expansion expands, if any, or
+    // None on error.
+    #[allow(clippy::option_option)]
+    fn find_pack(&mut self, dc: Option<NodeId>) -> Option<Option<NodeId>> {
+        let dc = match dc {
+            Some(dc) => dc,
+            None => return Some(None),
+        };
+        self.steps += 1;
+        if self.steps > MAX_STEPS {
+            return None;
+        }
+        match self.kind(dc) {
+            Kind::TemplateParam(index) => {
+                self.template?;
+                let arg = self.lookup_template_argument(index);
+                Some(arg.filter(|&arg| self.kind(arg) == Kind::TemplateArgList))
+            }
+            Kind::PackExpansion
+            | Kind::Lambda(_)
+            | Kind::Name(_)
+            | Kind::TaggedName
+            | Kind::Operator(_)
+            | Kind::Builtin(_)
+            | Kind::FloatN(..)
+            | Kind::SubStd(_)
+            | Kind::FunctionParam(_)
+            | Kind::UnnamedType(_)
+            | Kind::DefaultArg(_)
+            | Kind::Number(_) => Some(None),
+            _ => match self.find_pack(self.left(dc))? {
+                Some(pack) => Some(Some(pack)),
+                None => self.find_pack(self.right(dc)),
+            },
+        }
+    }
+
+    fn pack_length(&self, pack: Option<NodeId>) -> usize {
+        let mut len = 0;
+        let mut pack = pack;
+        while let Some(list) = pack {
+            if self.kind(list) != Kind::TemplateArgList || self.left(list).is_none() {
+                break;
+            }
+            len += 1;
+            pack = self.right(list);
+        }
+        len
+    }
+
+    // Number of arguments in the list, counting the elements of packs.
+    fn args_length(&mut self, args: NodeId) -> Option<usize> {
+        let mut len = 0;
+        let mut args = Some(args);
+        while let Some(list) = args {
+            if self.kind(list) != Kind::TemplateArgList {
+                break;
+            }
+            let arg = match self.left(list) {
+                Some(arg) => arg,
+                None => break,
+            };
+            if self.kind(arg) == Kind::PackExpansion {
+                let pack = self.find_pack(self.left(arg))?;
+                len += self.pack_length(pack);
+            } else {
+                len += 1;
+            }
+            args = self.right(list);
+        }
+        Some(len)
+    }
+
+    fn pack_expansion(&mut self, dc: NodeId) -> Option<()> {
+        let pattern = self.left(dc)?;
+        let pack = self.find_pack(Some(pattern))?;
+        if pack.is_none() {
+            // Not an expansion of a template argument pack, such as of a
+            // function parameter pack in an expression.
+            self.subexpr(pattern)?;
+            self.push("...");
+            return Some(());
+        }
+        let len = self.pack_length(pack);
+        for i in 0..len {
+            self.pack_index = Some(i);
+            self.comp(pattern)?;
+            if i + 1 < len {
+                self.push(", ");
+            }
+        }
+        Some(())
+    }
+
+    // Operand of an operator, parenthesized unless it is a name.
+    fn subexpr(&mut self, dc: NodeId) -> Option<()> {
+        let simple = matches!(
+            self.kind(dc),
+            Kind::Name(_) | Kind::QualName | Kind::InitializerList | Kind::FunctionParam(_)
+        );
+        if !simple {
+            self.push("(");
+        }
+        self.comp(dc)?;
+        if !simple {
+            self.push(")");
+        }
+        Some(())
+    }
+
+    fn expr_op(&mut self, op: NodeId) -> Option<()> {
+        match self.kind(op) {
+            Kind::Operator(info) => self.push(info.name),
+            _ => self.comp(op)?,
+        }
+        Some(())
+    }
+
+    fn operator(&self, op: NodeId) -> Option<&'static Operator> {
+        match self.kind(op) {
+            Kind::Operator(info) => Some(info),
+            _ => None,
+        }
+    }
+
+    fn unary(&mut self, dc: NodeId) -> Option<()> {
+        let op = self.left(dc)?;
+        let mut operand = self.right(dc)?;
+        let code = self.operator(op).map(|info| info.code);
+        if code.is_some() {
+            if code == Some("ad") && self.kind(operand) == Kind::TypedName {
+                // Address of a member function, without its signature.
+                let name = self.left(operand)?;
+                let function = self.right(operand)?;
+                if self.kind(name) == Kind::QualName && self.kind(function) == Kind::FunctionType {
+                    operand = name;
+                }
+            }
+            if self.kind(operand) == Kind::BinaryArgs {
+                // Postfix increment or decrement.
+                self.subexpr(self.left(operand)?)?;
+                return self.expr_op(op);
+            }
+        }
+
+        match code {
+            Some("sZ") => {
+                let pack = self.find_pack(Some(operand))?;
+                let len = self.pack_length(pack);
+                self.push_num(len);
+                return Some(());
+            }
+            Some("sP") => {
+                let len = self.args_length(operand)?;
+                self.push_num(len);
+                return Some(());
+            }
+            _ => {}
+        }
+
+        if self.kind(op) == Kind::Cast {
+            self.push("(");
+            self.comp(self.left(op)?)?;
+            self.push(")");
+        } else {
+            self.expr_op(op)?;
+        }
+        match code {
+            Some("gs") => self.comp(operand),
+            Some("st") => {
+                self.push("(");
+                self.comp(operand)?;
+                self.push(")");
+                Some(())
+            }
+            _ => self.subexpr(operand),
+        }
+    }
+
+    fn binary(&mut self, dc: NodeId) -> Option<()> {
+        let op = self.left(dc)?;
+        let info = self.operator(op)?;
+        let args = self.right(dc)?;
+        if self.kind(args) != Kind::BinaryArgs {
+            return None;
+        }
+        let left = self.left(args)?;
+        let right = self.right(args)?;
+
+        if is_new_cast(info.code) {
+            self.expr_op(op)?;
+            self.push("<");
+            self.comp(left)?;
+            self.push(">(");
+            self.comp(right)?;
+            self.push(")");
+            return Some(());
+        }
+        if self.fold(dc)? || self.designated_init(dc)? {
+            return Some(());
+        }
+
+        // Avoid a > that would end a template argument list.
+        let parens = info.name == ">";
+        if parens {
+            self.push("(");
+        }
+        if info.code == "cl" && self.kind(left) == Kind::TypedName {
+            // Call of a function named by its encoding, printed without its
+            // signature.
+            if self.kind(self.right(left)?) != Kind::FunctionType {
+                return None;
+            }
+            self.subexpr(self.left(left)?)?;
+        } else {
+            self.subexpr(left)?;
+        }
+        if info.code == "ix" {
+            self.push("[");
+            self.comp(right)?;
+            self.push("]");
+        } else {
+            if info.code != "cl" {
+                self.expr_op(op)?;
+            }
+            self.subexpr(right)?;
+        }
+        if parens {
+            self.push(")");
+        }
+        Some(())
+    }
+
+    fn trinary(&mut self, dc: NodeId) -> Option<()> {
+        let op = self.left(dc)?;
+        let info = self.operator(op)?;
+        let arg1 = self.right(dc)?;
+        let arg2 = self.right(arg1)?;
+        if self.kind(arg1) != Kind::TrinaryArg1 || self.kind(arg2) != Kind::TrinaryArg2 {
+            return None;
+        }
+        if self.fold(dc)? || self.designated_init(dc)? {
+            return Some(());
+        }
+        let first = self.left(arg1)?;
+        let second = self.left(arg2)?;
+        let third = self.right(arg2);
+        if info.code == "qu" {
+            self.subexpr(first)?;
+            self.expr_op(op)?;
+            self.subexpr(second)?;
+            self.push(" : ");
+            self.subexpr(third?)?;
+        } else {
+            self.push("new ");
+            if self.left(first).is_some() {
+                // Placement arguments.
+                self.subexpr(first)?;
+                self.push(" ");
+            }
+            self.comp(second)?;
+            if let Some(init) = third {
+                self.subexpr(init)?;
+            }
+        }
+        Some(())
+    }
+
+    // (... op x), (x op ...), or (x op ... op y), any of which expand the
+    // whole pack.
+    fn fold(&mut self, dc: NodeId) -> Option<bool> {
+        let code = self.operator(self.left(dc)?)?.code.as_bytes();
+        if code[0] != b'f' {
+            return Some(false);
+        }
+        let operands = self.right(dc)?;
+        let op = self.left(operands)?;
+        let mut first = self.right(operands)?;
+        let mut second = None;
+        if self.kind(first) == Kind::TrinaryArg2 {
+            second = self.right(first);
+            first = self.left(first)?;
+        }
+
+        let hold = self.pack_index;
+        self.pack_index = None;
+        match code[1] {
+            b'l' => {
+                self.push("(...");
+                self.expr_op(op)?;
+                self.subexpr(first)?;
+                self.push(")");
+            }
+            b'r' => {
+                self.push("(");
+                self.subexpr(first)?;
+                self.expr_op(op)?;
+                self.push("...)");
+            }
+            _ => {
+                self.push("(");
+                self.subexpr(first)?;
+                self.expr_op(op)?;
+                self.push("...");
+                self.expr_op(op)?;
+                self.subexpr(second?)?;
+                self.push(")");
+            }
+        }
+        self.pack_index = hold;
+        Some(true)
+    }
+
+    fn is_designated_init(&self, dc: NodeId) -> bool {
+        if !matches!(self.kind(dc), Kind::Binary | Kind::Trinary) {
+            return false;
+        }
+        let info = self.left(dc).and_then(|op| self.operator(op));
+        matches!(
+            info,
+            Some(Operator {
+                code: "di" | "dx" | "dX",
+                ..
+            })
+        )
+    }
+
+    // .field=x, [index]=x, or [first ... last]=x in a braced initializer.
+    fn designated_init(&mut self, dc: NodeId) -> Option<bool> {
+        if !self.is_designated_init(dc) {
+            return Some(false);
+        }
+        let code = self.operator(self.left(dc)?)?.code;
+        let operands = self.right(dc)?;
+        let designator = self.left(operands)?;
+        let mut init = self.right(operands)?;
+        self.push(if code == "di" { "." } else { "[" });
+        self.comp(designator)?;
+        if code == "dX" {
+            self.push(" ... ");
+            self.comp(self.left(init)?)?;
+            init = self.right(init)?;
+        }
+        if code != "di" {
+            self.push("]");
+        }
+        if self.is_designated_init(init) {
+            // Chained designators.
+            self.comp(init)?;
+        } else {
+            self.push("=");
+            self.subexpr(init)?;
+        }
+        Some(true)
+    }
+
+    fn literal(&mut self, dc: NodeId) -> Option<()> {
+        let ty = self.left(dc)?;
+        let value = self.right(dc)?;
+        let negative = self.kind(dc) == Kind::LiteralNeg;
+        let print = match self.kind(ty) {
+            Kind::Builtin(builtin) => builtin.print,
+            _ => Print::Default,
+        };
+        let suffix = match print {
+            Print::Int => Some(""),
+            Print::Unsigned => Some("u"),
+            Print::Long => Some("l"),
+            Print::UnsignedLong => Some("ul"),
+            Print::LongLong => Some("ll"),
+            Print::UnsignedLongLong => Some("ull"),
+            _ => None,
+        };
+        if let Some(suffix) = suffix {
+            if negative {
+                self.push("-");
+            }
+            self.comp(value)?;
+            self.push(suffix);
+            return Some(());
+        }
+        if let (Print::Bool, false, Kind::Name(value @ ("0" | "1"))) =
+            (print, negative, self.kind(value))
+        {
+            self.push(if value == "0" { "false" } else { "true" });
+            return Some(());
+        }
+
+        self.push("(");
+        self.comp(ty)?;
+        self.push(")");
+        if negative {
+            self.push("-");
+        }
+        if print == Print::Float {
+            self.push("[");
+        }
+        self.comp(value)?;
+        if print == Print::Float {
+            self.push("]");
+        }
+        Some(())
+    }
+}
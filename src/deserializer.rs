@@ -1,6 +1,9 @@
-use crate::kind::{AnyKind, Kind, SometimesBorrowedStrDeserializer};
-use crate::Node;
-use serde::de::value::BorrowedStrDeserializer;
+use crate::ancestry;
+use crate::kind::{AnyKind, Kind};
+use crate::loc::SkipTracked;
+use crate::strict;
+use crate::{Id, InnerVec, Node};
+use serde::de::value::{BoolDeserializer, BorrowedStrDeserializer};
 use serde::de::{
     Deserialize, DeserializeSeed, Deserializer, EnumAccess, Error, Expected, IgnoredAny, MapAccess,
     Unexpected, VariantAccess, Visitor,
@@ -10,31 +13,191 @@ use std::error::Error as StdError;
 use std::fmt::{self, Display};
 use std::marker::PhantomData;
 
-pub(crate) struct NodeDeserializer<'de, 'a, T, M> {
+/// Destination for the `"inner"` array of a node.
+///
+/// For `Node<T>` this is just the node's `Vec<Node<T>>`, but other
+/// representations of the tree provide their own storage for children.
+pub(crate) trait Inner<'de> {
+    /// Called with the node's kind before any of its fields or children are
+    /// deserialized.
+    fn begin_node(&mut self, kind: &AnyKind<'de>) {
+        let _ = kind;
+    }
+
+    /// Called after the node's fields and children have been deserialized.
+    fn end_node(&mut self) {}
+
+    /// Whether fields of the node that the kind does not deserialize should
+    /// still have their source locations tracked.
+    fn track_locs(&self) -> bool {
+        false
+    }
+
+    /// Whether the node's `"isImplicit"` needs to be recorded even if the
+    /// kind does not deserialize it.
+    fn track_implicit(&self) -> bool {
+        false
+    }
+
+    fn deserialize_inner<D>(&mut self, deserializer: D) -> Result<(), D::Error>
+    where
+        D: Deserializer<'de>;
+}
+
+impl<'de, T> Inner<'de> for InnerVec<Node<T>>
+where
+    T: Deserialize<'de>,
+{
+    fn deserialize_inner<D>(&mut self, deserializer: D) -> Result<(), D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        *self = InnerVec::deserialize(deserializer)?;
+        Ok(())
+    }
+}
+
+struct InnerSeed<'a, I>(&'a mut I);
+
+impl<'de, 'a, I> DeserializeSeed<'de> for InnerSeed<'a, I>
+where
+    I: Inner<'de>,
+{
+    type Value = ();
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        self.0.deserialize_inner(deserializer)
+    }
+}
+
+/// Deserialize the id and kind-specific fields of one node, routing its
+/// children into `inner`.
+pub(crate) fn visit_node<'de, M, T, I>(mut map: M, inner: &mut I) -> Result<(Id, T), M::Error>
+where
+    M: MapAccess<'de>,
+    T: Deserialize<'de>,
+    I: Inner<'de>,
+{
+    enum FirstField {
+        Id,
+        Kind,
+        Other(String),
+    }
+
+    struct FirstFieldVisitor;
+
+    impl<'de> Visitor<'de> for FirstFieldVisitor {
+        type Value = FirstField;
+
+        fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+            formatter.write_str("field identifier")
+        }
+
+        fn visit_str<E>(self, field: &str) -> Result<Self::Value, E>
+        where
+            E: Error,
+        {
+            match field {
+                "id" => Ok(FirstField::Id),
+                "kind" => Ok(FirstField::Kind),
+                _ => Ok(FirstField::Other(field.to_owned())),
+            }
+        }
+    }
+
+    impl<'de> Deserialize<'de> for FirstField {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: Deserializer<'de>,
+        {
+            deserializer.deserialize_identifier(FirstFieldVisitor)
+        }
+    }
+
+    let mut id = None;
+    let kind = loop {
+        match map.next_key()? {
+            None => {
+                let kind = AnyKind::Kind(Kind::null);
+                inner.begin_node(&kind);
+                let _ancestry = ancestry::enter(id.unwrap_or_default());
+                let deserializer = NodeDeserializer::new(&kind, inner, map);
+                let kind = T::deserialize(deserializer)?;
+                inner.end_node();
+                break kind;
+            }
+            Some(FirstField::Id) => {
+                if id.is_some() {
+                    return Err(Error::duplicate_field("id"));
+                }
+                id = Some(map.next_value()?);
+            }
+            Some(FirstField::Kind) => {
+                let kind: AnyKind = map.next_value()?;
+                inner.begin_node(&kind);
+                let _ancestry = ancestry::enter(id.unwrap_or_default());
+                let deserializer = NodeDeserializer::new(&kind, inner, map);
+                let kind = T::deserialize(deserializer)?;
+                inner.end_node();
+                break kind;
+            }
+            Some(FirstField::Other(field)) => {
+                // Clang writes "id" and "kind" first. A deserializer that
+                // reorders fields, such as one reading from a map sorted by
+                // key, also loses which location came previously.
+                return Err(Error::custom(format_args!(
+                    "node field `{}` comes before `kind`; deserialize nodes from a format that preserves the order of fields",
+                    field,
+                )));
+            }
+        }
+    };
+
+    let id = id.unwrap_or_default();
+
+    Ok((id, kind))
+}
+
+pub(crate) struct NodeDeserializer<'de, 'a, I, M> {
     kind: &'a AnyKind<'de>,
-    inner: &'a mut Vec<Node<T>>,
+    inner: &'a mut I,
     map: M,
     has_kind: bool,
+    // Whether the most recent key was "isImplicit", or None if the inner does
+    // not need that tracked.
+    is_implicit: Option<bool>,
 }
 
-impl<'de, 'a, T, M> NodeDeserializer<'de, 'a, T, M> {
-    pub(crate) fn new(kind: &'a AnyKind<'de>, inner: &'a mut Vec<Node<T>>, map: M) -> Self {
+impl<'de, 'a, I, M> NodeDeserializer<'de, 'a, I, M>
+where
+    I: Inner<'de>,
+{
+    pub(crate) fn new(kind: &'a AnyKind<'de>, inner: &'a mut I, map: M) -> Self {
         let has_kind = match kind {
             AnyKind::Kind(Kind::null) => false,
             _ => true,
         };
+        let is_implicit = if inner.track_implicit() {
+            Some(false)
+        } else {
+            None
+        };
         NodeDeserializer {
             kind,
             inner,
             map,
             has_kind,
+            is_implicit,
         }
     }
 }
 
-impl<'de, 'a, T, M> Deserializer<'de> for NodeDeserializer<'de, 'a, T, M>
+impl<'de, 'a, I, M> Deserializer<'de> for NodeDeserializer<'de, 'a, I, M>
 where
-    T: Deserialize<'de>,
+    I: Inner<'de>,
     M: MapAccess<'de>,
 {
     type Error = M::Error;
@@ -66,6 +229,14 @@ where
             }
         }
         if let Some(unexpected) = expects_the_unexpected {
+            if strict::is_active() {
+                let (id, _depth) = ancestry::current();
+                return Err(Error::custom(format_args!(
+                    "unknown node kind `{}` with id {}",
+                    self.kind,
+                    id.unwrap_or_default(),
+                )));
+            }
             visitor.visit_enum(UnknownNode {
                 name: unexpected,
                 node: self,
@@ -102,9 +273,9 @@ where
     }
 }
 
-impl<'de, 'a, T, M> EnumAccess<'de> for NodeDeserializer<'de, 'a, T, M>
+impl<'de, 'a, I, M> EnumAccess<'de> for NodeDeserializer<'de, 'a, I, M>
 where
-    T: Deserialize<'de>,
+    I: Inner<'de>,
     M: MapAccess<'de>,
 {
     type Error = M::Error;
@@ -114,19 +285,15 @@ where
     where
         V: DeserializeSeed<'de>,
     {
-        let deserializer = match &self.kind {
-            AnyKind::Kind(kind) => SometimesBorrowedStrDeserializer::borrowed(kind.as_str()),
-            AnyKind::Borrowed(kind) => SometimesBorrowedStrDeserializer::borrowed(kind),
-            AnyKind::Owned(kind) => SometimesBorrowedStrDeserializer::transient(kind),
-        };
+        let deserializer = self.kind.deserializer();
         let value = seed.deserialize(deserializer)?;
         Ok((value, self))
     }
 }
 
-impl<'de, 'a, T, M> VariantAccess<'de> for NodeDeserializer<'de, 'a, T, M>
+impl<'de, 'a, I, M> VariantAccess<'de> for NodeDeserializer<'de, 'a, I, M>
 where
-    T: Deserialize<'de>,
+    I: Inner<'de>,
     M: MapAccess<'de>,
 {
     type Error = M::Error;
@@ -171,9 +338,9 @@ where
     }
 }
 
-impl<'de, 'a, T, M> MapAccess<'de> for NodeDeserializer<'de, 'a, T, M>
+impl<'de, 'a, I, M> MapAccess<'de> for NodeDeserializer<'de, 'a, I, M>
 where
-    T: Deserialize<'de>,
+    I: Inner<'de>,
     M: MapAccess<'de>,
 {
     type Error = M::Error;
@@ -189,11 +356,12 @@ where
             loop {
                 seed = match self.map.next_key_seed(NodeFieldSeed {
                     kind: self.kind,
+                    is_implicit: &mut self.is_implicit,
                     seed,
                 })? {
                     None => return Ok(None),
                     Some(NodeField::Inner(seed)) => {
-                        *self.inner = self.map.next_value()?;
+                        self.map.next_value_seed(InnerSeed(&mut *self.inner))?;
                         seed
                     }
                     Some(NodeField::Delegate(value)) => return Ok(Some(value)),
@@ -207,36 +375,46 @@ where
         V: DeserializeSeed<'de>,
     {
         if self.has_kind {
-            let deserializer = match &self.kind {
-                AnyKind::Kind(kind) => SometimesBorrowedStrDeserializer::borrowed(kind.as_str()),
-                AnyKind::Borrowed(kind) => SometimesBorrowedStrDeserializer::borrowed(kind),
-                AnyKind::Owned(kind) => SometimesBorrowedStrDeserializer::transient(kind),
-            };
+            let deserializer = self.kind.deserializer();
             let value = seed.deserialize(deserializer);
             self.has_kind = false;
             value
         } else {
-            self.map.next_value_seed(seed)
+            self.next_field_value_seed(seed)
         }
     }
 }
 
-impl<'de, 'a, T, M> NodeDeserializer<'de, 'a, T, M>
+impl<'de, 'a, I, M> NodeDeserializer<'de, 'a, I, M>
 where
-    T: Deserialize<'de>,
+    I: Inner<'de>,
     M: MapAccess<'de>,
 {
+    fn next_field_value_seed<V>(&mut self, seed: V) -> Result<V::Value, M::Error>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        if self.is_implicit == Some(true) {
+            self.map.next_value_seed(TrackImplicit(seed))
+        } else if self.inner.track_locs() {
+            self.map.next_value_seed(TrackLocs(seed))
+        } else {
+            self.map.next_value_seed(seed)
+        }
+    }
+
     fn ignore(&mut self) -> Result<(), M::Error> {
         while let Some(node_field) = self.map.next_key_seed(NodeFieldSeed {
             kind: self.kind,
+            is_implicit: &mut self.is_implicit,
             seed: PhantomData::<IgnoredAny>,
         })? {
             match node_field {
                 NodeField::Inner(PhantomData) => {
-                    *self.inner = self.map.next_value()?;
+                    self.map.next_value_seed(InnerSeed(&mut *self.inner))?;
                 }
                 NodeField::Delegate(IgnoredAny) => {
-                    let _: IgnoredAny = self.map.next_value()?;
+                    let IgnoredAny = self.next_field_value_seed(PhantomData)?;
                 }
             }
         }
@@ -244,14 +422,119 @@ where
     }
 }
 
-struct UnknownNode<'de, 'a, T, M> {
+// Seed for a field value which replaces ignoring the value with skipping it
+// in a way that keeps track of source locations.
+struct TrackLocs<S>(S);
+
+impl<'de, S> DeserializeSeed<'de> for TrackLocs<S>
+where
+    S: DeserializeSeed<'de>,
+{
+    type Value = S::Value;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        self.0.deserialize(TrackLocs(deserializer))
+    }
+}
+
+// Seed for the value of "isImplicit", which records it in the node's
+// ancestry frame before handing it to the kind.
+struct TrackImplicit<S>(S);
+
+impl<'de, S> DeserializeSeed<'de> for TrackImplicit<S>
+where
+    S: DeserializeSeed<'de>,
+{
+    type Value = S::Value;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let is_implicit = bool::deserialize(deserializer)?;
+        if is_implicit {
+            ancestry::record_implicit();
+        }
+        self.0.deserialize(BoolDeserializer::new(is_implicit))
+    }
+}
+
+macro_rules! forward_deserialize {
+    ($($method:ident ( $($arg:ident : $ty:ty),* ))*) => {
+        $(
+            fn $method<V>(self, $($arg: $ty,)* visitor: V) -> Result<V::Value, Self::Error>
+            where
+                V: Visitor<'de>,
+            {
+                self.0.$method($($arg,)* visitor)
+            }
+        )*
+    };
+}
+
+impl<'de, D> Deserializer<'de> for TrackLocs<D>
+where
+    D: Deserializer<'de>,
+{
+    type Error = D::Error;
+
+    fn deserialize_ignored_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        SkipTracked.deserialize(self.0)?;
+        visitor.visit_unit()
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.0.is_human_readable()
+    }
+
+    forward_deserialize! {
+        deserialize_any()
+        deserialize_bool()
+        deserialize_i8()
+        deserialize_i16()
+        deserialize_i32()
+        deserialize_i64()
+        deserialize_i128()
+        deserialize_u8()
+        deserialize_u16()
+        deserialize_u32()
+        deserialize_u64()
+        deserialize_u128()
+        deserialize_f32()
+        deserialize_f64()
+        deserialize_char()
+        deserialize_str()
+        deserialize_string()
+        deserialize_bytes()
+        deserialize_byte_buf()
+        deserialize_option()
+        deserialize_unit()
+        deserialize_unit_struct(name: &'static str)
+        deserialize_newtype_struct(name: &'static str)
+        deserialize_seq()
+        deserialize_tuple(len: usize)
+        deserialize_tuple_struct(name: &'static str, len: usize)
+        deserialize_map()
+        deserialize_struct(name: &'static str, fields: &'static [&'static str])
+        deserialize_enum(name: &'static str, variants: &'static [&'static str])
+        deserialize_identifier()
+    }
+}
+
+struct UnknownNode<'de, 'a, I, M> {
     name: &'static str,
-    node: NodeDeserializer<'de, 'a, T, M>,
+    node: NodeDeserializer<'de, 'a, I, M>,
 }
 
-impl<'de, 'a, T, M> EnumAccess<'de> for UnknownNode<'de, 'a, T, M>
+impl<'de, 'a, I, M> EnumAccess<'de> for UnknownNode<'de, 'a, I, M>
 where
-    T: Deserialize<'de>,
+    I: Inner<'de>,
     M: MapAccess<'de>,
 {
     type Error = M::Error;
@@ -267,9 +550,9 @@ where
     }
 }
 
-impl<'de, 'a, T, M> VariantAccess<'de> for UnknownNode<'de, 'a, T, M>
+impl<'de, 'a, I, M> VariantAccess<'de> for UnknownNode<'de, 'a, I, M>
 where
-    T: Deserialize<'de>,
+    I: Inner<'de>,
     M: MapAccess<'de>,
 {
     type Error = M::Error;
@@ -305,13 +588,13 @@ where
     }
 }
 
-struct NodeFieldsDeserializer<'de, 'a, T, M> {
-    node: NodeDeserializer<'de, 'a, T, M>,
+struct NodeFieldsDeserializer<'de, 'a, I, M> {
+    node: NodeDeserializer<'de, 'a, I, M>,
 }
 
-impl<'de, 'a, T, M> Deserializer<'de> for NodeFieldsDeserializer<'de, 'a, T, M>
+impl<'de, 'a, I, M> Deserializer<'de> for NodeFieldsDeserializer<'de, 'a, I, M>
 where
-    T: Deserialize<'de>,
+    I: Inner<'de>,
     M: MapAccess<'de>,
 {
     type Error = M::Error;
@@ -370,9 +653,9 @@ where
     }
 }
 
-impl<'de, 'a, T, M> MapAccess<'de> for NodeFieldsDeserializer<'de, 'a, T, M>
+impl<'de, 'a, I, M> MapAccess<'de> for NodeFieldsDeserializer<'de, 'a, I, M>
 where
-    T: Deserialize<'de>,
+    I: Inner<'de>,
     M: MapAccess<'de>,
 {
     type Error = FieldOfKindError<M::Error>;
@@ -387,16 +670,16 @@ where
                 .map
                 .next_key_seed(NodeFieldSeed {
                     kind: self.node.kind,
+                    is_implicit: &mut self.node.is_implicit,
                     seed,
                 })
                 .map_err(FieldOfKindError::Other)?
             {
                 None => return Ok(None),
                 Some(NodeField::Inner(seed)) => {
-                    *self.node.inner = self
-                        .node
+                    self.node
                         .map
-                        .next_value()
+                        .next_value_seed(InnerSeed(&mut *self.node.inner))
                         .map_err(FieldOfKindError::Other)?;
                     seed
                 }
@@ -410,20 +693,19 @@ where
         V: DeserializeSeed<'de>,
     {
         self.node
-            .map
-            .next_value_seed(seed)
+            .next_field_value_seed(seed)
             .map_err(FieldOfKindError::Other)
     }
 }
 
-struct NodeEnumDeserializer<'de, 'a, T, M> {
+struct NodeEnumDeserializer<'de, 'a, I, M> {
     name: &'static str,
-    node: NodeDeserializer<'de, 'a, T, M>,
+    node: NodeDeserializer<'de, 'a, I, M>,
 }
 
-impl<'de, 'a, T, M> EnumAccess<'de> for NodeEnumDeserializer<'de, 'a, T, M>
+impl<'de, 'a, I, M> EnumAccess<'de> for NodeEnumDeserializer<'de, 'a, I, M>
 where
-    T: Deserialize<'de>,
+    I: Inner<'de>,
     M: MapAccess<'de>,
 {
     type Error = M::Error;
@@ -436,6 +718,7 @@ where
         loop {
             seed = match self.node.map.next_key_seed(NodeFieldSeed {
                 kind: self.node.kind,
+                is_implicit: &mut self.node.is_implicit,
                 seed,
             })? {
                 None => {
@@ -443,7 +726,9 @@ where
                     return Err(Error::invalid_type(Unexpected::Map, &expected));
                 }
                 Some(NodeField::Inner(seed)) => {
-                    *self.node.inner = self.node.map.next_value()?;
+                    self.node
+                        .map
+                        .next_value_seed(InnerSeed(&mut *self.node.inner))?;
                     seed
                 }
                 Some(NodeField::Delegate(value)) => return Ok((value, self)),
@@ -452,9 +737,9 @@ where
     }
 }
 
-impl<'de, 'a, T, M> VariantAccess<'de> for NodeEnumDeserializer<'de, 'a, T, M>
+impl<'de, 'a, I, M> VariantAccess<'de> for NodeEnumDeserializer<'de, 'a, I, M>
 where
-    T: Deserialize<'de>,
+    I: Inner<'de>,
     M: MapAccess<'de>,
 {
     type Error = M::Error;
@@ -468,15 +753,18 @@ where
     where
         V: DeserializeSeed<'de>,
     {
-        let value = self.node.map.next_value_seed(seed)?;
+        let value = self.node.next_field_value_seed(seed)?;
         loop {
             match self.node.map.next_key_seed(NodeFieldSeed {
                 kind: self.node.kind,
+                is_implicit: &mut self.node.is_implicit,
                 seed: PhantomData::<UnexpectedField>,
             })? {
                 None => return Ok(value),
                 Some(NodeField::Inner(PhantomData)) => {
-                    *self.node.inner = self.node.map.next_value()?;
+                    self.node
+                        .map
+                        .next_value_seed(InnerSeed(&mut *self.node.inner))?;
                 }
                 Some(NodeField::Delegate(unexpected)) => match unexpected {},
             }
@@ -508,8 +796,10 @@ where
     }
 }
 
-struct NodeFieldSeed<'a, K> {
+struct NodeFieldSeed<'a, 'b, K> {
     kind: &'a AnyKind<'a>,
+    // Set to whether the field is "isImplicit", if tracked.
+    is_implicit: &'b mut Option<bool>,
     seed: K,
 }
 
@@ -518,7 +808,7 @@ enum NodeField<K, X> {
     Delegate(X),
 }
 
-impl<'de, 'a, K> DeserializeSeed<'de> for NodeFieldSeed<'a, K>
+impl<'de, 'a, 'b, K> DeserializeSeed<'de> for NodeFieldSeed<'a, 'b, K>
 where
     K: DeserializeSeed<'de>,
 {
@@ -532,7 +822,7 @@ where
     }
 }
 
-impl<'de, 'a, K> Visitor<'de> for NodeFieldSeed<'a, K>
+impl<'de, 'a, 'b, K> Visitor<'de> for NodeFieldSeed<'a, 'b, K>
 where
     K: DeserializeSeed<'de>,
 {
@@ -546,6 +836,9 @@ where
     where
         E: Error,
     {
+        if let Some(is_implicit) = self.is_implicit {
+            *is_implicit = identifier == "isImplicit";
+        }
         match identifier {
             "inner" => Ok(NodeField::Inner(self.seed)),
             other => match self.seed.deserialize(FieldOfKindDeserializer {
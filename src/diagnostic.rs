@@ -0,0 +1,543 @@
+use crate::intern::Shared;
+use crate::{LineIndex, PositionEncoding, SourceManager, SourceRange};
+use std::fmt::{self, Display, Write};
+use std::io;
+use std::iter;
+use std::ops::Range;
+
+#[cfg(feature = "codespan-reporting")]
+use codespan_reporting::files;
+
+/// Message about some source code, rendered together with excerpts of the
+/// source it refers to.
+///
+/// Each label attaches a message to a [`SourceRange`]. Rendering through a
+/// [`SourceManager`] quotes the lines of source covered by the labels and
+/// underlines each range, with `^` for primary labels and `-` for secondary
+/// ones.
+///
+/// That built-in renderer produces plain text with no dependencies. For
+/// colored output and the layout of a dedicated diagnostics crate, the
+/// `"codespan-reporting"` and `"ariadne"` features provide conversions into
+/// the diagnostics of those crates.
+///
+/// ```
+/// use clang_ast::{Diagnostic, SourceManager, SourceRange};
+///
+/// let mut sources = SourceManager::new();
+/// sources.insert("main.c", "int f() {\n  return 42;\n}\n".to_owned());
+///
+/// # let json = r#"{
+/// #   "begin": { "offset": 19, "file": "main.c", "line": 2, "col": 10, "tokLen": 2 },
+/// #   "end": { "offset": 19, "col": 10, "tokLen": 2 }
+/// # }"#;
+/// let range: SourceRange = serde_json::from_str(json)?;
+/// let rendered = Diagnostic::warning("magic number")
+///     .primary(&range, "consider a named constant")
+///     .note("found by the magic-numbers lint")
+///     .render(&sources)?;
+///
+/// assert_eq!(
+///     rendered,
+///     "\
+/// warning: magic number
+///  --> main.c:2:10
+///   |
+/// 2 |   return 42;
+///   |          ^^ consider a named constant
+///   |
+///   = note: found by the magic-numbers lint
+/// ",
+/// );
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Clone, Debug)]
+pub struct Diagnostic {
+    severity: Severity,
+    message: String,
+    labels: Vec<Label>,
+    notes: Vec<String>,
+}
+
+/// Kind of [`Diagnostic`], using Clang's terms.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Severity {
+    Error,
+    Warning,
+    Note,
+    Remark,
+}
+
+#[derive(Clone, Debug)]
+struct Label {
+    // File and byte range, or none if the range is not within a single file.
+    span: Option<(Shared<str>, Range<usize>)>,
+    message: String,
+    primary: bool,
+}
+
+struct File<'a> {
+    name: &'a str,
+    contents: &'a str,
+    index: LineIndex<'a>,
+}
+
+// A label located within the text of its file.
+struct Located<'a> {
+    label: &'a Label,
+    // Index into the files being rendered.
+    file: usize,
+    // Zero-based line and byte column of either end.
+    begin: (usize, usize),
+    end: (usize, usize),
+}
+
+impl Diagnostic {
+    pub fn new(severity: Severity, message: impl Into<String>) -> Self {
+        Diagnostic {
+            severity,
+            message: message.into(),
+            labels: Vec::new(),
+            notes: Vec::new(),
+        }
+    }
+
+    pub fn error(message: impl Into<String>) -> Self {
+        Diagnostic::new(Severity::Error, message)
+    }
+
+    pub fn warning(message: impl Into<String>) -> Self {
+        Diagnostic::new(Severity::Warning, message)
+    }
+
+    /// Point at the code the diagnostic is about. The first primary label
+    /// determines the location shown in the diagnostic's header.
+    pub fn primary(&mut self, range: &SourceRange, message: impl Into<String>) -> &mut Self {
+        self.label(range, message.into(), true)
+    }
+
+    /// Point at code related to the primary labels.
+    pub fn secondary(&mut self, range: &SourceRange, message: impl Into<String>) -> &mut Self {
+        self.label(range, message.into(), false)
+    }
+
+    /// Add a line of text after the source excerpts.
+    pub fn note(&mut self, message: impl Into<String>) -> &mut Self {
+        self.notes.push(message.into());
+        self
+    }
+
+    fn label(&mut self, range: &SourceRange, message: String, primary: bool) -> &mut Self {
+        let span = match (range.begin.expansion(), range.byte_range()) {
+            (Some(begin), Some(bytes)) => Some((Shared::clone(&begin.file), bytes)),
+            _ => None,
+        };
+        self.labels.push(Label {
+            span,
+            message,
+            primary,
+        });
+        self
+    }
+
+    /// Render as plain text, reading the source from `sources`.
+    ///
+    /// # Errors
+    ///
+    /// Fails if a labeled file cannot be read, or if the range of a label
+    /// does not denote a span of text within a single file. Ranges are
+    /// located by their expansion location, as in [`SourceManager::text`].
+    pub fn render(&self, sources: &SourceManager) -> io::Result<String> {
+        let mut files = Vec::new();
+        let mut located = Vec::new();
+        for label in &self.labels {
+            located.push(locate(label, sources, &mut files)?);
+        }
+
+        // Files in order of their first label, but with that of the first
+        // primary label first.
+        let mut order: Vec<usize> = (0..files.len()).collect();
+        if let Some(first) = located.iter().find(|located| located.label.primary) {
+            order.retain(|&file| file != first.file);
+            order.insert(0, first.file);
+        }
+
+        let last_line = located.iter().map(|located| located.end.0 + 1).max();
+        let width = last_line.unwrap_or(0).to_string().len();
+
+        let mut out = String::new();
+        let _ = writeln!(out, "{}: {}", self.severity, self.message);
+        for (n, &file) in order.iter().enumerate() {
+            let mut labels: Vec<&Located> = located
+                .iter()
+                .filter(|located| located.file == file)
+                .collect();
+            if n == 0 {
+                // The first primary label, or else the first label, sorts
+                // first so as to be the location in the header.
+                labels.sort_by_key(|located| !located.label.primary);
+            }
+            let arrow = if n == 0 { "-->" } else { ":::" };
+            render_file(&mut out, &files[file], &mut labels, arrow, width);
+        }
+
+        let pad = " ".repeat(width);
+        if !self.notes.is_empty() {
+            let _ = writeln!(out, "{} |", pad);
+        }
+        for note in &self.notes {
+            let _ = writeln!(out, "{} = note: {}", pad, note);
+        }
+        Ok(out)
+    }
+}
+
+// Both codespan-reporting and ariadne show files in the order of their first
+// label, and the location of the first label in each file.
+#[cfg(any(feature = "codespan-reporting", feature = "ariadne"))]
+impl Diagnostic {
+    fn primary_first(&self) -> impl Iterator<Item = &Label> {
+        let primary = self.labels.iter().filter(|label| label.primary);
+        let secondary = self.labels.iter().filter(|label| !label.primary);
+        primary.chain(secondary)
+    }
+}
+
+fn locate<'a>(
+    label: &'a Label,
+    sources: &'a SourceManager,
+    files: &mut Vec<File<'a>>,
+) -> io::Result<Located<'a>> {
+    let (name, bytes) = if let Some((name, bytes)) = &label.span {
+        (&**name, bytes)
+    } else {
+        let msg = "source range has no position within a single file";
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, msg));
+    };
+
+    let file = if let Some(file) = files.iter().position(|file| file.name == name) {
+        file
+    } else {
+        let contents = sources.file(name)?;
+        files.push(File {
+            name,
+            contents,
+            index: LineIndex::new(contents),
+        });
+        files.len() - 1
+    };
+
+    let index = &files[file].index;
+    if let (Some(begin), Some(end)) = (
+        index.position(bytes.start, PositionEncoding::Utf8),
+        index.position(bytes.end, PositionEncoding::Utf8),
+    ) {
+        Ok(Located {
+            label,
+            file,
+            begin: (begin.line, begin.col),
+            end: (end.line, end.col),
+        })
+    } else {
+        let msg = format!("source range is out of bounds of {}", name);
+        Err(io::Error::new(io::ErrorKind::InvalidInput, msg))
+    }
+}
+
+// Excerpt of one file, with the labels in it. The first label is the one
+// whose location goes in the header.
+fn render_file(out: &mut String, file: &File, labels: &mut [&Located], arrow: &str, width: usize) {
+    let pad = " ".repeat(width);
+    let (line, col) = labels[0].begin;
+    let _ = writeln!(
+        out,
+        "{}{} {}:{}:{}",
+        pad,
+        arrow,
+        file.name,
+        line + 1,
+        col + 1
+    );
+    let _ = writeln!(out, "{} |", pad);
+    labels.sort_by_key(|located| (located.begin, located.end));
+
+    let mut lines: Vec<usize> = labels
+        .iter()
+        .flat_map(|located| iter::once(located.begin.0).chain(iter::once(located.end.0)))
+        .collect();
+    lines.sort_unstable();
+    lines.dedup();
+
+    let mut previous = None;
+    for &line in &lines {
+        if let Some(previous) = previous {
+            if line > previous + 1 {
+                out.push_str("...\n");
+            }
+        }
+        previous = Some(line);
+        let text = file
+            .index
+            .line_range(line)
+            .map_or("", |range| &file.contents[range]);
+        let text = text.strip_suffix('\r').unwrap_or(text);
+        let _ = writeln!(
+            out,
+            "{:<width$} | {}",
+            line + 1,
+            expand_tabs(text),
+            width = width
+        );
+        for located in labels.iter() {
+            if let Some(cols) = underline(located, line, text) {
+                let prefix = display_width(&text[..cols.start]);
+                let len = display_width(&text[cols]).max(1);
+                let mark = if located.label.primary { "^" } else { "-" };
+                let _ = write!(out, "{} | {}{}", pad, " ".repeat(prefix), mark.repeat(len));
+                if located.end.0 == line && !located.label.message.is_empty() {
+                    let _ = write!(out, " {}", located.label.message);
+                }
+                out.push('\n');
+            }
+        }
+    }
+}
+
+// Byte columns of a line to underline for a label. A label spanning several
+// lines is underlined to the end of its first line, and from the first
+// non-blank character of its last line.
+fn underline(located: &Located, line: usize, text: &str) -> Option<Range<usize>> {
+    let (begin, end) = (located.begin, located.end);
+    // A range ending in a line's "\r", which is not shown, stops short of it.
+    let end_col = end.1.min(text.len());
+    if line == begin.0 && line == end.0 {
+        Some(begin.1.min(end_col)..end_col)
+    } else if line == begin.0 {
+        Some(begin.1.min(text.len())..text.len())
+    } else if line == end.0 {
+        let indent = text.len() - text.trim_start().len();
+        Some(indent.min(end_col)..end_col)
+    } else {
+        None
+    }
+}
+
+const TAB_WIDTH: usize = 4;
+
+fn expand_tabs(text: &str) -> String {
+    text.replace('\t', &" ".repeat(TAB_WIDTH))
+}
+
+fn display_width(text: &str) -> usize {
+    text.chars()
+        .map(|ch| if ch == '\t' { TAB_WIDTH } else { 1 })
+        .sum()
+}
+
+impl Display for Severity {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str(match self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+            Severity::Note => "note",
+            Severity::Remark => "remark",
+        })
+    }
+}
+
+#[cfg(feature = "codespan-reporting")]
+impl Diagnostic {
+    /// Convert into a diagnostic of the codespan-reporting crate.
+    ///
+    /// Its file ids are the filepaths of the labels, which [`SourceManager`]
+    /// looks up as codespan-reporting's `Files`. A label whose range does
+    /// not denote a span of text within a single file becomes a note.
+    ///
+    /// ```
+    /// use clang_ast::{Diagnostic, SourceManager, SourceRange};
+    /// use codespan_reporting::term;
+    ///
+    /// let mut sources = SourceManager::new();
+    /// sources.insert("main.c", "int f() {\n  return 42;\n}\n".to_owned());
+    ///
+    /// # let json = r#"{
+    /// #   "begin": { "offset": 19, "file": "main.c", "line": 2, "col": 10, "tokLen": 2 },
+    /// #   "end": { "offset": 19, "col": 10, "tokLen": 2 }
+    /// # }"#;
+    /// let range: SourceRange = serde_json::from_str(json)?;
+    /// let mut diagnostic = Diagnostic::warning("magic number");
+    /// diagnostic.primary(&range, "consider a named constant");
+    ///
+    /// let config = term::Config::default();
+    /// let rendered = term::emit_into_string(&config, &sources, &diagnostic.to_codespan())?;
+    /// assert!(rendered.starts_with("warning: magic number"));
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn to_codespan(&self) -> codespan_reporting::diagnostic::Diagnostic<&str> {
+        use codespan_reporting::diagnostic::Label as CodespanLabel;
+
+        let mut diagnostic = codespan_reporting::diagnostic::Diagnostic::new(self.severity.into())
+            .with_message(&self.message);
+        for label in self.primary_first() {
+            if let Some((file, bytes)) = &label.span {
+                let label = if label.primary {
+                    CodespanLabel::primary(&**file, bytes.clone())
+                } else {
+                    CodespanLabel::secondary(&**file, bytes.clone())
+                }
+                .with_message(&label.message);
+                diagnostic.labels.push(label);
+            } else {
+                diagnostic.notes.push(label.message.clone());
+            }
+        }
+        diagnostic.notes.extend(self.notes.iter().cloned());
+        diagnostic
+    }
+}
+
+#[cfg(feature = "codespan-reporting")]
+impl From<Severity> for codespan_reporting::diagnostic::Severity {
+    fn from(severity: Severity) -> Self {
+        match severity {
+            Severity::Error => codespan_reporting::diagnostic::Severity::Error,
+            Severity::Warning => codespan_reporting::diagnostic::Severity::Warning,
+            Severity::Note | Severity::Remark => codespan_reporting::diagnostic::Severity::Note,
+        }
+    }
+}
+
+// Files are identified by their path, as in source locations. Lines are
+// indexed afresh for each lookup, which codespan-reporting makes only a few
+// of per label.
+#[cfg(feature = "codespan-reporting")]
+impl<'a> files::Files<'a> for SourceManager {
+    type FileId = &'a str;
+    type Name = &'a str;
+    type Source = &'a str;
+
+    fn name(&'a self, file: &'a str) -> Result<&'a str, files::Error> {
+        Ok(file)
+    }
+
+    fn source(&'a self, file: &'a str) -> Result<&'a str, files::Error> {
+        Ok(self.file(file)?)
+    }
+
+    fn line_index(&'a self, file: &'a str, byte_index: usize) -> Result<usize, files::Error> {
+        let contents = self.file(file)?;
+        match LineIndex::new(contents).position(byte_index, PositionEncoding::Utf8) {
+            Some(position) => Ok(position.line),
+            None if byte_index > contents.len() => Err(files::Error::IndexTooLarge {
+                given: byte_index,
+                max: contents.len(),
+            }),
+            None => Err(files::Error::InvalidCharBoundary { given: byte_index }),
+        }
+    }
+
+    fn line_range(
+        &'a self,
+        file: &'a str,
+        line_index: usize,
+    ) -> Result<Range<usize>, files::Error> {
+        let contents = self.file(file)?;
+        let index = LineIndex::new(contents);
+        match index.line_range(line_index) {
+            // Including the newline, as codespan-reporting expects.
+            Some(range) if range.end < contents.len() => Ok(range.start..range.end + 1),
+            Some(range) => Ok(range),
+            None => Err(files::Error::LineTooLarge {
+                given: line_index,
+                max: index.line_count() - 1,
+            }),
+        }
+    }
+}
+
+#[cfg(feature = "ariadne")]
+impl Diagnostic {
+    /// Convert into a report of the ariadne crate.
+    ///
+    /// Its spans pair the filepath of a label with the label's byte range.
+    /// Print it using the cache returned by
+    /// [`ariadne_sources`][Diagnostic::ariadne_sources]. A label whose range
+    /// does not denote a span of text within a single file becomes a note.
+    ///
+    /// ```
+    /// use clang_ast::{Diagnostic, SourceManager, SourceRange};
+    ///
+    /// let mut sources = SourceManager::new();
+    /// sources.insert("main.c", "int f() {\n  return 42;\n}\n".to_owned());
+    ///
+    /// # let json = r#"{
+    /// #   "begin": { "offset": 19, "file": "main.c", "line": 2, "col": 10, "tokLen": 2 },
+    /// #   "end": { "offset": 19, "col": 10, "tokLen": 2 }
+    /// # }"#;
+    /// let range: SourceRange = serde_json::from_str(json)?;
+    /// let mut diagnostic = Diagnostic::warning("magic number");
+    /// diagnostic.primary(&range, "consider a named constant");
+    ///
+    /// let report = diagnostic.to_ariadne();
+    /// let mut rendered = Vec::new();
+    /// report.write(diagnostic.ariadne_sources(&sources)?, &mut rendered)?;
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn to_ariadne(&self) -> ariadne::Report<'static, (Shared<str>, Range<usize>)> {
+        // The report's location is that of the first primary label, or else
+        // the first label.
+        let span = self
+            .primary_first()
+            .find_map(|label| label.span.clone())
+            .unwrap_or_else(|| (Shared::from(""), 0..0));
+        let config = ariadne::Config::default().with_index_type(ariadne::IndexType::Byte);
+
+        let mut report = ariadne::Report::build(self.severity.into(), span)
+            .with_config(config)
+            .with_message(&self.message);
+        for label in self.primary_first() {
+            if let Some(span) = &label.span {
+                let mut ariadne_label = ariadne::Label::new(span.clone());
+                if !label.message.is_empty() {
+                    ariadne_label = ariadne_label.with_message(&label.message);
+                }
+                if label.primary {
+                    ariadne_label = ariadne_label.with_priority(1);
+                }
+                report.add_label(ariadne_label);
+            } else {
+                report.add_note(&label.message);
+            }
+        }
+        report.with_notes(&self.notes);
+        report.finish()
+    }
+
+    /// Cache holding the text of each file the labels are in, for printing
+    /// the report from [`to_ariadne`][Diagnostic::to_ariadne].
+    ///
+    /// # Errors
+    ///
+    /// Fails if a labeled file cannot be read.
+    pub fn ariadne_sources<'a>(
+        &self,
+        sources: &'a SourceManager,
+    ) -> io::Result<impl ariadne::Cache<Shared<str>> + 'a> {
+        let mut files = Vec::new();
+        for (file, _bytes) in self.labels.iter().filter_map(|label| label.span.as_ref()) {
+            files.push((Shared::clone(file), sources.file(file)?));
+        }
+        Ok(ariadne::sources(files))
+    }
+}
+
+#[cfg(feature = "ariadne")]
+impl From<Severity> for ariadne::ReportKind<'static> {
+    fn from(severity: Severity) -> Self {
+        match severity {
+            Severity::Error => ariadne::ReportKind::Error,
+            Severity::Warning => ariadne::ReportKind::Warning,
+            Severity::Note | Severity::Remark => ariadne::ReportKind::Advice,
+        }
+    }
+}
@@ -0,0 +1,174 @@
+//! Comparing two syntax trees as an edit script.
+//!
+//! Node ids differ between compiler runs, so [`diff`] instead matches the
+//! children of corresponding nodes by a key computed from their kinds, such
+//! as the kind of declaration together with its name. Children with equal
+//! keys are matched up in order. Whatever is left over on the old side was
+//! removed, and on the new side inserted.
+//!
+//! ```
+//! use clang_ast::diff::{self, Edit};
+//! use clang_ast::Kind;
+//! use serde::Deserialize;
+//!
+//! #[derive(Deserialize, PartialEq)]
+//! pub struct Clang {
+//!     pub kind: Kind,
+//!     pub name: Option<String>,
+//! }
+//!
+//! # let old = r#"{"id":"0x1","kind":"TranslationUnitDecl","inner":[{"id":"0x2","kind":"FunctionDecl","name":"f"}]}"#;
+//! # let new = r#"{"id":"0x9","kind":"TranslationUnitDecl","inner":[{"id":"0x8","kind":"FunctionDecl","name":"g"}]}"#;
+//! # let old: clang_ast::Node<Clang> = serde_json::from_str(old).unwrap();
+//! # let new: clang_ast::Node<Clang> = serde_json::from_str(new).unwrap();
+//! // Report public API changes between two runs:
+//! for edit in diff::diff(&old, &new, |clang| (clang.kind.clone(), clang.name.as_deref())) {
+//!     match edit {
+//!         Edit::Insert { node, .. } => println!("added {:?}", node.kind.name),
+//!         Edit::Remove { node, .. } => println!("removed {:?}", node.kind.name),
+//!         Edit::Move { .. } | Edit::Update { .. } => {}
+//!     }
+//! }
+//! ```
+
+use crate::Node;
+use std::collections::{HashMap, VecDeque};
+use std::hash::Hash;
+
+/// One difference between the old tree and the new tree.
+///
+/// Inserted and removed subtrees are reported once at their root; nothing
+/// is reported for their descendants.
+#[derive(Debug)]
+pub enum Edit<'a, T> {
+    /// Subtree present only in the new tree, under `parent` of the new tree.
+    Insert {
+        parent: &'a Node<T>,
+        node: &'a Node<T>,
+    },
+    /// Subtree present only in the old tree, under `parent` of the old tree.
+    Remove {
+        parent: &'a Node<T>,
+        node: &'a Node<T>,
+    },
+    /// Matched node whose position among its siblings changed.
+    Move { old: &'a Node<T>, new: &'a Node<T> },
+    /// Matched node whose kind compares unequal.
+    Update { old: &'a Node<T>, new: &'a Node<T> },
+}
+
+/// Compute the edit script from `old` to `new`, matching children by `key`
+/// and reporting matched nodes with unequal kinds as updates.
+///
+/// The two roots are taken to correspond to one another regardless of key.
+/// Subtrees moved to a different parent appear as a removal and an
+/// insertion.
+pub fn diff<'a, T, K, F>(old: &'a Node<T>, new: &'a Node<T>, key: F) -> Vec<Edit<'a, T>>
+where
+    T: PartialEq,
+    K: Eq + Hash,
+    F: FnMut(&'a T) -> K,
+{
+    diff_by(old, new, key, T::eq)
+}
+
+/// Like [`diff`] but with a custom comparison for deciding which matched
+/// nodes are updates, for example one that ignores source locations.
+pub fn diff_by<'a, T, K, F, E>(
+    old: &'a Node<T>,
+    new: &'a Node<T>,
+    mut key: F,
+    mut eq: E,
+) -> Vec<Edit<'a, T>>
+where
+    K: Eq + Hash,
+    F: FnMut(&'a T) -> K,
+    E: FnMut(&T, &T) -> bool,
+{
+    let mut edits = Vec::new();
+    let mut stack = vec![(old, new)];
+    while let Some((old, new)) = stack.pop() {
+        if !eq(&old.kind, &new.kind) {
+            edits.push(Edit::Update { old, new });
+        }
+
+        let mut unmatched: HashMap<K, VecDeque<usize>> = HashMap::new();
+        for (i, child) in old.inner.iter().enumerate() {
+            unmatched.entry(key(&child.kind)).or_default().push_back(i);
+        }
+        let mut old_matched = vec![false; old.inner.len()];
+        // Old index of each matched child, in new order.
+        let mut pairs = Vec::new();
+        let mut insertions = Vec::new();
+        for (j, child) in new.inner.iter().enumerate() {
+            match unmatched
+                .get_mut(&key(&child.kind))
+                .and_then(VecDeque::pop_front)
+            {
+                Some(i) => {
+                    old_matched[i] = true;
+                    pairs.push((i, j));
+                }
+                None => insertions.push(child),
+            }
+        }
+
+        for (child, matched) in old.inner.iter().zip(old_matched) {
+            if !matched {
+                edits.push(Edit::Remove {
+                    parent: old,
+                    node: child,
+                });
+            }
+        }
+        for child in insertions {
+            edits.push(Edit::Insert {
+                parent: new,
+                node: child,
+            });
+        }
+        let in_order = longest_increasing(&pairs);
+        for (&(i, j), in_order) in pairs.iter().zip(in_order) {
+            if !in_order {
+                edits.push(Edit::Move {
+                    old: &old.inner[i],
+                    new: &new.inner[j],
+                });
+            }
+        }
+
+        for &(i, j) in pairs.iter().rev() {
+            stack.push((&old.inner[i], &new.inner[j]));
+        }
+    }
+    edits
+}
+
+// Mark a longest subsequence of pairs whose old indices are increasing. The
+// children outside of it are the fewest that need to be considered moved.
+fn longest_increasing(pairs: &[(usize, usize)]) -> Vec<bool> {
+    // tails[k] is the position in pairs of the smallest old index ending an
+    // increasing run of length k + 1.
+    let mut tails: Vec<usize> = Vec::new();
+    let mut prev = vec![None; pairs.len()];
+    for (pos, &(i, _j)) in pairs.iter().enumerate() {
+        let k = tails
+            .binary_search_by(|&tail| pairs[tail].0.cmp(&i))
+            .unwrap_or_else(|k| k);
+        if k > 0 {
+            prev[pos] = Some(tails[k - 1]);
+        }
+        if k == tails.len() {
+            tails.push(pos);
+        } else {
+            tails[k] = pos;
+        }
+    }
+    let mut in_order = vec![false; pairs.len()];
+    let mut next = tails.last().copied();
+    while let Some(pos) = next {
+        in_order[pos] = true;
+        next = prev[pos];
+    }
+    in_order
+}
@@ -0,0 +1,162 @@
+use crate::Node;
+use serde::de::Deserialize;
+use std::fmt::{self, Debug};
+use std::marker::PhantomData;
+
+/// Deserialize every syntax tree in the output of `-ast-dump-filter`.
+///
+/// When Clang is given `-ast-dump-filter=name` it dumps each declaration
+/// whose name matches as a separate JSON document, preceded by a line naming
+/// the declaration such as `Dumping ns::f:`. Lines outside of the JSON
+/// documents are skipped.
+///
+/// ```
+/// use clang_ast::Kind;
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// pub struct Clang {
+///     pub kind: Kind,
+///     pub name: Option<String>,
+/// }
+///
+/// # fn main() -> serde_json::Result<()> {
+/// let output = r#"
+/// Dumping f:
+/// {"id":"0x1","kind":"FunctionDecl","name":"f"}
+/// Dumping ns::f:
+/// {"id":"0x2","kind":"FunctionDecl","name":"f"}
+/// "#;
+/// for node in clang_ast::documents::<Clang>(output) {
+///     let node = node?;
+///     println!("{:?}", node.kind.name);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub fn documents<'a, T>(input: &'a str) -> Documents<'a, T>
+where
+    T: Deserialize<'a>,
+{
+    Documents {
+        rest: input,
+        marker: PhantomData,
+    }
+}
+
+/// Iterator returned by [`documents`].
+///
+/// After a document fails to deserialize, the iterator yields the error and
+/// then ends.
+pub struct Documents<'a, T> {
+    rest: &'a str,
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<'a, T> Iterator for Documents<'a, T>
+where
+    T: Deserialize<'a>,
+{
+    type Item = serde_json::Result<Node<T>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let rest = self.rest.trim_start();
+            if rest.is_empty() {
+                self.rest = rest;
+                return None;
+            }
+            if rest.starts_with('{') {
+                let mut stream = serde_json::Deserializer::from_str(rest).into_iter();
+                let result = stream.next()?;
+                self.rest = if result.is_ok() {
+                    &rest[stream.byte_offset()..]
+                } else {
+                    ""
+                };
+                return Some(result);
+            }
+            self.rest = match rest.find('\n') {
+                Some(newline) => &rest[newline + 1..],
+                None => "",
+            };
+        }
+    }
+}
+
+impl<'a, T> Debug for Documents<'a, T> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter
+            .debug_struct("Documents")
+            .field("remaining", &self.rest.len())
+            .finish()
+    }
+}
+
+/// Iterator over a sequence of whitespace-separated syntax trees, such as
+/// several AST dumps concatenated into one file.
+///
+/// Each tree is deserialized independently of the others, the same as if it
+/// were the only one in the input: abbreviated source locations, which omit
+/// the file or line when unchanged from the previous location, never refer
+/// back into a preceding tree.
+///
+/// ```no_run
+/// use clang_ast::{Concatenated, Kind};
+/// use serde::Deserialize;
+/// use std::fs::File;
+/// use std::io::BufReader;
+///
+/// #[derive(Deserialize)]
+/// pub struct Clang {
+///     pub kind: Kind,
+/// }
+///
+/// fn main() -> serde_json::Result<()> {
+///     let reader = BufReader::new(File::open("all.json").unwrap());
+///     let deserializer = serde_json::Deserializer::from_reader(reader);
+///     for node in Concatenated::<_, Clang>::new(deserializer) {
+///         println!("{} top-level decls", node?.inner.len());
+///     }
+///     Ok(())
+/// }
+/// ```
+pub struct Concatenated<'de, R, T> {
+    stream: serde_json::StreamDeserializer<'de, R, Node<T>>,
+}
+
+impl<'de, R, T> Concatenated<'de, R, T>
+where
+    R: serde_json::de::Read<'de>,
+    T: Deserialize<'de>,
+{
+    pub fn new(deserializer: serde_json::Deserializer<R>) -> Self {
+        Concatenated {
+            stream: deserializer.into_iter(),
+        }
+    }
+
+    /// Number of bytes of input consumed so far, up to the end of the most
+    /// recently deserialized tree.
+    pub fn byte_offset(&self) -> usize {
+        self.stream.byte_offset()
+    }
+}
+
+impl<'de, R, T> Iterator for Concatenated<'de, R, T>
+where
+    R: serde_json::de::Read<'de>,
+    T: Deserialize<'de>,
+{
+    type Item = serde_json::Result<Node<T>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.stream.next()
+    }
+}
+
+impl<'de, R, T> Debug for Concatenated<'de, R, T> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.debug_struct("Concatenated").finish()
+    }
+}
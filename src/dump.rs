@@ -0,0 +1,219 @@
+use crate::{BareSourceLocation, Node, SourceLocation, SourceRange};
+use std::fmt::{self, Debug, Write};
+
+type Label<'a, T> = dyn Fn(&T) -> String + 'a;
+type Range<'a, T> = dyn Fn(&T) -> Option<&SourceRange> + 'a;
+
+/// Options for printing a syntax tree in the indented text layout of
+/// `clang -Xclang -ast-dump`, with [`Node::to_text`].
+///
+/// Each node is printed on one line as its kind, its id, its source range,
+/// and then any further details, beneath its parent:
+///
+/// ```text
+/// TranslationUnitDecl 0x1 <<invalid sloc>>
+/// `-FunctionDecl 0x2 <main.cc:1:1, line:3:1> main 'int ()'
+///   `-CompoundStmt 0x3 <line:1:12, line:3:1>
+/// ```
+///
+/// As in Clang's own output, a location only mentions the file and line
+/// when they differ from the previously printed location.
+///
+/// ```
+/// use clang_ast::{Kind, Node, SourceRange, TextDump};
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// pub struct Clang {
+///     pub kind: Kind,
+///     pub name: Option<String>,
+///     pub range: Option<SourceRange>,
+/// }
+///
+/// # fn example(node: &Node<Clang>) {
+/// let mut dump = TextDump::new(|clang: &Clang| clang.kind.to_string());
+/// dump.range(|clang| clang.range.as_ref());
+/// dump.details(|clang| clang.name.clone().unwrap_or_default());
+/// print!("{}", node.to_text(&dump));
+/// # }
+/// ```
+pub struct TextDump<'a, T> {
+    kind: Box<Label<'a, T>>,
+    range: Option<Box<Range<'a, T>>>,
+    details: Option<Box<Label<'a, T>>>,
+    color: bool,
+}
+
+impl<'a, T> TextDump<'a, T> {
+    /// Print each node starting with the name returned by `kind`.
+    pub fn new<F>(kind: F) -> Self
+    where
+        F: Fn(&T) -> String + 'a,
+    {
+        TextDump {
+            kind: Box::new(kind),
+            range: None,
+            details: None,
+            color: false,
+        }
+    }
+
+    /// Print the source range of each node after its id.
+    pub fn range<F>(&mut self, range: F) -> &mut Self
+    where
+        F: Fn(&T) -> Option<&SourceRange> + 'a,
+    {
+        self.range = Some(Box::new(range));
+        self
+    }
+
+    /// Print the string returned by `details` at the end of each node's
+    /// line.
+    pub fn details<F>(&mut self, details: F) -> &mut Self
+    where
+        F: Fn(&T) -> String + 'a,
+    {
+        self.details = Some(Box::new(details));
+        self
+    }
+
+    /// Color the output with ANSI escape codes for a terminal, in the same
+    /// colors as Clang's `-fcolor-diagnostics` dump: declarations in green,
+    /// statements and everything else in magenta, ids and locations in
+    /// yellow, and details in cyan.
+    pub fn color(&mut self, color: bool) -> &mut Self {
+        self.color = color;
+        self
+    }
+}
+
+impl<'a, T> Debug for TextDump<'a, T> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter
+            .debug_struct("TextDump")
+            .field("range", &self.range.is_some())
+            .field("details", &self.details.is_some())
+            .field("color", &self.color)
+            .finish()
+    }
+}
+
+const TREE: &str = "\x1b[0;34m";
+const DECL: &str = "\x1b[1;32m";
+const STMT: &str = "\x1b[1;35m";
+const ADDRESS: &str = "\x1b[0;33m";
+const LOCATION: &str = "\x1b[0;33m";
+const DETAILS: &str = "\x1b[1;36m";
+const RESET: &str = "\x1b[0m";
+
+// File and line of the most recently printed location.
+#[derive(Default)]
+struct LastLoc {
+    file: String,
+    line: usize,
+}
+
+pub(crate) fn to_text<T>(node: &Node<T>, dump: &TextDump<T>) -> String {
+    let mut out = String::new();
+    let mut last_loc = LastLoc::default();
+    // Node, prefix of its line, and whether it is the last of its siblings.
+    let mut stack = vec![(node, String::new(), None)];
+    while let Some((node, prefix, last)) = stack.pop() {
+        let color = |out: &mut String, color| {
+            if dump.color {
+                out.push_str(color);
+            }
+        };
+        color(&mut out, TREE);
+        out.push_str(&prefix);
+        match last {
+            None => {}
+            Some(false) => out.push_str("|-"),
+            Some(true) => out.push_str("`-"),
+        }
+        let kind = (dump.kind)(&node.kind);
+        color(&mut out, if kind.ends_with("Decl") { DECL } else { STMT });
+        out.push_str(&kind);
+        color(&mut out, RESET);
+        out.push(' ');
+        color(&mut out, ADDRESS);
+        let _ = write!(out, "{}", node.id);
+        color(&mut out, RESET);
+        if let Some(range) = &dump.range {
+            out.push(' ');
+            color(&mut out, LOCATION);
+            write_range(&mut out, range(&node.kind), &mut last_loc);
+            color(&mut out, RESET);
+        }
+        if let Some(details) = &dump.details {
+            let details = details(&node.kind);
+            if !details.is_empty() {
+                out.push(' ');
+                color(&mut out, DETAILS);
+                out.push_str(&details);
+                color(&mut out, RESET);
+            }
+        }
+        out.push('\n');
+
+        let child_prefix = match last {
+            None => prefix,
+            Some(false) => prefix + "| ",
+            Some(true) => prefix + "  ",
+        };
+        let len = node.inner.len();
+        for (i, child) in node.inner.iter().enumerate().rev() {
+            stack.push((child, child_prefix.clone(), Some(i + 1 == len)));
+        }
+    }
+    out
+}
+
+fn write_range(out: &mut String, range: Option<&SourceRange>, last_loc: &mut LastLoc) {
+    out.push('<');
+    match range {
+        Some(range) => {
+            write_loc(out, &range.begin, last_loc);
+            if !same_loc(&range.begin, &range.end) {
+                out.push_str(", ");
+                write_loc(out, &range.end, last_loc);
+            }
+        }
+        None => out.push_str("<invalid sloc>"),
+    }
+    out.push('>');
+}
+
+fn write_loc(out: &mut String, loc: &SourceLocation, last_loc: &mut LastLoc) {
+    let bare = if let Some(bare) = bare(loc) {
+        bare
+    } else {
+        out.push_str("<invalid sloc>");
+        return;
+    };
+    let file = bare.presumed_file.as_ref().unwrap_or(&bare.file);
+    let line = bare.presumed_line.unwrap_or(bare.line);
+    if **file != *last_loc.file {
+        let _ = write!(out, "{}:{}:{}", file, line, bare.col);
+        last_loc.file = file.to_string();
+        last_loc.line = line;
+    } else if line != last_loc.line {
+        let _ = write!(out, "line:{}:{}", line, bare.col);
+        last_loc.line = line;
+    } else {
+        let _ = write!(out, "col:{}", bare.col);
+    }
+}
+
+fn same_loc(a: &SourceLocation, b: &SourceLocation) -> bool {
+    match (bare(a), bare(b)) {
+        (Some(a), Some(b)) => a.offset == b.offset && a.file == b.file,
+        (None, None) => true,
+        _ => false,
+    }
+}
+
+// Clang prints the spelling location.
+fn bare(loc: &SourceLocation) -> Option<&BareSourceLocation> {
+    loc.spelling()
+}
@@ -0,0 +1,165 @@
+//! Running clang to dump the syntax tree of a source file.
+//!
+//! ```no_run
+//! use clang_ast::exec::Exec;
+//! use clang_ast::Kind;
+//! use serde::Deserialize;
+//!
+//! #[derive(Deserialize)]
+//! pub struct Clang {
+//!     pub kind: Kind,
+//! }
+//!
+//! fn main() -> Result<(), clang_ast::exec::Error> {
+//!     let node: clang_ast::Node<Clang> = Exec::new("src/main.cc")
+//!         .args(&["-std=c++17", "-Iinclude"])
+//!         .run()?;
+//!     println!("{} top-level decls", node.inner.len());
+//!     Ok(())
+//! }
+//! ```
+//!
+//! If clang reports errors, the resulting [`Error::Clang`] carries its
+//! diagnostics.
+
+use crate::Node;
+use serde::de::DeserializeOwned;
+use std::error::Error as StdError;
+use std::ffi::{OsStr, OsString};
+use std::fmt::{self, Display};
+use std::io::{self, BufReader, Read};
+use std::path::PathBuf;
+use std::process::{Command, ExitStatus, Stdio};
+use std::thread;
+
+pub(crate) const DUMP_FLAGS: &[&str] = &["-Xclang", "-ast-dump=json", "-fsyntax-only"];
+
+/// Invocation of clang on one source file, as
+/// `clang++ [args...] -Xclang -ast-dump=json -fsyntax-only <source>`.
+#[derive(Clone, Debug)]
+pub struct Exec {
+    program: OsString,
+    args: Vec<OsString>,
+    source: OsString,
+    current_dir: Option<PathBuf>,
+}
+
+impl Exec {
+    pub fn new(source: impl AsRef<OsStr>) -> Self {
+        Exec {
+            program: OsString::from("clang++"),
+            args: Vec::new(),
+            source: source.as_ref().to_owned(),
+            current_dir: None,
+        }
+    }
+
+    /// Run this compiler instead of `clang++` from the `PATH`.
+    pub fn program(&mut self, program: impl AsRef<OsStr>) -> &mut Self {
+        self.program = OsString::from(program.as_ref());
+        self
+    }
+
+    /// Pass a flag to clang, such as `-std=c++17` or `-DNDEBUG`.
+    pub fn arg(&mut self, arg: impl AsRef<OsStr>) -> &mut Self {
+        self.args.push(arg.as_ref().to_owned());
+        self
+    }
+
+    pub fn args<I>(&mut self, args: I) -> &mut Self
+    where
+        I: IntoIterator,
+        I::Item: AsRef<OsStr>,
+    {
+        for arg in args {
+            self.arg(arg);
+        }
+        self
+    }
+
+    /// Run clang in this working directory instead of the current one.
+    pub fn current_dir(&mut self, dir: impl Into<PathBuf>) -> &mut Self {
+        self.current_dir = Some(dir.into());
+        self
+    }
+
+    /// Run clang, deserializing the dump from its output while it runs.
+    pub fn run<T>(&self) -> Result<Node<T>, Error>
+    where
+        T: DeserializeOwned,
+    {
+        let mut command = Command::new(&self.program);
+        command.args(&self.args).args(DUMP_FLAGS).arg(&self.source);
+        if let Some(dir) = &self.current_dir {
+            command.current_dir(dir);
+        }
+        run(&mut command)
+    }
+}
+
+pub(crate) fn run<T>(command: &mut Command) -> Result<Node<T>, Error>
+where
+    T: DeserializeOwned,
+{
+    let mut child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(Error::Io)?;
+
+    // Drain stderr on another thread so that a compiler producing lots of
+    // diagnostics cannot block while stdout is being deserialized.
+    let mut stderr = child.stderr.take().unwrap();
+    let diagnostics = thread::spawn(move || {
+        let mut diagnostics = String::new();
+        let _ = stderr.read_to_string(&mut diagnostics);
+        diagnostics
+    });
+
+    let stdout = BufReader::new(child.stdout.take().unwrap());
+    let result = serde_json::from_reader(stdout);
+    let status = child.wait().map_err(Error::Io)?;
+    let stderr = diagnostics.join().unwrap_or_default();
+    if !status.success() {
+        return Err(Error::Clang { status, stderr });
+    }
+    result.map_err(Error::Json)
+}
+
+/// Error from running clang, or from reading a compilation database.
+#[derive(Debug)]
+pub enum Error {
+    /// Failed to run the compiler, or to read a compilation database.
+    Io(io::Error),
+    /// The compiler exited unsuccessfully, with these diagnostics.
+    Clang { status: ExitStatus, stderr: String },
+    /// The AST dump or compilation database was not valid.
+    Json(serde_json::Error),
+}
+
+impl Display for Error {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Io(error) => Display::fmt(error, formatter),
+            Error::Clang { status, stderr } => {
+                write!(formatter, "clang failed with {}", status)?;
+                if !stderr.is_empty() {
+                    write!(formatter, ":\n{}", stderr.trim_end())?;
+                }
+                Ok(())
+            }
+            Error::Json(error) => Display::fmt(error, formatter),
+        }
+    }
+}
+
+impl StdError for Error {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self {
+            Error::Io(error) => Some(error),
+            Error::Clang { .. } => None,
+            Error::Json(error) => Some(error),
+        }
+    }
+}
@@ -0,0 +1,283 @@
+use crate::Node;
+use serde::de::DeserializeOwned;
+use std::collections::VecDeque;
+use std::fmt::{self, Debug};
+use std::future::Future;
+use std::io::{self, BufReader, Read};
+use std::panic;
+use std::pin::Pin;
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::task::{Context, Poll, Waker};
+use std::thread::{self, JoinHandle};
+
+const CAPACITY: usize = 1024 * 1024;
+
+/// Deserialize a syntax tree from JSON delivered in chunks, for example as
+/// it arrives over the network, without buffering the whole dump or
+/// blocking an async task.
+///
+/// Deserialization runs on a thread of its own, reading the chunks passed
+/// to [`Feed::send`] as they come. Dropping the `Feed` marks the end of the
+/// input, after which the tree is available from the [`Parsing`] future.
+///
+/// Both `Feed::send` and `Parsing` are ordinary futures, so this works
+/// under any async runtime, with input from `AsyncRead` or anywhere else.
+///
+/// # Threads
+///
+/// Every call to `feed` spawns a new OS thread, which is why it sits behind
+/// a feature named `"feed-thread"`. The thread exists for the whole parse,
+/// blocked waiting for input in between chunks, and exits once the tree is
+/// built or the input turns out to be invalid. Dropping the `Parsing` future
+/// does not stop it early; it keeps consuming chunks until the `Feed` is
+/// dropped too. With many dumps arriving concurrently, that is as many
+/// threads, so to bound their number buffer the input and deserialize it on
+/// a thread pool of your own instead.
+///
+/// ```no_run
+/// # struct Socket;
+/// # impl Socket {
+/// #     async fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+/// #         Ok(0)
+/// #     }
+/// # }
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// pub enum Clang {
+///     FunctionDecl { name: String },
+///     Other,
+/// }
+///
+/// async fn receive(mut socket: Socket) -> serde_json::Result<clang_ast::Node<Clang>> {
+///     let (mut feed, parsing) = clang_ast::feed();
+///     let mut buf = vec![0; 64 * 1024];
+///     loop {
+///         let n = socket.read(&mut buf).await.map_err(serde_json::Error::io)?;
+///         if n == 0 {
+///             break;
+///         }
+///         feed.send(&buf[..n]).await;
+///     }
+///     drop(feed);
+///     parsing.await
+/// }
+/// ```
+pub fn feed<T>() -> (Feed, Parsing<T>)
+where
+    T: DeserializeOwned + Send + 'static,
+{
+    let shared = Arc::new(Shared {
+        state: Mutex::new(State {
+            chunks: VecDeque::new(),
+            offset: 0,
+            buffered: 0,
+            closed: false,
+            done: false,
+            feed_waker: None,
+            parsing_waker: None,
+        }),
+        more_input: Condvar::new(),
+    });
+
+    let thread = {
+        let shared = Arc::clone(&shared);
+        thread::spawn(move || {
+            let _done = Done(&shared);
+            let reader = BufReader::with_capacity(64 * 1024, Chunks(&shared));
+            serde_json::from_reader(reader)
+        })
+    };
+
+    let feed = Feed {
+        shared: Arc::clone(&shared),
+    };
+    let parsing = Parsing {
+        shared,
+        thread: Some(thread),
+    };
+    (feed, parsing)
+}
+
+/// Sending half of [`feed()`].
+pub struct Feed {
+    shared: Arc<Shared>,
+}
+
+/// Future of the syntax tree deserialized by [`feed()`].
+///
+/// Resolves once the input has ended, or as soon as it has been found to
+/// be invalid.
+pub struct Parsing<T> {
+    shared: Arc<Shared>,
+    thread: Option<JoinHandle<serde_json::Result<Node<T>>>>,
+}
+
+/// Future returned by [`Feed::send`].
+pub struct SendChunk<'a> {
+    feed: &'a mut Feed,
+    chunk: Option<Vec<u8>>,
+}
+
+struct Shared {
+    state: Mutex<State>,
+    more_input: Condvar,
+}
+
+struct State {
+    chunks: VecDeque<Vec<u8>>,
+    // Bytes of the front chunk already read.
+    offset: usize,
+    buffered: usize,
+    closed: bool,
+    done: bool,
+    feed_waker: Option<Waker>,
+    parsing_waker: Option<Waker>,
+}
+
+impl Shared {
+    fn lock(&self) -> MutexGuard<'_, State> {
+        // Nothing panics while holding the lock.
+        self.state.lock().unwrap()
+    }
+}
+
+impl Feed {
+    /// Pass the next chunk of JSON to the deserializer.
+    ///
+    /// Completes once the chunk has been accepted. While more than a
+    /// megabyte of input is waiting to be deserialized, that is delayed
+    /// until the deserializer catches up, so that a slow consumer holds
+    /// back the sender rather than accumulating the whole input.
+    ///
+    /// If deserialization has already finished, for example because the
+    /// input is invalid, the chunk is discarded.
+    pub fn send(&mut self, chunk: impl Into<Vec<u8>>) -> SendChunk<'_> {
+        SendChunk {
+            feed: self,
+            chunk: Some(chunk.into()),
+        }
+    }
+}
+
+impl<'a> Future for SendChunk<'a> {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        let mut state = this.feed.shared.lock();
+        if state.done {
+            this.chunk = None;
+            return Poll::Ready(());
+        }
+        if state.buffered >= CAPACITY {
+            state.feed_waker = Some(cx.waker().clone());
+            return Poll::Pending;
+        }
+        if let Some(chunk) = this.chunk.take().filter(|chunk| !chunk.is_empty()) {
+            // An empty chunk in the queue would read as the end of input.
+            state.buffered += chunk.len();
+            state.chunks.push_back(chunk);
+            this.feed.shared.more_input.notify_one();
+        }
+        Poll::Ready(())
+    }
+}
+
+impl Drop for Feed {
+    fn drop(&mut self) {
+        let mut state = self.shared.lock();
+        state.closed = true;
+        self.shared.more_input.notify_one();
+    }
+}
+
+impl<T> Future for Parsing<T> {
+    type Output = serde_json::Result<Node<T>>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        let mut state = this.shared.lock();
+        if !state.done {
+            state.parsing_waker = Some(cx.waker().clone());
+            return Poll::Pending;
+        }
+        drop(state);
+        let thread = this.thread.take().expect("Parsing polled after completion");
+        match thread.join() {
+            Ok(result) => Poll::Ready(result),
+            Err(panic) => panic::resume_unwind(panic),
+        }
+    }
+}
+
+// Reads on the deserializing thread, blocking until a chunk arrives.
+struct Chunks<'a>(&'a Shared);
+
+impl<'a> Read for Chunks<'a> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let mut state = self.0.lock();
+        while state.chunks.is_empty() && !state.closed {
+            state = self.0.more_input.wait(state).unwrap();
+        }
+        let offset = state.offset;
+        let chunk = match state.chunks.front() {
+            Some(chunk) => &chunk[offset..],
+            None => return Ok(0),
+        };
+        let n = chunk.len().min(buf.len());
+        buf[..n].copy_from_slice(&chunk[..n]);
+        if n == chunk.len() {
+            state.chunks.pop_front();
+            state.offset = 0;
+        } else {
+            state.offset += n;
+        }
+        state.buffered -= n;
+        if state.buffered < CAPACITY {
+            if let Some(waker) = state.feed_waker.take() {
+                waker.wake();
+            }
+        }
+        Ok(n)
+    }
+}
+
+// Signals the end of deserialization, including by panic.
+struct Done<'a>(&'a Shared);
+
+impl<'a> Drop for Done<'a> {
+    fn drop(&mut self) {
+        let mut state = self.0.lock();
+        state.done = true;
+        state.chunks.clear();
+        state.buffered = 0;
+        let feed_waker = state.feed_waker.take();
+        let parsing_waker = state.parsing_waker.take();
+        drop(state);
+        if let Some(waker) = feed_waker {
+            waker.wake();
+        }
+        if let Some(waker) = parsing_waker {
+            waker.wake();
+        }
+    }
+}
+
+impl Debug for Feed {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.debug_struct("Feed").finish()
+    }
+}
+
+impl<T> Debug for Parsing<T> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.debug_struct("Parsing").finish()
+    }
+}
+
+impl<'a> Debug for SendChunk<'a> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.debug_struct("SendChunk").finish()
+    }
+}
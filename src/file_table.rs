@@ -0,0 +1,113 @@
+use crate::intern::Shared;
+use crate::{BareSourceLocation, Node, SourceLocation};
+use std::collections::HashMap;
+
+/// Every distinct file referred to by the source locations of a syntax
+/// tree, each with a dense index.
+///
+/// Indices are assigned in the order files are first seen in a depth-first
+/// pre-order walk of the tree, so building the table from the same tree
+/// twice gives the same indices. The paths are the same shared allocations
+/// as those in the tree's source locations.
+///
+/// ```
+/// use clang_ast::{FileTable, Node, SourceLocation};
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// pub struct Clang {
+///     pub loc: Option<SourceLocation>,
+/// }
+///
+/// # fn example(node: &Node<Clang>) {
+/// let files = FileTable::new(node, |kind| kind.loc.as_ref());
+/// for file in files.files() {
+///     println!("{}: {} nodes", file.path, file.nodes);
+/// }
+/// # }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct FileTable {
+    files: Vec<FileEntry>,
+    indices: HashMap<Shared<str>, usize>,
+}
+
+/// One file in a [`FileTable`].
+#[derive(Clone, Debug)]
+pub struct FileEntry {
+    pub path: Shared<str>,
+    /// Number of nodes located in this file, according to the expansion
+    /// location of each node's location.
+    pub nodes: usize,
+}
+
+impl FileTable {
+    /// Collect the files of every node's location, retrieved from its kind
+    /// by the callback. Both the spelling and expansion location contribute
+    /// files, as do the files they were included from.
+    pub fn new<T, F>(root: &Node<T>, loc: F) -> Self
+    where
+        F: Fn(&T) -> Option<&SourceLocation>,
+    {
+        let mut table = FileTable::default();
+        for node in root.descendants() {
+            let loc = match loc(&node.kind) {
+                Some(loc) => loc,
+                None => continue,
+            };
+            if let Some(expansion) = loc.expansion() {
+                let index = table.insert_bare(expansion);
+                table.files[index].nodes += 1;
+            }
+            if let Some(spelling) = &loc.spelling_loc {
+                table.insert_bare(spelling);
+            }
+        }
+        table
+    }
+
+    fn insert_bare(&mut self, loc: &BareSourceLocation) -> usize {
+        let index = self.insert(&loc.file);
+        if let Some(included_from) = &loc.included_from {
+            for included_from in included_from.chain() {
+                self.insert(&included_from.file);
+            }
+        }
+        index
+    }
+
+    fn insert(&mut self, path: &Shared<str>) -> usize {
+        if let Some(&index) = self.indices.get(path) {
+            return index;
+        }
+        let index = self.files.len();
+        self.files.push(FileEntry {
+            path: Shared::clone(path),
+            nodes: 0,
+        });
+        self.indices.insert(Shared::clone(path), index);
+        index
+    }
+
+    /// Index of the file with the given path.
+    pub fn index(&self, path: &str) -> Option<usize> {
+        self.indices.get(path).copied()
+    }
+
+    pub fn get(&self, index: usize) -> Option<&FileEntry> {
+        self.files.get(index)
+    }
+
+    /// Every file in the table, ordered by index.
+    pub fn files(&self) -> &[FileEntry] {
+        &self.files
+    }
+
+    pub fn len(&self) -> usize {
+        self.files.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+}
@@ -0,0 +1,359 @@
+use crate::deserializer::{self, Inner};
+use crate::kind::AnyKind;
+use crate::loc::SkipTracked;
+use crate::{ancestry, intern, InnerVec, Kind, Node};
+use serde::de::{Deserialize, DeserializeSeed, Deserializer, Error, MapAccess, SeqAccess, Visitor};
+use std::collections::HashSet;
+use std::fmt::{self, Debug};
+use std::marker::PhantomData;
+
+/// Deserialization of a syntax tree that leaves out parts of it.
+///
+/// Pruning happens as the input is parsed, so nodes that are not wanted are
+/// never allocated. Source locations inside of the pruned parts are still
+/// tracked, so `"file"` and `"line"` omitted from later locations continue to
+/// resolve correctly.
+///
+/// ```
+/// use clang_ast::{Filter, Kind, Node};
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// pub struct Clang {
+///     pub kind: Kind,
+/// }
+///
+/// # fn main() -> serde_json::Result<()> {
+/// # let json = r#"{"id":"0x1","kind":"FunctionDecl","inner":[{"id":"0x2","kind":"CompoundStmt","inner":[{"id":"0x3","kind":"ReturnStmt"}]}]}"#;
+/// // Declarations only; no function bodies.
+/// let mut filter = Filter::new();
+/// filter.skip_inner(Kind::CompoundStmt);
+///
+/// let mut deserializer = serde_json::Deserializer::from_str(json);
+/// let node: Node<Clang> = filter.deserialize(&mut deserializer)?;
+/// # assert!(node.inner[0].inner.is_empty());
+/// # Ok(())
+/// # }
+/// ```
+pub struct Filter {
+    skip_inner: HashSet<Kind>,
+    exclude_file: Option<Box<FilePredicate>>,
+    implicit: Option<Implicit>,
+    max_depth: Option<usize>,
+    recursion_limit: Option<usize>,
+}
+
+#[derive(Copy, Clone, Debug)]
+enum Implicit {
+    Exclude,
+    Hoist,
+}
+
+type FilePredicate = dyn Fn(&str) -> bool;
+
+impl Filter {
+    pub fn new() -> Self {
+        Filter {
+            skip_inner: HashSet::new(),
+            exclude_file: None,
+            implicit: None,
+            max_depth: None,
+            recursion_limit: None,
+        }
+    }
+
+    /// Keep nodes of the given kind, but skip over all of their children.
+    pub fn skip_inner(&mut self, kind: Kind) -> &mut Self {
+        self.skip_inner.insert(kind);
+        self
+    }
+
+    /// Leave out nodes located in a file for which the predicate returns
+    /// true, together with everything nested inside of them.
+    ///
+    /// A node's file is that of its `"loc"`, or of the beginning of its
+    /// `"range"` for nodes which have no `"loc"`, using the expansion location
+    /// in the case of macros. This works whether or not the kind type
+    /// deserializes those fields. Nodes without a location, and the root
+    /// node, are always kept.
+    ///
+    /// ```
+    /// # use clang_ast::Filter;
+    /// #
+    /// let mut filter = Filter::new();
+    /// filter.exclude_file(|path| path.starts_with("/usr/include/"));
+    /// ```
+    pub fn exclude_file<F>(&mut self, predicate: F) -> &mut Self
+    where
+        F: Fn(&str) -> bool + 'static,
+    {
+        self.exclude_file = Some(Box::new(predicate));
+        self
+    }
+
+    /// Leave out nodes marked `"isImplicit": true`, together with everything
+    /// nested inside of them.
+    ///
+    /// These are declarations synthesized by the compiler, such as implicit
+    /// special member functions and the injected class name of every C++
+    /// class, which tend to dominate dumps of C++ code. The root node is
+    /// always kept.
+    pub fn exclude_implicit(&mut self) -> &mut Self {
+        self.implicit = Some(Implicit::Exclude);
+        self
+    }
+
+    /// Leave out nodes marked `"isImplicit": true`, but keep their children,
+    /// which take the implicit node's place in its parent.
+    pub fn hoist_implicit(&mut self) -> &mut Self {
+        self.implicit = Some(Implicit::Hoist);
+        self
+    }
+
+    /// Skip over the children of nodes at the given depth, so that nothing
+    /// is nested more deeply than that. The root node is at depth 0.
+    ///
+    /// ```
+    /// # use clang_ast::Filter;
+    /// #
+    /// // Top-level declarations and their immediate members only.
+    /// let mut filter = Filter::new();
+    /// filter.max_depth(2);
+    /// ```
+    pub fn max_depth(&mut self, depth: usize) -> &mut Self {
+        self.max_depth = Some(depth);
+        self
+    }
+
+    /// Fail to deserialize syntax trees in which nodes are nested more deeply
+    /// than the given depth, instead of skipping over the excess like
+    /// [`max_depth`][Filter::max_depth].
+    ///
+    /// This is a safeguard for when the deserializer's own recursion limit has
+    /// been disabled; see [*Deeply nested syntax trees*][crate#deeply-nested-syntax-trees].
+    pub fn recursion_limit(&mut self, depth: usize) -> &mut Self {
+        self.recursion_limit = Some(depth);
+        self
+    }
+
+    pub fn deserialize<'de, T, D>(&self, deserializer: D) -> Result<Node<T>, D::Error>
+    where
+        T: Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        let _intern = intern::activate();
+        let _ancestry = if self.implicit.is_some() || self.exclude_file.is_some() {
+            Some(ancestry::activate())
+        } else {
+            None
+        };
+        let (node, _disposition) = deserializer.deserialize_map(FilterVisitor {
+            filter: self,
+            depth: 0,
+            marker: PhantomData,
+        })?;
+        Ok(node)
+    }
+}
+
+impl Default for Filter {
+    fn default() -> Self {
+        Filter::new()
+    }
+}
+
+impl Debug for Filter {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter
+            .debug_struct("Filter")
+            .field("skip_inner", &self.skip_inner)
+            .field("exclude_file", &self.exclude_file.is_some())
+            .field("implicit", &self.implicit)
+            .field("max_depth", &self.max_depth)
+            .field("recursion_limit", &self.recursion_limit)
+            .finish()
+    }
+}
+
+struct FilterVisitor<'a, T> {
+    filter: &'a Filter,
+    depth: usize,
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<'de, 'a, T> Visitor<'de> for FilterVisitor<'a, T>
+where
+    T: Deserialize<'de>,
+{
+    type Value = (Node<T>, Disposition);
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("clang syntax tree node")
+    }
+
+    fn visit_map<M>(self, map: M) -> Result<Self::Value, M::Error>
+    where
+        M: MapAccess<'de>,
+    {
+        let mut inner = FilterInner {
+            filter: self.filter,
+            depth: self.depth,
+            children: InnerVec::new(),
+            skip: false,
+            exclude: None,
+            hoist: false,
+        };
+        let (id, kind) = deserializer::visit_node(map, &mut inner)?;
+        let node = Node {
+            id,
+            kind,
+            inner: inner.children,
+        };
+        let disposition = if inner.exclude == Some(true) {
+            Disposition::Exclude
+        } else if inner.hoist {
+            Disposition::Hoist
+        } else {
+            Disposition::Keep
+        };
+        Ok((node, disposition))
+    }
+}
+
+impl<'de, 'a, T> DeserializeSeed<'de> for FilterVisitor<'a, T>
+where
+    T: Deserialize<'de>,
+{
+    type Value = (Node<T>, Disposition);
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_map(self)
+    }
+}
+
+// What becomes of a node in its parent.
+enum Disposition {
+    Keep,
+    Exclude,
+    // Replace the node by its children.
+    Hoist,
+}
+
+struct FilterInner<'a, T> {
+    filter: &'a Filter,
+    depth: usize,
+    children: InnerVec<Node<T>>,
+    skip: bool,
+    // Decided once the node's location is known, which is by the time its
+    // children are reached or else at the end of the node.
+    exclude: Option<bool>,
+    hoist: bool,
+}
+
+impl<'a, T> FilterInner<'a, T> {
+    fn exclude(&mut self) -> bool {
+        if let Some(exclude) = self.exclude {
+            return exclude;
+        }
+        let implicit = match self.filter.implicit {
+            Some(Implicit::Exclude) => ancestry::current_is_implicit(),
+            Some(Implicit::Hoist) | None => false,
+        };
+        let exclude = self.depth > 0
+            && (implicit
+                || match (&self.filter.exclude_file, ancestry::current_file()) {
+                    (Some(predicate), Some(file)) => predicate(&file),
+                    _ => false,
+                });
+        self.exclude = Some(exclude);
+        exclude
+    }
+}
+
+impl<'de, 'a, T> Inner<'de> for FilterInner<'a, T>
+where
+    T: Deserialize<'de>,
+{
+    fn begin_node(&mut self, kind: &AnyKind<'de>) {
+        if let AnyKind::Kind(kind) = kind {
+            self.skip = self.filter.skip_inner.contains(kind);
+        }
+    }
+
+    fn end_node(&mut self) {
+        self.exclude();
+        if let Some(Implicit::Hoist) = self.filter.implicit {
+            self.hoist = self.depth > 0 && ancestry::current_is_implicit();
+        }
+    }
+
+    fn track_locs(&self) -> bool {
+        self.filter.exclude_file.is_some()
+    }
+
+    fn track_implicit(&self) -> bool {
+        self.filter.implicit.is_some()
+    }
+
+    fn deserialize_inner<D>(&mut self, deserializer: D) -> Result<(), D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let too_deep = match self.filter.max_depth {
+            Some(max_depth) => self.depth >= max_depth,
+            None => false,
+        };
+        if self.skip || too_deep || self.exclude() {
+            SkipTracked.deserialize(deserializer)
+        } else if let Some(limit) = self.filter.recursion_limit.filter(|&l| self.depth >= l) {
+            Err(D::Error::custom(format_args!(
+                "syntax tree nested more deeply than the recursion limit of {}",
+                limit,
+            )))
+        } else {
+            deserializer.deserialize_seq(FilterInnerVisitor {
+                filter: self.filter,
+                depth: self.depth + 1,
+                children: &mut self.children,
+            })
+        }
+    }
+}
+
+struct FilterInnerVisitor<'a, 'b, T> {
+    filter: &'a Filter,
+    depth: usize,
+    children: &'b mut InnerVec<Node<T>>,
+}
+
+impl<'de, 'a, 'b, T> Visitor<'de> for FilterInnerVisitor<'a, 'b, T>
+where
+    T: Deserialize<'de>,
+{
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("array of clang syntax tree nodes")
+    }
+
+    fn visit_seq<S>(self, mut seq: S) -> Result<Self::Value, S::Error>
+    where
+        S: SeqAccess<'de>,
+    {
+        while let Some((mut node, disposition)) = seq.next_element_seed(FilterVisitor {
+            filter: self.filter,
+            depth: self.depth,
+            marker: PhantomData,
+        })? {
+            match disposition {
+                Disposition::Keep => self.children.push(node),
+                Disposition::Exclude => {}
+                Disposition::Hoist => self.children.append(&mut node.inner),
+            }
+        }
+        Ok(())
+    }
+}
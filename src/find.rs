@@ -0,0 +1,166 @@
+use crate::deserializer::{self, Inner};
+use crate::{intern, InnerVec, Node};
+use serde::de::{Deserialize, DeserializeSeed, Deserializer, Error, MapAccess, SeqAccess, Visitor};
+use std::fmt;
+
+/// Deserialize only as much of a syntax tree as necessary to find one node
+/// whose kind satisfies the predicate.
+///
+/// Deserialization stops as soon as a matching node has been decoded; the rest
+/// of the input is not parsed. The match is returned along with its complete
+/// subtree, or `None` if the input contains no matching node.
+///
+/// A node's kind is decoded only after all of its children, so if a node and
+/// one of its descendants both match, the descendant is the one found.
+///
+/// ```
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// pub enum Clang {
+///     CXXRecordDecl { name: Option<String> },
+///     Other,
+/// }
+///
+/// # fn main() -> serde_json::Result<()> {
+/// # let json = r#"{"id":"0x1","kind":"TranslationUnitDecl","inner":[{"id":"0x2","kind":"CXXRecordDecl","name":"Foo"}]}"#;
+/// let mut deserializer = serde_json::Deserializer::from_str(json);
+/// let foo = clang_ast::find(&mut deserializer, |kind: &Clang| match kind {
+///     Clang::CXXRecordDecl { name } => name.as_deref() == Some("Foo"),
+///     Clang::Other => false,
+/// })?;
+/// # assert_eq!(foo.unwrap().id.to_string(), "0x2");
+/// # Ok(())
+/// # }
+/// ```
+pub fn find<'de, D, T, P>(deserializer: D, predicate: P) -> Result<Option<Node<T>>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+    P: FnMut(&T) -> bool,
+{
+    let _intern = intern::activate();
+    let mut state = State {
+        predicate,
+        found: None,
+    };
+    let mut root = InnerVec::new();
+    let result = deserializer.deserialize_map(FindVisitor {
+        siblings: &mut root,
+        state: &mut state,
+    });
+    match (state.found, result) {
+        (Some(found), _) => Ok(Some(found)),
+        (None, Ok(())) => Ok(None),
+        (None, Err(error)) => Err(error),
+    }
+}
+
+struct State<T, P> {
+    predicate: P,
+    found: Option<Node<T>>,
+}
+
+struct FindVisitor<'a, T, P> {
+    siblings: &'a mut InnerVec<Node<T>>,
+    state: &'a mut State<T, P>,
+}
+
+impl<'de, 'a, T, P> Visitor<'de> for FindVisitor<'a, T, P>
+where
+    T: Deserialize<'de>,
+    P: FnMut(&T) -> bool,
+{
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("clang syntax tree node")
+    }
+
+    fn visit_map<M>(self, map: M) -> Result<Self::Value, M::Error>
+    where
+        M: MapAccess<'de>,
+    {
+        let mut inner = InnerVec::new();
+        let (id, kind) = deserializer::visit_node(
+            map,
+            &mut FindInner {
+                children: &mut inner,
+                state: &mut *self.state,
+            },
+        )?;
+        let node = Node { id, kind, inner };
+        if (self.state.predicate)(&node.kind) {
+            self.state.found = Some(node);
+            // Abandon the rest of the input. The caller recognizes this error
+            // by the presence of `found`.
+            return Err(M::Error::custom("found matching clang syntax tree node"));
+        }
+        self.siblings.push(node);
+        Ok(())
+    }
+}
+
+impl<'de, 'a, T, P> DeserializeSeed<'de> for FindVisitor<'a, T, P>
+where
+    T: Deserialize<'de>,
+    P: FnMut(&T) -> bool,
+{
+    type Value = ();
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_map(self)
+    }
+}
+
+struct FindInner<'a, T, P> {
+    children: &'a mut InnerVec<Node<T>>,
+    state: &'a mut State<T, P>,
+}
+
+impl<'de, 'a, T, P> Inner<'de> for FindInner<'a, T, P>
+where
+    T: Deserialize<'de>,
+    P: FnMut(&T) -> bool,
+{
+    fn deserialize_inner<D>(&mut self, deserializer: D) -> Result<(), D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_seq(FindInnerVisitor {
+            children: &mut *self.children,
+            state: &mut *self.state,
+        })
+    }
+}
+
+struct FindInnerVisitor<'a, T, P> {
+    children: &'a mut InnerVec<Node<T>>,
+    state: &'a mut State<T, P>,
+}
+
+impl<'de, 'a, T, P> Visitor<'de> for FindInnerVisitor<'a, T, P>
+where
+    T: Deserialize<'de>,
+    P: FnMut(&T) -> bool,
+{
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("array of clang syntax tree nodes")
+    }
+
+    fn visit_seq<S>(self, mut seq: S) -> Result<Self::Value, S::Error>
+    where
+        S: SeqAccess<'de>,
+    {
+        while let Some(()) = seq.next_element_seed(FindVisitor {
+            siblings: &mut *self.children,
+            state: &mut *self.state,
+        })? {}
+        Ok(())
+    }
+}
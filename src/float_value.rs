@@ -0,0 +1,194 @@
+use serde::de::{Deserialize, Deserializer, Error, Unexpected, Visitor};
+use serde::ser::{Serialize, Serializer};
+use std::fmt::{self, Display};
+
+/// The `"value"` of a `FloatingLiteral`.
+///
+/// Clang writes the value as text, like `"1.5"`, `"1.0E+10"` or `"+Inf"`.
+/// This keeps that text alongside the value as an `f64`, for which the
+/// decimal, hexadecimal (`0x1.8p3`), infinity and NaN spellings are all
+/// understood.
+///
+/// ```
+/// use clang_ast::FloatValue;
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// pub enum Clang {
+///     FloatingLiteral { value: FloatValue },
+///     Other,
+/// }
+/// #
+/// # let json = r#"{"id":"0x1","kind":"FloatingLiteral","value":"-Inf"}"#;
+/// # let node: clang_ast::Node<Clang> = serde_json::from_str(json).unwrap();
+/// # match node.kind {
+/// #     Clang::FloatingLiteral { value } => {
+/// #         assert_eq!(value.value(), f64::NEG_INFINITY);
+/// #         assert_eq!(value.text(), "-Inf");
+/// #     }
+/// #     Clang::Other => panic!(),
+/// # }
+/// ```
+///
+/// A `float` or `long double` literal is converted to the nearest `f64`;
+/// the text is exact for any of them.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FloatValue {
+    value: f64,
+    text: Box<str>,
+}
+
+impl FloatValue {
+    /// Parses a floating point value as Clang or C source spells it, without
+    /// a type suffix.
+    pub fn parse(text: &str) -> Option<Self> {
+        let value = parse_f64(text)?;
+        Some(FloatValue {
+            value,
+            text: Box::from(text),
+        })
+    }
+
+    pub fn value(&self) -> f64 {
+        self.value
+    }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+}
+
+fn parse_f64(text: &str) -> Option<f64> {
+    let (negative, unsigned) = match text.as_bytes().first() {
+        Some(b'-') => (true, &text[1..]),
+        Some(b'+') => (false, &text[1..]),
+        _ => (false, text),
+    };
+    let magnitude =
+        if unsigned.eq_ignore_ascii_case("inf") || unsigned.eq_ignore_ascii_case("infinity") {
+            f64::INFINITY
+        } else if unsigned.eq_ignore_ascii_case("nan") {
+            f64::NAN
+        } else if unsigned.starts_with("0x") || unsigned.starts_with("0X") {
+            parse_hex(&unsigned[2..])?
+        } else if unsigned.starts_with(|ch: char| ch.is_ascii_digit() || ch == '.') {
+            unsigned.parse().ok()?
+        } else {
+            return None;
+        };
+    Some(if negative { -magnitude } else { magnitude })
+}
+
+// Hexadecimal significand and binary exponent, as in 1.8p3.
+fn parse_hex(text: &str) -> Option<f64> {
+    let p = text.find(&['p', 'P'][..])?;
+    let (significand, exponent) = (&text[..p], &text[p + 1..]);
+
+    // Keep the first 60 bits of significand, plus a sticky bit recording
+    // whether anything nonzero was dropped, which is enough for the
+    // conversion to f64 to round correctly.
+    let mut mantissa: u64 = 0;
+    let mut scale: i64 = 0;
+    let mut sticky = false;
+    let mut digits = 0;
+    let mut seen_point = false;
+    for ch in significand.chars() {
+        if ch == '.' && !seen_point {
+            seen_point = true;
+            continue;
+        }
+        let digit = ch.to_digit(16)?;
+        digits += 1;
+        if mantissa >> 60 == 0 {
+            mantissa = mantissa << 4 | u64::from(digit);
+            if seen_point {
+                scale -= 4;
+            }
+        } else {
+            sticky |= digit != 0;
+            if !seen_point {
+                scale += 4;
+            }
+        }
+    }
+    if digits == 0 {
+        return None;
+    }
+    if sticky {
+        mantissa |= 1;
+    }
+
+    let (exponent_negative, exponent_digits) = match exponent.as_bytes().first() {
+        Some(b'-') => (true, &exponent[1..]),
+        Some(b'+') => (false, &exponent[1..]),
+        _ => (false, exponent),
+    };
+    if exponent_digits.is_empty() || !exponent_digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    // Anything beyond this is infinite or zero regardless of the mantissa.
+    let mut exponent: i64 = 0;
+    for b in exponent_digits.bytes() {
+        exponent = (exponent * 10 + i64::from(b - b'0')).min(1 << 20);
+    }
+    if exponent_negative {
+        exponent = -exponent;
+    }
+
+    #[allow(clippy::cast_precision_loss)] // rounds to nearest, as intended
+    let mut value = mantissa as f64;
+    let mut exponent = exponent + scale;
+    while exponent > 1000 {
+        value *= 2f64.powi(1000);
+        exponent -= 1000;
+    }
+    while exponent < -1000 {
+        value *= 2f64.powi(-1000);
+        exponent += 1000;
+    }
+    #[allow(clippy::cast_possible_truncation)] // within ±1000
+    let exponent = exponent as i32;
+    Some(value * 2f64.powi(exponent))
+}
+
+impl Display for FloatValue {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str(&self.text)
+    }
+}
+
+impl<'de> Deserialize<'de> for FloatValue {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct FloatValueVisitor;
+
+        impl<'de> Visitor<'de> for FloatValueVisitor {
+            type Value = FloatValue;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("floating point value")
+            }
+
+            fn visit_str<E>(self, text: &str) -> Result<Self::Value, E>
+            where
+                E: Error,
+            {
+                FloatValue::parse(text)
+                    .ok_or_else(|| E::invalid_value(Unexpected::Str(text), &self))
+            }
+        }
+
+        deserializer.deserialize_str(FloatValueVisitor)
+    }
+}
+
+impl Serialize for FloatValue {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.text)
+    }
+}
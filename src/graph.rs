@@ -0,0 +1,186 @@
+use crate::{Id, Node};
+use std::collections::HashMap;
+use std::fmt::{self, Debug, Write};
+
+type Label<'a, T> = dyn Fn(&T) -> String + 'a;
+type References<'a, T> = dyn Fn(&T) -> Vec<Id> + 'a;
+
+/// Options for drawing a syntax tree as a graph, with [`Node::to_dot`] or
+/// [`Node::to_mermaid`].
+///
+/// Every node is drawn as a box showing the label computed from its kind,
+/// with an edge to each of its children. Optionally, references from one
+/// node to another by id, such as from a `DeclRefExpr` to the declaration it
+/// refers to, are drawn as dashed edges.
+///
+/// ```
+/// use clang_ast::{Graph, Id, Node};
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// pub enum Clang {
+///     FunctionDecl { name: String },
+///     DeclRefExpr {
+///         #[serde(rename = "referencedDecl")]
+///         referenced_decl: Decl,
+///     },
+///     Other { kind: clang_ast::Kind },
+/// }
+///
+/// #[derive(Deserialize)]
+/// pub struct Decl {
+///     pub id: Id,
+/// }
+///
+/// # fn example(node: &Node<Clang>) {
+/// let mut graph = Graph::new(|kind: &Clang| match kind {
+///     Clang::FunctionDecl { name } => format!("FunctionDecl {}", name),
+///     Clang::DeclRefExpr { .. } => "DeclRefExpr".to_owned(),
+///     Clang::Other { kind } => kind.to_string(),
+/// });
+/// graph.references(|kind| match kind {
+///     Clang::DeclRefExpr { referenced_decl } => vec![referenced_decl.id],
+///     _ => Vec::new(),
+/// });
+/// println!("{}", node.to_dot(&graph));
+/// # }
+/// ```
+pub struct Graph<'a, T> {
+    label: Box<Label<'a, T>>,
+    references: Option<Box<References<'a, T>>>,
+}
+
+impl<'a, T> Graph<'a, T> {
+    pub fn new<F>(label: F) -> Self
+    where
+        F: Fn(&T) -> String + 'a,
+    {
+        Graph {
+            label: Box::new(label),
+            references: None,
+        }
+    }
+
+    /// Draw a dashed edge from each node to the nodes whose ids are returned
+    /// for it. Ids of nodes outside of the tree being drawn are ignored.
+    pub fn references<F>(&mut self, references: F) -> &mut Self
+    where
+        F: Fn(&T) -> Vec<Id> + 'a,
+    {
+        self.references = Some(Box::new(references));
+        self
+    }
+}
+
+impl<'a, T> Debug for Graph<'a, T> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter
+            .debug_struct("Graph")
+            .field("references", &self.references.is_some())
+            .finish()
+    }
+}
+
+// Tree edges and reference edges between nodes numbered in pre-order.
+struct Layout {
+    labels: Vec<String>,
+    children: Vec<(usize, usize)>,
+    references: Vec<(usize, usize)>,
+}
+
+fn layout<T>(node: &Node<T>, graph: &Graph<T>) -> Layout {
+    let mut layout = Layout {
+        labels: Vec::new(),
+        children: Vec::new(),
+        references: Vec::new(),
+    };
+    let mut index = HashMap::new();
+    let mut pending_references = Vec::new();
+    let mut stack = vec![(None, node)];
+    while let Some((parent, node)) = stack.pop() {
+        let n = layout.labels.len();
+        layout.labels.push((graph.label)(&node.kind));
+        index.entry(node.id).or_insert(n);
+        if let Some(parent) = parent {
+            layout.children.push((parent, n));
+        }
+        if let Some(references) = &graph.references {
+            for id in references(&node.kind) {
+                pending_references.push((n, id));
+            }
+        }
+        stack.extend(node.inner.iter().rev().map(|child| (Some(n), child)));
+    }
+    for (n, id) in pending_references {
+        if let Some(&target) = index.get(&id) {
+            layout.references.push((n, target));
+        }
+    }
+    layout
+}
+
+pub(crate) fn to_dot<T>(node: &Node<T>, graph: &Graph<T>) -> String {
+    let layout = layout(node, graph);
+    let mut dot = String::new();
+    dot.push_str("digraph ast {\n");
+    dot.push_str("    node [shape=box];\n");
+    for (n, label) in layout.labels.iter().enumerate() {
+        let _ = writeln!(dot, "    n{} [label=\"{}\"];", n, DotEscape(label));
+    }
+    for (parent, child) in layout.children {
+        let _ = writeln!(dot, "    n{} -> n{};", parent, child);
+    }
+    for (from, to) in layout.references {
+        let _ = writeln!(dot, "    n{} -> n{} [style=dashed];", from, to);
+    }
+    dot.push_str("}\n");
+    dot
+}
+
+pub(crate) fn to_mermaid<T>(node: &Node<T>, graph: &Graph<T>) -> String {
+    let layout = layout(node, graph);
+    let mut mermaid = String::new();
+    mermaid.push_str("flowchart TD\n");
+    for (n, label) in layout.labels.iter().enumerate() {
+        let _ = writeln!(mermaid, "    n{}[\"{}\"]", n, MermaidEscape(label));
+    }
+    for (parent, child) in layout.children {
+        let _ = writeln!(mermaid, "    n{} --> n{}", parent, child);
+    }
+    for (from, to) in layout.references {
+        let _ = writeln!(mermaid, "    n{} -.-> n{}", from, to);
+    }
+    mermaid
+}
+
+struct DotEscape<'a>(&'a str);
+
+impl<'a> fmt::Display for DotEscape<'a> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        for ch in self.0.chars() {
+            match ch {
+                '"' => formatter.write_str("\\\"")?,
+                '\\' => formatter.write_str("\\\\")?,
+                '\n' => formatter.write_str("\\n")?,
+                _ => formatter.write_char(ch)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+struct MermaidEscape<'a>(&'a str);
+
+impl<'a> fmt::Display for MermaidEscape<'a> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        for ch in self.0.chars() {
+            match ch {
+                '"' => formatter.write_str("#quot;")?,
+                '#' => formatter.write_str("#35;")?,
+                '\n' => formatter.write_str("<br>")?,
+                _ => formatter.write_char(ch)?,
+            }
+        }
+        Ok(())
+    }
+}
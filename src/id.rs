@@ -1,19 +1,73 @@
+use crate::normalize;
 use serde::de::{Deserialize, Deserializer, Error, Unexpected, Visitor};
 use serde::ser::{Serialize, Serializer};
-use std::fmt::{self, Debug, Display};
+use std::cmp::Ordering;
+use std::convert::TryFrom;
+use std::fmt::{self, Debug, Display, LowerHex};
+use std::num::NonZeroU64;
 
-#[derive(Copy, Clone, Default, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[derive(Copy, Clone, Eq, PartialEq, Hash)]
 pub struct Id {
-    id: u64,
+    // Bitwise complement of the id, so that Option<Id> is the same size as
+    // Id. The one id this cannot hold is u64::MAX, which is never the address
+    // of a node.
+    repr: NonZeroU64,
 }
 
 impl Id {
-    pub const NULL: Id = Id { id: 0 };
+    pub const NULL: Id = match NonZeroU64::new(!0) {
+        Some(repr) => Id { repr },
+        None => panic!(),
+    };
+
+    // For ids this crate numbers itself, which never come near u64::MAX.
+    pub(crate) fn new(id: u64) -> Self {
+        Id::from_u64(id).expect("u64::MAX is not a valid clang syntax tree node id")
+    }
+
+    /// The numeric value of the id, which Clang prints in hex as `0x...`.
+    ///
+    /// This is the address of the node in the memory of the compiler
+    /// process, so it is only meaningful in relation to other ids from the
+    /// same dump.
+    pub const fn as_u64(self) -> u64 {
+        !self.repr.get()
+    }
+
+    /// The id with the given numeric value, or `None` if `id` is `u64::MAX`,
+    /// which is not a possible node address.
+    pub fn from_u64(id: u64) -> Option<Self> {
+        NonZeroU64::new(!id).map(|repr| Id { repr })
+    }
+}
+
+impl Default for Id {
+    fn default() -> Self {
+        Id::NULL
+    }
+}
+
+impl Ord for Id {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.as_u64().cmp(&other.as_u64())
+    }
+}
+
+impl PartialOrd for Id {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
 }
 
 impl Display for Id {
     fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-        write!(formatter, "0x{:x}", self.id)
+        write!(formatter, "0x{:x}", self.as_u64())
+    }
+}
+
+impl LowerHex for Id {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        LowerHex::fmt(&self.as_u64(), formatter)
     }
 }
 
@@ -44,12 +98,39 @@ impl<'de> Deserialize<'de> for Id {
                 string
                     .strip_prefix("0x")
                     .and_then(|hex| u64::from_str_radix(hex, 16).ok())
-                    .map(|id| Id { id })
+                    .and_then(Id::from_u64)
                     .ok_or_else(|| E::invalid_value(Unexpected::Str(string), &self))
             }
+
+            // Not produced by Clang, but by tools which rewrite the ids of a
+            // dump as plain JSON numbers.
+            fn visit_u64<E>(self, id: u64) -> Result<Self::Value, E>
+            where
+                E: Error,
+            {
+                Id::from_u64(id).ok_or_else(|| E::invalid_value(Unexpected::Unsigned(id), &self))
+            }
+
+            fn visit_i64<E>(self, id: i64) -> Result<Self::Value, E>
+            where
+                E: Error,
+            {
+                match u64::try_from(id) {
+                    Ok(unsigned) => self.visit_u64(unsigned),
+                    Err(_) => Err(E::invalid_value(Unexpected::Signed(id), &self)),
+                }
+            }
         }
 
-        deserializer.deserialize_str(IdVisitor)
+        // Ids are strings. Only ask for whatever the input holds, which may
+        // be an integer, from human-readable formats such as JSON; those are
+        // all self-describing, while compact binary formats like bincode are
+        // not and cannot deserialize_any.
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_any(IdVisitor)
+        } else {
+            deserializer.deserialize_str(IdVisitor)
+        }
     }
 }
 
@@ -58,6 +139,6 @@ impl Serialize for Id {
     where
         S: Serializer,
     {
-        serializer.collect_str(self)
+        serializer.collect_str(&normalize::id(*self))
     }
 }
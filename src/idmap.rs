@@ -0,0 +1,105 @@
+use crate::{Id, Node};
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::iter::FromIterator;
+
+/// Assignment of a dense `u32` index to each distinct [`Id`].
+///
+/// Indices are handed out sequentially starting from 0, in the order ids are
+/// first inserted, so they can index into a `Vec` or a bitset in place of
+/// hashing the 64-bit ids everywhere.
+///
+/// ```
+/// use clang_ast::{Id, IdMap};
+///
+/// let id = |id| Id::from_u64(id).unwrap();
+/// let mut ids = IdMap::new();
+/// let a = ids.insert(id(0x55d0f9a3c1e8));
+/// let b = ids.insert(id(0x55d0f9a3c220));
+/// assert_eq!((a, b), (0, 1));
+/// assert_eq!(ids.insert(id(0x55d0f9a3c1e8)), 0);
+/// assert_eq!(ids.id(1), Some(id(0x55d0f9a3c220)));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct IdMap {
+    indices: HashMap<Id, u32>,
+    ids: Vec<Id>,
+}
+
+impl IdMap {
+    pub fn new() -> Self {
+        IdMap::default()
+    }
+
+    /// Index every node of a syntax tree in depth-first pre-order, so that
+    /// the root receives index 0.
+    ///
+    /// Nodes which have the null id are skipped.
+    pub fn from_tree<T>(root: &Node<T>) -> Self {
+        let mut map = IdMap::new();
+        for node in root.descendants() {
+            if node.id != Id::NULL {
+                map.insert(node.id);
+            }
+        }
+        map
+    }
+
+    /// Return the index of `id`, assigning it the next index if it has not
+    /// been seen before.
+    ///
+    /// # Panics
+    ///
+    /// Panics if more than `u32::MAX` distinct ids are inserted.
+    pub fn insert(&mut self, id: Id) -> u32 {
+        if let Some(&index) = self.indices.get(&id) {
+            return index;
+        }
+        let index = u32::try_from(self.ids.len()).expect("too many ids for IdMap");
+        self.indices.insert(id, index);
+        self.ids.push(id);
+        index
+    }
+
+    pub fn get(&self, id: Id) -> Option<u32> {
+        self.indices.get(&id).copied()
+    }
+
+    /// The id which was assigned `index`.
+    pub fn id(&self, index: u32) -> Option<Id> {
+        self.ids.get(index as usize).copied()
+    }
+
+    pub fn contains(&self, id: Id) -> bool {
+        self.indices.contains_key(&id)
+    }
+
+    /// Every id in the map, ordered by index.
+    pub fn ids(&self) -> &[Id] {
+        &self.ids
+    }
+
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ids.is_empty()
+    }
+}
+
+impl Extend<Id> for IdMap {
+    fn extend<I: IntoIterator<Item = Id>>(&mut self, iter: I) {
+        for id in iter {
+            self.insert(id);
+        }
+    }
+}
+
+impl FromIterator<Id> for IdMap {
+    fn from_iter<I: IntoIterator<Item = Id>>(iter: I) -> Self {
+        let mut map = IdMap::new();
+        map.extend(iter);
+        map
+    }
+}
@@ -0,0 +1,238 @@
+use crate::intern::{self, Shared};
+use crate::loc::{self, SkipTrackedKey};
+use crate::{BareSourceLocation, SourceLocation};
+use serde::de::{
+    Deserialize, DeserializeSeed, Deserializer, IgnoredAny, MapAccess, SeqAccess, Visitor,
+};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+/// Which files a translation unit's AST dump refers to, and which of them
+/// included which.
+///
+/// This is pieced together from the `"file"` and `"includedFrom"` of source
+/// locations, so it only knows about headers in which the dump has at least
+/// one location; a header containing nothing but macros, or whose contents
+/// were all filtered out of the dump, does not appear.
+///
+/// Deserializing an `IncludeGraph` walks the entire dump without building
+/// the tree. Alternatively, locations from an already deserialized tree can
+/// be added one at a time using [`insert`][IncludeGraph::insert].
+///
+/// ```
+/// use clang_ast::IncludeGraph;
+///
+/// let json = r#"
+///     {
+///       "id": "0x1",
+///       "kind": "TranslationUnitDecl",
+///       "inner": [
+///         {
+///           "id": "0x2",
+///           "kind": "TypedefDecl",
+///           "loc": {
+///             "offset": 100, "file": "/usr/include/stdint.h", "line": 4, "col": 13, "tokLen": 7,
+///             "includedFrom": { "file": "main.c" }
+///           }
+///         },
+///         {
+///           "id": "0x3",
+///           "kind": "FunctionDecl",
+///           "loc": { "offset": 33, "file": "main.c", "line": 3, "col": 5, "tokLen": 4 }
+///         }
+///       ]
+///     }
+/// "#;
+/// let graph: IncludeGraph = serde_json::from_str(json).unwrap();
+/// assert_eq!(graph.files().collect::<Vec<_>>(), ["/usr/include/stdint.h", "main.c"]);
+/// assert_eq!(graph.includes("main.c").collect::<Vec<_>>(), ["/usr/include/stdint.h"]);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct IncludeGraph {
+    files: Vec<Shared<str>>,
+    indices: HashMap<Shared<str>, usize>,
+    // Pairs of (includer, included) in the order first seen.
+    edges: Vec<(usize, usize)>,
+    edge_set: HashSet<(usize, usize)>,
+}
+
+impl IncludeGraph {
+    pub fn new() -> Self {
+        IncludeGraph::default()
+    }
+
+    /// Record the file of both the spelling and expansion location, and
+    /// where each was included from.
+    pub fn insert(&mut self, loc: &SourceLocation) {
+        if let Some(spelling) = &loc.spelling_loc {
+            self.insert_bare(spelling);
+        }
+        if let Some(expansion) = &loc.expansion_loc {
+            self.insert_bare(expansion);
+        }
+    }
+
+    fn insert_bare(&mut self, loc: &BareSourceLocation) {
+        let file = self.file_index(&loc.file);
+        if let Some(included_from) = &loc.included_from {
+            let includer = self.file_index(&included_from.file);
+            if self.edge_set.insert((includer, file)) {
+                self.edges.push((includer, file));
+            }
+        }
+    }
+
+    fn file_index(&mut self, file: &Shared<str>) -> usize {
+        if let Some(&index) = self.indices.get(file) {
+            return index;
+        }
+        let index = self.files.len();
+        self.files.push(Shared::clone(file));
+        self.indices.insert(Shared::clone(file), index);
+        index
+    }
+
+    /// Every file seen, in the order of their first appearance.
+    pub fn files(&self) -> impl Iterator<Item = &str> {
+        self.files.iter().map(|file| &**file)
+    }
+
+    /// Every pair of including file and file it included.
+    pub fn edges(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.edges
+            .iter()
+            .map(move |&(includer, file)| (&*self.files[includer], &*self.files[file]))
+    }
+
+    /// Files directly included by `file`.
+    pub fn includes<'a>(&'a self, file: &str) -> impl Iterator<Item = &'a str> {
+        let index = self.indices.get(file).copied();
+        self.edges
+            .iter()
+            .filter(move |&&(includer, _)| Some(includer) == index)
+            .map(move |&(_, file)| &*self.files[file])
+    }
+
+    /// Files which directly include `file`.
+    pub fn included_by<'a>(&'a self, file: &str) -> impl Iterator<Item = &'a str> {
+        let index = self.indices.get(file).copied();
+        self.edges
+            .iter()
+            .filter(move |&&(_, file)| Some(file) == index)
+            .map(move |&(includer, _)| &*self.files[includer])
+    }
+
+    /// Files which were not included from anywhere: normally the main file,
+    /// as well as pseudo-files such as `<built-in>` and `<scratch space>`.
+    pub fn roots(&self) -> impl Iterator<Item = &str> {
+        let included: HashSet<usize> = self.edges.iter().map(|&(_, file)| file).collect();
+        self.files
+            .iter()
+            .enumerate()
+            .filter(move |(index, _)| !included.contains(index))
+            .map(|(_, file)| &**file)
+    }
+}
+
+impl<'de> Deserialize<'de> for IncludeGraph {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let _intern = intern::activate();
+        let mut graph = IncludeGraph::new();
+        Collect { graph: &mut graph }.deserialize(deserializer)?;
+        Ok(graph)
+    }
+}
+
+struct Collect<'a> {
+    graph: &'a mut IncludeGraph,
+}
+
+impl<'de, 'a> DeserializeSeed<'de> for Collect<'a> {
+    type Value = ();
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(self)
+    }
+}
+
+impl<'de, 'a> Visitor<'de> for Collect<'a> {
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("clang syntax tree")
+    }
+
+    fn visit_bool<E>(self, _: bool) -> Result<Self::Value, E> {
+        Ok(())
+    }
+
+    fn visit_i64<E>(self, _: i64) -> Result<Self::Value, E> {
+        Ok(())
+    }
+
+    fn visit_u64<E>(self, _: u64) -> Result<Self::Value, E> {
+        Ok(())
+    }
+
+    fn visit_f64<E>(self, _: f64) -> Result<Self::Value, E> {
+        Ok(())
+    }
+
+    fn visit_str<E>(self, _: &str) -> Result<Self::Value, E> {
+        Ok(())
+    }
+
+    fn visit_none<E>(self) -> Result<Self::Value, E> {
+        Ok(())
+    }
+
+    fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        self.deserialize(deserializer)
+    }
+
+    fn visit_unit<E>(self) -> Result<Self::Value, E> {
+        Ok(())
+    }
+
+    fn visit_seq<S>(self, mut seq: S) -> Result<Self::Value, S::Error>
+    where
+        S: SeqAccess<'de>,
+    {
+        while let Some(()) = seq.next_element_seed(Collect {
+            graph: &mut *self.graph,
+        })? {}
+        Ok(())
+    }
+
+    fn visit_map<M>(self, mut map: M) -> Result<Self::Value, M::Error>
+    where
+        M: MapAccess<'de>,
+    {
+        match map.next_key_seed(SkipTrackedKey)? {
+            None => return Ok(()),
+            Some(Some(first)) => {
+                let loc = loc::de_rest_of_source_location(first, map)?;
+                self.graph.insert(&loc);
+                return Ok(());
+            }
+            Some(None) => map.next_value_seed(Collect {
+                graph: &mut *self.graph,
+            })?,
+        }
+        while let Some(IgnoredAny) = map.next_key()? {
+            map.next_value_seed(Collect {
+                graph: &mut *self.graph,
+            })?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,48 @@
+use crate::{Id, Node};
+use std::collections::HashMap;
+use std::fmt::{self, Debug};
+
+/// Lookup table from [`Id`] to the node carrying that id.
+///
+/// Built by a single walk over a syntax tree, after which backreferences such
+/// as a [`Ref`][crate::Ref] can be resolved in constant time.
+pub struct NodeIndex<'a, T> {
+    map: HashMap<Id, &'a Node<T>>,
+}
+
+impl<'a, T> NodeIndex<'a, T> {
+    pub fn new(root: &'a Node<T>) -> Self {
+        let mut map = HashMap::new();
+        for node in root.descendants() {
+            if node.id != Id::NULL {
+                map.insert(node.id, node);
+            }
+        }
+        NodeIndex { map }
+    }
+
+    pub fn get(&self, id: Id) -> Option<&'a Node<T>> {
+        self.map.get(&id).copied()
+    }
+
+    pub fn contains(&self, id: Id) -> bool {
+        self.map.contains_key(&id)
+    }
+
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+}
+
+impl<'a, T> Debug for NodeIndex<'a, T> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter
+            .debug_struct("NodeIndex")
+            .field("len", &self.map.len())
+            .finish()
+    }
+}
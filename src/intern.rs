@@ -1,50 +1,170 @@
+use crate::context::{self, Context};
 use serde::de::{DeserializeSeed, Deserializer, Error, Visitor};
-use std::cell::{Cell, RefCell};
 use std::collections::HashSet;
-use std::fmt;
-use std::sync::Arc;
+use std::fmt::{self, Debug};
+use std::mem;
+use std::sync::{Arc, Mutex, PoisonError};
 
-thread_local! {
-    static REFCOUNT: Cell<usize> = Cell::new(0);
-    static INTERN: RefCell<HashSet<Arc<str>>> = RefCell::new(HashSet::new());
+// Pointer type of interned strings, such as the filepaths in source locations.
+#[cfg(feature = "rc")]
+pub(crate) use std::rc::Rc as Shared;
+#[cfg(not(feature = "rc"))]
+pub(crate) use std::sync::Arc as Shared;
+
+#[derive(Default)]
+pub(crate) struct State {
+    // Number of parses in progress relying on `set`.
+    refcount: usize,
+    // Strings interned by the current syntax tree.
+    set: HashSet<Shared<str>>,
+    // Set by Interner::scope to intern into that instead of `set`.
+    interner: Option<Interner>,
+}
+
+/// Table of interned filepaths which outlives any one parse.
+///
+/// By default the filepaths in source locations are interned only within a
+/// single syntax tree, so parsing many translation units that share headers
+/// ends up with a separate copy of every header path per tree. Parsing them
+/// inside of [`Interner::scope`] instead makes equal paths share one
+/// allocation across all of them.
+///
+/// Clones of an `Interner` refer to the same table, and with the default
+/// `Arc<str>` filepaths it can be used from multiple threads at once.
+///
+/// To carry the rest of the deserialization state from one parse to the next
+/// as well, see [`Session`](crate::Session).
+///
+/// ```
+/// use clang_ast::{Interner, SourceRange};
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// pub struct Clang {
+///     pub range: Option<SourceRange>,
+/// }
+///
+/// # fn main() -> serde_json::Result<()> {
+/// # let dumps = ["{\"id\":\"0x1\",\"kind\":\"TranslationUnitDecl\"}"];
+/// let interner = Interner::new();
+/// let mut trees = Vec::new();
+/// for json in &dumps {
+///     let node: clang_ast::Node<Clang> = interner.scope(|| serde_json::from_str(json))?;
+///     trees.push(node);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Default)]
+pub struct Interner {
+    set: Arc<Mutex<HashSet<Shared<str>>>>,
 }
 
-fn borrowed(string: &str) -> Arc<str> {
+impl Interner {
+    pub fn new() -> Self {
+        Interner::default()
+    }
+
+    /// Run `f` with this interner in place of the per-tree one. Syntax trees
+    /// deserialized by `f` on the current thread intern their filepaths into
+    /// this interner.
+    pub fn scope<R>(&self, f: impl FnOnce() -> R) -> R {
+        struct Restore(Option<Interner>);
+
+        impl Drop for Restore {
+            fn drop(&mut self) {
+                let prev = self.0.take();
+                context::with(|context| context.intern.interner = prev);
+            }
+        }
+
+        let prev = context::with(|context| context.intern.interner.replace(self.clone()));
+        let _restore = Restore(prev);
+        f()
+    }
+
+    /// Look up the interned copy of a string, adding it if not present.
+    pub fn intern(&self, string: &str) -> Shared<str> {
+        let mut set = self.set.lock().unwrap_or_else(PoisonError::into_inner);
+        intern_into(&mut set, string)
+    }
+
+    /// Number of distinct strings interned so far.
+    pub fn len(&self) -> usize {
+        self.set
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+pub(crate) fn borrowed(string: &str) -> Shared<str> {
     do_intern(string)
 }
 
-fn owned(string: String) -> Arc<str> {
+pub(crate) fn owned(string: String) -> Shared<str> {
     do_intern(string)
 }
 
-fn do_intern(string: impl AsRef<str> + Into<Arc<str>>) -> Arc<str> {
-    INTERN.with(|intern| {
-        let mut intern = intern.borrow_mut();
-        if let Some(arc) = intern.get(string.as_ref()) {
-            Arc::clone(arc)
+fn do_intern(string: impl AsRef<str> + Into<Shared<str>>) -> Shared<str> {
+    context::with(|context| {
+        let intern = &mut context.intern;
+        if let Some(interner) = &intern.interner {
+            let mut set = interner.set.lock().unwrap_or_else(PoisonError::into_inner);
+            intern_into(&mut set, string)
         } else {
-            let arc: Arc<str> = string.into();
-            intern.insert(Arc::clone(&arc));
-            arc
+            intern_into(&mut intern.set, string)
         }
     })
 }
 
+fn intern_into(
+    set: &mut HashSet<Shared<str>>,
+    string: impl AsRef<str> + Into<Shared<str>>,
+) -> Shared<str> {
+    if let Some(shared) = set.get(string.as_ref()) {
+        Shared::clone(shared)
+    } else {
+        let shared: Shared<str> = string.into();
+        set.insert(Shared::clone(&shared));
+        shared
+    }
+}
+
+// Swap in the interner which strings are interned into instead of the
+// current tree's own set.
+pub(crate) fn replace_interner(
+    context: &mut Context,
+    interner: Option<Interner>,
+) -> Option<Interner> {
+    mem::replace(&mut context.intern.interner, interner)
+}
+
 pub(crate) struct Guard {
     _private: (),
 }
 
 pub(crate) fn activate() -> Guard {
-    REFCOUNT.with(|refcount| refcount.set(refcount.get() + 1));
+    context::with(|context| context.intern.refcount += 1);
     Guard { _private: () }
 }
 
 impl Drop for Guard {
     fn drop(&mut self) {
-        let prev = REFCOUNT.with(|refcount| refcount.replace(refcount.get() - 1));
-        if prev == 1 {
+        let last = context::with(|context| {
+            let intern = &mut context.intern;
+            intern.refcount -= 1;
+            if intern.refcount == 0 {
+                intern.set.clear();
+            }
+            intern.refcount == 0
+        });
+        if last {
             crate::loc::thread_local_reset();
-            INTERN.with(|intern| intern.borrow_mut().clear());
         }
     }
 }
@@ -52,7 +172,7 @@ impl Drop for Guard {
 pub(crate) struct InternVisitor;
 
 impl<'de> Visitor<'de> for InternVisitor {
-    type Value = Arc<str>;
+    type Value = Shared<str>;
 
     fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
         formatter.write_str("a string")
@@ -74,7 +194,7 @@ impl<'de> Visitor<'de> for InternVisitor {
 }
 
 impl<'de> DeserializeSeed<'de> for InternVisitor {
-    type Value = Arc<str>;
+    type Value = Shared<str>;
 
     fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
     where
@@ -83,3 +203,13 @@ impl<'de> DeserializeSeed<'de> for InternVisitor {
         deserializer.deserialize_str(self)
     }
 }
+
+#[allow(clippy::missing_fields_in_debug)]
+impl Debug for Interner {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter
+            .debug_struct("Interner")
+            .field("len", &self.len())
+            .finish()
+    }
+}
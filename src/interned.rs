@@ -0,0 +1,109 @@
+use crate::intern::{InternVisitor, Shared};
+use serde::de::{Deserialize, DeserializeSeed, Deserializer};
+use serde::ser::{Serialize, Serializer};
+use std::borrow::Borrow;
+use std::fmt::{self, Debug, Display};
+use std::ops::Deref;
+
+/// String field which is interned along with the filepaths in source
+/// locations.
+///
+/// C++ dumps repeat the same `"name"`, `"mangledName"` and especially
+/// `"qualType"` strings over and over; the spelling of one template
+/// instantiation's type can be thousands of bytes long and appear thousands
+/// of times. Deserializing such fields as `InternedStr` instead of `String`
+/// stores each distinct string once per syntax tree, or once per
+/// [`Interner`] when deserialized inside of [`Interner::scope`].
+///
+/// [`Interner`]: crate::Interner
+/// [`Interner::scope`]: crate::Interner::scope
+///
+/// ```
+/// use clang_ast::InternedStr;
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// pub struct Type {
+///     #[serde(rename = "qualType")]
+///     pub qual_type: InternedStr,
+/// }
+/// ```
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct InternedStr {
+    string: Shared<str>,
+}
+
+impl InternedStr {
+    pub fn as_str(&self) -> &str {
+        &self.string
+    }
+}
+
+impl Deref for InternedStr {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target {
+        &self.string
+    }
+}
+
+impl AsRef<str> for InternedStr {
+    fn as_ref(&self) -> &str {
+        &self.string
+    }
+}
+
+impl Borrow<str> for InternedStr {
+    fn borrow(&self) -> &str {
+        &self.string
+    }
+}
+
+impl PartialEq<str> for InternedStr {
+    fn eq(&self, other: &str) -> bool {
+        *self.string == *other
+    }
+}
+
+impl<'a> PartialEq<&'a str> for InternedStr {
+    fn eq(&self, other: &&'a str) -> bool {
+        *self.string == **other
+    }
+}
+
+impl From<InternedStr> for Shared<str> {
+    fn from(interned: InternedStr) -> Self {
+        interned.string
+    }
+}
+
+impl Display for InternedStr {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        Display::fmt(&*self.string, formatter)
+    }
+}
+
+impl Debug for InternedStr {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        Debug::fmt(&*self.string, formatter)
+    }
+}
+
+impl<'de> Deserialize<'de> for InternedStr {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let string = InternVisitor.deserialize(deserializer)?;
+        Ok(InternedStr { string })
+    }
+}
+
+impl Serialize for InternedStr {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.string)
+    }
+}
@@ -0,0 +1,74 @@
+use crate::Node;
+use std::iter::FusedIterator;
+use std::slice;
+
+/// Iterator over a syntax tree in depth-first pre-order.
+///
+/// Returned by [`Node::descendants`]. The traversal keeps an explicit stack
+/// with one entry per level of depth, so arbitrarily deep trees are walked
+/// without recursion.
+pub struct Descendants<'a, T> {
+    stack: Vec<slice::Iter<'a, Node<T>>>,
+    pending: Option<&'a Node<T>>,
+}
+
+impl<'a, T> Descendants<'a, T> {
+    pub(crate) fn new(node: &'a Node<T>) -> Self {
+        Descendants {
+            stack: vec![slice::from_ref(node).iter()],
+            pending: None,
+        }
+    }
+
+    /// Do not descend into the children of the node most recently returned
+    /// by `next()`. Iteration resumes with that node's next sibling.
+    ///
+    /// ```
+    /// # use clang_ast::Node;
+    /// # use serde::Deserialize;
+    /// #
+    /// # #[derive(Deserialize)]
+    /// # pub enum Clang {
+    /// #     FunctionDecl,
+    /// #     Other,
+    /// # }
+    /// #
+    /// # fn count(node: &Node<Clang>) -> usize {
+    /// // Count the nodes outside of function bodies.
+    /// let mut descendants = node.descendants();
+    /// let mut count = 0;
+    /// while let Some(node) = descendants.next() {
+    ///     if let Clang::FunctionDecl = node.kind {
+    ///         descendants.skip_children();
+    ///     }
+    ///     count += 1;
+    /// }
+    /// # count
+    /// # }
+    /// ```
+    pub fn skip_children(&mut self) {
+        self.pending = None;
+    }
+}
+
+impl<'a, T> Iterator for Descendants<'a, T> {
+    type Item = &'a Node<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(pending) = self.pending.take() {
+            if !pending.inner.is_empty() {
+                self.stack.push(pending.inner.iter());
+            }
+        }
+        loop {
+            let top = self.stack.last_mut()?;
+            if let Some(node) = top.next() {
+                self.pending = Some(node);
+                return Some(node);
+            }
+            self.stack.pop();
+        }
+    }
+}
+
+impl<'a, T> FusedIterator for Descendants<'a, T> {}
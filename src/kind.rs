@@ -1,7 +1,4 @@
-use serde::de::{
-    DeserializeSeed, Deserializer, EnumAccess, Expected, IntoDeserializer, Unexpected,
-    VariantAccess, Visitor,
-};
+use serde::de::{DeserializeSeed, Deserializer, EnumAccess, Unexpected, VariantAccess, Visitor};
 use serde::ser::{Serialize, Serializer};
 use serde::{forward_to_deserialize_any, Deserialize};
 use std::fmt::{self, Debug, Display};
@@ -10,45 +7,95 @@ use std::str::FromStr;
 
 macro_rules! kind {
     ($($kind:ident,)*) => {
-        #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+        /// Kind of a syntax tree node, as found in its `"kind"` field.
+        ///
+        /// Since 0.2, `Kind` is `Clone` but no longer `Copy`, because
+        /// [`Kind::Other`] owns the name of the unknown kind, and
+        /// [`Kind::as_str`] borrows from the `Kind`. Code which needs a
+        /// `&'static str` can use [`Kind::known_str`].
+        #[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
         #[non_exhaustive]
         pub enum Kind {
             $(
                 $kind,
             )*
+            /// A kind not known to this version of this crate, such as one
+            /// introduced by a newer Clang release.
+            ///
+            /// Produced only by deserialization; `Kind::from_str` rejects
+            /// unknown kinds. Deserialization never puts the name of a known
+            /// kind in `Other`, but nothing stops one being constructed by
+            /// hand, and `Kind::Other("CXXRecordDecl".into())` compares
+            /// unequal to `Kind::CXXRecordDecl`. Use [`Kind::from_name`] to
+            /// get the canonical `Kind` for an arbitrary name.
+            Other(Box<str>),
             #[allow(non_camel_case_types)]
             null,
         }
 
         impl Kind {
-            pub fn as_str(&self) -> &'static str {
+            /// Every kind of node, in alphabetical order.
+            ///
+            /// This does not include `Kind::null`, which stands for a node
+            /// whose kind is missing rather than for a kind of node.
+            pub const ALL: &'static [Kind] = &[
+                $(
+                    Kind::$kind,
+                )*
+            ];
+
+            /// Iterate over [`Kind::ALL`].
+            pub fn iter() -> impl Iterator<Item = Kind> {
+                Kind::ALL.iter().cloned()
+            }
+
+            /// The kind with the given name, or `Kind::Other` if the name is
+            /// not one known to this version of this crate.
+            pub fn from_name(kind: &str) -> Self {
+                match Kind::known(kind) {
+                    Some(kind) => kind,
+                    None => Kind::Other(Box::from(kind)),
+                }
+            }
+
+            /// Name of the kind as it appears in the `"kind"` field of the
+            /// JSON, which is also its `Display` representation.
+            pub fn as_str(&self) -> &str {
+                match self {
+                    Kind::Other(kind) => kind,
+                    _ => self.known_str().unwrap(),
+                }
+            }
+
+            /// Same as [`as_str`][Kind::as_str], but `'static`, which is
+            /// possible for every kind except `Kind::Other`.
+            pub fn known_str(&self) -> Option<&'static str> {
                 match self {
                     $(
-                        Kind::$kind => stringify!($kind),
+                        Kind::$kind => Some(stringify!($kind)),
                     )*
-                    Kind::null => "null",
+                    Kind::Other(_) => None,
+                    Kind::null => Some("null"),
                 }
             }
-        }
-
-        impl FromStr for Kind {
-            type Err = ParseKindError;
 
-            fn from_str(kind: &str) -> Result<Self, Self::Err> {
+            fn known(kind: &str) -> Option<Self> {
                 match kind {
                     $(
-                        stringify!($kind) => Ok(Kind::$kind),
+                        stringify!($kind) => Some(Kind::$kind),
                     )*
-                    _other => Err(ParseKindError { _private: () }),
+                    _ => None,
                 }
             }
         }
 
-        static VARIANTS: &'static [&'static str] = &[
-            $(
-                stringify!($kind),
-            )*
-        ];
+        impl FromStr for Kind {
+            type Err = ParseKindError;
+
+            fn from_str(kind: &str) -> Result<Self, Self::Err> {
+                Kind::known(kind).ok_or(ParseKindError { _private: () })
+            }
+        }
     };
 }
 
@@ -320,12 +367,7 @@ impl<'de> Visitor<'de> for KindVisitor {
     where
         E: serde::de::Error,
     {
-        match Kind::from_str(kind) {
-            Ok(kind) => Ok(kind),
-            Err(ParseKindError { .. }) => {
-                Err(serde::de::Error::unknown_variant(kind, self::VARIANTS))
-            }
-        }
+        Ok(Kind::from_name(kind))
     }
 }
 
@@ -342,6 +384,8 @@ impl Serialize for Kind {
     }
 }
 
+/// Error returned by `Kind::from_str` for a string which is not the name of
+/// any kind of node.
 pub struct ParseKindError {
     _private: (),
 }
@@ -374,6 +418,17 @@ impl<'de> AnyKind<'de> {
             AnyKind::Owned(kind) => kind,
         }
     }
+
+    pub(crate) fn deserializer<'a, E>(&'a self) -> SometimesBorrowedStrDeserializer<'a, 'de, E> {
+        match self {
+            AnyKind::Kind(kind) => match kind.known_str() {
+                Some(kind) => SometimesBorrowedStrDeserializer::borrowed(kind),
+                None => SometimesBorrowedStrDeserializer::transient(kind.as_str()),
+            },
+            AnyKind::Borrowed(kind) => SometimesBorrowedStrDeserializer::borrowed(kind),
+            AnyKind::Owned(kind) => SometimesBorrowedStrDeserializer::transient(kind),
+        }
+    }
 }
 
 impl<'de> Display for AnyKind<'de> {
@@ -408,9 +463,9 @@ impl<'de> Visitor<'de> for AnyKindVisitor<'de> {
     where
         E: serde::de::Error,
     {
-        match Kind::deserialize(kind.into_deserializer()) {
-            Ok(kind) => Ok(AnyKind::Kind(kind)),
-            Err(UnknownVariant) => Ok(AnyKind::Owned(Box::from(kind))),
+        match Kind::known(kind) {
+            Some(kind) => Ok(AnyKind::Kind(kind)),
+            None => Ok(AnyKind::Owned(Box::from(kind))),
         }
     }
 
@@ -418,9 +473,9 @@ impl<'de> Visitor<'de> for AnyKindVisitor<'de> {
     where
         E: serde::de::Error,
     {
-        match Kind::deserialize(kind.into_deserializer()) {
-            Ok(kind) => Ok(AnyKind::Kind(kind)),
-            Err(UnknownVariant) => Ok(AnyKind::Borrowed(kind)),
+        match Kind::known(kind) {
+            Some(kind) => Ok(AnyKind::Kind(kind)),
+            None => Ok(AnyKind::Borrowed(kind)),
         }
     }
 
@@ -428,71 +483,13 @@ impl<'de> Visitor<'de> for AnyKindVisitor<'de> {
     where
         E: serde::de::Error,
     {
-        match Kind::deserialize(kind.as_str().into_deserializer()) {
-            Ok(kind) => Ok(AnyKind::Kind(kind)),
-            Err(UnknownVariant) => Ok(AnyKind::Owned(kind.into_boxed_str())),
+        match Kind::known(&kind) {
+            Some(kind) => Ok(AnyKind::Kind(kind)),
+            None => Ok(AnyKind::Owned(kind.into_boxed_str())),
         }
     }
 }
 
-#[derive(Debug)]
-struct UnknownVariant;
-
-impl std::error::Error for UnknownVariant {}
-
-impl serde::de::Error for UnknownVariant {
-    fn custom<T: Display>(msg: T) -> Self {
-        let _ = msg;
-        unreachable!()
-    }
-
-    fn invalid_type(unexp: Unexpected<'_>, exp: &dyn Expected) -> Self {
-        let _ = unexp;
-        let _ = exp;
-        unreachable!()
-    }
-
-    fn invalid_value(unexp: Unexpected<'_>, exp: &dyn Expected) -> Self {
-        let _ = unexp;
-        let _ = exp;
-        unreachable!()
-    }
-
-    fn invalid_length(len: usize, exp: &dyn Expected) -> Self {
-        let _ = len;
-        let _ = exp;
-        unreachable!()
-    }
-
-    fn unknown_variant(variant: &str, expected: &'static [&'static str]) -> Self {
-        let _ = variant;
-        let _ = expected;
-        UnknownVariant
-    }
-
-    fn unknown_field(field: &str, expected: &'static [&'static str]) -> Self {
-        let _ = field;
-        let _ = expected;
-        unreachable!()
-    }
-
-    fn missing_field(field: &'static str) -> Self {
-        let _ = field;
-        unreachable!()
-    }
-
-    fn duplicate_field(field: &'static str) -> Self {
-        let _ = field;
-        unreachable!()
-    }
-}
-
-impl Display for UnknownVariant {
-    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-        formatter.write_str("unknown variant")
-    }
-}
-
 enum SometimesBorrowedStr<'a, 'de> {
     Transient(&'a str),
     Borrowed(&'de str),
@@ -0,0 +1,154 @@
+use crate::deserializer::{self, Inner};
+use crate::loc::{self, LocState, SkipTracked};
+use crate::{intern, Id};
+use serde::de::{Deserialize, DeserializeSeed, Deserializer, Error, MapAccess, Visitor};
+use serde_json::value::RawValue;
+use std::fmt::{self, Debug};
+use std::marker::PhantomData;
+
+/// Syntax tree node whose children are deserialized on demand.
+///
+/// Deserializing a `LazyNode` from JSON decodes the node's own id and kind,
+/// but keeps its `"inner"` array as unparsed JSON text. Calling
+/// [`children`][LazyNode::children] deserializes just the next level down,
+/// again as `LazyNode`s, so an interactive tool can parse the skeleton of a
+/// dump up front and inflate only the subtrees it touches.
+///
+/// Lazy children work correctly with [`SourceLocation`][crate::SourceLocation]
+/// despite Clang omitting `"file"` and `"line"` from locations that share them
+/// with the previously serialized one; source locations inside of a deferred
+/// subtree are tracked at the time it is skipped over.
+///
+/// *This type is available if clang-ast is built with the `"lazy"` feature.*
+///
+/// ```
+/// use clang_ast::LazyNode;
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// pub enum Clang {
+///     NamespaceDecl { name: Option<String> },
+///     Other,
+/// }
+///
+/// # fn main() -> serde_json::Result<()> {
+/// # let json = r#"{"id":"0x1","kind":"TranslationUnitDecl","inner":[{"id":"0x2","kind":"NamespaceDecl","name":"std","inner":[{"id":"0x3","kind":"ClassTemplateDecl"}]}]}"#;
+/// let root: LazyNode<Clang> = serde_json::from_str(json)?;
+/// for child in root.children()? {
+///     if let Clang::NamespaceDecl { name: Some(name) } = &child.kind {
+///         println!("{}: {} children", name, child.children()?.len());
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct LazyNode<T> {
+    pub id: Id,
+    pub kind: T,
+    inner: Option<Deferred>,
+}
+
+struct Deferred {
+    json: Box<RawValue>,
+    locs: LocState,
+}
+
+impl<T> LazyNode<T> {
+    /// Deserialize the immediate children of this node.
+    ///
+    /// Each call parses the children anew from the retained JSON.
+    pub fn children<'a>(&'a self) -> serde_json::Result<Vec<LazyNode<T>>>
+    where
+        T: Deserialize<'a>,
+    {
+        let deferred = match &self.inner {
+            Some(deferred) => deferred,
+            None => return Ok(Vec::new()),
+        };
+        let _intern = intern::activate();
+        let _restore = loc::thread_local_replace(deferred.locs.clone());
+        serde_json::from_str(deferred.json.get())
+    }
+
+    /// The node's `"inner"` array as JSON text, if it has one.
+    pub fn inner_json(&self) -> Option<&str> {
+        self.inner.as_ref().map(|deferred| deferred.json.get())
+    }
+}
+
+impl<'de, T> Deserialize<'de> for LazyNode<T>
+where
+    T: Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let _intern = intern::activate();
+        deserializer.deserialize_map(LazyNodeVisitor {
+            marker: PhantomData,
+        })
+    }
+}
+
+struct LazyNodeVisitor<T> {
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<'de, T> Visitor<'de> for LazyNodeVisitor<T>
+where
+    T: Deserialize<'de>,
+{
+    type Value = LazyNode<T>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("clang syntax tree node")
+    }
+
+    fn visit_map<M>(self, map: M) -> Result<Self::Value, M::Error>
+    where
+        M: MapAccess<'de>,
+    {
+        let mut inner = LazyInner(None);
+        let (id, kind) = deserializer::visit_node(map, &mut inner)?;
+        Ok(LazyNode {
+            id,
+            kind,
+            inner: inner.0,
+        })
+    }
+}
+
+struct LazyInner(Option<Deferred>);
+
+impl<'de> Inner<'de> for LazyInner {
+    fn deserialize_inner<D>(&mut self, deserializer: D) -> Result<(), D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let locs = loc::thread_local_save();
+        let json = Box::<RawValue>::deserialize(deserializer)?;
+        // Bring the "same as previous" state up to where it would be had the
+        // children been deserialized right away.
+        let mut skip = serde_json::Deserializer::from_str(json.get());
+        SkipTracked
+            .deserialize(&mut skip)
+            .map_err(D::Error::custom)?;
+        self.0 = Some(Deferred { json, locs });
+        Ok(())
+    }
+}
+
+impl<T> Debug for LazyNode<T>
+where
+    T: Debug,
+{
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter
+            .debug_struct("LazyNode")
+            .field("id", &self.id)
+            .field("kind", &self.kind)
+            .field("inner", &self.inner_json())
+            .finish()
+    }
+}
@@ -317,6 +317,14 @@
 //! information painlessly, producing `Arc<str>` as the type of filepaths which
 //! may be shared across multiple source locations.
 //!
+//! Programs which process syntax trees on a single thread can enable the
+//! `"rc"` feature of clang-ast to use `Rc<str>` instead of `Arc<str>`, which
+//! avoids atomic reference counting for every source location.
+//!
+//! Filepaths are shared within one syntax tree. To share them across the
+//! trees of many translation units, deserialize those inside of
+//! [`Interner::scope`].
+//!
 //! ```
 //! use serde::Deserialize;
 //!
@@ -385,131 +393,779 @@
 //! provides an `Id` type for this purpose, which is cheaply copyable, hashable,
 //! and comparible more cheaply than a string. You may find yourself with lots
 //! of hashtables keyed on `Id`.
+//!
+//! For the common case of following a backreference to the node it names,
+//! declare the field as `clang_ast::Ref<Clang>` and resolve it through a
+//! `clang_ast::NodeIndex` built over the whole tree after deserialization.
+//!
+//! <br><br>
+//!
+//! # Borrowed strings
+//!
+//! Nothing about `Node<T>` requires `T` to own its strings. When the whole AST
+//! dump is in memory for as long as the syntax tree is in use, for example
+//! after reading the file into a `String` or memory-mapping it, string fields
+//! of the kind type can borrow from the input instead of allocating.
+//!
+//! ```
+//! use serde::Deserialize;
+//! use std::borrow::Cow;
+//!
+//! pub type Node<'a> = clang_ast::Node<Clang<'a>>;
+//!
+//! #[derive(Deserialize)]
+//! pub enum Clang<'a> {
+//!     FunctionDecl {
+//!         #[serde(borrow)]
+//!         name: Cow<'a, str>,
+//!         #[serde(rename = "type", borrow)]
+//!         ty: Type<'a>,
+//!     },
+//!     Other,
+//! }
+//!
+//! #[derive(Deserialize)]
+//! pub struct Type<'a> {
+//!     #[serde(rename = "qualType", borrow)]
+//!     pub qual_type: Cow<'a, str>,
+//! }
+//!
+//! fn parse(json: &str) -> serde_json::Result<Node> {
+//!     serde_json::from_str(json)
+//! }
+//! #
+//! # let json = r#"{"id":"0x1","kind":"FunctionDecl","name":"f","type":{"qualType":"void ()"}}"#;
+//! # let node = parse(json).unwrap();
+//! # match node.kind {
+//! #     Clang::FunctionDecl { name: Cow::Borrowed("f"), ty: Type { qual_type: Cow::Borrowed("void ()") } } => {}
+//! #     _ => panic!(),
+//! # }
+//! ```
+//!
+//! Prefer `Cow<'a, str>` over `&'a str`. A JSON string containing escape
+//! sequences cannot be borrowed from the input, in which case `Cow` falls back
+//! to an owned copy while `&str` fails to deserialize.
+//!
+//! File paths in source locations are `Arc<str>`, or `Rc<str>` with the `"rc"`
+//! feature. These are interned, so each distinct path is allocated once no
+//! matter how many locations refer to it.
+//!
+//! <br><br>
+//!
+//! # Memory use
+//!
+//! The children of a [`Node`] are a `Vec<Node<T>>`, which is 24 bytes per node
+//! even though most nodes in real dumps are leaves. The `"thin-inner"` feature
+//! replaces it with a `ThinVec<Node<T>>` from the thin-vec crate, which keeps
+//! its length and capacity in the heap allocation and so is 8 bytes per node,
+//! with nothing allocated for a leaf. There is no type parameter for choosing
+//! some other container such as `SmallVec`, because the traversals in this
+//! crate, such as [`Visit`], [`VisitMut`], [`Cursor`], [`Descendants`] and
+//! [`Filter`], are all written against the one type.
+//!
+//! When peak memory matters more than the convenience of `Node`, deserialize
+//! an [`Ast`] instead. It stores the whole tree in one arena and spends 12
+//! bytes per node on structure, against 24 bytes for each `Vec` plus its spare
+//! capacity.
+//!
+//! The strings inside of the nodes can go in an arena too. Fields of type
+//! [`ArenaStr`] deserialized inside of [`StrArena::scope`] are appended to one
+//! buffer instead of each being a separate `String`, so that an `Ast` and its
+//! strings are freed in a few deallocations, and a reused `StrArena` stops
+//! allocating once it has grown to fit the largest translation unit.
+//!
+//! Alternatively, a `Node` can be converted after parsing into a tree of your
+//! own design. Converting to a boxed slice, for example, drops the spare
+//! capacity and 8 bytes per node:
+//!
+//! ```
+//! use clang_ast::{Id, Node};
+//! use serde::Deserialize;
+//!
+//! #[derive(Deserialize)]
+//! pub enum Clang {
+//!     FunctionDecl { name: String },
+//!     Other,
+//! }
+//!
+//! pub struct CompactNode<T> {
+//!     pub id: Id,
+//!     pub kind: T,
+//!     pub inner: Box<[CompactNode<T>]>,
+//! }
+//!
+//! // Recursive, so only suitable for trees of modest depth.
+//! fn compact<T>(node: Node<T>) -> CompactNode<T> {
+//!     CompactNode {
+//!         id: node.id,
+//!         kind: node.kind,
+//!         inner: node.inner.into_iter().map(compact).collect(),
+//!     }
+//! }
+//! #
+//! # let json = r#"{"id":"0x1","kind":"TranslationUnitDecl","inner":[{"id":"0x2","kind":"FunctionDecl","name":"f"}]}"#;
+//! # let node: Node<Clang> = serde_json::from_str(json).unwrap();
+//! # let node = compact(node);
+//! # assert_eq!(node.inner.len(), 1);
+//! # assert!(std::mem::size_of::<Box<[CompactNode<Clang>]>>() < std::mem::size_of::<Vec<Node<Clang>>>());
+//! ```
+//!
+//! <br><br>
+//!
+//! # Deeply nested syntax trees
+//!
+//! By default serde_json refuses to deserialize JSON nested more than 128
+//! levels deep, which for an AST dump is a bit over 60 levels of nodes because
+//! each node and its `"inner"` array count as a level each. Deeper input fails
+//! with a "recursion limit exceeded" error rather than overflowing the stack.
+//!
+//! Long chains of binary operators or nested initializer lists do go deeper
+//! than that. To deserialize such trees, disable serde_json's recursion limit
+//! using its `"unbounded_depth"` feature and make sure there is enough stack,
+//! either by running on a thread with a large stack or by wrapping the
+//! deserializer in `serde_stacker::Deserializer` which grows the stack on
+//! demand. [`Filter::recursion_limit`] can take over the job of rejecting
+//! pathologically deep input.
+//!
+//! ```no_run
+//! use clang_ast::{Filter, Node};
+//! use serde::Deserialize;
+//! use std::thread;
+//!
+//! #[derive(Deserialize)]
+//! pub enum Clang {
+//!     BinaryOperator { opcode: String },
+//!     Other,
+//! }
+//!
+//! fn main() {
+//!     let json = std::fs::read_to_string("ast.json").unwrap();
+//!     let thread = thread::Builder::new().stack_size(1 << 30);
+//!     let handle = thread.spawn(move || {
+//!         let mut deserializer = serde_json::Deserializer::from_str(&json);
+//!         deserializer.disable_recursion_limit();
+//!         let mut filter = Filter::new();
+//!         filter.recursion_limit(100_000);
+//!         let node: Node<Clang> = filter.deserialize(&mut deserializer).unwrap();
+//!         node.descendants().count()
+//!     });
+//!     println!("{} nodes", handle.unwrap().join().unwrap());
+//! }
+//! ```
+//!
+//! <br><br>
+//!
+//! # Other JSON parsers
+//!
+//! Nothing about deserializing a `Node` is tied to serde_json. The bookkeeping
+//! for interned filepaths and for locations which refer back to the previous
+//! one is set up by the `Deserialize` impls of this crate's types, so any
+//! serde `Deserializer` for JSON can drive them, for example the one from
+//! simd-json's `simd_json::serde::from_slice`.
+//!
+//! The deserializer must present the fields of each object in the order they
+//! appear in the document. Clang leaves out the file and line of a location
+//! when they are the same as in the location written before it, so the order
+//! carries meaning. This rules out going through a JSON value type which
+//! stores objects in a sorted or hashed map, such as `serde_json::Value`
+//! without serde_json's `"preserve_order"` feature, or simd-json's
+//! `OwnedValue` and `BorrowedValue`. Deserializing a `Node` from one of those
+//! is rejected with an error as soon as some field ends up ahead of `kind`.
 
-#![doc(html_root_url = "https://docs.rs/clang-ast/0.1.12")]
+#![doc(html_root_url = "https://docs.rs/clang-ast/0.2.0")]
 #![allow(
     clippy::blocks_in_if_conditions,
+    clippy::manual_let_else,
     clippy::match_like_matches_macro,
+    clippy::missing_errors_doc,
     clippy::must_use_candidate,
     clippy::option_if_let_else,
     clippy::ptr_arg,
     clippy::uninlined_format_args
 )]
 
+mod ancestry;
+mod ast;
+mod base;
+#[cfg(feature = "cache")]
+pub mod cache;
+mod cast_kind;
+mod class_hierarchy;
+mod codegen;
+#[cfg(feature = "compile-commands")]
+pub mod compile_commands;
+mod constant_value;
+mod context;
+mod cursor;
+mod decl_context;
+mod decl_spec;
+#[cfg(feature = "reader")]
+mod decompress;
 mod dedup;
+mod definition_data;
+#[cfg(feature = "demangle")]
+mod demangle;
 mod deserializer;
+mod diagnostic;
+pub mod diff;
+#[cfg(feature = "documents")]
+mod documents;
+mod dump;
+#[cfg(feature = "exec")]
+pub mod exec;
+#[cfg(feature = "feed-thread")]
+mod feed;
+mod file_table;
+mod filter;
+mod find;
+mod float_value;
+mod graph;
 mod id;
+mod idmap;
+mod include_graph;
+mod index;
 mod intern;
+mod interned;
+mod iter;
 mod kind;
+#[cfg(feature = "lazy")]
+mod lazy;
+mod line_index;
+mod literal_value;
 mod loc;
+mod locate;
+pub mod lsp;
+#[cfg(feature = "mmap")]
+mod mmap;
+mod name_index;
+mod normalize;
+mod opcode;
+mod path_map;
+mod private;
+mod progress;
+#[cfg(feature = "project")]
+mod project;
+mod qual_type;
+mod qualified_name;
+mod range_index;
+mod reference;
+mod schema;
 mod serializer;
+mod session;
+mod source_manager;
+mod str_arena;
+pub mod stream;
+mod strict;
+mod string_value;
+mod template_argument;
+pub mod text;
+mod ty;
+mod value_category;
+mod version;
+mod visit;
 
 extern crate serde;
 
-use crate::deserializer::NodeDeserializer;
-use crate::kind::AnyKind;
 use crate::serializer::NodeSerializer;
 use serde::de::{Deserialize, Deserializer, MapAccess, Visitor};
 use serde::ser::{Serialize, SerializeMap, Serializer};
+use std::collections::hash_map::DefaultHasher;
 use std::fmt;
+use std::hash::{Hash, Hasher};
 use std::marker::PhantomData;
+use std::mem;
 
+// Container type of the children of Node.
+#[cfg(not(feature = "thin-inner"))]
+pub(crate) use std::vec::{IntoIter as InnerIntoIter, Vec as InnerVec};
+#[cfg(feature = "thin-inner")]
+pub(crate) use thin_vec::{IntoIter as InnerIntoIter, ThinVec as InnerVec};
+
+pub use crate::ancestry::{WithDepth, WithParent};
+pub use crate::ast::{Ast, AstNode, NodeId};
+pub use crate::base::{Access, Base};
+pub use crate::cast_kind::CastKind;
+pub use crate::class_hierarchy::{Class, ClassHierarchy, Inheritance};
+pub use crate::constant_value::ConstantValue;
+pub use crate::cursor::{Children, Cursor};
+pub use crate::decl_context::{DeclContexts, DeclInfo};
+pub use crate::decl_spec::{InitStyle, StorageClass, TagKind};
+#[cfg(feature = "reader")]
+pub use crate::decompress::{from_reader, Compression, Decoders};
+pub use crate::definition_data::{DefinitionData, SpecialMember};
+#[cfg(feature = "demangle")]
+pub use crate::demangle::demangle;
+pub use crate::diagnostic::{Diagnostic, Severity};
+#[cfg(feature = "documents")]
+pub use crate::documents::{documents, Concatenated, Documents};
+pub use crate::dump::TextDump;
+#[cfg(feature = "feed-thread")]
+pub use crate::feed::{feed, Feed, Parsing, SendChunk};
+pub use crate::file_table::{FileEntry, FileTable};
+pub use crate::filter::Filter;
+pub use crate::find::find;
+pub use crate::float_value::FloatValue;
+pub use crate::graph::Graph;
 pub use crate::id::Id;
-pub use crate::kind::Kind;
-pub use crate::loc::{BareSourceLocation, IncludedFrom, SourceLocation, SourceRange};
+pub use crate::idmap::IdMap;
+pub use crate::include_graph::IncludeGraph;
+pub use crate::index::NodeIndex;
+pub use crate::intern::Interner;
+pub use crate::interned::InternedStr;
+pub use crate::iter::Descendants;
+pub use crate::kind::{Kind, ParseKindError};
+#[cfg(feature = "lazy")]
+pub use crate::lazy::LazyNode;
+pub use crate::line_index::{LineIndex, Position, PositionEncoding};
+pub use crate::literal_value::LiteralValue;
+pub use crate::loc::{BareSourceLocation, IncludedFrom, Origin, SourceLocation, SourceRange};
+#[cfg(feature = "mmap")]
+pub use crate::mmap::from_path;
+pub use crate::name_index::NameIndex;
+pub use crate::normalize::Normalize;
+pub use crate::opcode::{BinaryOpcode, UnaryOpcode};
+pub use crate::path_map::{PathMap, PathStyle};
+pub use crate::progress::Progress;
+#[cfg(feature = "project")]
+pub use crate::project::{LoadError, Project, TranslationUnit};
+pub use crate::qual_type::{
+    FunctionType, Name, NameSegment, ParseTypeError, ParsedType, Qualifiers, TemplateArg,
+};
+pub use crate::qualified_name::{DeclName, QualifiedNames};
+pub use crate::range_index::RangeIndex;
+pub use crate::reference::Ref;
+pub use crate::schema::{FieldSchema, KindSchema, Schema, ValueType};
+pub use crate::session::{NodeSeed, Session};
+pub use crate::source_manager::SourceManager;
+pub use crate::str_arena::{ArenaStr, StrArena};
+pub use crate::strict::Strict;
+pub use crate::string_value::{StringEncoding, StringValue};
+pub use crate::template_argument::TemplateArgument;
+pub use crate::ty::Type;
+pub use crate::value_category::ValueCategory;
+pub use crate::version::ClangVersion;
+pub use crate::visit::{Control, Visit, VisitMut};
+/// Derive `Deserialize` for a `Clang` enum of node kinds.
+///
+/// This is a replacement for serde's derive on the enum (the structs held by
+/// its variants should still use serde's derive), with some abilities
+/// beyond it:
+///
+/// - Each variant name is checked at compile time against the node kinds
+///   known to this crate, so that a misspelled `CXXRecordDecl` is an error
+///   rather than a variant which silently never matches. Mark a variant
+///   `#[clang(unchecked)]` to opt out, for kinds newer than this crate.
+///
+/// - The fallback variant for unrecognized kinds is marked
+///   `#[clang(other)]` and can have any name. Without the attribute, a
+///   variant named `Other` or `Unknown` is the fallback as usual.
+///
+/// - A fallback variant with one field, such as `String`, receives the
+///   unrecognized kind.
+///
+/// Only unit variants and newtype variants are supported.
+///
+/// ```
+/// use clang_ast::ClangKind;
+/// use serde::Deserialize;
+///
+/// pub type Node = clang_ast::Node<Clang>;
+///
+/// #[derive(ClangKind, Debug)]
+/// pub enum Clang {
+///     TranslationUnitDecl,
+///     FunctionDecl(FunctionDecl),
+///     #[clang(other)]
+///     Unrecognized(String),
+/// }
+///
+/// #[derive(Deserialize, Debug)]
+/// pub struct FunctionDecl {
+///     pub name: String,
+/// }
+///
+/// let json = r#"
+///     {
+///       "id": "0x1",
+///       "kind": "TranslationUnitDecl",
+///       "inner": [
+///         { "id": "0x2", "kind": "FunctionDecl", "name": "f" },
+///         { "id": "0x3", "kind": "VarDecl", "name": "x" }
+///       ]
+///     }
+/// "#;
+/// let node: Node = serde_json::from_str(json).unwrap();
+/// match &node.inner[1].kind {
+///     Clang::Unrecognized(kind) => assert_eq!(kind, "VarDecl"),
+///     _ => unreachable!(),
+/// }
+/// ```
+#[cfg(feature = "derive")]
+pub use clang_ast_derive::ClangKind;
+/// Generate a visitor trait with one method per variant of a `Clang` enum.
+///
+/// For an enum named `Clang`, the derive emits a trait `ClangVisitor<'ast>`
+/// containing a method `visit_function_decl` for the variant `FunctionDecl`,
+/// and so on. Each method receives the node, along with the variant's field
+/// in the case of a newtype variant, and returns a [`Control`]. All of them
+/// default to doing nothing, so an implementation only needs to mention the
+/// kinds it cares about. When variants are added to the enum, the trait
+/// grows along with it.
+///
+/// The trait also has the following provided methods:
+///
+/// - `exit_node`, called after a node's children have been walked;
+/// - `dispatch`, which calls the method for the kind of one node;
+/// - `walk`, which calls `dispatch` and `exit_node` on every node of a tree,
+///   in the same order as [`Node::visit`].
+///
+/// ```
+/// use clang_ast::{ClangVisitor, Control};
+/// use serde::Deserialize;
+///
+/// pub type Node = clang_ast::Node<Clang>;
+///
+/// #[derive(ClangVisitor, Deserialize)]
+/// pub enum Clang {
+///     FunctionDecl(FunctionDecl),
+///     CXXRecordDecl,
+///     Other,
+/// }
+///
+/// #[derive(Deserialize)]
+/// pub struct FunctionDecl {
+///     pub name: String,
+/// }
+///
+/// #[derive(Default)]
+/// struct FunctionNames<'ast> {
+///     names: Vec<&'ast str>,
+/// }
+///
+/// impl<'ast> ClangVisitor<'ast> for FunctionNames<'ast> {
+///     fn visit_function_decl(&mut self, _node: &'ast Node, decl: &'ast FunctionDecl) -> Control {
+///         self.names.push(&decl.name);
+///         Control::SkipChildren
+///     }
+///
+///     fn visit_cxx_record_decl(&mut self, _node: &'ast Node) -> Control {
+///         // Leave out member functions.
+///         Control::SkipChildren
+///     }
+/// }
+///
+/// let json = r#"
+///     {
+///       "id": "0x1",
+///       "kind": "TranslationUnitDecl",
+///       "inner": [
+///         { "id": "0x2", "kind": "FunctionDecl", "name": "f" },
+///         {
+///           "id": "0x3",
+///           "kind": "CXXRecordDecl",
+///           "inner": [
+///             { "id": "0x4", "kind": "FunctionDecl", "name": "method" }
+///           ]
+///         }
+///       ]
+///     }
+/// "#;
+/// let node: Node = serde_json::from_str(json).unwrap();
+/// let mut visitor = FunctionNames::default();
+/// visitor.walk(&node);
+/// assert_eq!(visitor.names, ["f"]);
+/// ```
+#[cfg(feature = "derive")]
+pub use clang_ast_derive::ClangVisitor;
+
+// Not public API. Used by code generated by #[derive(ClangKind)].
+#[doc(hidden)]
+pub mod __private {
+    pub use crate::private::*;
+}
 
 /// <font style="font-variant:small-caps">syntax tree root</font>
 #[derive(Debug)]
 pub struct Node<T> {
     pub id: Id,
     pub kind: T,
-    pub inner: Vec<Node<T>>,
+    /// `Vec<Node<T>>`, or `ThinVec<Node<T>>` with the `"thin-inner"` feature.
+    pub inner: InnerVec<Node<T>>,
 }
 
-struct NodeVisitor<T> {
-    marker: PhantomData<fn() -> T>,
-}
+impl<T> Node<T> {
+    /// Iterate over this node and every node beneath it, in depth-first
+    /// pre-order.
+    ///
+    /// The first item produced is `self`. Children are visited in the order
+    /// they appear in `inner`.
+    pub fn descendants(&self) -> Descendants<'_, T> {
+        Descendants::new(self)
+    }
 
-impl<'de, T> Visitor<'de> for NodeVisitor<T>
-where
-    T: Deserialize<'de>,
-{
-    type Value = Node<T>;
+    /// Start a [`Cursor`] at this node for navigating to parents and siblings.
+    pub fn cursor(&self) -> Cursor<'_, T> {
+        Cursor::new(self)
+    }
 
-    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-        formatter.write_str("clang syntax tree node")
+    /// Walk the tree rooted at this node, invoking the visitor's
+    /// `enter_node` and `exit_node` callbacks around each node's children.
+    /// The [`Control`] returned by `enter_node` can prune a subtree or end the
+    /// walk early.
+    ///
+    /// The walk does not recurse, so it is safe on arbitrarily deep trees.
+    pub fn visit<'ast, V>(&'ast self, visitor: &mut V)
+    where
+        V: ?Sized + Visit<'ast, T>,
+    {
+        visit::visit(self, visitor);
     }
 
-    fn visit_map<M>(self, mut map: M) -> Result<Self::Value, M::Error>
+    /// Walk the tree rooted at this node in pre-order, passing each node to
+    /// the callback together with the chain of nodes enclosing it.
+    ///
+    /// The ancestors slice begins with `self` and ends with the immediate
+    /// parent of the node being visited; it is empty for `self`.
+    ///
+    /// ```
+    /// # use clang_ast::{Control, Node};
+    /// # use serde::Deserialize;
+    /// #
+    /// #[derive(Deserialize)]
+    /// pub enum Clang {
+    ///     FunctionDecl { name: String },
+    ///     CallExpr,
+    ///     Other,
+    /// }
+    ///
+    /// # fn callers(node: &Node<Clang>) {
+    /// // Find the enclosing function of every call expression.
+    /// node.visit_with_ancestors(|ancestors, node| {
+    ///     if let Clang::CallExpr = node.kind {
+    ///         let function = ancestors.iter().rev().find_map(|ancestor| match &ancestor.kind {
+    ///             Clang::FunctionDecl { name } => Some(name),
+    ///             _ => None,
+    ///         });
+    ///         println!("call {} in {:?}", node.id, function);
+    ///     }
+    ///     Control::Continue
+    /// });
+    /// # }
+    /// ```
+    pub fn visit_with_ancestors<'ast, F>(&'ast self, f: F)
     where
-        M: MapAccess<'de>,
+        F: FnMut(&[&'ast Node<T>], &'ast Node<T>) -> Control,
     {
-        enum FirstField {
-            Id,
-            Kind,
-            Inner,
-        }
+        visit::visit_with_ancestors(self, f);
+    }
 
-        struct FirstFieldVisitor;
+    /// Walk the tree rooted at this node, allowing the visitor to rewrite
+    /// nodes in place.
+    ///
+    /// The walk does not recurse, so it is safe on arbitrarily deep trees.
+    /// While a node's children are being walked they are detached from it,
+    /// so if a callback panics the tree is left with those subtrees missing.
+    pub fn visit_mut<V>(&mut self, visitor: &mut V)
+    where
+        V: ?Sized + VisitMut<T>,
+    {
+        visit::visit_mut(self, visitor);
+    }
 
-        impl<'de> Visitor<'de> for FirstFieldVisitor {
-            type Value = FirstField;
+    /// Take the tree apart into a list of all its nodes in depth-first
+    /// pre-order, each with an empty `inner`.
+    ///
+    /// Dropping a `Node` drops its children recursively, which can overflow
+    /// the stack for trees nested tens of thousands of levels deep. Flattening
+    /// does not recurse, and neither does dropping the resulting `Vec`:
+    ///
+    /// ```
+    /// # use clang_ast::Node;
+    /// #
+    /// # fn teardown<T>(node: Node<T>) {
+    /// drop(node.into_flattened());
+    /// # }
+    /// ```
+    pub fn into_flattened(self) -> Vec<Node<T>> {
+        let mut flattened = Vec::new();
+        let mut stack = vec![self];
+        while let Some(mut node) = stack.pop() {
+            let inner = mem::take(&mut node.inner);
+            flattened.push(node);
+            stack.extend(inner.into_iter().rev());
+        }
+        flattened
+    }
 
-            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
-                formatter.write_str("field identifier")
-            }
+    /// Hash the tree rooted at this node by its shape and the kind of every
+    /// node, ignoring node ids.
+    ///
+    /// Trees which differ only in the ids Clang assigned them, such as
+    /// identical template instantiations or the same header parsed in two
+    /// builds, hash the same. The hash is stable for a given build of the
+    /// program, but not necessarily across Rust versions.
+    ///
+    /// Source locations do not implement `Hash`; to hash a kind containing
+    /// them, use [`structural_hash_by`][Node::structural_hash_by].
+    pub fn structural_hash(&self) -> u64
+    where
+        T: Hash,
+    {
+        self.structural_hash_by(T::hash)
+    }
 
-            fn visit_str<E>(self, field: &str) -> Result<Self::Value, E>
-            where
-                E: serde::de::Error,
-            {
-                static FIELDS: &[&str] = &["id", "kind", "inner"];
-                match field {
-                    "id" => Ok(FirstField::Id),
-                    "kind" => Ok(FirstField::Kind),
-                    "inner" => Ok(FirstField::Inner),
-                    _ => Err(E::unknown_field(field, FIELDS)),
-                }
-            }
+    /// Hash the tree rooted at this node by its shape and whichever parts of
+    /// each node's kind the callback feeds into the hasher.
+    ///
+    /// ```
+    /// # use clang_ast::{Node, SourceRange};
+    /// # use serde::Deserialize;
+    /// # use std::hash::Hash;
+    /// #
+    /// #[derive(Deserialize)]
+    /// pub struct Clang {
+    ///     pub name: Option<String>,
+    ///     pub range: Option<SourceRange>,
+    /// }
+    ///
+    /// # fn example(node: &Node<Clang>) -> u64 {
+    /// node.structural_hash_by(|kind, hasher| kind.name.hash(hasher))
+    /// # }
+    /// ```
+    pub fn structural_hash_by<F>(&self, mut f: F) -> u64
+    where
+        F: FnMut(&T, &mut DefaultHasher),
+    {
+        // Pre-order together with the number of children of each node
+        // determines the shape of the tree.
+        let mut hasher = DefaultHasher::new();
+        for node in self.descendants() {
+            f(&node.kind, &mut hasher);
+            hasher.write_usize(node.inner.len());
         }
+        hasher.finish()
+    }
 
-        impl<'de> Deserialize<'de> for FirstField {
-            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
-            where
-                D: Deserializer<'de>,
-            {
-                deserializer.deserialize_identifier(FirstFieldVisitor)
-            }
-        }
+    /// Compare two trees by their shape and the kind of every node, ignoring
+    /// node ids.
+    ///
+    /// Source locations do not implement `PartialEq`; to compare kinds
+    /// containing them, use [`structurally_eq_by`][Node::structurally_eq_by].
+    pub fn structurally_eq(&self, other: &Self) -> bool
+    where
+        T: PartialEq,
+    {
+        self.structurally_eq_by(other, T::eq)
+    }
 
-        let mut id = None;
-        let mut inner = Vec::new();
-        let kind = loop {
-            match map.next_key()? {
-                None => {
-                    let kind = AnyKind::Kind(Kind::null);
-                    let deserializer = NodeDeserializer::new(&kind, &mut inner, map);
-                    break T::deserialize(deserializer)?;
-                }
-                Some(FirstField::Id) => {
-                    if id.is_some() {
-                        return Err(serde::de::Error::duplicate_field("id"));
+    /// Compare two trees by their shape and whichever parts of each pair of
+    /// corresponding kinds the callback considers relevant.
+    ///
+    /// ```
+    /// # use clang_ast::{Node, SourceRange};
+    /// # use serde::Deserialize;
+    /// #
+    /// #[derive(Deserialize)]
+    /// pub struct Clang {
+    ///     pub name: Option<String>,
+    ///     pub range: Option<SourceRange>,
+    /// }
+    ///
+    /// # fn example(golden: &Node<Clang>, actual: &Node<Clang>) {
+    /// // Ids and source locations change with every rebuild.
+    /// assert!(golden.structurally_eq_by(actual, |a, b| a.name == b.name));
+    /// # }
+    /// ```
+    pub fn structurally_eq_by<U, F>(&self, other: &Node<U>, mut f: F) -> bool
+    where
+        F: FnMut(&T, &U) -> bool,
+    {
+        let mut a = self.descendants();
+        let mut b = other.descendants();
+        loop {
+            match (a.next(), b.next()) {
+                (Some(a), Some(b)) => {
+                    if a.inner.len() != b.inner.len() || !f(&a.kind, &b.kind) {
+                        return false;
                     }
-                    id = Some(map.next_value()?);
-                }
-                Some(FirstField::Kind) => {
-                    let kind: AnyKind = map.next_value()?;
-                    let deserializer = NodeDeserializer::new(&kind, &mut inner, map);
-                    break T::deserialize(deserializer)?;
-                }
-                Some(FirstField::Inner) => {
-                    return Err(serde::de::Error::missing_field("kind"));
                 }
+                (None, None) => return true,
+                _ => return false,
             }
-        };
+        }
+    }
+
+    /// Find the innermost node whose source range covers the given 1-based
+    /// line and column of `file`, as for a cursor position in an editor.
+    ///
+    /// The callback retrieves a node's range from its kind. Subtrees of a
+    /// node whose range does not cover the position are not searched; nodes
+    /// without a range, such as the `TranslationUnitDecl`, are searched
+    /// through but never returned. Positions are compared against the
+    /// expansion location of each end of a range, and the last token of a
+    /// range is included in it.
+    ///
+    /// ```
+    /// # use clang_ast::{Node, SourceRange};
+    /// # use serde::Deserialize;
+    /// #
+    /// #[derive(Deserialize)]
+    /// pub struct Clang {
+    ///     pub kind: clang_ast::Kind,
+    ///     pub range: Option<SourceRange>,
+    /// }
+    ///
+    /// # fn example(node: &Node<Clang>) {
+    /// if let Some(node) = node.node_at("main.c", 3, 12, |kind| kind.range.as_ref()) {
+    ///     println!("cursor is on {} {}", node.kind.kind, node.id);
+    /// }
+    /// # }
+    /// ```
+    pub fn node_at<F>(&self, file: &str, line: usize, col: usize, range: F) -> Option<&Node<T>>
+    where
+        F: Fn(&T) -> Option<&SourceRange>,
+    {
+        locate::node_at(self, file, line, col, range)
+    }
+
+    /// Render the tree rooted at this node in Graphviz DOT syntax.
+    pub fn to_dot(&self, graph: &Graph<T>) -> String {
+        graph::to_dot(self, graph)
+    }
 
-        let id = id.unwrap_or_default();
+    /// Render the tree rooted at this node as a Mermaid flowchart, which
+    /// can be embedded in Markdown inside a ```` ```mermaid ```` code block.
+    pub fn to_mermaid(&self, graph: &Graph<T>) -> String {
+        graph::to_mermaid(self, graph)
+    }
+
+    /// Render the tree rooted at this node in the indented text layout of
+    /// Clang's `-ast-dump`.
+    pub fn to_text(&self, dump: &TextDump<T>) -> String {
+        dump::to_text(self, dump)
+    }
+}
 
+struct NodeVisitor<T> {
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<'de, T> Visitor<'de> for NodeVisitor<T>
+where
+    T: Deserialize<'de>,
+{
+    type Value = Node<T>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("clang syntax tree node")
+    }
+
+    fn visit_map<M>(self, map: M) -> Result<Self::Value, M::Error>
+    where
+        M: MapAccess<'de>,
+    {
+        let mut inner = InnerVec::new();
+        let (id, kind) = deserializer::visit_node(map, &mut inner)?;
         Ok(Node { id, kind, inner })
     }
 }
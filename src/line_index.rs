@@ -0,0 +1,138 @@
+use std::ops::Range;
+
+/// Conversion between byte offsets into a source file and line and column
+/// positions.
+///
+/// Clang reports locations as a byte offset together with a 1-based line and
+/// a 1-based column counted in bytes. Editors count from 0, and the Language
+/// Server Protocol by default measures columns in UTF-16 code units, which
+/// differs from the byte count on any line containing non-ASCII text.
+/// Converting the `offset` of a [`BareSourceLocation`][crate::BareSourceLocation]
+/// sidesteps the difference in counting from 0 or 1.
+///
+/// ```
+/// use clang_ast::{LineIndex, Position, PositionEncoding};
+///
+/// let text = "// héllo\nint x;\n";
+/// let index = LineIndex::new(text);
+///
+/// let x = text.find('x').unwrap();
+/// let position = Position { line: 1, col: 4 };
+/// assert_eq!(index.position(x, PositionEncoding::Utf16), Some(position));
+/// assert_eq!(index.offset(position, PositionEncoding::Utf16), Some(x));
+///
+/// // The é is two bytes but one UTF-16 code unit.
+/// let end = text.find('\n').unwrap();
+/// assert_eq!(index.position(end, PositionEncoding::Utf8), Some(Position { line: 0, col: 9 }));
+/// assert_eq!(index.position(end, PositionEncoding::Utf16), Some(Position { line: 0, col: 8 }));
+/// ```
+#[derive(Clone, Debug)]
+pub struct LineIndex<'a> {
+    text: &'a str,
+    // Byte offset at which each line begins. The first is always 0.
+    starts: Vec<usize>,
+}
+
+/// Zero-based line and column within a file.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Position {
+    pub line: usize,
+    /// Measured in the units of some [`PositionEncoding`].
+    pub col: usize,
+}
+
+/// Unit in which the column of a [`Position`] is measured.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum PositionEncoding {
+    /// Bytes, as in Clang's `col`.
+    Utf8,
+    /// UTF-16 code units, as in the Language Server Protocol by default.
+    Utf16,
+    /// Unicode scalar values, that is Rust `char`s.
+    Utf32,
+}
+
+impl<'a> LineIndex<'a> {
+    pub fn new(text: &'a str) -> Self {
+        let mut starts = vec![0];
+        starts.extend(text.match_indices('\n').map(|(i, _)| i + 1));
+        LineIndex { text, starts }
+    }
+
+    /// Number of lines, counting the possibly empty line after the last
+    /// newline.
+    pub fn line_count(&self) -> usize {
+        self.starts.len()
+    }
+
+    /// Byte range of a zero-based line, not including its terminating
+    /// newline.
+    pub fn line_range(&self, line: usize) -> Option<Range<usize>> {
+        let start = *self.starts.get(line)?;
+        let end = match self.starts.get(line + 1) {
+            Some(next) => next - 1,
+            None => self.text.len(),
+        };
+        Some(start..end)
+    }
+
+    /// Position of the byte at `offset`.
+    ///
+    /// An offset equal to the length of the text, just past the last
+    /// character, is allowed. Returns `None` for offsets beyond that or not
+    /// on a character boundary.
+    pub fn position(&self, offset: usize, encoding: PositionEncoding) -> Option<Position> {
+        if !self.text.is_char_boundary(offset) {
+            return None;
+        }
+        let line = match self.starts.binary_search(&offset) {
+            Ok(line) => line,
+            Err(next) => next - 1,
+        };
+        let prefix = &self.text[self.starts[line]..offset];
+        let col = match encoding {
+            PositionEncoding::Utf8 => prefix.len(),
+            PositionEncoding::Utf16 => prefix.encode_utf16().count(),
+            PositionEncoding::Utf32 => prefix.chars().count(),
+        };
+        Some(Position { line, col })
+    }
+
+    /// Byte offset of a position.
+    ///
+    /// The column may point just past the end of the line. Returns `None` if
+    /// the line does not exist, the column is beyond the end of the line, or
+    /// the column falls inside a character.
+    pub fn offset(&self, position: Position, encoding: PositionEncoding) -> Option<usize> {
+        let range = self.line_range(position.line)?;
+        let line = &self.text[range.clone()];
+        let len = match encoding {
+            PositionEncoding::Utf8 => {
+                if line.is_char_boundary(position.col) {
+                    position.col
+                } else {
+                    return None;
+                }
+            }
+            PositionEncoding::Utf16 => {
+                let mut units = 0;
+                let mut chars = line.char_indices();
+                loop {
+                    if units == position.col {
+                        break chars.next().map_or(line.len(), |(i, _)| i);
+                    }
+                    let (_, ch) = chars.next()?;
+                    units += ch.len_utf16();
+                    if units > position.col {
+                        return None;
+                    }
+                }
+            }
+            PositionEncoding::Utf32 => {
+                let mut chars = line.char_indices().map(|(i, _)| i).chain(Some(line.len()));
+                chars.nth(position.col)?
+            }
+        };
+        Some(range.start + len)
+    }
+}
@@ -0,0 +1,257 @@
+use serde::de::{Deserialize, Deserializer, Error, Unexpected, Visitor};
+use serde::ser::{Serialize, Serializer};
+use std::convert::TryFrom;
+use std::fmt::{self, Display};
+
+/// The `"value"` of an `IntegerLiteral`, or of a `ConstantExpr` computing
+/// an enumerator's value.
+///
+/// Clang writes the value as a decimal string, signed or unsigned according
+/// to the literal's type, and as wide as the type requires. An `unsigned
+/// __int128` or `_BitInt(256)` value does not fit in an `i64`, so the
+/// accessors here are fallible: pick the one matching the type you expect.
+///
+/// ```
+/// use clang_ast::LiteralValue;
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// pub enum Clang {
+///     IntegerLiteral { value: LiteralValue },
+///     Other,
+/// }
+/// #
+/// # let json = r#"{"id":"0x1","kind":"IntegerLiteral","value":"340282366920938463463374607431768211455"}"#;
+/// # let node: clang_ast::Node<Clang> = serde_json::from_str(json).unwrap();
+/// # match node.kind {
+/// #     Clang::IntegerLiteral { value } => {
+/// #         assert_eq!(value.as_u64(), None);
+/// #         assert_eq!(value.as_u128(), Some(u128::MAX));
+/// #     }
+/// #     Clang::Other => panic!(),
+/// # }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct LiteralValue {
+    repr: Repr,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+enum Repr {
+    Small { negative: bool, magnitude: u128 },
+    // Beyond 128 bits, as from _BitInt; kept as written.
+    Wide(Box<str>),
+}
+
+impl LiteralValue {
+    pub fn is_negative(&self) -> bool {
+        match &self.repr {
+            Repr::Small { negative, .. } => *negative,
+            Repr::Wide(digits) => digits.starts_with('-'),
+        }
+    }
+
+    /// Whether the value is too wide for even 128 bits. Such a value is
+    /// available only through its `Display` impl.
+    pub fn is_wide(&self) -> bool {
+        match self.repr {
+            Repr::Small { .. } => false,
+            Repr::Wide(_) => true,
+        }
+    }
+
+    pub fn as_i64(&self) -> Option<i64> {
+        i64::try_from(self.as_i128()?).ok()
+    }
+
+    pub fn as_u64(&self) -> Option<u64> {
+        u64::try_from(self.as_u128()?).ok()
+    }
+
+    pub fn as_i128(&self) -> Option<i128> {
+        match self.repr {
+            Repr::Small {
+                negative: false,
+                magnitude,
+            } => i128::try_from(magnitude).ok(),
+            Repr::Small {
+                negative: true,
+                magnitude,
+            } => i128::try_from(magnitude - 1).ok().map(|value| -value - 1),
+            Repr::Wide(_) => None,
+        }
+    }
+
+    pub fn as_u128(&self) -> Option<u128> {
+        match self.repr {
+            Repr::Small {
+                negative: false,
+                magnitude,
+            } => Some(magnitude),
+            _ => None,
+        }
+    }
+
+    /// The two's complement bit pattern of the value in an integer type of
+    /// the given width, or `None` if the value is out of range for both the
+    /// signed and unsigned type of that width.
+    ///
+    /// This recovers the bits of a value regardless of the signedness Clang
+    /// printed it with, for example `-1` and `255` both give `0xff` at width
+    /// 8.
+    pub fn to_bits(&self, width: u32) -> Option<u128> {
+        if width == 0 || width > 128 {
+            return None;
+        }
+        let mask = u128::MAX >> (128 - width);
+        match self.repr {
+            Repr::Small {
+                negative: false,
+                magnitude,
+            } if magnitude <= mask => Some(magnitude),
+            Repr::Small {
+                negative: true,
+                magnitude,
+            } if magnitude <= (mask >> 1) + 1 => Some(magnitude.wrapping_neg() & mask),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn parse(value: &str) -> Option<Self> {
+        let (negative, digits) = match value.strip_prefix('-') {
+            Some(digits) => (true, digits),
+            None => (false, value),
+        };
+        if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return None;
+        }
+        let mut magnitude: u128 = 0;
+        for b in digits.bytes() {
+            let next = magnitude
+                .checked_mul(10)
+                .and_then(|magnitude| magnitude.checked_add(u128::from(b - b'0')));
+            magnitude = match next {
+                Some(magnitude) => magnitude,
+                None => {
+                    return Some(LiteralValue {
+                        repr: Repr::Wide(Box::from(value)),
+                    });
+                }
+            };
+        }
+        Some(LiteralValue {
+            repr: Repr::Small {
+                negative: negative && magnitude != 0,
+                magnitude,
+            },
+        })
+    }
+}
+
+impl From<i64> for LiteralValue {
+    fn from(value: i64) -> Self {
+        LiteralValue::from(i128::from(value))
+    }
+}
+
+impl From<u64> for LiteralValue {
+    fn from(value: u64) -> Self {
+        LiteralValue::from(u128::from(value))
+    }
+}
+
+impl From<i128> for LiteralValue {
+    fn from(value: i128) -> Self {
+        let magnitude = match u128::try_from(value) {
+            Ok(magnitude) => magnitude,
+            // Offset by one so that i128::MIN does not overflow.
+            Err(_) => u128::try_from(-(value + 1)).unwrap() + 1,
+        };
+        LiteralValue {
+            repr: Repr::Small {
+                negative: value < 0,
+                magnitude,
+            },
+        }
+    }
+}
+
+impl From<u128> for LiteralValue {
+    fn from(value: u128) -> Self {
+        LiteralValue {
+            repr: Repr::Small {
+                negative: false,
+                magnitude: value,
+            },
+        }
+    }
+}
+
+impl Display for LiteralValue {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match &self.repr {
+            Repr::Small {
+                negative,
+                magnitude,
+            } => {
+                if *negative {
+                    formatter.write_str("-")?;
+                }
+                Display::fmt(magnitude, formatter)
+            }
+            Repr::Wide(digits) => formatter.write_str(digits),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for LiteralValue {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct LiteralValueVisitor;
+
+        impl<'de> Visitor<'de> for LiteralValueVisitor {
+            type Value = LiteralValue;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("integer or decimal string")
+            }
+
+            fn visit_i64<E>(self, value: i64) -> Result<Self::Value, E> {
+                Ok(LiteralValue::from(value))
+            }
+
+            fn visit_u64<E>(self, value: u64) -> Result<Self::Value, E> {
+                Ok(LiteralValue::from(value))
+            }
+
+            fn visit_i128<E>(self, value: i128) -> Result<Self::Value, E> {
+                Ok(LiteralValue::from(value))
+            }
+
+            fn visit_u128<E>(self, value: u128) -> Result<Self::Value, E> {
+                Ok(LiteralValue::from(value))
+            }
+
+            fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+            where
+                E: Error,
+            {
+                LiteralValue::parse(value)
+                    .ok_or_else(|| E::invalid_value(Unexpected::Str(value), &self))
+            }
+        }
+
+        deserializer.deserialize_any(LiteralValueVisitor)
+    }
+}
+
+impl Serialize for LiteralValue {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
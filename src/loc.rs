@@ -1,9 +1,14 @@
-use crate::intern::InternVisitor;
-use serde::de::{Deserialize, Deserializer, Error, IgnoredAny, MapAccess, Visitor};
+use crate::intern::Shared;
+use crate::path_map::{PathSeed, PathStyle};
+use crate::{ancestry, context, normalize};
+use serde::de::{
+    Deserialize, DeserializeSeed, Deserializer, Error, IgnoredAny, MapAccess, SeqAccess, Visitor,
+};
 use serde::ser::{Serialize, SerializeMap, Serializer};
-use std::cell::{Cell, RefCell};
+use std::cmp::Ordering;
+use std::collections::HashMap;
 use std::fmt::{self, Debug};
-use std::sync::Arc;
+use std::ops::Range;
 
 #[derive(Default)]
 pub struct SourceRange {
@@ -17,12 +22,204 @@ pub struct SourceLocation {
     pub expansion_loc: Option<BareSourceLocation>,
 }
 
+impl SourceLocation {
+    /// Where the characters of the token are written.
+    ///
+    /// For a token produced by a macro expansion, this is inside the
+    /// definition of the macro, or at the macro argument. For any other token
+    /// it is the same as the expansion location.
+    pub fn spelling(&self) -> Option<&BareSourceLocation> {
+        self.spelling_loc.as_ref().or(self.expansion_loc.as_ref())
+    }
+
+    /// Where the token ends up after macro expansion.
+    ///
+    /// For a token produced by a macro expansion, this is the location of
+    /// the macro invocation. This is the location a compiler diagnostic
+    /// would point to.
+    pub fn expansion(&self) -> Option<&BareSourceLocation> {
+        self.expansion_loc.as_ref().or(self.spelling_loc.as_ref())
+    }
+
+    /// Whether the token was produced by expanding a macro, either as part
+    /// of the macro's body or as one of its arguments.
+    pub fn is_macro_expansion(&self) -> bool {
+        match (&self.spelling_loc, &self.expansion_loc) {
+            (Some(spelling), Some(expansion)) => {
+                spelling.offset != expansion.offset || spelling.file != expansion.file
+            }
+            _ => false,
+        }
+    }
+
+    /// Whether the token was written as an argument of a macro invocation.
+    ///
+    /// Such a token is spelled at the invocation, so editing it there is
+    /// usually fine, unlike a token from the macro's body.
+    pub fn is_macro_arg_expansion(&self) -> bool {
+        self.expansion_loc
+            .as_ref()
+            .map_or(false, |expansion| expansion.is_macro_arg_expansion)
+    }
+
+    /// Whether the token comes from the definition of a macro rather than
+    /// from anything written at the place the macro is invoked.
+    pub fn is_macro_body_expansion(&self) -> bool {
+        self.is_macro_expansion() && !self.is_macro_arg_expansion()
+    }
+
+    /// Whether the location, after macro expansion, is in the translation
+    /// unit's main file.
+    pub fn is_in_main_file(&self, main_file: &str) -> bool {
+        self.expansion()
+            .map_or(false, |loc| &*loc.file == main_file)
+    }
+
+    /// Whether the location, after macro expansion, is in a file inside one
+    /// of the given system include directories.
+    pub fn is_in_system_header(&self, system_dirs: &[&str]) -> bool {
+        self.expansion().map_or(false, |loc| {
+            system_dirs
+                .iter()
+                .any(|dir| PathStyle::Posix.has_prefix(&loc.file, dir))
+        })
+    }
+
+    /// Classify the file the location is in after macro expansion.
+    ///
+    /// ```
+    /// # use clang_ast::{Origin, SourceLocation};
+    /// #
+    /// # fn example(loc: &SourceLocation) {
+    /// let system_dirs = ["/usr/include", "/usr/lib/clang"];
+    /// if loc.origin("src/main.c", &system_dirs) == Some(Origin::SystemHeader) {
+    ///     // not our code
+    /// }
+    /// # }
+    /// ```
+    ///
+    /// Returns `None` for a location without a position, or in a pseudo-file
+    /// such as `<built-in>` or `<scratch space>`.
+    pub fn origin(&self, main_file: &str, system_dirs: &[&str]) -> Option<Origin> {
+        let loc = self.expansion()?;
+        if loc.file.starts_with('<') {
+            None
+        } else if self.is_in_main_file(main_file) {
+            Some(Origin::MainFile)
+        } else if self.is_in_system_header(system_dirs) {
+            Some(Origin::SystemHeader)
+        } else {
+            Some(Origin::ProjectHeader)
+        }
+    }
+}
+
+/// Kind of file a source location is in; see [`SourceLocation::origin`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Origin {
+    /// The file being compiled.
+    MainFile,
+    /// A header outside of the system include directories.
+    ProjectHeader,
+    /// A header inside one of the system include directories.
+    SystemHeader,
+}
+
+impl SourceRange {
+    /// Whether either end of the range is the product of a macro expansion.
+    pub fn is_macro_expansion(&self) -> bool {
+        self.begin.is_macro_expansion() || self.end.is_macro_expansion()
+    }
+
+    /// Whether the token at `loc` lies entirely within this range.
+    ///
+    /// Like Clang's ranges, the range extends to the end of its last token.
+    /// Positions are compared using the expansion location of each end of the
+    /// range, which is where the range's text appears in the file. A range
+    /// whose ends are in different files contains nothing.
+    pub fn contains(&self, loc: &BareSourceLocation) -> bool {
+        self.extent().map_or(false, |(file, begin, end)| {
+            file.same_file(loc) && begin <= loc.offset && loc.end_offset() <= end
+        })
+    }
+
+    /// Whether `other` lies entirely within this range.
+    pub fn contains_range(&self, other: &SourceRange) -> bool {
+        match (self.extent(), other.extent()) {
+            (Some((file, begin, end)), Some((other_file, other_begin, other_end))) => {
+                file.same_file(other_file) && begin <= other_begin && other_end <= end
+            }
+            _ => false,
+        }
+    }
+
+    /// Whether this range and `other` have at least one byte in common.
+    pub fn overlaps(&self, other: &SourceRange) -> bool {
+        match (self.extent(), other.extent()) {
+            (Some((file, begin, end)), Some((other_file, other_begin, other_end))) => {
+                file.same_file(other_file) && begin < other_end && other_begin < end
+            }
+            _ => false,
+        }
+    }
+
+    /// Bytes of the file covered by the range, from the start of its first
+    /// token to the end of its last token.
+    ///
+    /// Clang's ranges end at the start of the last token, so this is not
+    /// simply `begin.offset..end.offset`. Returns `None` if either end of the
+    /// range is missing or they are in different files.
+    ///
+    /// ```
+    /// # use clang_ast::SourceRange;
+    /// #
+    /// let json = r#"
+    ///     {
+    ///       "begin": { "offset": 4, "file": "main.c", "line": 1, "col": 5, "tokLen": 4 },
+    ///       "end": { "offset": 12, "col": 13, "tokLen": 1 }
+    ///     }
+    /// "#;
+    /// let range: SourceRange = serde_json::from_str(json).unwrap();
+    /// assert_eq!(range.byte_range(), Some(4..13));
+    /// ```
+    pub fn byte_range(&self) -> Option<Range<usize>> {
+        self.extent().map(|(_file, begin, end)| begin..end)
+    }
+
+    // Whether the character at a 1-based line and column of the named file
+    // lies within the range.
+    pub(crate) fn covers(&self, file: &str, line: usize, col: usize) -> bool {
+        let (begin, end) = match (self.begin.expansion(), self.end.expansion()) {
+            (Some(begin), Some(end)) if begin.same_file(end) && &*begin.file == file => {
+                (begin, end)
+            }
+            _ => return false,
+        };
+        (begin.line, begin.col) <= (line, col) && (line, col) < (end.line, end.col + end.tok_len)
+    }
+
+    // Byte offsets of the start of the first token and the end of the last.
+    fn extent(&self) -> Option<(&BareSourceLocation, usize, usize)> {
+        let begin = self.begin.expansion()?;
+        let end = self.end.expansion()?;
+        if begin.same_file(end) {
+            Some((begin, begin.offset, end.end_offset()))
+        } else {
+            None
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct BareSourceLocation {
     pub offset: usize,
-    pub file: Arc<str>,
+    pub file: Shared<str>,
     pub line: usize,
-    pub presumed_file: Option<Arc<str>>,
+    /// File named by a `#line` directive in effect at this location, if it
+    /// differs from `file`.
+    pub presumed_file: Option<Shared<str>>,
+    /// Line number according to a `#line` directive in effect at this
+    /// location, if it differs from `line`.
     pub presumed_line: Option<usize>,
     pub col: usize,
     pub tok_len: usize,
@@ -30,28 +227,191 @@ pub struct BareSourceLocation {
     pub is_macro_arg_expansion: bool,
 }
 
+impl BareSourceLocation {
+    /// Offset of the first byte after the token at this location.
+    pub fn end_offset(&self) -> usize {
+        self.offset + self.tok_len
+    }
+
+    /// Whether both locations are in the same file.
+    pub fn same_file(&self, other: &Self) -> bool {
+        Shared::ptr_eq(&self.file, &other.file) || self.file == other.file
+    }
+
+    /// Order two locations in the same file by their position in it.
+    ///
+    /// Returns `None` if the locations are in different files, since
+    /// offsets into different files are not comparable. Use
+    /// [`cmp_by_file`][Self::cmp_by_file] to put locations from several
+    /// files in some order.
+    pub fn cmp_in_file(&self, other: &Self) -> Option<Ordering> {
+        if self.same_file(other) {
+            Some(self.offset.cmp(&other.offset))
+        } else {
+            None
+        }
+    }
+
+    /// Total order on locations: by file path, then by position within the
+    /// file.
+    ///
+    /// Suitable for sorting, as in `locs.sort_by(BareSourceLocation::cmp_by_file)`.
+    pub fn cmp_by_file(&self, other: &Self) -> Ordering {
+        if self.same_file(other) {
+            self.offset.cmp(&other.offset)
+        } else {
+            self.file.cmp(&other.file)
+        }
+    }
+}
+
+/// File containing the `#include` through which a location's file was
+/// entered.
+///
+/// Clang's dump names only the directly including file. Within
+/// [`IncludedFrom::with_chains`], the rest of the chain back to the main file
+/// is filled in from where that file was itself included according to the
+/// locations which came before it in the dump.
 #[derive(Clone, Debug)]
 pub struct IncludedFrom {
+    /// Where `file` was included from, unless it is the main file.
     pub included_from: Option<Box<IncludedFrom>>,
-    pub file: Arc<str>,
+    pub file: Shared<str>,
 }
 
-thread_local! {
-    static LAST_LOC_FILENAME: RefCell<Arc<str>> = RefCell::new(Arc::from(""));
-    static LAST_LOC_LINE: Cell<usize> = Cell::new(0);
+impl IncludedFrom {
+    /// Run `f` with the full include chain filled in for source locations
+    /// deserialized by `f` on the current thread.
+    ///
+    /// Otherwise the `included_from` of each location names only its direct
+    /// includer, as written by Clang. Keeping track of every file's includer
+    /// costs a hash map lookup and an allocation per link for each location
+    /// with an `"includedFrom"`, which a dump of a header-heavy translation
+    /// unit has a great many of.
+    ///
+    /// ```
+    /// use clang_ast::{IncludedFrom, SourceLocation};
+    ///
+    /// let json = r#"
+    ///     [
+    ///       { "offset": 0, "file": "a.h", "line": 1, "col": 1, "tokLen": 1,
+    ///         "includedFrom": { "file": "main.c" } },
+    ///       { "offset": 0, "file": "b.h", "line": 1, "col": 1, "tokLen": 1,
+    ///         "includedFrom": { "file": "a.h" } }
+    ///     ]
+    /// "#;
+    /// let locs: Vec<SourceLocation> =
+    ///     IncludedFrom::with_chains(|| serde_json::from_str(json)).unwrap();
+    /// let included_from = locs[1].spelling().unwrap().included_from.as_ref().unwrap();
+    /// let chain: Vec<&str> = included_from.chain().map(|inc| &*inc.file).collect();
+    /// assert_eq!(chain, ["a.h", "main.c"]);
+    /// ```
+    pub fn with_chains<R>(f: impl FnOnce() -> R) -> R {
+        struct Restore(bool);
+
+        impl Drop for Restore {
+            fn drop(&mut self) {
+                let prev = self.0;
+                context::with(|context| context.include_chains = prev);
+            }
+        }
+
+        let prev = context::with(|context| std::mem::replace(&mut context.include_chains, true));
+        let _restore = Restore(prev);
+        f()
+    }
+
+    /// Iterate over the chain of including files, beginning with this one
+    /// and ending with the main file.
+    ///
+    /// ```
+    /// # use clang_ast::BareSourceLocation;
+    /// #
+    /// # fn example(loc: &BareSourceLocation) {
+    /// // Through which header did this declaration get into the TU?
+    /// if let Some(included_from) = &loc.included_from {
+    ///     let chain: Vec<&str> = included_from.chain().map(|inc| &*inc.file).collect();
+    ///     println!("{} via {}", loc.file, chain.join(" <- "));
+    /// }
+    /// # }
+    /// ```
+    pub fn chain(&self) -> impl Iterator<Item = &IncludedFrom> {
+        let mut next = Some(self);
+        std::iter::from_fn(move || {
+            let current = next?;
+            next = current.included_from.as_deref();
+            Some(current)
+        })
+    }
 }
 
 pub(crate) fn thread_local_reset() {
-    LAST_LOC_FILENAME.with(|last_loc_filename| {
-        let mut last_loc_filename = last_loc_filename.borrow_mut();
-        if !last_loc_filename.is_empty() {
-            *last_loc_filename = Arc::from("");
+    context::with(|context| {
+        let last = &mut context.locs;
+        if !last.file.is_empty() {
+            last.file = Shared::from("");
+        }
+        last.line = 0;
+        last.presumed_file = None;
+        last.presumed_line = None;
+        if !last.includers.is_empty() {
+            last.includers = Shared::new(HashMap::new());
         }
     });
-    LAST_LOC_LINE.with(|last_loc_line| last_loc_line.set(0));
 }
 
-enum SourceLocationField {
+// The "same as previous" state at some point during deserialization.
+#[derive(Clone)]
+pub(crate) struct LocState {
+    file: Shared<str>,
+    line: usize,
+    presumed_file: Option<Shared<str>>,
+    presumed_line: Option<usize>,
+    // File which most recently included each file.
+    includers: Shared<HashMap<Shared<str>, Shared<str>>>,
+}
+
+impl Default for LocState {
+    fn default() -> Self {
+        LocState {
+            file: Shared::from(""),
+            line: 0,
+            presumed_file: None,
+            presumed_line: None,
+            includers: Shared::new(HashMap::new()),
+        }
+    }
+}
+
+#[cfg(feature = "lazy")]
+pub(crate) fn thread_local_save() -> LocState {
+    context::with(|context| context.locs.clone())
+}
+
+// Puts back the thread's previous location state when dropped, including
+// when unwinding out of a panicking Deserialize impl.
+#[cfg(feature = "lazy")]
+pub(crate) struct Restore {
+    outer: LocState,
+}
+
+// Install `state` as the thread's location state until the returned guard is
+// dropped.
+#[cfg(feature = "lazy")]
+pub(crate) fn thread_local_replace(state: LocState) -> Restore {
+    let outer = context::with(|context| std::mem::replace(&mut context.locs, state));
+    Restore { outer }
+}
+
+#[cfg(feature = "lazy")]
+impl Drop for Restore {
+    fn drop(&mut self) {
+        let outer = std::mem::take(&mut self.outer);
+        context::with(|context| context.locs = outer);
+    }
+}
+
+pub(crate) enum SourceLocationField {
     SpellingLoc,
     ExpansionLoc,
     Offset,
@@ -85,28 +445,7 @@ impl<'de> Deserialize<'de> for SourceLocation {
             {
                 match map.next_key()? {
                     None => Ok(SourceLocation::default()),
-                    Some(SourceLocationField::SpellingLoc) => {
-                        let spelling_loc: BareSourceLocation = map.next_value()?;
-                        match map.next_key()? {
-                            None => Err(Error::missing_field("expansionLoc")),
-                            Some(SourceLocationField::ExpansionLoc) => {
-                                let expansion_loc: BareSourceLocation = map.next_value()?;
-                                Ok(SourceLocation {
-                                    spelling_loc: Some(spelling_loc),
-                                    expansion_loc: Some(expansion_loc),
-                                })
-                            }
-                            Some(other) => Err(other.unexpected()),
-                        }
-                    }
-                    Some(SourceLocationField::Offset) => {
-                        let loc = de_rest_of_bare_source_location(map)?;
-                        Ok(SourceLocation {
-                            spelling_loc: Some(loc.clone()),
-                            expansion_loc: Some(loc),
-                        })
-                    }
-                    Some(other) => Err(other.unexpected()),
+                    Some(first) => de_rest_of_source_location(first, map),
                 }
             }
         }
@@ -115,6 +454,75 @@ impl<'de> Deserialize<'de> for SourceLocation {
     }
 }
 
+pub(crate) fn de_rest_of_source_location<'de, M>(
+    first: SourceLocationField,
+    mut map: M,
+) -> Result<SourceLocation, M::Error>
+where
+    M: MapAccess<'de>,
+{
+    let loc = match first {
+        SourceLocationField::SpellingLoc => {
+            let spelling_loc: BareSourceLocation = map.next_value()?;
+            match map.next_key()? {
+                None => return Err(Error::missing_field("expansionLoc")),
+                Some(SourceLocationField::ExpansionLoc) => {
+                    let expansion_loc: BareSourceLocation = map.next_value()?;
+                    SourceLocation {
+                        spelling_loc: Some(spelling_loc),
+                        expansion_loc: Some(expansion_loc),
+                    }
+                }
+                Some(other) => return Err(other.unexpected()),
+            }
+        }
+        // Older Clang releases may put the expansion location first.
+        #[cfg(feature = "legacy")]
+        SourceLocationField::ExpansionLoc => {
+            let expansion_loc: BareSourceLocation = map.next_value()?;
+            match map.next_key()? {
+                None => SourceLocation {
+                    spelling_loc: Some(expansion_loc.clone()),
+                    expansion_loc: Some(expansion_loc),
+                },
+                Some(SourceLocationField::SpellingLoc) => {
+                    let spelling_loc: BareSourceLocation = map.next_value()?;
+                    SourceLocation {
+                        spelling_loc: Some(spelling_loc),
+                        expansion_loc: Some(expansion_loc),
+                    }
+                }
+                Some(other) => return Err(other.unexpected()),
+            }
+        }
+        SourceLocationField::Offset => {
+            let loc = de_rest_of_bare_source_location(first, map)?;
+            SourceLocation {
+                spelling_loc: Some(loc.clone()),
+                expansion_loc: Some(loc),
+            }
+        }
+        #[cfg(feature = "legacy")]
+        SourceLocationField::File
+        | SourceLocationField::Line
+        | SourceLocationField::PresumedFile
+        | SourceLocationField::PresumedLine
+        | SourceLocationField::Col
+        | SourceLocationField::TokLen => {
+            let loc = de_rest_of_bare_source_location(first, map)?;
+            SourceLocation {
+                spelling_loc: Some(loc.clone()),
+                expansion_loc: Some(loc),
+            }
+        }
+        other => return Err(other.unexpected()),
+    };
+    if let Some(expansion_loc) = &loc.expansion_loc {
+        ancestry::record_file(&expansion_loc.file);
+    }
+    Ok(loc)
+}
+
 impl<'de> Deserialize<'de> for BareSourceLocation {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
@@ -135,7 +543,12 @@ impl<'de> Deserialize<'de> for BareSourceLocation {
             {
                 match map.next_key()? {
                     None => Err(Error::missing_field("offset")),
-                    Some(SourceLocationField::Offset) => de_rest_of_bare_source_location(map),
+                    Some(first @ SourceLocationField::Offset) => {
+                        de_rest_of_bare_source_location(first, map)
+                    }
+                    #[cfg(feature = "legacy")]
+                    Some(first) => de_rest_of_bare_source_location(first, map),
+                    #[cfg(not(feature = "legacy"))]
                     Some(other) => Err(other.unexpected()),
                 }
             }
@@ -296,7 +709,7 @@ impl<'de> Deserialize<'de> for IncludedFrom {
                             if file.is_some() {
                                 return Err(Error::duplicate_field("file"));
                             }
-                            file = Some(map.next_value_seed(InternVisitor)?);
+                            file = Some(map.next_value_seed(PathSeed)?);
                         }
                     }
                 }
@@ -364,12 +777,14 @@ impl<'de> Deserialize<'de> for SourceLocationField {
     }
 }
 
-fn de_rest_of_bare_source_location<'de, M>(mut map: M) -> Result<BareSourceLocation, M::Error>
+fn de_rest_of_bare_source_location<'de, M>(
+    first: SourceLocationField,
+    mut map: M,
+) -> Result<BareSourceLocation, M::Error>
 where
     M: MapAccess<'de>,
 {
-    let offset: usize = map.next_value()?;
-
+    let mut offset: Option<usize> = None;
     let mut file = None;
     let mut line = None;
     let mut presumed_file = None;
@@ -379,13 +794,19 @@ where
     let mut included_from = None;
     let mut is_macro_arg_expansion = false;
 
-    while let Some(field) = map.next_key()? {
+    let mut next = Some(first);
+    while let Some(field) = next {
         match field {
-            SourceLocationField::Offset => return Err(Error::duplicate_field("offset")),
-            SourceLocationField::File => file = Some(map.next_value_seed(InternVisitor)?),
+            SourceLocationField::Offset => {
+                if offset.is_some() {
+                    return Err(Error::duplicate_field("offset"));
+                }
+                offset = Some(map.next_value()?);
+            }
+            SourceLocationField::File => file = Some(map.next_value_seed(PathSeed)?),
             SourceLocationField::Line => line = Some(map.next_value()?),
             SourceLocationField::PresumedFile => {
-                presumed_file = Some(map.next_value_seed(InternVisitor)?);
+                presumed_file = Some(map.next_value_seed(PathSeed)?);
             }
             SourceLocationField::PresumedLine => presumed_line = Some(map.next_value()?),
             SourceLocationField::Col => col = Some(map.next_value()?),
@@ -398,22 +819,70 @@ where
                 return Err(field.unexpected());
             }
         }
+        next = map.next_key()?;
     }
 
-    let file = LAST_LOC_FILENAME.with(|last_loc_filename| match file {
-        Some(file) => {
-            *last_loc_filename.borrow_mut() = Arc::clone(&file);
-            file
-        }
-        None => Arc::clone(&last_loc_filename.borrow()),
-    });
+    // Clang releases before 10 did not write the offset.
+    #[cfg(feature = "legacy")]
+    let offset = offset.unwrap_or(0);
+    #[cfg(not(feature = "legacy"))]
+    let offset = offset.ok_or_else(|| Error::missing_field("offset"))?;
 
-    let line = LAST_LOC_LINE.with(|last_loc_line| match line {
-        Some(line) => {
-            last_loc_line.set(line);
-            line
-        }
-        None => last_loc_line.get(),
+    // Clang omits "presumedFile" and "presumedLine" both when they are the
+    // same as the actual file and line, and when they are the same as in the
+    // previous source location. Within the same file and line as the
+    // previous location, the latter is far more likely.
+    let file_given = file.is_some();
+    let line_given = line.is_some();
+
+    let (file, line, presumed_file, presumed_line, included_from) = context::with(|context| {
+        let include_chains = context.include_chains;
+        let last = &mut context.locs;
+
+        let file = match file {
+            Some(file) => {
+                last.file = Shared::clone(&file);
+                file
+            }
+            None => Shared::clone(&last.file),
+        };
+
+        let line = match line {
+            Some(line) => {
+                last.line = line;
+                line
+            }
+            None => last.line,
+        };
+
+        let presumed_file = match presumed_file {
+            Some(presumed_file) if presumed_file == file => None,
+            Some(presumed_file) => Some(presumed_file),
+            None if file_given => None,
+            None => last.presumed_file.clone(),
+        };
+        last.presumed_file.clone_from(&presumed_file);
+
+        let presumed_line = match presumed_line {
+            Some(presumed_line) if presumed_line == line => None,
+            Some(presumed_line) => Some(presumed_line),
+            None if file_given || line_given => None,
+            None => last.presumed_line,
+        };
+        last.presumed_line = presumed_line;
+
+        let included_from = included_from.map(|included_from: IncludedFrom| {
+            if !include_chains {
+                return included_from;
+            }
+            if last.includers.get(&file) != Some(&included_from.file) {
+                Shared::make_mut(&mut last.includers)
+                    .insert(Shared::clone(&file), Shared::clone(&included_from.file));
+            }
+            include_chain(&last.includers, &file, included_from.file)
+        });
+
+        (file, line, presumed_file, presumed_line, included_from)
     });
 
     let col = col.ok_or_else(|| Error::missing_field("col"))?;
@@ -432,6 +901,176 @@ where
     })
 }
 
+// Link up the including files of `includer`, which included `file`. A file
+// which appears to include itself, directly or indirectly, ends the chain.
+fn include_chain(
+    includers: &HashMap<Shared<str>, Shared<str>>,
+    file: &Shared<str>,
+    includer: Shared<str>,
+) -> IncludedFrom {
+    let mut files = vec![includer];
+    while let Some(next) = includers.get(files.last().unwrap()) {
+        if next == file || files.contains(next) {
+            break;
+        }
+        files.push(Shared::clone(next));
+    }
+    let mut included_from = None;
+    for file in files.into_iter().rev() {
+        included_from = Some(IncludedFrom {
+            included_from: included_from.map(Box::new),
+            file,
+        });
+    }
+    included_from.unwrap()
+}
+
+/// Ignores any value, like `IgnoredAny`, except that source locations nested
+/// anywhere inside of it still count toward the "same as previous" file and
+/// line of subsequent source locations.
+///
+/// Subtrees skipped using `IgnoredAny` would make every later loc that omits
+/// its `"file"` appear to belong to whatever file preceded the skipped part.
+pub(crate) struct SkipTracked;
+
+impl<'de> DeserializeSeed<'de> for SkipTracked {
+    type Value = ();
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(self)
+    }
+}
+
+impl<'de> Visitor<'de> for SkipTracked {
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("any value")
+    }
+
+    fn visit_bool<E>(self, _: bool) -> Result<Self::Value, E> {
+        Ok(())
+    }
+
+    fn visit_i64<E>(self, _: i64) -> Result<Self::Value, E> {
+        Ok(())
+    }
+
+    fn visit_i128<E>(self, _: i128) -> Result<Self::Value, E> {
+        Ok(())
+    }
+
+    fn visit_u64<E>(self, _: u64) -> Result<Self::Value, E> {
+        Ok(())
+    }
+
+    fn visit_u128<E>(self, _: u128) -> Result<Self::Value, E> {
+        Ok(())
+    }
+
+    fn visit_f64<E>(self, _: f64) -> Result<Self::Value, E> {
+        Ok(())
+    }
+
+    fn visit_str<E>(self, _: &str) -> Result<Self::Value, E> {
+        Ok(())
+    }
+
+    fn visit_bytes<E>(self, _: &[u8]) -> Result<Self::Value, E> {
+        Ok(())
+    }
+
+    fn visit_none<E>(self) -> Result<Self::Value, E> {
+        Ok(())
+    }
+
+    fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        self.deserialize(deserializer)
+    }
+
+    fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        self.deserialize(deserializer)
+    }
+
+    fn visit_unit<E>(self) -> Result<Self::Value, E> {
+        Ok(())
+    }
+
+    fn visit_seq<S>(self, mut seq: S) -> Result<Self::Value, S::Error>
+    where
+        S: SeqAccess<'de>,
+    {
+        while let Some(()) = seq.next_element_seed(SkipTracked)? {}
+        Ok(())
+    }
+
+    fn visit_map<M>(self, mut map: M) -> Result<Self::Value, M::Error>
+    where
+        M: MapAccess<'de>,
+    {
+        match map.next_key_seed(SkipTrackedKey)? {
+            None => return Ok(()),
+            // Source location. These are the only maps in Clang's output with
+            // a field called "offset" or "spellingLoc", or with the "legacy"
+            // feature, beginning with "expansionLoc", "line", or "col".
+            Some(Some(first)) => {
+                de_rest_of_source_location(first, map)?;
+                return Ok(());
+            }
+            Some(None) => map.next_value_seed(SkipTracked)?,
+        }
+        while let Some(IgnoredAny) = map.next_key()? {
+            map.next_value_seed(SkipTracked)?;
+        }
+        Ok(())
+    }
+}
+
+// Recognizes the fields which may begin a source location.
+pub(crate) struct SkipTrackedKey;
+
+impl<'de> DeserializeSeed<'de> for SkipTrackedKey {
+    type Value = Option<SourceLocationField>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_identifier(self)
+    }
+}
+
+impl<'de> Visitor<'de> for SkipTrackedKey {
+    type Value = Option<SourceLocationField>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("field identifier")
+    }
+
+    fn visit_str<E>(self, field: &str) -> Result<Self::Value, E> {
+        match field {
+            "offset" => Ok(Some(SourceLocationField::Offset)),
+            "spellingLoc" => Ok(Some(SourceLocationField::SpellingLoc)),
+            #[cfg(feature = "legacy")]
+            "expansionLoc" => Ok(Some(SourceLocationField::ExpansionLoc)),
+            #[cfg(feature = "legacy")]
+            "line" => Ok(Some(SourceLocationField::Line)),
+            #[cfg(feature = "legacy")]
+            "col" => Ok(Some(SourceLocationField::Col)),
+            _ => Ok(None),
+        }
+    }
+}
+
 impl SourceLocationField {
     fn unexpected<E: Error>(&self) -> E {
         Error::unknown_field(
@@ -574,32 +1213,49 @@ impl Serialize for BareSourceLocation {
         S: Serializer,
     {
         let mut map = serializer.serialize_map(None)?;
-        map.serialize_entry("offset", &self.offset)?;
-        if LAST_LOC_FILENAME.with(|last_loc_filename| {
-            let mut last_loc_filename = last_loc_filename.borrow_mut();
-            if *last_loc_filename == self.file {
-                false
+        map.serialize_entry("offset", &normalize::offset(self.offset))?;
+        let (file_given, line_given, presumed_file, presumed_line) = context::with(|context| {
+            let last = &mut context.locs;
+            let file_given = last.file != self.file;
+            let line_given = file_given || last.line != self.line;
+            if file_given {
+                last.file = Shared::clone(&self.file);
             } else {
-                *last_loc_filename = Arc::clone(&self.file);
-                true
+                last.line = self.line;
             }
-        }) {
-            map.serialize_entry("file", &*self.file)?;
-            map.serialize_entry("line", &self.line)?;
-        } else if LAST_LOC_LINE.with(|last_loc_line| {
-            if last_loc_line.get() == self.line {
-                false
+            // Written whenever the deserializer would not otherwise infer the
+            // same value, spelled as the actual file or line to mean "no
+            // longer different from the actual one".
+            let inferred = if file_given {
+                None
             } else {
-                last_loc_line.set(self.line);
-                true
-            }
-        }) {
+                last.presumed_file.clone()
+            };
+            last.presumed_file.clone_from(&self.presumed_file);
+            let presumed_file = if inferred == self.presumed_file {
+                None
+            } else {
+                Some(self.presumed_file.as_ref().unwrap_or(&self.file).clone())
+            };
+            let inferred = if line_given { None } else { last.presumed_line };
+            last.presumed_line = self.presumed_line;
+            let presumed_line = if inferred == self.presumed_line {
+                None
+            } else {
+                Some(self.presumed_line.unwrap_or(self.line))
+            };
+            (file_given, line_given, presumed_file, presumed_line)
+        });
+        if file_given {
+            map.serialize_entry("file", &normalize::file(&self.file))?;
+        }
+        if line_given {
             map.serialize_entry("line", &self.line)?;
         }
-        if let Some(presumed_file) = &self.presumed_file {
-            map.serialize_entry("presumedFile", &**presumed_file)?;
+        if let Some(presumed_file) = &presumed_file {
+            map.serialize_entry("presumedFile", &normalize::file(presumed_file))?;
         }
-        if let Some(presumed_line) = &self.presumed_line {
+        if let Some(presumed_line) = &presumed_line {
             map.serialize_entry("presumedLine", presumed_line)?;
         }
         map.serialize_entry("col", &self.col)?;
@@ -619,11 +1275,10 @@ impl Serialize for IncludedFrom {
     where
         S: Serializer,
     {
+        // Like Clang, write only the directly including file. The rest of the
+        // chain is reconstructed when deserializing.
         let mut map = serializer.serialize_map(None)?;
-        if let Some(included_from) = &self.included_from {
-            map.serialize_entry("includedFrom", included_from)?;
-        }
-        map.serialize_entry("file", &*self.file)?;
+        map.serialize_entry("file", &normalize::file(&self.file))?;
         map.end()
     }
 }
@@ -0,0 +1,31 @@
+use crate::{Node, SourceRange};
+
+pub(crate) fn node_at<'a, T, F>(
+    node: &'a Node<T>,
+    file: &str,
+    line: usize,
+    col: usize,
+    range: F,
+) -> Option<&'a Node<T>>
+where
+    F: Fn(&T) -> Option<&SourceRange>,
+{
+    let mut found = None;
+    let mut found_depth = 0;
+    let mut stack = vec![(node, 0)];
+    while let Some((node, depth)) = stack.pop() {
+        // Ranges without a usable position, such as those of implicit
+        // declarations, neither match nor prune their subtree.
+        if let Some(range) = range(&node.kind).filter(|range| range.byte_range().is_some()) {
+            if !range.covers(file, line, col) {
+                continue;
+            }
+            if found.is_none() || depth > found_depth {
+                found = Some(node);
+                found_depth = depth;
+            }
+        }
+        stack.extend(node.inner.iter().rev().map(|child| (child, depth + 1)));
+    }
+    found
+}
@@ -0,0 +1,225 @@
+//! Source locations in the form used by the Language Server Protocol.
+//!
+//! An LSP server built on AST dumps needs to report the `Range` and
+//! `Location` of nodes. Clang locates them by byte offset, whereas LSP
+//! positions count columns in UTF-16 code units unless client and server
+//! agree on another [`PositionEncoding`]. The functions here do that
+//! conversion using the file's text from a [`SourceManager`].
+//!
+//! The types serialize to exactly the JSON of their LSP counterparts, so
+//! they can be sent as they are. With the `"lsp-types"` feature they also
+//! convert into the corresponding types of the lsp-types crate using
+//! `From`.
+//!
+//! ```
+//! use clang_ast::{PositionEncoding, SourceManager, SourceRange};
+//!
+//! let mut sources = SourceManager::new();
+//! sources.root("/home/me/project");
+//! sources.insert("main.c", "// café\nint x;\n".to_owned());
+//!
+//! # let json = r#"{
+//! #   "begin": { "offset": 13, "file": "main.c", "line": 2, "col": 5, "tokLen": 1 },
+//! #   "end": { "offset": 13, "col": 5, "tokLen": 1 }
+//! # }"#;
+//! let range: SourceRange = serde_json::from_str(json)?;
+//! let location = clang_ast::lsp::location(&sources, &range, PositionEncoding::Utf16)?;
+//! # #[cfg(unix)]
+//! assert_eq!(
+//!     serde_json::to_string(&location)?,
+//!     r#"{"uri":"file:///home/me/project/main.c","range":{"start":{"line":1,"character":4},"end":{"line":1,"character":5}}}"#,
+//! );
+//!
+//! # #[cfg(feature = "lsp-types")]
+//! let location = lsp_types::Location::from(location);
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+use crate::{LineIndex, PositionEncoding, SourceManager, SourceRange};
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+use std::convert::TryFrom;
+use std::env;
+use std::fmt::Write;
+use std::io;
+use std::path::Path;
+
+/// Zero-based line and column, like LSP's `Position`.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Position {
+    pub line: u32,
+    /// Measured in the units of the [`PositionEncoding`] it was converted
+    /// with.
+    pub character: u32,
+}
+
+/// Span from a start position up to but not including an end position,
+/// like LSP's `Range`.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Range {
+    pub start: Position,
+    pub end: Position,
+}
+
+/// Range within a file identified by a URI, like LSP's `Location`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Location {
+    /// `file://` URI of the absolute path of the file.
+    pub uri: String,
+    pub range: Range,
+}
+
+/// Convert the span of text of a `SourceRange`, from the start of its first
+/// token to the end of its last token.
+///
+/// # Errors
+///
+/// Fails if the file cannot be read, or if the range does not denote a span
+/// of text within a single file, the same as [`SourceManager::text`].
+pub fn range(
+    sources: &SourceManager,
+    range: &SourceRange,
+    encoding: PositionEncoding,
+) -> io::Result<Range> {
+    convert(sources, range, encoding).map(|(_file, range)| range)
+}
+
+fn convert<'a>(
+    sources: &SourceManager,
+    range: &'a SourceRange,
+    encoding: PositionEncoding,
+) -> io::Result<(&'a str, Range)> {
+    let (file, bytes) =
+        if let (Some(begin), Some(bytes)) = (range.begin.expansion(), range.byte_range()) {
+            (&*begin.file, bytes)
+        } else {
+            let msg = "source range has no position within a single file";
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, msg));
+        };
+    let index = LineIndex::new(sources.file(file)?);
+    let position = |offset| {
+        let position = index.position(offset, encoding)?;
+        let line = u32::try_from(position.line).ok()?;
+        let character = u32::try_from(position.col).ok()?;
+        Some(Position { line, character })
+    };
+    if let (Some(start), Some(end)) = (position(bytes.start), position(bytes.end)) {
+        Ok((file, Range { start, end }))
+    } else {
+        let msg = format!("source range is out of bounds of {}", file);
+        Err(io::Error::new(io::ErrorKind::InvalidInput, msg))
+    }
+}
+
+/// Convert a `SourceRange` together with the file it is in.
+///
+/// Relative paths are resolved against the [`SourceManager::root`], or else
+/// the current directory, the same as for reading the file.
+///
+/// # Errors
+///
+/// As for [`range()`], and if the current directory is needed but cannot be
+/// determined.
+pub fn location(
+    sources: &SourceManager,
+    source_range: &SourceRange,
+    encoding: PositionEncoding,
+) -> io::Result<Location> {
+    let (file, range) = convert(sources, source_range, encoding)?;
+    let mut path = sources.path(file);
+    if path.is_relative() {
+        path = env::current_dir()?.join(path);
+    }
+    let uri = file_uri(&path);
+    Ok(Location { uri, range })
+}
+
+fn file_uri(path: &Path) -> String {
+    let path = path.to_string_lossy();
+    #[cfg(windows)]
+    let path = path.replace('\\', "/");
+    let mut uri = String::from("file://");
+    if !path.starts_with('/') {
+        // Windows drive letter.
+        uri.push('/');
+    }
+    for byte in path.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' | b':' => {
+                uri.push(char::from(byte));
+            }
+            _ => {
+                let _ = write!(uri, "%{:02X}", byte);
+            }
+        }
+    }
+    uri
+}
+
+impl Serialize for Position {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer.serialize_struct("Position", 2)?;
+        state.serialize_field("line", &self.line)?;
+        state.serialize_field("character", &self.character)?;
+        state.end()
+    }
+}
+
+impl Serialize for Range {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer.serialize_struct("Range", 2)?;
+        state.serialize_field("start", &self.start)?;
+        state.serialize_field("end", &self.end)?;
+        state.end()
+    }
+}
+
+impl Serialize for Location {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut state = serializer.serialize_struct("Location", 2)?;
+        state.serialize_field("uri", &self.uri)?;
+        state.serialize_field("range", &self.range)?;
+        state.end()
+    }
+}
+
+#[cfg(feature = "lsp-types")]
+impl From<Position> for lsp_types::Position {
+    fn from(position: Position) -> Self {
+        lsp_types::Position {
+            line: position.line,
+            character: position.character,
+        }
+    }
+}
+
+#[cfg(feature = "lsp-types")]
+impl From<Range> for lsp_types::Range {
+    fn from(range: Range) -> Self {
+        lsp_types::Range {
+            start: range.start.into(),
+            end: range.end.into(),
+        }
+    }
+}
+
+#[cfg(feature = "lsp-types")]
+impl From<Location> for lsp_types::Location {
+    fn from(location: Location) -> Self {
+        // Every character of the path outside of the URI's unreserved set is
+        // percent-encoded by file_uri, so this always parses.
+        let uri = location.uri.parse().expect("file URI");
+        lsp_types::Location {
+            uri,
+            range: location.range.into(),
+        }
+    }
+}
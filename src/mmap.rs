@@ -0,0 +1,44 @@
+use crate::Node;
+use memmap2::Mmap;
+use serde::de::DeserializeOwned;
+use std::fs::File;
+use std::path::Path;
+
+/// Deserialize a syntax tree from a file containing an AST dump.
+///
+/// The file is memory-mapped rather than read into a buffer, so that peak
+/// memory usage is not doubled by a copy of the JSON for dumps which may be
+/// hundreds of megabytes.
+///
+/// The file must not be modified while it is being deserialized.
+///
+/// ```no_run
+/// use clang_ast::Kind;
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// pub struct Clang {
+///     pub kind: Kind,
+/// }
+///
+/// fn main() -> serde_json::Result<()> {
+///     let node: clang_ast::Node<Clang> = clang_ast::from_path("ast.json")?;
+///     println!("{} top-level decls", node.inner.len());
+///     Ok(())
+/// }
+/// ```
+pub fn from_path<T, P>(path: P) -> serde_json::Result<Node<T>>
+where
+    T: DeserializeOwned,
+    P: AsRef<Path>,
+{
+    let file = File::open(path).map_err(serde_json::Error::io)?;
+    let len = file.metadata().map_err(serde_json::Error::io)?.len();
+    if len == 0 {
+        // Zero-length mappings are rejected by the OS; report the same error
+        // as deserializing from an empty string would.
+        return serde_json::from_slice(&[]);
+    }
+    let mmap = unsafe { Mmap::map(&file) }.map_err(serde_json::Error::io)?;
+    serde_json::from_slice(&mmap)
+}
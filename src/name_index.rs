@@ -0,0 +1,95 @@
+use crate::{Id, Node, QualifiedNames};
+use std::collections::HashMap;
+
+/// Lookup table from a name to the ids of the declarations having that name.
+///
+/// Built by a single walk over a syntax tree. The names indexed are
+/// whichever the callback retrieves from each node's kind, such as its
+/// `"name"` or its `"mangledName"`; [`NameIndex::qualified`] instead indexes
+/// the names computed by [`QualifiedNames`].
+///
+/// ```
+/// use clang_ast::{Node, NameIndex};
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// pub struct Clang {
+///     pub name: Option<String>,
+/// }
+///
+/// let json = r#"
+///     {
+///       "id": "0x1",
+///       "kind": "TranslationUnitDecl",
+///       "inner": [
+///         { "id": "0x2", "kind": "FunctionDecl", "name": "f" },
+///         { "id": "0x3", "kind": "VarDecl", "name": "x" },
+///         { "id": "0x4", "kind": "FunctionDecl", "name": "f" }
+///       ]
+///     }
+/// "#;
+/// let node: Node<Clang> = serde_json::from_str(json).unwrap();
+/// let index = NameIndex::new(&node, |kind| kind.name.as_deref());
+/// assert_eq!(index.get("f"), [node.inner[0].id, node.inner[2].id]);
+/// assert!(index.get("g").is_empty());
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct NameIndex {
+    map: HashMap<Box<str>, Vec<Id>>,
+}
+
+impl NameIndex {
+    /// Index every node by the name retrieved from its kind by the callback,
+    /// if any.
+    pub fn new<T, F>(root: &Node<T>, name: F) -> Self
+    where
+        F: Fn(&T) -> Option<&str>,
+    {
+        let mut index = NameIndex::default();
+        for node in root.descendants() {
+            if let Some(name) = name(&node.kind) {
+                index.insert(name, node.id);
+            }
+        }
+        index
+    }
+
+    /// Index every node of the tree which has a qualified name.
+    pub fn qualified<T>(root: &Node<T>, names: &QualifiedNames) -> Self {
+        let mut index = NameIndex::default();
+        for node in root.descendants() {
+            if let Some(name) = names.get(node.id) {
+                index.insert(name, node.id);
+            }
+        }
+        index
+    }
+
+    fn insert(&mut self, name: &str, id: Id) {
+        if let Some(ids) = self.map.get_mut(name) {
+            ids.push(id);
+        } else {
+            self.map.insert(Box::from(name), vec![id]);
+        }
+    }
+
+    /// Ids of the nodes with the given name, in the order of a depth-first
+    /// pre-order walk of the tree.
+    pub fn get(&self, name: &str) -> &[Id] {
+        self.map.get(name).map_or(&[], Vec::as_slice)
+    }
+
+    /// Every distinct name in the index, in no particular order.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.map.keys().map(|name| &**name)
+    }
+
+    /// Number of distinct names.
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+}
@@ -0,0 +1,163 @@
+use crate::{context, dedup, Id};
+use serde::ser::{Serialize, Serializer};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Serialization of a syntax tree in a canonical form, for snapshot tests.
+///
+/// Dumps of the same source differ from one build to the next: node ids are
+/// pointer values, and file paths and offsets depend on where the build ran
+/// and what was included. Serializing through `Normalize` replaces those
+/// with values that only depend on the content of the tree.
+///
+/// Only the parts of the tree that get serialized are affected, so the kind
+/// type used for deserializing determines which other fields appear in the
+/// output.
+///
+/// ```
+/// use clang_ast::{Kind, Node, Normalize, SourceRange};
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Deserialize, Serialize)]
+/// pub struct Clang {
+///     pub kind: Kind,
+///     pub range: Option<SourceRange>,
+/// }
+///
+/// # fn main() -> serde_json::Result<()> {
+/// # let json = r#"{"id":"0x7f8e2c001a38","kind":"TranslationUnitDecl"}"#;
+/// let node: Node<Clang> = serde_json::from_str(json)?;
+///
+/// let mut normalize = Normalize::new();
+/// normalize.renumber_ids().zero_offsets().relative_to("/home/ci/build");
+///
+/// let mut snapshot = Vec::new();
+/// normalize.serialize(&node, &mut serde_json::Serializer::pretty(&mut snapshot))?;
+/// # assert!(String::from_utf8(snapshot).unwrap().contains("\"0x1\""));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Normalize {
+    renumber_ids: bool,
+    zero_offsets: bool,
+    relative_to: Vec<String>,
+}
+
+impl Normalize {
+    pub fn new() -> Self {
+        Normalize::default()
+    }
+
+    /// Replace node ids with 0x1, 0x2, ... in the order they first appear in
+    /// the output. References to other nodes by id are renumbered the same
+    /// way, so they continue to line up.
+    pub fn renumber_ids(&mut self) -> &mut Self {
+        self.renumber_ids = true;
+        self
+    }
+
+    /// Write 0 for the `"offset"` of every source location.
+    pub fn zero_offsets(&mut self) -> &mut Self {
+        self.zero_offsets = true;
+        self
+    }
+
+    /// Remove the given directory from the beginning of file paths in
+    /// source locations that are inside of it. May be called more than once;
+    /// the first directory that matches is removed.
+    pub fn relative_to(&mut self, dir: impl Into<String>) -> &mut Self {
+        let mut dir = dir.into();
+        if !dir.ends_with('/') {
+            dir.push('/');
+        }
+        self.relative_to.push(dir);
+        self
+    }
+
+    /// Serialize `value`, which is typically a `Node` or `Ast`, with this
+    /// normalization applied.
+    pub fn serialize<V, S>(&self, value: &V, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        V: ?Sized + Serialize,
+        S: Serializer,
+    {
+        let _dedup = dedup::activate();
+        let _guard = activate(State {
+            normalize: self.clone(),
+            ids: HashMap::new(),
+        });
+        value.serialize(serializer)
+    }
+}
+
+pub(crate) struct State {
+    normalize: Normalize,
+    ids: HashMap<Id, Id>,
+}
+
+// Number of normalizing serializations in progress on any thread. While this
+// is zero, as it is for every ordinary serialization, ids and locations are
+// written as they are without looking up the thread's context.
+static ACTIVE: AtomicUsize = AtomicUsize::new(0);
+
+struct Guard {
+    prev: Option<State>,
+}
+
+fn activate(state: State) -> Guard {
+    ACTIVE.fetch_add(1, Ordering::Relaxed);
+    let prev = context::with(|context| context.normalize.replace(state));
+    Guard { prev }
+}
+
+impl Drop for Guard {
+    fn drop(&mut self) {
+        let prev = self.prev.take();
+        context::with(|context| context.normalize = prev);
+        ACTIVE.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+fn is_active() -> bool {
+    ACTIVE.load(Ordering::Relaxed) > 0
+}
+
+pub(crate) fn id(id: Id) -> Id {
+    if !is_active() {
+        return id;
+    }
+    context::with(|context| match &mut context.normalize {
+        Some(state) if state.normalize.renumber_ids && id != Id::NULL => {
+            let next = Id::new(state.ids.len() as u64 + 1);
+            *state.ids.entry(id).or_insert(next)
+        }
+        _ => id,
+    })
+}
+
+pub(crate) fn offset(offset: usize) -> usize {
+    if !is_active() {
+        return offset;
+    }
+    context::with(|context| match &context.normalize {
+        Some(state) if state.normalize.zero_offsets => 0,
+        _ => offset,
+    })
+}
+
+pub(crate) fn file(file: &str) -> &str {
+    if !is_active() {
+        return file;
+    }
+    context::with(|context| {
+        if let Some(state) = &context.normalize {
+            for dir in &state.normalize.relative_to {
+                if let Some(relative) = file.strip_prefix(dir.as_str()) {
+                    return relative;
+                }
+            }
+        }
+        file
+    })
+}
@@ -0,0 +1,301 @@
+use serde::de::{Deserialize, Deserializer, Error, Visitor};
+use serde::ser::{Serialize, Serializer};
+use std::fmt::{self, Display};
+
+macro_rules! opcode {
+    (
+        $(#[$attr:meta])*
+        pub enum $name:ident {
+            $(
+                $(#[$variant_attr:meta])*
+                $variant:ident = $spelling:literal,
+            )*
+        }
+    ) => {
+        $(#[$attr])*
+        #[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+        pub enum $name {
+            $(
+                $(#[$variant_attr])*
+                $variant,
+            )*
+        }
+
+        impl $name {
+            /// The operator as it appears in the `"opcode"` field of the
+            /// JSON, which is also its `Display` representation.
+            pub fn as_str(self) -> &'static str {
+                match self {
+                    $(
+                        $name::$variant => $spelling,
+                    )*
+                }
+            }
+
+            fn from_str(opcode: &str) -> Option<Self> {
+                match opcode {
+                    $(
+                        $spelling => Some($name::$variant),
+                    )*
+                    _ => None,
+                }
+            }
+        }
+
+        impl Display for $name {
+            fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str(self.as_str())
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                struct OpcodeVisitor;
+
+                impl<'de> Visitor<'de> for OpcodeVisitor {
+                    type Value = $name;
+
+                    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                        formatter.write_str("opcode")
+                    }
+
+                    fn visit_str<E>(self, opcode: &str) -> Result<Self::Value, E>
+                    where
+                        E: Error,
+                    {
+                        const OPCODES: &[&str] = &[$($spelling),*];
+                        $name::from_str(opcode).ok_or_else(|| E::unknown_variant(opcode, OPCODES))
+                    }
+                }
+
+                deserializer.deserialize_str(OpcodeVisitor)
+            }
+        }
+
+        impl Serialize for $name {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: Serializer,
+            {
+                serializer.serialize_str(self.as_str())
+            }
+        }
+    };
+}
+
+opcode! {
+    /// The `"opcode"` of a `BinaryOperator` or `CompoundAssignOperator`.
+    ///
+    /// ```
+    /// use clang_ast::BinaryOpcode;
+    /// use serde::Deserialize;
+    ///
+    /// #[derive(Deserialize)]
+    /// pub enum Clang {
+    ///     BinaryOperator { opcode: BinaryOpcode },
+    ///     CompoundAssignOperator { opcode: BinaryOpcode },
+    ///     Other,
+    /// }
+    /// #
+    /// # let json = r#"{"id":"0x1","kind":"CompoundAssignOperator","opcode":"<<="}"#;
+    /// # let node: clang_ast::Node<Clang> = serde_json::from_str(json).unwrap();
+    /// # match node.kind {
+    /// #     Clang::CompoundAssignOperator { opcode } => {
+    /// #         assert!(opcode.is_compound_assignment());
+    /// #         assert_eq!(opcode.op_for_compound_assignment(), Some(BinaryOpcode::Shl));
+    /// #     }
+    /// #     _ => panic!(),
+    /// # }
+    /// ```
+    pub enum BinaryOpcode {
+        /// `.*`
+        PtrMemD = ".*",
+        /// `->*`
+        PtrMemI = "->*",
+        Mul = "*",
+        Div = "/",
+        Rem = "%",
+        Add = "+",
+        Sub = "-",
+        Shl = "<<",
+        Shr = ">>",
+        /// `<=>`
+        Cmp = "<=>",
+        Lt = "<",
+        Gt = ">",
+        Le = "<=",
+        Ge = ">=",
+        Eq = "==",
+        Ne = "!=",
+        And = "&",
+        Xor = "^",
+        Or = "|",
+        LAnd = "&&",
+        LOr = "||",
+        Assign = "=",
+        MulAssign = "*=",
+        DivAssign = "/=",
+        RemAssign = "%=",
+        AddAssign = "+=",
+        SubAssign = "-=",
+        ShlAssign = "<<=",
+        ShrAssign = ">>=",
+        AndAssign = "&=",
+        XorAssign = "^=",
+        OrAssign = "|=",
+        Comma = ",",
+    }
+}
+
+impl BinaryOpcode {
+    /// `.*` or `->*`.
+    pub fn is_pointer_to_member(self) -> bool {
+        matches!(self, BinaryOpcode::PtrMemD | BinaryOpcode::PtrMemI)
+    }
+
+    pub fn is_multiplicative(self) -> bool {
+        matches!(
+            self,
+            BinaryOpcode::Mul | BinaryOpcode::Div | BinaryOpcode::Rem
+        )
+    }
+
+    pub fn is_additive(self) -> bool {
+        matches!(self, BinaryOpcode::Add | BinaryOpcode::Sub)
+    }
+
+    pub fn is_shift(self) -> bool {
+        matches!(self, BinaryOpcode::Shl | BinaryOpcode::Shr)
+    }
+
+    /// `&`, `^` or `|`.
+    pub fn is_bitwise(self) -> bool {
+        matches!(
+            self,
+            BinaryOpcode::And | BinaryOpcode::Xor | BinaryOpcode::Or
+        )
+    }
+
+    /// `<`, `>`, `<=` or `>=`.
+    pub fn is_relational(self) -> bool {
+        matches!(
+            self,
+            BinaryOpcode::Lt | BinaryOpcode::Gt | BinaryOpcode::Le | BinaryOpcode::Ge
+        )
+    }
+
+    /// `==` or `!=`.
+    pub fn is_equality(self) -> bool {
+        matches!(self, BinaryOpcode::Eq | BinaryOpcode::Ne)
+    }
+
+    /// A relational or equality operator, or `<=>`, following Clang's
+    /// `BinaryOperator::isComparisonOp`.
+    pub fn is_comparison(self) -> bool {
+        self == BinaryOpcode::Cmp || self.is_relational() || self.is_equality()
+    }
+
+    /// `&&` or `||`.
+    pub fn is_logical(self) -> bool {
+        matches!(self, BinaryOpcode::LAnd | BinaryOpcode::LOr)
+    }
+
+    /// `=` or a compound assignment.
+    pub fn is_assignment(self) -> bool {
+        self == BinaryOpcode::Assign || self.is_compound_assignment()
+    }
+
+    /// An assignment which also performs an operation, like `+=`.
+    pub fn is_compound_assignment(self) -> bool {
+        self.op_for_compound_assignment().is_some()
+    }
+
+    /// The operation performed by a compound assignment, like `+` for `+=`.
+    pub fn op_for_compound_assignment(self) -> Option<BinaryOpcode> {
+        match self {
+            BinaryOpcode::MulAssign => Some(BinaryOpcode::Mul),
+            BinaryOpcode::DivAssign => Some(BinaryOpcode::Div),
+            BinaryOpcode::RemAssign => Some(BinaryOpcode::Rem),
+            BinaryOpcode::AddAssign => Some(BinaryOpcode::Add),
+            BinaryOpcode::SubAssign => Some(BinaryOpcode::Sub),
+            BinaryOpcode::ShlAssign => Some(BinaryOpcode::Shl),
+            BinaryOpcode::ShrAssign => Some(BinaryOpcode::Shr),
+            BinaryOpcode::AndAssign => Some(BinaryOpcode::And),
+            BinaryOpcode::XorAssign => Some(BinaryOpcode::Xor),
+            BinaryOpcode::OrAssign => Some(BinaryOpcode::Or),
+            _ => None,
+        }
+    }
+}
+
+opcode! {
+    /// The `"opcode"` of a `UnaryOperator`.
+    ///
+    /// Clang spells the prefix and postfix forms of `++` and `--` the same;
+    /// the `UnaryOperator`'s `"isPostfix"` field tells them apart.
+    ///
+    /// ```
+    /// use clang_ast::UnaryOpcode;
+    /// use serde::Deserialize;
+    ///
+    /// #[derive(Deserialize)]
+    /// pub enum Clang {
+    ///     UnaryOperator {
+    ///         opcode: UnaryOpcode,
+    ///         #[serde(rename = "isPostfix")]
+    ///         is_postfix: bool,
+    ///     },
+    ///     Other,
+    /// }
+    /// #
+    /// # let json = r#"{"id":"0x1","kind":"UnaryOperator","isPostfix":true,"opcode":"++"}"#;
+    /// # let node: clang_ast::Node<Clang> = serde_json::from_str(json).unwrap();
+    /// # match node.kind {
+    /// #     Clang::UnaryOperator { opcode, is_postfix } => {
+    /// #         assert_eq!(opcode, UnaryOpcode::Inc);
+    /// #         assert!(is_postfix);
+    /// #     }
+    /// #     Clang::Other => panic!(),
+    /// # }
+    /// ```
+    pub enum UnaryOpcode {
+        Inc = "++",
+        Dec = "--",
+        AddrOf = "&",
+        Deref = "*",
+        Plus = "+",
+        Minus = "-",
+        /// `~`
+        Not = "~",
+        /// `!`
+        LNot = "!",
+        Real = "__real",
+        Imag = "__imag",
+        Extension = "__extension__",
+        Coawait = "co_await",
+    }
+}
+
+impl UnaryOpcode {
+    /// `++` or `--`.
+    pub fn is_increment_decrement(self) -> bool {
+        matches!(self, UnaryOpcode::Inc | UnaryOpcode::Dec)
+    }
+
+    /// `+`, `-`, `~` or `!`, following Clang's
+    /// `UnaryOperator::isArithmeticOp`.
+    pub fn is_arithmetic(self) -> bool {
+        matches!(
+            self,
+            UnaryOpcode::Plus | UnaryOpcode::Minus | UnaryOpcode::Not | UnaryOpcode::LNot
+        )
+    }
+
+    /// `!`.
+    pub fn is_logical(self) -> bool {
+        self == UnaryOpcode::LNot
+    }
+}
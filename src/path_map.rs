@@ -0,0 +1,295 @@
+use crate::context;
+use crate::intern::{self, Shared};
+use serde::de::{DeserializeSeed, Deserializer, Error, Visitor};
+use std::fmt;
+
+/// Rewriting of the file paths in source locations as they are
+/// deserialized.
+///
+/// Clang writes paths the way they were spelled on the command line and in
+/// `#include` directives, so one dump can refer to a mix of relative and
+/// absolute paths, and dumps from different machines disagree on where the
+/// sources live. Deserializing inside of [`PathMap::scope`] rewrites every
+/// path before it is interned, so that paths can be compared directly.
+///
+/// ```
+/// use clang_ast::{PathMap, SourceLocation};
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// pub struct Clang {
+///     pub loc: Option<SourceLocation>,
+/// }
+///
+/// # fn main() -> serde_json::Result<()> {
+/// # let json = r#"{"id":"0x1","kind":"VarDecl","loc":{"offset":4,"file":"src/main.c","line":1,"col":5,"tokLen":1}}"#;
+/// let mut paths = PathMap::new();
+/// paths
+///     .absolute("/home/ci/build")
+///     .remap("/home/ci/build", "/src");
+///
+/// let node: clang_ast::Node<Clang> = paths.scope(|| serde_json::from_str(json))?;
+/// let loc = node.kind.loc.as_ref().and_then(|loc| loc.expansion());
+/// assert_eq!(&*loc.unwrap().file, "/src/src/main.c");
+/// # Ok(())
+/// # }
+/// ```
+///
+/// Pseudo-files such as `<built-in>` and `<scratch space>` are left alone.
+#[derive(Clone, Debug, Default)]
+pub struct PathMap {
+    style: PathStyle,
+    absolute: Option<String>,
+    remap: Vec<(String, String)>,
+    forward_slashes: bool,
+}
+
+/// Conventions by which file paths are compared.
+///
+/// Dumps produced by clang-cl contain Windows paths, which may use either
+/// `\` or `/` as the separator and are not case sensitive. Comparing them
+/// with [`PathStyle::Windows`] lets a path written as `C:/src/main.c` match
+/// the `c:\src\Main.c` that clang emitted, regardless of which platform
+/// the dump is being read on.
+///
+/// ```
+/// use clang_ast::PathStyle;
+///
+/// let file = r"C:\src\Main.c";
+/// assert!(PathStyle::Windows.same_path(file, "c:/src/main.c"));
+/// assert!(PathStyle::Windows.has_prefix(file, "C:/SRC"));
+/// assert!(!PathStyle::Posix.has_prefix(file, "C:/SRC"));
+/// ```
+///
+/// Case is compared without regard to ASCII case only; other characters must
+/// match exactly.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum PathStyle {
+    /// Paths separated by `/` and compared exactly.
+    Posix,
+    /// Paths separated by `\` or `/`, compared without regard to case, with
+    /// drive letters and UNC paths recognized as absolute.
+    Windows,
+}
+
+#[allow(clippy::derivable_impls)] // #[default] on an enum variant needs Rust 1.62, past our MSRV
+impl Default for PathStyle {
+    fn default() -> Self {
+        PathStyle::Posix
+    }
+}
+
+impl PathStyle {
+    /// Whether both strings name the same path.
+    pub fn same_path(self, a: &str, b: &str) -> bool {
+        a.len() == b.len() && self.match_prefix(a, b).is_some()
+    }
+
+    /// Whether `path` is `dir` or is inside of it. Only whole path components
+    /// match, so `/src` is a prefix of `/src/main.c` but not `/srcs/main.c`.
+    pub fn has_prefix(self, path: &str, dir: &str) -> bool {
+        self.match_prefix(path, dir).is_some()
+    }
+
+    /// Whether a path is absolute rather than relative to the working
+    /// directory.
+    pub fn is_absolute(self, path: &str) -> bool {
+        match self {
+            PathStyle::Posix => path.starts_with('/'),
+            PathStyle::Windows => {
+                let bytes = path.as_bytes();
+                bytes.first().map_or(false, |&b| self.is_separator(b))
+                    || bytes.len() >= 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':'
+            }
+        }
+    }
+
+    fn separators(self) -> &'static [char] {
+        match self {
+            PathStyle::Posix => &['/'],
+            PathStyle::Windows => &['/', '\\'],
+        }
+    }
+
+    fn is_separator(self, byte: u8) -> bool {
+        byte == b'/' || self == PathStyle::Windows && byte == b'\\'
+    }
+
+    fn bytes_eq(self, a: u8, b: u8) -> bool {
+        match self {
+            PathStyle::Posix => a == b,
+            PathStyle::Windows => {
+                a.eq_ignore_ascii_case(&b) || self.is_separator(a) && self.is_separator(b)
+            }
+        }
+    }
+
+    // The rest of `path` after `prefix`, beginning with a separator unless
+    // empty or the prefix ended in one.
+    fn match_prefix<'a>(self, path: &'a str, prefix: &str) -> Option<&'a str> {
+        let (head, rest) = (
+            path.as_bytes().get(..prefix.len())?,
+            path.get(prefix.len()..)?,
+        );
+        let boundary = prefix.bytes().last().map_or(true, |b| self.is_separator(b))
+            || rest.bytes().next().map_or(true, |b| self.is_separator(b));
+        if boundary
+            && head
+                .iter()
+                .zip(prefix.bytes())
+                .all(|(&a, b)| self.bytes_eq(a, b))
+        {
+            Some(rest)
+        } else {
+            None
+        }
+    }
+}
+
+impl PathMap {
+    pub fn new() -> Self {
+        PathMap::default()
+    }
+
+    /// Conventions for recognizing absolute paths and matching prefixes. By
+    /// default, [`PathStyle::Posix`].
+    pub fn style(&mut self, style: PathStyle) -> &mut Self {
+        self.style = style;
+        self
+    }
+
+    /// Join relative paths onto the given directory, usually the directory
+    /// clang was run in. This happens before any remapping.
+    pub fn absolute(&mut self, dir: impl Into<String>) -> &mut Self {
+        self.absolute = Some(dir.into());
+        self
+    }
+
+    /// Remove the given directory from the beginning of paths inside of it,
+    /// leaving them relative to it.
+    pub fn strip_prefix(&mut self, dir: impl Into<String>) -> &mut Self {
+        self.remap.push((dir.into(), String::new()));
+        self
+    }
+
+    /// Replace the prefix `from` of paths beginning with it by `to`, like
+    /// clang's `-fdebug-prefix-map=from=to`.
+    ///
+    /// A prefix only matches whole path components, so `/src` matches
+    /// `/src/main.c` but not `/srcs/main.c`. May be called more than once,
+    /// along with `strip_prefix`; the first rule that matches is applied.
+    pub fn remap(&mut self, from: impl Into<String>, to: impl Into<String>) -> &mut Self {
+        self.remap.push((from.into(), to.into()));
+        self
+    }
+
+    /// Replace every `\` in paths by `/`, after remapping, so that Windows
+    /// paths are written the same way throughout.
+    pub fn forward_slashes(&mut self) -> &mut Self {
+        self.forward_slashes = true;
+        self
+    }
+
+    /// Run `f` with this mapping applied to the paths of source locations
+    /// deserialized by `f` on the current thread.
+    pub fn scope<R>(&self, f: impl FnOnce() -> R) -> R {
+        struct Restore(Option<PathMap>);
+
+        impl Drop for Restore {
+            fn drop(&mut self) {
+                let prev = self.0.take();
+                context::with(|context| context.path_map = prev);
+            }
+        }
+
+        let prev = context::with(|context| context.path_map.replace(self.clone()));
+        let _restore = Restore(prev);
+        f()
+    }
+
+    /// Rewrite a single path, returning `None` if it is unchanged.
+    pub fn apply(&self, path: &str) -> Option<String> {
+        if path.starts_with('<') {
+            return None;
+        }
+        let mut mapped = None;
+        if let Some(dir) = &self.absolute {
+            if !self.style.is_absolute(path) {
+                let path = path
+                    .strip_prefix('.')
+                    .and_then(|rest| rest.strip_prefix(self.style.separators()))
+                    .unwrap_or(path);
+                mapped = Some(match dir.bytes().last() {
+                    Some(last) if self.style.is_separator(last) => format!("{}{}", dir, path),
+                    _ if self.style == PathStyle::Windows && dir.contains('\\') => {
+                        format!("{}\\{}", dir, path)
+                    }
+                    _ => format!("{}/{}", dir, path),
+                });
+            }
+        }
+        let current = mapped.as_deref().unwrap_or(path);
+        for (from, to) in &self.remap {
+            if let Some(mut rest) = self.style.match_prefix(current, from) {
+                if to.is_empty() {
+                    rest = rest.trim_start_matches(self.style.separators());
+                }
+                mapped = Some(format!("{}{}", to, rest));
+                break;
+            }
+        }
+        if self.forward_slashes {
+            let current = mapped.as_deref().unwrap_or(path);
+            if current.contains('\\') {
+                mapped = Some(current.replace('\\', "/"));
+            }
+        }
+        mapped
+    }
+}
+
+fn apply(path: &str) -> Option<String> {
+    context::with(|context| {
+        let path_map = context.path_map.as_ref()?;
+        path_map.apply(path)
+    })
+}
+
+// Deserializes and interns the path of a source location.
+pub(crate) struct PathSeed;
+
+impl<'de> DeserializeSeed<'de> for PathSeed {
+    type Value = Shared<str>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_str(self)
+    }
+}
+
+impl<'de> Visitor<'de> for PathSeed {
+    type Value = Shared<str>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a file path")
+    }
+
+    fn visit_str<E>(self, path: &str) -> Result<Self::Value, E>
+    where
+        E: Error,
+    {
+        Ok(match apply(path) {
+            Some(mapped) => intern::owned(mapped),
+            None => intern::borrowed(path),
+        })
+    }
+
+    fn visit_string<E>(self, path: String) -> Result<Self::Value, E>
+    where
+        E: Error,
+    {
+        Ok(intern::owned(apply(&path).unwrap_or(path)))
+    }
+}
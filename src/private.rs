@@ -0,0 +1,67 @@
+use serde::de::{
+    DeserializeSeed, Deserializer, Error, IgnoredAny, MapAccess, VariantAccess, Visitor,
+};
+use std::fmt;
+
+pub mod serde {
+    pub use ::serde::{de, Deserialize, Deserializer};
+}
+
+// Fallback variant of a #[derive(ClangKind)] enum which holds the kind of
+// the unrecognized node.
+pub fn fallback_kind<'de, V>(variant: V) -> Result<String, V::Error>
+where
+    V: VariantAccess<'de>,
+{
+    variant.struct_variant(&["kind"], KindVisitor)
+}
+
+struct KindVisitor;
+
+impl<'de> Visitor<'de> for KindVisitor {
+    type Value = String;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("clang syntax tree node")
+    }
+
+    fn visit_map<M>(self, mut map: M) -> Result<Self::Value, M::Error>
+    where
+        M: MapAccess<'de>,
+    {
+        let mut kind = None;
+        while let Some(is_kind) = map.next_key_seed(IsKind)? {
+            if is_kind && kind.is_none() {
+                kind = Some(map.next_value()?);
+            } else {
+                let IgnoredAny = map.next_value()?;
+            }
+        }
+        kind.ok_or_else(|| Error::missing_field("kind"))
+    }
+}
+
+struct IsKind;
+
+impl<'de> DeserializeSeed<'de> for IsKind {
+    type Value = bool;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_identifier(self)
+    }
+}
+
+impl<'de> Visitor<'de> for IsKind {
+    type Value = bool;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("field identifier")
+    }
+
+    fn visit_str<E>(self, field: &str) -> Result<Self::Value, E> {
+        Ok(field == "kind")
+    }
+}
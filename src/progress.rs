@@ -0,0 +1,100 @@
+use std::fmt::{self, Debug};
+use std::io::{self, Read};
+
+const INTERVAL: u64 = 1 << 20;
+
+/// Reader adapter which reports how many bytes of an AST dump have been
+/// consumed so far.
+///
+/// The callback is invoked with the running total after every mebibyte read
+/// from the underlying reader, and once more upon reaching the end of input.
+/// Comparing against the size of the file gives a progress bar for long
+/// parses.
+///
+/// ```no_run
+/// use clang_ast::{Kind, Progress};
+/// use serde::Deserialize;
+/// use std::fs::File;
+/// use std::io::BufReader;
+///
+/// #[derive(Deserialize)]
+/// pub struct Clang {
+///     pub kind: Kind,
+/// }
+///
+/// fn main() -> serde_json::Result<()> {
+///     let file = File::open("ast.json").unwrap();
+///     let total = file.metadata().unwrap().len();
+///     let progress = Progress::new(file, |consumed| {
+///         eprint!("\r{}%", consumed * 100 / total);
+///     });
+///     let reader = BufReader::new(progress);
+///     let node: clang_ast::Node<Clang> = serde_json::from_reader(reader)?;
+///     eprintln!();
+///     println!("{} top-level decls", node.inner.len());
+///     Ok(())
+/// }
+/// ```
+///
+/// For a count of nodes rather than bytes, use [`stream::parse`], whose
+/// callback runs once per node.
+///
+/// [`stream::parse`]: crate::stream::parse
+pub struct Progress<R, F> {
+    reader: R,
+    f: F,
+    consumed: u64,
+    reported: u64,
+}
+
+impl<R, F> Progress<R, F>
+where
+    R: Read,
+    F: FnMut(u64),
+{
+    pub fn new(reader: R, f: F) -> Self {
+        Progress {
+            reader,
+            f,
+            consumed: 0,
+            reported: 0,
+        }
+    }
+
+    /// Total number of bytes read from the underlying reader so far.
+    pub fn consumed(&self) -> u64 {
+        self.consumed
+    }
+
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<R, F> Read for Progress<R, F>
+where
+    R: Read,
+    F: FnMut(u64),
+{
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.reader.read(buf)?;
+        self.consumed += n as u64;
+        let eof = n == 0 && !buf.is_empty();
+        if self.consumed - self.reported >= INTERVAL || (eof && self.consumed > self.reported) {
+            self.reported = self.consumed;
+            (self.f)(self.consumed);
+        }
+        Ok(n)
+    }
+}
+
+// The reader and callback need not implement Debug.
+#[allow(clippy::missing_fields_in_debug)]
+impl<R, F> Debug for Progress<R, F> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter
+            .debug_struct("Progress")
+            .field("consumed", &self.consumed)
+            .finish()
+    }
+}
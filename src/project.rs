@@ -0,0 +1,258 @@
+use crate::{Interner, Node};
+use serde::de::DeserializeOwned;
+use std::borrow::Cow;
+use std::error::Error as StdError;
+use std::fmt::{self, Display};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::slice;
+
+/// Syntax trees of many translation units, loaded from their AST dumps.
+///
+/// File paths in source locations are interned across all of the trees in
+/// the project, so that the same header included by a thousand translation
+/// units is stored once.
+///
+/// ```no_run
+/// use clang_ast::{Kind, Project};
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// pub struct Clang {
+///     pub kind: Kind,
+/// }
+///
+/// fn main() -> Result<(), clang_ast::LoadError> {
+///     let mut project = Project::<Clang>::new();
+///     project.load("build/ast")?;
+///     project.load("vendor/*.json")?;
+///     for unit in &project {
+///         println!("{}: {} decls", unit.path.display(), unit.ast.inner.len());
+///     }
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug)]
+pub struct Project<T> {
+    units: Vec<TranslationUnit<T>>,
+    interner: Interner,
+}
+
+/// One loaded AST dump within a [`Project`].
+#[derive(Debug)]
+pub struct TranslationUnit<T> {
+    /// Path of the dump it was loaded from.
+    pub path: PathBuf,
+    /// Size of the dump in bytes.
+    pub len: u64,
+    pub ast: Node<T>,
+}
+
+impl<T> Project<T> {
+    pub fn new() -> Self {
+        Project {
+            units: Vec::new(),
+            interner: Interner::new(),
+        }
+    }
+
+    pub fn units(&self) -> &[TranslationUnit<T>] {
+        &self.units
+    }
+
+    pub fn iter(&self) -> slice::Iter<'_, TranslationUnit<T>> {
+        self.units.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.units.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.units.is_empty()
+    }
+
+    /// The interner holding file paths of all translation units in the
+    /// project.
+    pub fn interner(&self) -> &Interner {
+        &self.interner
+    }
+
+    pub fn into_units(self) -> Vec<TranslationUnit<T>> {
+        self.units
+    }
+}
+
+impl<T> Project<T>
+where
+    T: DeserializeOwned,
+{
+    /// Load AST dumps from `path`, returning how many were loaded.
+    ///
+    /// The path may be:
+    ///
+    /// - a JSON file, which is loaded;
+    /// - a directory, from which every file with a `.json` extension is
+    ///   loaded, recursively, in order of path;
+    /// - a pattern such as `out/*.ast.json` whose last component contains
+    ///   `*` wildcards, each of which matches any sequence of characters.
+    ///
+    /// Translation units loaded before an error are kept.
+    pub fn load<P: AsRef<Path>>(&mut self, path: P) -> Result<usize, LoadError> {
+        let path = path.as_ref();
+        let mut paths = Vec::new();
+        if let Some(pattern) = pattern(path) {
+            let dir = match path.parent() {
+                Some(dir) if !dir.as_os_str().is_empty() => dir,
+                _ => Path::new("."),
+            };
+            for entry in read_dir(dir)? {
+                let matches = entry
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .map_or(false, |name| wildcard_match(&pattern, name));
+                if matches && !entry.is_dir() {
+                    paths.push(entry);
+                }
+            }
+        } else if path.is_dir() {
+            collect_json(path, &mut paths)?;
+        } else {
+            paths.push(path.to_owned());
+        }
+
+        let count = paths.len();
+        for path in paths {
+            self.load_file(path)?;
+        }
+        Ok(count)
+    }
+
+    fn load_file(&mut self, path: PathBuf) -> Result<(), LoadError> {
+        let json = match fs::read(&path) {
+            Ok(json) => json,
+            Err(error) => return Err(LoadError::new(path, serde_json::Error::io(error))),
+        };
+        let ast = match self.interner.scope(|| serde_json::from_slice(&json)) {
+            Ok(ast) => ast,
+            Err(error) => return Err(LoadError::new(path, error)),
+        };
+        self.units.push(TranslationUnit {
+            path,
+            len: json.len() as u64,
+            ast,
+        });
+        Ok(())
+    }
+}
+
+impl<T> Default for Project<T> {
+    fn default() -> Self {
+        Project::new()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a Project<T> {
+    type Item = &'a TranslationUnit<T>;
+    type IntoIter = slice::Iter<'a, TranslationUnit<T>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.units.iter()
+    }
+}
+
+impl<T> IntoIterator for Project<T> {
+    type Item = TranslationUnit<T>;
+    type IntoIter = std::vec::IntoIter<TranslationUnit<T>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.units.into_iter()
+    }
+}
+
+/// Error from [`Project::load`], identifying which file failed to load.
+#[derive(Debug)]
+pub struct LoadError {
+    path: PathBuf,
+    error: serde_json::Error,
+}
+
+impl LoadError {
+    fn new(path: PathBuf, error: serde_json::Error) -> Self {
+        LoadError { path, error }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn into_inner(self) -> serde_json::Error {
+        self.error
+    }
+}
+
+impl Display for LoadError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "{}: {}", self.path.display(), self.error)
+    }
+}
+
+impl StdError for LoadError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        Some(&self.error)
+    }
+}
+
+// Last component of the path, if it contains a wildcard.
+fn pattern(path: &Path) -> Option<Cow<'_, str>> {
+    let name = path.file_name()?.to_string_lossy();
+    if name.contains('*') {
+        Some(name)
+    } else {
+        None
+    }
+}
+
+// Entries of a directory, sorted by path.
+fn read_dir(dir: &Path) -> Result<Vec<PathBuf>, LoadError> {
+    let io_error = |error| LoadError::new(dir.to_owned(), serde_json::Error::io(error));
+    let mut paths = Vec::new();
+    for entry in fs::read_dir(dir).map_err(io_error)? {
+        paths.push(entry.map_err(io_error)?.path());
+    }
+    paths.sort();
+    Ok(paths)
+}
+
+fn collect_json(dir: &Path, paths: &mut Vec<PathBuf>) -> Result<(), LoadError> {
+    for path in read_dir(dir)? {
+        if path.is_dir() {
+            collect_json(&path, paths)?;
+        } else if path.extension().map_or(false, |ext| ext == "json") {
+            paths.push(path);
+        }
+    }
+    Ok(())
+}
+
+fn wildcard_match(pattern: &str, name: &str) -> bool {
+    let mut pieces = pattern.split('*');
+    let first = pieces.next().unwrap();
+    let mut rest = match name.strip_prefix(first) {
+        Some(rest) => rest,
+        None => return false,
+    };
+    let pieces: Vec<&str> = pieces.collect();
+    let (last, middle) = match pieces.split_last() {
+        Some(split) => split,
+        // No wildcard at all.
+        None => return rest.is_empty(),
+    };
+    for piece in middle {
+        match rest.find(piece) {
+            Some(i) => rest = &rest[i + piece.len()..],
+            None => return false,
+        }
+    }
+    rest.ends_with(last)
+}
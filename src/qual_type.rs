@@ -0,0 +1,787 @@
+use std::fmt::{self, Debug, Display};
+use std::str::FromStr;
+
+/// Structure of a C or C++ type, parsed from Clang's spelling of it in a
+/// `"qualType"`.
+///
+/// The parser understands qualifiers, pointers, references, arrays,
+/// function signatures, nested names and template arguments, which covers
+/// most of what appears in practice. It is best-effort: types spelled in
+/// some less common way, such as pointers to members, fail to parse rather
+/// than produce a wrong answer. Types nested more than 128 levels deep, for
+/// example through template arguments or pointers, are rejected too.
+///
+/// ```
+/// use clang_ast::{ParsedType, TemplateArg};
+///
+/// let ty: ParsedType = "const std::vector<int> &".parse().unwrap();
+/// let referent = match &ty {
+///     ParsedType::LValueReference(referent) => referent,
+///     _ => unreachable!(),
+/// };
+/// assert!(referent.qualifiers().is_const);
+/// let name = referent.unqualified().name().unwrap();
+/// assert_eq!(name.to_string(), "std::vector<int>");
+/// let args = name.segments[1].args.as_ref().unwrap();
+/// assert!(matches!(&args[0], TemplateArg::Type(ParsedType::Named(int)) if int.to_string() == "int"));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ParsedType {
+    /// A builtin type, or a class, enum, typedef or template specialization
+    /// named by a possibly qualified name.
+    Named(Name),
+    Qualified(Qualifiers, Box<ParsedType>),
+    Pointer(Box<ParsedType>),
+    /// Clang's blocks extension, `int (^)(int)`.
+    BlockPointer(Box<ParsedType>),
+    LValueReference(Box<ParsedType>),
+    RValueReference(Box<ParsedType>),
+    Array {
+        element: Box<ParsedType>,
+        /// The size as written, or `None` for an array of unknown bound.
+        size: Option<String>,
+    },
+    Function(Box<FunctionType>),
+}
+
+/// Qualifiers `const`, `volatile` and `restrict`.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Qualifiers {
+    pub is_const: bool,
+    pub is_volatile: bool,
+    pub is_restrict: bool,
+}
+
+/// Possibly qualified name of a type, like `std::vector<int>::iterator`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Name {
+    /// Leading `struct`, `class`, `union`, `enum` or `typename`, if any.
+    pub keyword: Option<String>,
+    pub segments: Vec<NameSegment>,
+}
+
+/// One `::`-separated component of a [`Name`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct NameSegment {
+    /// An identifier, several words making up a builtin type such as
+    /// `unsigned long`, or a placeholder such as `(anonymous namespace)`.
+    pub name: String,
+    /// Template arguments, if the segment is a template specialization.
+    pub args: Option<Vec<TemplateArg>>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum TemplateArg {
+    Type(ParsedType),
+    /// A non-type template argument, or anything else which did not parse
+    /// as a type, as written.
+    Expr(String),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct FunctionType {
+    pub ret: ParsedType,
+    pub params: Vec<ParsedType>,
+    /// Whether the parameter list ends in `...`.
+    pub variadic: bool,
+    /// Qualifiers following the parameter list, such as `const`, `&&` and
+    /// `noexcept`, as written.
+    pub trailing: Vec<String>,
+}
+
+impl ParsedType {
+    /// The qualifiers applied directly to this type.
+    pub fn qualifiers(&self) -> Qualifiers {
+        match self {
+            ParsedType::Qualified(qualifiers, _) => *qualifiers,
+            _ => Qualifiers::default(),
+        }
+    }
+
+    /// This type with its top-level qualifiers removed.
+    pub fn unqualified(&self) -> &ParsedType {
+        match self {
+            ParsedType::Qualified(_, ty) => ty.unqualified(),
+            _ => self,
+        }
+    }
+
+    /// Name of this type, if it is a named type after removing top-level
+    /// qualifiers.
+    pub fn name(&self) -> Option<&Name> {
+        match self.unqualified() {
+            ParsedType::Named(name) => Some(name),
+            _ => None,
+        }
+    }
+
+    /// The type pointed or referred to, if this is a pointer or reference
+    /// after removing top-level qualifiers.
+    pub fn pointee(&self) -> Option<&ParsedType> {
+        match self.unqualified() {
+            ParsedType::Pointer(pointee)
+            | ParsedType::BlockPointer(pointee)
+            | ParsedType::LValueReference(pointee)
+            | ParsedType::RValueReference(pointee) => Some(pointee),
+            _ => None,
+        }
+    }
+}
+
+impl Qualifiers {
+    fn is_empty(self) -> bool {
+        !self.is_const && !self.is_volatile && !self.is_restrict
+    }
+
+    fn add(&mut self, word: &str) -> bool {
+        match word {
+            "const" => self.is_const = true,
+            "volatile" => self.is_volatile = true,
+            "restrict" | "__restrict" | "__restrict__" => self.is_restrict = true,
+            // Nullability and similar annotations carry no information
+            // about the structure of the type.
+            "_Nonnull" | "_Nullable" | "_Nullable_result" | "_Null_unspecified" | "__unaligned" => {
+            }
+            _ => return false,
+        }
+        true
+    }
+}
+
+impl Display for Name {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(keyword) = &self.keyword {
+            write!(formatter, "{} ", keyword)?;
+        }
+        for (i, segment) in self.segments.iter().enumerate() {
+            if i > 0 {
+                formatter.write_str("::")?;
+            }
+            formatter.write_str(&segment.name)?;
+            if let Some(args) = &segment.args {
+                formatter.write_str("<")?;
+                for (i, arg) in args.iter().enumerate() {
+                    if i > 0 {
+                        formatter.write_str(", ")?;
+                    }
+                    match arg {
+                        TemplateArg::Type(ParsedType::Named(name)) => {
+                            Display::fmt(name, formatter)?;
+                        }
+                        TemplateArg::Type(_) => formatter.write_str("...")?,
+                        TemplateArg::Expr(expr) => formatter.write_str(expr)?,
+                    }
+                }
+                formatter.write_str(">")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for ParsedType {
+    type Err = ParseTypeError;
+
+    fn from_str(qual_type: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser {
+            src: qual_type,
+            tokens: tokenize(qual_type),
+            pos: 0,
+            depth: 0,
+            too_deep: false,
+        };
+        let ty = parser.parse_type()?;
+        if parser.pos == parser.tokens.len() {
+            Ok(ty)
+        } else {
+            Err(ParseTypeError { _private: () })
+        }
+    }
+}
+
+/// Error returned when a `qualType` string is not understood by
+/// [`ParsedType`]'s parser.
+pub struct ParseTypeError {
+    _private: (),
+}
+
+impl Debug for ParseTypeError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.debug_struct("ParseTypeError").finish()
+    }
+}
+
+impl Display for ParseTypeError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("unrecognized syntax in qualType")
+    }
+}
+
+impl std::error::Error for ParseTypeError {}
+
+#[derive(Copy, Clone, PartialEq)]
+enum Token<'a> {
+    Ident(&'a str),
+    Punct(&'a str),
+}
+
+impl<'a> Token<'a> {
+    fn text(self) -> &'a str {
+        match self {
+            Token::Ident(text) | Token::Punct(text) => text,
+        }
+    }
+
+    // An identifier, leading `::`, or placeholder like `<dependent type>`.
+    fn starts_name(self) -> bool {
+        match self {
+            Token::Ident(_) => true,
+            Token::Punct(punct) => punct == "::" || punct == "<",
+        }
+    }
+}
+
+struct Spanned<'a> {
+    token: Token<'a>,
+    start: usize,
+    end: usize,
+}
+
+fn tokenize(src: &str) -> Vec<Spanned<'_>> {
+    let mut tokens = Vec::new();
+    let mut pos = 0;
+    while let Some(ch) = src[pos..].chars().next() {
+        let rest = &src[pos..];
+        let len = if ch.is_whitespace() {
+            pos += ch.len_utf8();
+            continue;
+        } else if ch == '(' && is_placeholder(&rest[1..]) {
+            // (anonymous namespace), (unnamed struct at a.c:1:1), ...
+            let mut depth = 0;
+            let mut len = rest.len();
+            for (i, ch) in rest.char_indices() {
+                match ch {
+                    '(' => depth += 1,
+                    ')' => depth -= 1,
+                    _ => continue,
+                }
+                if depth == 0 {
+                    len = i + 1;
+                    break;
+                }
+            }
+            tokens.push(Spanned {
+                token: Token::Ident(&rest[..len]),
+                start: pos,
+                end: pos + len,
+            });
+            pos += len;
+            continue;
+        } else if is_ident_char(ch) {
+            // Dependent types print as type-parameter-0-0.
+            let dash = rest.starts_with("type-parameter-");
+            rest.find(|ch: char| !(is_ident_char(ch) || dash && ch == '-'))
+                .unwrap_or(rest.len())
+        } else if ["::", "->", "...", "&&"]
+            .iter()
+            .any(|punct| rest.starts_with(punct))
+        {
+            if rest.starts_with("...") {
+                3
+            } else {
+                2
+            }
+        } else {
+            ch.len_utf8()
+        };
+        let text = &rest[..len];
+        let token = if is_ident_char(ch) {
+            Token::Ident(text)
+        } else {
+            Token::Punct(text)
+        };
+        tokens.push(Spanned {
+            token,
+            start: pos,
+            end: pos + len,
+        });
+        pos += len;
+    }
+    tokens
+}
+
+fn is_ident_char(ch: char) -> bool {
+    ch.is_alphanumeric() || ch == '_' || ch == '$'
+}
+
+fn is_placeholder(rest: &str) -> bool {
+    ["anonymous", "unnamed", "lambda"]
+        .iter()
+        .any(|word| rest.starts_with(word))
+}
+
+fn is_builtin_word(word: &str) -> bool {
+    match word {
+        "void" | "bool" | "_Bool" | "char" | "wchar_t" | "char8_t" | "char16_t" | "char32_t"
+        | "short" | "int" | "long" | "signed" | "unsigned" | "__int128" | "float" | "double"
+        | "_Complex" | "_Imaginary" | "_Float16" | "__fp16" | "__bf16" | "__float128" => true,
+        _ => false,
+    }
+}
+
+fn is_tag_keyword(word: &str) -> bool {
+    match word {
+        "struct" | "class" | "union" | "enum" | "typename" | "__interface" => true,
+        _ => false,
+    }
+}
+
+fn is_type_operator(word: &str) -> bool {
+    match word {
+        "decltype" | "typeof" | "__typeof" | "__typeof__" | "_Atomic" | "__underlying_type" => true,
+        _ => false,
+    }
+}
+
+struct Parser<'a> {
+    src: &'a str,
+    tokens: Vec<Spanned<'a>>,
+    pos: usize,
+    // How deeply the type currently being parsed is nested, counting every
+    // pointer, reference, array and function layer along with types nested
+    // in template arguments and parameter lists.
+    depth: usize,
+    // Set once the depth limit has been hit, so that nothing falls back to
+    // reparsing the same input some other way.
+    too_deep: bool,
+}
+
+// Same as serde_json's default recursion limit. This keeps both the parser's
+// recursion and the resulting ParsedType's recursive Drop from overflowing
+// the stack on adversarial input.
+const RECURSION_LIMIT: usize = 128;
+
+// The parts of an abstract declarator, like the `*const (&)[3]` in
+// `int *const (&)[3]`.
+struct Declarator {
+    prefix: Vec<Prefix>,
+    nested: Option<Box<Declarator>>,
+    suffix: Vec<Suffix>,
+}
+
+enum Prefix {
+    Pointer,
+    BlockPointer,
+    LValueReference,
+    RValueReference,
+    Qualifiers(Qualifiers),
+}
+
+enum Suffix {
+    Array(Option<String>),
+    Function {
+        params: Vec<ParsedType>,
+        variadic: bool,
+        trailing: Vec<String>,
+        ret: Option<ParsedType>,
+    },
+}
+
+impl Declarator {
+    // Operators closest to the name bind tightest, so `int *[3]` is an array
+    // of pointers and `int (*)[3]` is a pointer to an array.
+    fn apply(self, mut ty: ParsedType) -> ParsedType {
+        for prefix in self.prefix {
+            ty = match prefix {
+                Prefix::Pointer => ParsedType::Pointer(Box::new(ty)),
+                Prefix::BlockPointer => ParsedType::BlockPointer(Box::new(ty)),
+                Prefix::LValueReference => ParsedType::LValueReference(Box::new(ty)),
+                Prefix::RValueReference => ParsedType::RValueReference(Box::new(ty)),
+                Prefix::Qualifiers(qualifiers) => ParsedType::Qualified(qualifiers, Box::new(ty)),
+            };
+        }
+        for suffix in self.suffix.into_iter().rev() {
+            ty = match suffix {
+                Suffix::Array(size) => ParsedType::Array {
+                    element: Box::new(ty),
+                    size,
+                },
+                Suffix::Function {
+                    params,
+                    variadic,
+                    trailing,
+                    ret,
+                } => ParsedType::Function(Box::new(FunctionType {
+                    ret: ret.unwrap_or(ty),
+                    params,
+                    variadic,
+                    trailing,
+                })),
+            };
+        }
+        match self.nested {
+            Some(nested) => nested.apply(ty),
+            None => ty,
+        }
+    }
+}
+
+fn error<T>() -> Result<T, ParseTypeError> {
+    Err(ParseTypeError { _private: () })
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<Token<'a>> {
+        self.peek_nth(0)
+    }
+
+    fn peek_nth(&self, n: usize) -> Option<Token<'a>> {
+        self.tokens.get(self.pos + n).map(|spanned| spanned.token)
+    }
+
+    fn is_punct(&self, punct: &str) -> bool {
+        self.peek() == Some(Token::Punct(punct))
+    }
+
+    fn eat_punct(&mut self, punct: &str) -> bool {
+        let matches = self.is_punct(punct);
+        if matches {
+            self.pos += 1;
+        }
+        matches
+    }
+
+    fn expect_punct(&mut self, punct: &str) -> Result<(), ParseTypeError> {
+        if self.eat_punct(punct) {
+            Ok(())
+        } else {
+            error()
+        }
+    }
+
+    // Source text of the tokens from `start` up to the current position.
+    fn text_since(&self, start: usize) -> String {
+        if start == self.pos {
+            return String::new();
+        }
+        let begin = self.tokens[start].start;
+        let end = self.tokens[self.pos - 1].end;
+        self.src[begin..end].to_owned()
+    }
+
+    // Skip tokens up to one of `terminators` outside of any brackets,
+    // returning the text skipped.
+    fn skip_balanced(&mut self, terminators: &[&str]) -> Result<String, ParseTypeError> {
+        let start = self.pos;
+        let mut depth = 0usize;
+        loop {
+            match self.peek() {
+                None => return error(),
+                Some(Token::Punct(punct)) if depth == 0 && terminators.contains(&punct) => {
+                    return Ok(self.text_since(start));
+                }
+                Some(Token::Punct(open)) if ["(", "[", "{"].contains(&open) => depth += 1,
+                Some(Token::Punct(close)) if [")", "]", "}"].contains(&close) => {
+                    depth = match depth.checked_sub(1) {
+                        Some(depth) => depth,
+                        None => return error(),
+                    };
+                }
+                Some(_) => {}
+            }
+            self.pos += 1;
+        }
+    }
+
+    // A parenthesized group such as the `((noreturn))` of an attribute.
+    fn skip_parens(&mut self) -> Result<String, ParseTypeError> {
+        let start = self.pos;
+        self.expect_punct("(")?;
+        self.skip_balanced(&[")"])?;
+        self.expect_punct(")")?;
+        Ok(self.text_since(start))
+    }
+
+    fn skip_attribute(&mut self) -> Result<bool, ParseTypeError> {
+        if self.peek() == Some(Token::Ident("__attribute__")) {
+            self.pos += 1;
+            self.skip_parens()?;
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+
+    // Go one level deeper into the type being parsed.
+    fn nest(&mut self) -> Result<(), ParseTypeError> {
+        self.depth += 1;
+        if self.depth > RECURSION_LIMIT {
+            self.too_deep = true;
+            error()
+        } else {
+            Ok(())
+        }
+    }
+
+    fn parse_type(&mut self) -> Result<ParsedType, ParseTypeError> {
+        let depth = self.depth;
+        let ty = self.parse_type_at_depth();
+        self.depth = depth;
+        ty
+    }
+
+    fn parse_type_at_depth(&mut self) -> Result<ParsedType, ParseTypeError> {
+        self.nest()?;
+        let base = self.parse_specifiers()?;
+        let declarator = self.parse_declarator()?;
+        Ok(declarator.apply(base))
+    }
+
+    fn parse_specifiers(&mut self) -> Result<ParsedType, ParseTypeError> {
+        let mut qualifiers = Qualifiers::default();
+        let mut keyword = None;
+        let mut name: Option<Name> = None;
+        loop {
+            if self.skip_attribute()? {
+                continue;
+            }
+            match self.peek() {
+                Some(Token::Ident(word)) if qualifiers.add(word) => self.pos += 1,
+                Some(Token::Ident(word))
+                    if is_tag_keyword(word) && name.is_none() && keyword.is_none() =>
+                {
+                    keyword = Some(word.to_owned());
+                    self.pos += 1;
+                }
+                Some(token) if name.is_none() && token.starts_name() => {
+                    name = Some(self.parse_name(keyword.take())?);
+                }
+                Some(Token::Ident(word)) if is_builtin_word(word) => {
+                    let segments = match &mut name {
+                        Some(Name {
+                            keyword: None,
+                            segments,
+                        }) if segments.len() == 1 => segments,
+                        _ => break,
+                    };
+                    let segment = &mut segments[0];
+                    if segment.args.is_some() || !segment.name.split(' ').all(is_builtin_word) {
+                        break;
+                    }
+                    segment.name.push(' ');
+                    segment.name.push_str(word);
+                    self.pos += 1;
+                }
+                _ => break,
+            }
+        }
+        let ty = match name {
+            Some(name) => ParsedType::Named(name),
+            None => return error(),
+        };
+        if qualifiers.is_empty() {
+            Ok(ty)
+        } else {
+            Ok(ParsedType::Qualified(qualifiers, Box::new(ty)))
+        }
+    }
+
+    fn parse_name(&mut self, keyword: Option<String>) -> Result<Name, ParseTypeError> {
+        self.eat_punct("::");
+        let mut segments = Vec::new();
+        loop {
+            let start = self.pos;
+            let name = match self.peek() {
+                // Placeholders such as <dependent type>.
+                Some(Token::Punct("<")) => {
+                    self.pos += 1;
+                    self.skip_balanced(&[">"])?;
+                    self.expect_punct(">")?;
+                    self.text_since(start)
+                }
+                Some(Token::Ident(word))
+                    if is_type_operator(word) && self.peek_nth(1) == Some(Token::Punct("(")) =>
+                {
+                    self.pos += 1;
+                    self.skip_parens()?;
+                    self.text_since(start)
+                }
+                // A number is never a type, as in the `3` of
+                // `std::array<int, 3>`.
+                Some(Token::Ident(word)) if !word.starts_with(|ch: char| ch.is_ascii_digit()) => {
+                    self.pos += 1;
+                    word.to_owned()
+                }
+                _ => return error(),
+            };
+            let args = if self.is_punct("<") {
+                Some(self.parse_template_args()?)
+            } else {
+                None
+            };
+            segments.push(NameSegment { name, args });
+            let continues = self.peek_nth(1).map_or(false, Token::starts_name);
+            if continues && self.is_punct("::") {
+                self.pos += 1;
+            } else {
+                break;
+            }
+        }
+        Ok(Name { keyword, segments })
+    }
+
+    fn parse_template_args(&mut self) -> Result<Vec<TemplateArg>, ParseTypeError> {
+        self.expect_punct("<")?;
+        let mut args = Vec::new();
+        if self.eat_punct(">") {
+            return Ok(args);
+        }
+        loop {
+            let start = self.pos;
+            let arg = match self.parse_type() {
+                Ok(ty) if self.is_punct(",") || self.is_punct(">") => TemplateArg::Type(ty),
+                _ if self.too_deep => return error(),
+                _ => {
+                    self.pos = start;
+                    TemplateArg::Expr(self.skip_balanced(&[",", ">"])?)
+                }
+            };
+            args.push(arg);
+            if self.eat_punct(">") {
+                return Ok(args);
+            }
+            self.expect_punct(",")?;
+        }
+    }
+
+    fn parse_declarator(&mut self) -> Result<Declarator, ParseTypeError> {
+        let mut prefix = Vec::new();
+        loop {
+            if self.skip_attribute()? {
+                continue;
+            }
+            let op = match self.peek() {
+                Some(Token::Punct("*")) => Prefix::Pointer,
+                Some(Token::Punct("^")) => Prefix::BlockPointer,
+                Some(Token::Punct("&")) => Prefix::LValueReference,
+                Some(Token::Punct("&&")) => Prefix::RValueReference,
+                _ => break,
+            };
+            self.pos += 1;
+            self.nest()?;
+            prefix.push(op);
+            let mut qualifiers = Qualifiers::default();
+            while let Some(Token::Ident(word)) = self.peek() {
+                if !qualifiers.add(word) {
+                    break;
+                }
+                self.pos += 1;
+            }
+            if !qualifiers.is_empty() {
+                prefix.push(Prefix::Qualifiers(qualifiers));
+            }
+        }
+
+        let nested = match self.peek_nth(1) {
+            Some(Token::Punct(op)) if self.is_punct("(") && ["*", "^", "&", "&&"].contains(&op) => {
+                self.pos += 1;
+                self.nest()?;
+                let nested = self.parse_declarator()?;
+                self.expect_punct(")")?;
+                Some(Box::new(nested))
+            }
+            _ => None,
+        };
+
+        let mut suffix = Vec::new();
+        loop {
+            if self.eat_punct("[") {
+                self.nest()?;
+                let size = self.skip_balanced(&["]"])?;
+                self.expect_punct("]")?;
+                suffix.push(Suffix::Array(if size.is_empty() {
+                    None
+                } else {
+                    Some(size)
+                }));
+            } else if self.is_punct("(") {
+                self.nest()?;
+                suffix.push(self.parse_function()?);
+            } else {
+                break;
+            }
+        }
+
+        Ok(Declarator {
+            prefix,
+            nested,
+            suffix,
+        })
+    }
+
+    fn parse_function(&mut self) -> Result<Suffix, ParseTypeError> {
+        self.expect_punct("(")?;
+        let mut params = Vec::new();
+        let mut variadic = false;
+        if !self.eat_punct(")") {
+            loop {
+                if self.eat_punct("...") {
+                    variadic = true;
+                    self.expect_punct(")")?;
+                    break;
+                }
+                params.push(self.parse_type()?);
+                if self.eat_punct(")") {
+                    break;
+                }
+                self.expect_punct(",")?;
+            }
+        }
+        // C's `int (void)` takes no parameters.
+        if let [ParsedType::Named(name)] = params.as_slice() {
+            if name.keyword.is_none()
+                && name.segments.len() == 1
+                && name.segments[0].name == "void"
+                && name.segments[0].args.is_none()
+            {
+                params.clear();
+            }
+        }
+
+        let mut trailing = Vec::new();
+        let mut ret = None;
+        loop {
+            if self.skip_attribute()? {
+                continue;
+            }
+            match self.peek() {
+                Some(token)
+                    if ["const", "volatile", "__restrict", "&", "&&"].contains(&token.text()) =>
+                {
+                    trailing.push(token.text().to_owned());
+                    self.pos += 1;
+                }
+                Some(Token::Ident(word)) if word == "noexcept" || word == "throw" => {
+                    let start = self.pos;
+                    self.pos += 1;
+                    if self.is_punct("(") || word == "throw" {
+                        self.skip_parens()?;
+                    }
+                    trailing.push(self.text_since(start));
+                }
+                Some(Token::Punct("->")) if ret.is_none() => {
+                    self.pos += 1;
+                    ret = Some(self.parse_type()?);
+                }
+                _ => break,
+            }
+        }
+
+        Ok(Suffix::Function {
+            params,
+            variadic,
+            trailing,
+            ret,
+        })
+    }
+}
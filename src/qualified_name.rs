@@ -0,0 +1,162 @@
+use crate::{Control, Id, Node, Visit};
+use std::collections::HashMap;
+
+/// How a declaration is named, as needed by [`QualifiedNames`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum DeclName<'a> {
+    Named(&'a str),
+    /// A namespace without a name, which is written as
+    /// `(anonymous namespace)`.
+    AnonymousNamespace,
+    /// A struct, class, union or enum without a name, identified by its
+    /// keyword such as `"struct"` and written as `(anonymous struct)`.
+    AnonymousTag(&'a str),
+}
+
+/// Fully qualified name of every declaration in a syntax tree, such as
+/// `ns::Outer::Inner::method`.
+///
+/// Clang records only the unqualified `"name"` of each declaration. The
+/// qualified name is reconstructed from the declarations enclosing it in
+/// the tree: every node for which the callback returns a name contributes
+/// one component to the names of the declarations nested within it, and
+/// every other node is passed through. In particular the callback should
+/// return `None` for `ClassTemplateDecl` and `FunctionTemplateDecl`, whose
+/// name is repeated by the templated declaration inside them, and may
+/// return `None` for namespaces with `"isInline": true` to get
+/// `std::vector` in place of `std::__1::vector`.
+///
+/// ```
+/// use clang_ast::{DeclName, Node, QualifiedNames};
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// pub enum Clang {
+///     NamespaceDecl {
+///         name: Option<String>,
+///     },
+///     CXXRecordDecl {
+///         name: Option<String>,
+///         #[serde(rename = "tagUsed")]
+///         tag_used: String,
+///     },
+///     CXXMethodDecl {
+///         name: String,
+///     },
+///     Other,
+/// }
+///
+/// fn decl_name(kind: &Clang) -> Option<DeclName<'_>> {
+///     match kind {
+///         Clang::NamespaceDecl { name: Some(name) }
+///         | Clang::CXXRecordDecl { name: Some(name), .. }
+///         | Clang::CXXMethodDecl { name } => Some(DeclName::Named(name)),
+///         Clang::NamespaceDecl { name: None } => Some(DeclName::AnonymousNamespace),
+///         Clang::CXXRecordDecl { name: None, tag_used } => Some(DeclName::AnonymousTag(tag_used)),
+///         Clang::Other => None,
+///     }
+/// }
+///
+/// let json = r#"
+///     {
+///       "id": "0x1",
+///       "kind": "NamespaceDecl",
+///       "name": "ns",
+///       "inner": [
+///         {
+///           "id": "0x2",
+///           "kind": "CXXRecordDecl",
+///           "name": "Outer",
+///           "tagUsed": "class",
+///           "inner": [
+///             { "id": "0x3", "kind": "CXXMethodDecl", "name": "method" }
+///           ]
+///         }
+///       ]
+///     }
+/// "#;
+/// let node: Node<Clang> = serde_json::from_str(json).unwrap();
+/// let names = QualifiedNames::new(&node, decl_name);
+/// let method = &node.inner[0].inner[0];
+/// assert_eq!(names.get(method.id), Some("ns::Outer::method"));
+/// ```
+///
+/// The names follow the lexical nesting of the tree, so an out-of-line
+/// definition such as `void Outer::method() {}` is named as if it were
+/// declared where the definition appears.
+#[derive(Clone, Debug, Default)]
+pub struct QualifiedNames {
+    names: HashMap<Id, String>,
+}
+
+impl QualifiedNames {
+    /// Compute the qualified name of every node for which the callback
+    /// returns a name.
+    pub fn new<T, F>(root: &Node<T>, decl_name: F) -> Self
+    where
+        F: Fn(&T) -> Option<DeclName<'_>>,
+    {
+        let mut builder = Builder {
+            decl_name,
+            names: HashMap::new(),
+            prefix: String::new(),
+            lengths: Vec::new(),
+        };
+        root.visit(&mut builder);
+        QualifiedNames {
+            names: builder.names,
+        }
+    }
+
+    pub fn get(&self, id: Id) -> Option<&str> {
+        self.names.get(&id).map(String::as_str)
+    }
+
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+}
+
+struct Builder<F> {
+    decl_name: F,
+    names: HashMap<Id, String>,
+    // Qualified name of the innermost enclosing declaration.
+    prefix: String,
+    // Length of `prefix` before each node on the current path was entered.
+    lengths: Vec<usize>,
+}
+
+impl<'ast, T, F> Visit<'ast, T> for Builder<F>
+where
+    F: Fn(&T) -> Option<DeclName<'_>>,
+{
+    fn enter_node(&mut self, node: &'ast Node<T>) -> Control {
+        self.lengths.push(self.prefix.len());
+        if let Some(decl_name) = (self.decl_name)(&node.kind) {
+            if !self.prefix.is_empty() {
+                self.prefix.push_str("::");
+            }
+            match decl_name {
+                DeclName::Named(name) => self.prefix.push_str(name),
+                DeclName::AnonymousNamespace => self.prefix.push_str("(anonymous namespace)"),
+                DeclName::AnonymousTag(keyword) => {
+                    self.prefix.push_str("(anonymous ");
+                    self.prefix.push_str(keyword);
+                    self.prefix.push(')');
+                }
+            }
+            self.names.insert(node.id, self.prefix.clone());
+        }
+        Control::Continue
+    }
+
+    fn exit_node(&mut self, _node: &'ast Node<T>) {
+        if let Some(len) = self.lengths.pop() {
+            self.prefix.truncate(len);
+        }
+    }
+}
@@ -0,0 +1,153 @@
+use crate::intern::Shared;
+use crate::{Node, SourceRange};
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::fmt::{self, Debug};
+use std::ops::Range;
+
+/// Interval tree over the source ranges of every node in a syntax tree, for
+/// finding the nodes at a position or within a span of a file without
+/// scanning the whole tree.
+///
+/// Positions are byte offsets into a file, and ranges extend to the end of
+/// their last token as in [`SourceRange::byte_range`]. Nodes whose range has
+/// no position, or whose ends are in different files, are not indexed.
+///
+/// ```
+/// use clang_ast::{Node, RangeIndex, SourceRange};
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// pub struct Clang {
+///     pub kind: clang_ast::Kind,
+///     pub range: Option<SourceRange>,
+/// }
+///
+/// # fn example(node: &Node<Clang>) {
+/// let index = RangeIndex::new(node, |kind| kind.range.as_ref());
+/// for node in index.nodes_at("main.c", 120) {
+///     println!("{} {}", node.kind.kind, node.id);
+/// }
+/// # }
+/// ```
+pub struct RangeIndex<'a, T> {
+    files: HashMap<Shared<str>, Vec<Entry<'a, T>>>,
+    len: usize,
+}
+
+struct Entry<'a, T> {
+    start: usize,
+    end: usize,
+    depth: usize,
+    // Greatest `end` in the subtree of the implicit binary search tree
+    // rooted at this entry.
+    max_end: usize,
+    node: &'a Node<T>,
+}
+
+impl<'a, T> RangeIndex<'a, T> {
+    /// Index every node of the tree rooted at `root`, using the callback to
+    /// retrieve each node's range from its kind.
+    pub fn new<F>(root: &'a Node<T>, range: F) -> Self
+    where
+        F: Fn(&T) -> Option<&SourceRange>,
+    {
+        let mut files = HashMap::new();
+        let mut len = 0;
+        let mut stack = vec![(root, 0)];
+        while let Some((node, depth)) = stack.pop() {
+            if let Some(range) = range(&node.kind) {
+                if let (Some(bytes), Some(begin)) = (range.byte_range(), range.begin.expansion()) {
+                    let entries: &mut Vec<_> = files.entry(Shared::clone(&begin.file)).or_default();
+                    entries.push(Entry {
+                        start: bytes.start,
+                        end: bytes.end,
+                        depth,
+                        max_end: bytes.end,
+                        node,
+                    });
+                    len += 1;
+                }
+            }
+            stack.extend(node.inner.iter().map(|child| (child, depth + 1)));
+        }
+        for entries in files.values_mut() {
+            // Enclosing nodes sort before the nodes they contain.
+            entries.sort_by_key(|entry| (entry.start, Reverse(entry.end), entry.depth));
+            augment(entries);
+        }
+        RangeIndex { files, len }
+    }
+
+    /// Nodes whose range covers the byte at `offset` in `file`, from
+    /// outermost to innermost.
+    pub fn nodes_at(&self, file: &str, offset: usize) -> Vec<&'a Node<T>> {
+        self.overlapping(file, offset..offset + 1)
+    }
+
+    /// The innermost node whose range covers the byte at `offset` in `file`.
+    pub fn node_at(&self, file: &str, offset: usize) -> Option<&'a Node<T>> {
+        self.nodes_at(file, offset).pop()
+    }
+
+    /// Nodes whose range has at least one byte in common with `range` in
+    /// `file`, ordered by where they begin.
+    pub fn overlapping(&self, file: &str, range: Range<usize>) -> Vec<&'a Node<T>> {
+        let mut nodes = Vec::new();
+        if let Some(entries) = self.files.get(file) {
+            query(entries, &range, &mut nodes);
+        }
+        nodes
+    }
+
+    /// Number of nodes in the index.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+// Compute `max_end` for the implicit tree in which the root of every slice is
+// its middle element.
+fn augment<T>(entries: &mut [Entry<T>]) -> usize {
+    if entries.is_empty() {
+        return 0;
+    }
+    let mid = entries.len() / 2;
+    let (left, rest) = entries.split_at_mut(mid);
+    let (entry, right) = rest.split_first_mut().unwrap();
+    entry.max_end = entry.end.max(augment(left)).max(augment(right));
+    entry.max_end
+}
+
+fn query<'a, T>(entries: &[Entry<'a, T>], range: &Range<usize>, nodes: &mut Vec<&'a Node<T>>) {
+    if entries.is_empty() {
+        return;
+    }
+    let mid = entries.len() / 2;
+    let entry = &entries[mid];
+    if entry.max_end <= range.start {
+        return;
+    }
+    query(&entries[..mid], range, nodes);
+    if entry.start >= range.end {
+        return;
+    }
+    if range.start < entry.end {
+        nodes.push(entry.node);
+    }
+    query(&entries[mid + 1..], range, nodes);
+}
+
+impl<'a, T> Debug for RangeIndex<'a, T> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter
+            .debug_struct("RangeIndex")
+            .field("files", &self.files.len())
+            .field("len", &self.len)
+            .finish()
+    }
+}
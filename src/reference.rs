@@ -0,0 +1,132 @@
+use crate::{Id, Node, NodeIndex};
+use serde::de::{Deserialize, Deserializer};
+use serde::ser::{Serialize, Serializer};
+use std::cmp::Ordering;
+use std::fmt::{self, Debug};
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
+
+/// Backreference to another node of the same syntax tree.
+///
+/// Fields like `"referencedDecl"`, `"ownedTagDecl"` and `"previousDecl"` hold
+/// the id of some other node that appears elsewhere in the dump. A `Ref`
+/// deserializes from that id and is resolved afterward against a
+/// [`NodeIndex`] of the tree.
+///
+/// ```
+/// use clang_ast::{NodeIndex, Ref};
+/// use serde::Deserialize;
+///
+/// pub type Node = clang_ast::Node<Clang>;
+///
+/// #[derive(Deserialize)]
+/// pub enum Clang {
+///     DeclRefExpr {
+///         #[serde(rename = "referencedDecl")]
+///         referenced_decl: Decl,
+///     },
+///     VarDecl {
+///         name: String,
+///     },
+///     Other,
+/// }
+///
+/// #[derive(Deserialize)]
+/// pub struct Decl {
+///     pub id: Ref<Clang>,
+/// }
+///
+/// fn print_references(root: &Node) {
+///     let index = NodeIndex::new(root);
+///     for node in root.descendants() {
+///         if let Clang::DeclRefExpr { referenced_decl } = &node.kind {
+///             if let Some(Node { kind: Clang::VarDecl { name }, .. }) =
+///                 referenced_decl.id.resolve(&index)
+///             {
+///                 println!("{} refers to {}", node.id, name);
+///             }
+///         }
+///     }
+/// }
+/// ```
+pub struct Ref<T> {
+    pub id: Id,
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<T> Ref<T> {
+    pub fn new(id: Id) -> Self {
+        Ref {
+            id,
+            marker: PhantomData,
+        }
+    }
+
+    pub fn resolve<'a>(&self, index: &NodeIndex<'a, T>) -> Option<&'a Node<T>> {
+        index.get(self.id)
+    }
+}
+
+impl<T> From<Id> for Ref<T> {
+    fn from(id: Id) -> Self {
+        Ref::new(id)
+    }
+}
+
+impl<T> Copy for Ref<T> {}
+
+impl<T> Clone for Ref<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Eq for Ref<T> {}
+
+impl<T> PartialEq for Ref<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl<T> Ord for Ref<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.id.cmp(&other.id)
+    }
+}
+
+impl<T> PartialOrd for Ref<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T> Hash for Ref<T> {
+    fn hash<H: Hasher>(&self, hasher: &mut H) {
+        self.id.hash(hasher);
+    }
+}
+
+impl<T> Debug for Ref<T> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "Ref({})", self.id)
+    }
+}
+
+impl<'de, T> Deserialize<'de> for Ref<T> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Id::deserialize(deserializer).map(Ref::new)
+    }
+}
+
+impl<T> Serialize for Ref<T> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.id.serialize(serializer)
+    }
+}
@@ -0,0 +1,415 @@
+use crate::codegen;
+use serde::de::{
+    Deserialize, DeserializeSeed, Deserializer, Error, IgnoredAny, MapAccess, SeqAccess, Visitor,
+};
+use std::borrow::Cow;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt::{self, Display};
+
+/// Summary of which kinds of node occur in an AST dump, and which fields
+/// each of them has.
+///
+/// Deserializing a `Schema` from a dump records, for every distinct
+/// `"kind"`, how many nodes of that kind there were, and for each field that
+/// appears on them, how many of those nodes had it and what types of JSON
+/// value it held. This is a starting point for deciding which variants and
+/// fields to put in a `Clang` enum.
+///
+/// ```
+/// use clang_ast::Schema;
+///
+/// let json = r#"
+///     {
+///       "id": "0x1",
+///       "kind": "TranslationUnitDecl",
+///       "inner": [
+///         { "id": "0x2", "kind": "VarDecl", "name": "x", "isUsed": true },
+///         { "id": "0x3", "kind": "VarDecl", "name": "y" }
+///       ]
+///     }
+/// "#;
+/// let schema: Schema = serde_json::from_str(json).unwrap();
+/// print!("{}", schema);
+/// ```
+///
+/// ```text
+/// TranslationUnitDecl (1)
+///     inner: array
+/// VarDecl (2)
+///     isUsed: bool (1 of 2)
+///     name: string
+/// ```
+///
+/// The `"id"` and `"kind"` fields, which every node has, are left out. Only
+/// nodes of the tree itself are counted, not the abbreviated nodes which
+/// appear as the value of fields such as `"referencedDecl"`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Schema {
+    pub kinds: BTreeMap<String, KindSchema>,
+}
+
+/// Fields observed on the nodes of one kind.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct KindSchema {
+    /// Number of nodes of this kind.
+    pub count: u64,
+    pub fields: BTreeMap<String, FieldSchema>,
+}
+
+/// Values observed for one field of one kind.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FieldSchema {
+    /// Number of nodes which had this field.
+    pub count: u64,
+    pub types: BTreeSet<ValueType>,
+}
+
+/// Type of a JSON value.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ValueType {
+    Null,
+    Bool,
+    Integer,
+    Float,
+    String,
+    Array,
+    Object,
+}
+
+impl Schema {
+    pub fn new() -> Self {
+        Schema::default()
+    }
+
+    /// Combine the observations from another dump into this one.
+    pub fn merge(&mut self, other: Schema) {
+        for (kind, other) in other.kinds {
+            let kind = self.kinds.entry(kind).or_default();
+            kind.count += other.count;
+            for (field, other) in other.fields {
+                let field = kind.fields.entry(field).or_default();
+                field.count += other.count;
+                field.types.extend(other.types);
+            }
+        }
+    }
+
+    /// Generate Rust source code for a `Clang` enum with a variant for every
+    /// kind in the schema, each holding a struct with the kind's fields.
+    ///
+    /// The result is meant as a first draft to be edited by hand: fields
+    /// whose values are objects or arrays are given the type
+    /// `serde_json::Value`, and fields which were absent from some of the
+    /// nodes become `Option`.
+    ///
+    /// ```
+    /// use clang_ast::Schema;
+    ///
+    /// # fn main() -> serde_json::Result<()> {
+    /// let mut schema = Schema::new();
+    /// for json in &[
+    ///     r#"{"id": "0x1", "kind": "VarDecl", "name": "x", "isUsed": true}"#,
+    ///     r#"{"id": "0x2", "kind": "VarDecl", "name": "y"}"#,
+    /// ] {
+    ///     schema.merge(serde_json::from_str(json)?);
+    /// }
+    /// print!("{}", schema.to_rust());
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// ```text
+    /// use serde::Deserialize;
+    ///
+    /// pub type Node = clang_ast::Node<Clang>;
+    ///
+    /// #[derive(Deserialize, Debug)]
+    /// pub enum Clang {
+    ///     VarDecl(VarDecl),
+    ///     Other,
+    /// }
+    ///
+    /// #[derive(Deserialize, Debug)]
+    /// pub struct VarDecl {
+    ///     #[serde(rename = "isUsed")]
+    ///     #[serde(default)]
+    ///     pub is_used: bool,
+    ///     pub name: String,
+    /// }
+    /// ```
+    pub fn to_rust(&self) -> String {
+        codegen::to_rust(self)
+    }
+}
+
+impl FieldSchema {
+    /// Whether this field was present on every node of the kind.
+    pub fn is_required(&self, kind: &KindSchema) -> bool {
+        self.count == kind.count
+    }
+}
+
+impl Display for Schema {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        for (name, kind) in &self.kinds {
+            writeln!(formatter, "{} ({})", name, kind.count)?;
+            for (name, field) in &kind.fields {
+                write!(formatter, "    {}: ", name)?;
+                for (i, ty) in field.types.iter().enumerate() {
+                    if i > 0 {
+                        formatter.write_str(" | ")?;
+                    }
+                    Display::fmt(ty, formatter)?;
+                }
+                if !field.is_required(kind) {
+                    write!(formatter, " ({} of {})", field.count, kind.count)?;
+                }
+                writeln!(formatter)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Display for ValueType {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str(match self {
+            ValueType::Null => "null",
+            ValueType::Bool => "bool",
+            ValueType::Integer => "integer",
+            ValueType::Float => "float",
+            ValueType::String => "string",
+            ValueType::Array => "array",
+            ValueType::Object => "object",
+        })
+    }
+}
+
+impl<'de> Deserialize<'de> for Schema {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let mut schema = Schema::new();
+        deserializer.deserialize_map(NodeVisitor {
+            schema: &mut schema,
+        })?;
+        Ok(schema)
+    }
+}
+
+struct NodeVisitor<'a> {
+    schema: &'a mut Schema,
+}
+
+impl<'de, 'a> Visitor<'de> for NodeVisitor<'a> {
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("clang syntax tree node")
+    }
+
+    fn visit_map<M>(self, mut map: M) -> Result<Self::Value, M::Error>
+    where
+        M: MapAccess<'de>,
+    {
+        let mut kind = None;
+        let mut fields = Vec::new();
+        while let Some(key) = map.next_key_seed(KeySeed)? {
+            match &*key {
+                "id" => {
+                    let IgnoredAny = map.next_value()?;
+                }
+                "kind" => {
+                    if kind.is_some() {
+                        return Err(Error::duplicate_field("kind"));
+                    }
+                    kind = Some(map.next_value_seed(KeySeed)?);
+                }
+                "inner" => {
+                    map.next_value_seed(InnerSeed {
+                        schema: &mut *self.schema,
+                    })?;
+                    fields.push((key, ValueType::Array));
+                }
+                _ => {
+                    let ty = map.next_value_seed(TypeSeed)?;
+                    fields.push((key, ty));
+                }
+            }
+        }
+
+        let kind = kind.as_deref().unwrap_or("null");
+        let kind = match self.schema.kinds.get_mut(kind) {
+            Some(existing) => existing,
+            None => self.schema.kinds.entry(kind.to_owned()).or_default(),
+        };
+        kind.count += 1;
+        for (name, ty) in fields {
+            let field = match kind.fields.get_mut(&*name) {
+                Some(existing) => existing,
+                None => kind.fields.entry(name.into_owned()).or_default(),
+            };
+            field.count += 1;
+            field.types.insert(ty);
+        }
+        Ok(())
+    }
+}
+
+struct InnerSeed<'a> {
+    schema: &'a mut Schema,
+}
+
+impl<'de, 'a> DeserializeSeed<'de> for InnerSeed<'a> {
+    type Value = ();
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+impl<'de, 'a> Visitor<'de> for InnerSeed<'a> {
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("array of clang syntax tree nodes")
+    }
+
+    fn visit_seq<S>(self, mut seq: S) -> Result<Self::Value, S::Error>
+    where
+        S: SeqAccess<'de>,
+    {
+        while let Some(()) = seq.next_element_seed(NodeSeed {
+            schema: &mut *self.schema,
+        })? {}
+        Ok(())
+    }
+}
+
+struct NodeSeed<'a> {
+    schema: &'a mut Schema,
+}
+
+impl<'de, 'a> DeserializeSeed<'de> for NodeSeed<'a> {
+    type Value = ();
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_map(NodeVisitor {
+            schema: self.schema,
+        })
+    }
+}
+
+// Field name or kind, borrowed from the input when possible.
+struct KeySeed;
+
+impl<'de> DeserializeSeed<'de> for KeySeed {
+    type Value = Cow<'de, str>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_str(self)
+    }
+}
+
+impl<'de> Visitor<'de> for KeySeed {
+    type Value = Cow<'de, str>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("string")
+    }
+
+    fn visit_borrowed_str<E>(self, string: &'de str) -> Result<Self::Value, E> {
+        Ok(Cow::Borrowed(string))
+    }
+
+    fn visit_str<E>(self, string: &str) -> Result<Self::Value, E> {
+        Ok(Cow::Owned(string.to_owned()))
+    }
+
+    fn visit_string<E>(self, string: String) -> Result<Self::Value, E> {
+        Ok(Cow::Owned(string))
+    }
+}
+
+// Skips a value, reporting only its type.
+struct TypeSeed;
+
+impl<'de> DeserializeSeed<'de> for TypeSeed {
+    type Value = ValueType;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(self)
+    }
+}
+
+impl<'de> Visitor<'de> for TypeSeed {
+    type Value = ValueType;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("any value")
+    }
+
+    fn visit_bool<E>(self, _: bool) -> Result<Self::Value, E> {
+        Ok(ValueType::Bool)
+    }
+
+    fn visit_i64<E>(self, _: i64) -> Result<Self::Value, E> {
+        Ok(ValueType::Integer)
+    }
+
+    fn visit_u64<E>(self, _: u64) -> Result<Self::Value, E> {
+        Ok(ValueType::Integer)
+    }
+
+    fn visit_f64<E>(self, _: f64) -> Result<Self::Value, E> {
+        Ok(ValueType::Float)
+    }
+
+    fn visit_str<E>(self, _: &str) -> Result<Self::Value, E> {
+        Ok(ValueType::String)
+    }
+
+    fn visit_none<E>(self) -> Result<Self::Value, E> {
+        Ok(ValueType::Null)
+    }
+
+    fn visit_unit<E>(self) -> Result<Self::Value, E> {
+        Ok(ValueType::Null)
+    }
+
+    fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        self.deserialize(deserializer)
+    }
+
+    fn visit_seq<S>(self, mut seq: S) -> Result<Self::Value, S::Error>
+    where
+        S: SeqAccess<'de>,
+    {
+        while let Some(IgnoredAny) = seq.next_element()? {}
+        Ok(ValueType::Array)
+    }
+
+    fn visit_map<M>(self, mut map: M) -> Result<Self::Value, M::Error>
+    where
+        M: MapAccess<'de>,
+    {
+        while let Some((IgnoredAny, IgnoredAny)) = map.next_entry()? {}
+        Ok(ValueType::Object)
+    }
+}
@@ -0,0 +1,160 @@
+use crate::intern::{self, Interner};
+use crate::loc::LocState;
+use crate::{context, Node};
+use serde::de::{Deserialize, DeserializeSeed, Deserializer};
+use std::fmt::{self, Debug};
+use std::marker::PhantomData;
+use std::mem;
+
+/// Deserialization state carried explicitly from one parse to the next,
+/// instead of being kept by the thread doing the parsing.
+///
+/// Clang's JSON abbreviates each source location by leaving out whatever is
+/// the same as in the location before it, and the filepaths it names are
+/// interned as they are read. Ordinarily that state lives in thread-locals
+/// for the duration of one call to `Node::deserialize`. A `Session` owns it
+/// instead: parsing through [`Session::seed`] picks up where the previous
+/// parse in the same session left off, no matter which thread either of them
+/// ran on. This suits a dump that arrives in pieces, for example one
+/// top-level declaration at a time handed out to a pool of worker threads.
+///
+/// The seed moves the session's state onto the parsing thread for as long as
+/// the parse runs, since that is the only place the `Deserialize` impl of
+/// [`SourceLocation`](crate::SourceLocation) can find it, and moves it back
+/// into the session once the parse returns or panics. Nothing is left behind
+/// on the thread in between parses.
+///
+/// With the default `Arc<str>` filepaths, a `Session` is `Send` and can move
+/// between threads in between parses.
+///
+/// ```
+/// use clang_ast::{Node, Session, SourceLocation};
+/// use serde::de::DeserializeSeed;
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// pub struct Clang {
+///     pub loc: Option<SourceLocation>,
+/// }
+///
+/// # fn main() -> serde_json::Result<()> {
+/// let first = r#"{"id":"0x1","kind":"VarDecl","loc":{"offset":0,"file":"a.h","line":1,"col":5,"tokLen":1}}"#;
+/// let second = r#"{"id":"0x2","kind":"VarDecl","loc":{"offset":8,"col":6,"tokLen":1}}"#;
+///
+/// let mut session = Session::new();
+/// let mut deserializer = serde_json::Deserializer::from_str(first);
+/// let _: Node<Clang> = session.seed().deserialize(&mut deserializer)?;
+/// let mut deserializer = serde_json::Deserializer::from_str(second);
+/// let node: Node<Clang> = session.seed().deserialize(&mut deserializer)?;
+///
+/// // The second location's file comes from the first parse.
+/// let loc = node.kind.loc.unwrap().expansion_loc.unwrap();
+/// assert_eq!(&*loc.file, "a.h");
+/// assert_eq!(loc.line, 1);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Default)]
+pub struct Session {
+    interner: Interner,
+    locs: LocState,
+}
+
+/// [`DeserializeSeed`] for a [`Node`] parsed as part of a [`Session`].
+pub struct NodeSeed<'a, T> {
+    session: &'a mut Session,
+    marker: PhantomData<fn() -> T>,
+}
+
+impl Session {
+    pub fn new() -> Self {
+        Session::default()
+    }
+
+    /// Start a session whose filepaths are interned into an existing
+    /// interner, such as one shared with other sessions.
+    pub fn with_interner(interner: Interner) -> Self {
+        Session {
+            interner,
+            locs: LocState::default(),
+        }
+    }
+
+    pub fn interner(&self) -> &Interner {
+        &self.interner
+    }
+
+    /// Seed for deserializing the next `Node<T>` of this session.
+    pub fn seed<T>(&mut self) -> NodeSeed<'_, T> {
+        NodeSeed {
+            session: self,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<'de, 'a, T> DeserializeSeed<'de> for NodeSeed<'a, T>
+where
+    T: Deserialize<'de>,
+{
+    type Value = Node<T>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let _lend = Lend::new(self.session);
+        Node::deserialize(deserializer)
+    }
+}
+
+// Moves the session's state into the thread's deserialization context for
+// the duration of one parse, and back out into the session when dropped,
+// including when unwinding out of a panicking Deserialize impl.
+struct Lend<'a> {
+    session: &'a mut Session,
+    outer_locs: LocState,
+    outer_interner: Option<Interner>,
+    _intern: intern::Guard,
+}
+
+impl<'a> Lend<'a> {
+    fn new(session: &'a mut Session) -> Self {
+        let locs = mem::take(&mut session.locs);
+        let interner = session.interner.clone();
+        let (outer_locs, outer_interner) = context::with(|context| {
+            let outer_locs = mem::replace(&mut context.locs, locs);
+            let outer_interner = intern::replace_interner(context, Some(interner));
+            (outer_locs, outer_interner)
+        });
+        Lend {
+            session,
+            outer_locs,
+            outer_interner,
+            // Keeps Node::deserialize from resetting the location state on
+            // its way out, before it has been moved back into the session.
+            _intern: intern::activate(),
+        }
+    }
+}
+
+impl<'a> Drop for Lend<'a> {
+    fn drop(&mut self) {
+        let outer_locs = mem::take(&mut self.outer_locs);
+        let outer_interner = self.outer_interner.take();
+        self.session.locs = context::with(|context| {
+            intern::replace_interner(context, outer_interner);
+            mem::replace(&mut context.locs, outer_locs)
+        });
+    }
+}
+
+#[allow(clippy::missing_fields_in_debug)]
+impl Debug for Session {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter
+            .debug_struct("Session")
+            .field("interner", &self.interner)
+            .finish()
+    }
+}
@@ -0,0 +1,138 @@
+use crate::intern::Shared;
+use crate::SourceRange;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fmt::{self, Debug};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::ptr::NonNull;
+
+/// Cache of the source files referred to by an AST dump, for retrieving the
+/// text of a [`SourceRange`].
+///
+/// Files are read the first time text from them is requested and kept for
+/// the lifetime of the `SourceManager`.
+///
+/// ```no_run
+/// use clang_ast::{Node, SourceManager, SourceRange};
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// pub struct Clang {
+///     pub range: Option<SourceRange>,
+/// }
+///
+/// # fn example(node: &Node<Clang>) -> std::io::Result<()> {
+/// let mut sources = SourceManager::new();
+/// sources.root("/path/to/build");
+/// if let Some(range) = &node.kind.range {
+///     println!("{}", sources.text(range)?);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct SourceManager {
+    root: Option<PathBuf>,
+    files: RefCell<HashMap<Shared<str>, Box<str>>>,
+}
+
+impl SourceManager {
+    pub fn new() -> Self {
+        SourceManager {
+            root: None,
+            files: RefCell::new(HashMap::new()),
+        }
+    }
+
+    /// Directory against which relative file paths are resolved, usually
+    /// the directory clang was run in. By default they are relative to the
+    /// current directory.
+    pub fn root<P: AsRef<Path>>(&mut self, dir: P) -> &mut Self {
+        self.root = Some(dir.as_ref().to_owned());
+        self
+    }
+
+    /// Provide the contents of a file instead of reading it from disk, such
+    /// as an editor buffer with unsaved changes.
+    pub fn insert(&mut self, file: &str, contents: String) -> &mut Self {
+        self.files
+            .get_mut()
+            .insert(Shared::from(file), contents.into_boxed_str());
+        self
+    }
+
+    /// Entire contents of a file, reading it if this is the first request
+    /// for it.
+    pub fn file(&self, file: &str) -> io::Result<&str> {
+        if let Some(contents) = self.files.borrow().get(file) {
+            // SAFETY: the boxed contents are never dropped or replaced
+            // while `self` is borrowed, since only `insert`, which takes
+            // `&mut self`, can overwrite an entry.
+            let contents = NonNull::from(&**contents);
+            return Ok(unsafe { contents.as_ref() });
+        }
+
+        let contents = if let Ok(contents) = String::from_utf8(fs::read(self.path(file))?) {
+            contents.into_boxed_str()
+        } else {
+            let msg = format!("{} is not valid UTF-8", file);
+            return Err(io::Error::new(io::ErrorKind::InvalidData, msg));
+        };
+        let ptr = NonNull::from(&*contents);
+        self.files.borrow_mut().insert(Shared::from(file), contents);
+        // SAFETY: as above. Moving the box into the map does not move the
+        // heap allocation it points to.
+        Ok(unsafe { ptr.as_ref() })
+    }
+
+    // Where on disk a file is read from.
+    pub(crate) fn path(&self, file: &str) -> PathBuf {
+        match &self.root {
+            Some(root) => root.join(file),
+            None => PathBuf::from(file),
+        }
+    }
+
+    /// Source text spanned by a range, from the start of its first token to
+    /// the end of its last token.
+    ///
+    /// The range is located by the expansion location of each end, so for a
+    /// range produced by a macro this is the text of the macro invocation.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the file cannot be read, or if the range does not denote a
+    /// span of text within a single file.
+    pub fn text(&self, range: &SourceRange) -> io::Result<&str> {
+        let (file, bytes) =
+            if let (Some(begin), Some(bytes)) = (range.begin.expansion(), range.byte_range()) {
+                (&begin.file, bytes)
+            } else {
+                let msg = "source range has no position within a single file";
+                return Err(io::Error::new(io::ErrorKind::InvalidInput, msg));
+            };
+        if let Some(text) = self.file(file)?.get(bytes) {
+            Ok(text)
+        } else {
+            let msg = format!("source range is out of bounds of {}", file);
+            Err(io::Error::new(io::ErrorKind::InvalidInput, msg))
+        }
+    }
+}
+
+impl Default for SourceManager {
+    fn default() -> Self {
+        SourceManager::new()
+    }
+}
+
+impl Debug for SourceManager {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter
+            .debug_struct("SourceManager")
+            .field("root", &self.root)
+            .field("files", &self.files.borrow().len())
+            .finish()
+    }
+}
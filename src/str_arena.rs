@@ -0,0 +1,212 @@
+use crate::context;
+use serde::de::{Deserialize, Deserializer, Error, Visitor};
+use std::convert::TryFrom;
+use std::fmt::{self, Debug};
+use std::mem;
+use std::ops::Index;
+
+/// Storage for the strings of syntax trees, all of which are freed at once.
+///
+/// Deserializing string fields as [`ArenaStr`] inside of [`StrArena::scope`]
+/// appends each string to one buffer owned by the arena, rather than making
+/// a separate heap allocation for it. Together with [`Ast`], which keeps all
+/// of a tree's nodes in one allocation, a whole syntax tree then takes a
+/// handful of allocations to build, and is dropped without recursion or a
+/// per-node `free`.
+///
+/// Only strings go in the arena. The nodes themselves are not arena allocated:
+/// a [`Node`] tree still allocates a separate `Vec` for the children of each
+/// node and frees them one by one when dropped, which is why the arena is
+/// paired with `Ast` above rather than with `Node`.
+///
+/// An indexer processing many translation units in a batch can [`clear`]
+/// the arena in between them and reuse its buffer, so that after the first
+/// few trees deserialization no longer touches the allocator for strings at
+/// all.
+///
+/// [`Ast`]: crate::Ast
+/// [`Node`]: crate::Node
+/// [`clear`]: StrArena::clear
+///
+/// ```
+/// use clang_ast::{ArenaStr, Ast, StrArena};
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// pub enum Clang {
+///     FunctionDecl { name: ArenaStr },
+///     Other,
+/// }
+///
+/// # fn main() -> serde_json::Result<()> {
+/// # let dumps = [r#"{"id":"0x1","kind":"TranslationUnitDecl","inner":[{"id":"0x2","kind":"FunctionDecl","name":"f"}]}"#];
+/// let mut arena = StrArena::new();
+/// for json in &dumps {
+///     let ast: Ast<Clang> = arena.scope(|| serde_json::from_str(json))?;
+///     for (_id, node) in ast.iter() {
+///         if let Clang::FunctionDecl { name } = &node.kind {
+///             println!("{}", &arena[*name]);
+///         }
+///     }
+///     drop(ast);
+///     arena.clear();
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Default)]
+pub struct StrArena {
+    buf: String,
+}
+
+/// Handle to a string stored in a [`StrArena`].
+///
+/// Look up the string by indexing the arena it was deserialized into. A
+/// handle is only meaningful for that arena, and only until the arena is
+/// cleared; used with any other, it refers to some unrelated text or to
+/// nothing.
+///
+/// Deserializing an `ArenaStr` outside of [`StrArena::scope`] is an error.
+#[derive(Copy, Clone, Eq, PartialEq, Hash)]
+pub struct ArenaStr {
+    start: u32,
+    end: u32,
+}
+
+impl StrArena {
+    pub fn new() -> Self {
+        StrArena::default()
+    }
+
+    /// Start with room for `bytes` bytes of strings before the buffer needs
+    /// to grow.
+    pub fn with_capacity(bytes: usize) -> Self {
+        StrArena {
+            buf: String::with_capacity(bytes),
+        }
+    }
+
+    /// Run `f` with this arena receiving the strings of every `ArenaStr`
+    /// deserialized by `f` on the current thread.
+    pub fn scope<R>(&mut self, f: impl FnOnce() -> R) -> R {
+        struct Restore<'a> {
+            arena: &'a mut StrArena,
+            prev: Option<String>,
+        }
+
+        impl<'a> Drop for Restore<'a> {
+            fn drop(&mut self) {
+                let prev = self.prev.take();
+                let buf = context::with(|context| mem::replace(&mut context.str_arena, prev));
+                self.arena.buf = buf.unwrap_or_default();
+            }
+        }
+
+        let buf = mem::take(&mut self.buf);
+        let prev = context::with(|context| context.str_arena.replace(buf));
+        let _restore = Restore { arena: self, prev };
+        f()
+    }
+
+    /// The string of a handle, or `None` if the handle does not refer to a
+    /// string in this arena.
+    pub fn get(&self, string: ArenaStr) -> Option<&str> {
+        self.buf.get(string.start as usize..string.end as usize)
+    }
+
+    /// Number of bytes of strings stored.
+    pub fn len(&self) -> usize {
+        self.buf.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.buf.is_empty()
+    }
+
+    /// Forget every string, keeping the buffer for reuse. Existing handles no
+    /// longer refer to their string afterward.
+    pub fn clear(&mut self) {
+        self.buf.clear();
+    }
+}
+
+impl Index<ArenaStr> for StrArena {
+    type Output = str;
+
+    fn index(&self, string: ArenaStr) -> &Self::Output {
+        match self.get(string) {
+            Some(string) => string,
+            None => panic!("ArenaStr does not belong to this StrArena"),
+        }
+    }
+}
+
+impl ArenaStr {
+    /// Length of the string in bytes.
+    pub fn len(self) -> usize {
+        (self.end - self.start) as usize
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.start == self.end
+    }
+}
+
+impl<'de> Deserialize<'de> for ArenaStr {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct ArenaStrVisitor;
+
+        impl<'de> Visitor<'de> for ArenaStrVisitor {
+            type Value = ArenaStr;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a string")
+            }
+
+            fn visit_str<E>(self, string: &str) -> Result<Self::Value, E>
+            where
+                E: Error,
+            {
+                context::with(|context| {
+                    let buf = match context.str_arena.as_mut() {
+                        Some(buf) => buf,
+                        None => {
+                            return Err(E::custom(
+                                "ArenaStr deserialized outside of StrArena::scope",
+                            ));
+                        }
+                    };
+                    let start = u32::try_from(buf.len());
+                    let end = u32::try_from(buf.len() + string.len());
+                    match (start, end) {
+                        (Ok(start), Ok(end)) => {
+                            buf.push_str(string);
+                            Ok(ArenaStr { start, end })
+                        }
+                        _ => Err(E::custom("StrArena cannot hold more than 4 GiB")),
+                    }
+                })
+            }
+        }
+
+        deserializer.deserialize_str(ArenaStrVisitor)
+    }
+}
+
+impl Debug for StrArena {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter
+            .debug_struct("StrArena")
+            .field("len", &self.buf.len())
+            .finish()
+    }
+}
+
+impl Debug for ArenaStr {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "ArenaStr({}..{})", self.start, self.end)
+    }
+}
@@ -0,0 +1,210 @@
+//! Streaming deserialization that never builds the tree.
+//!
+//! For tools that only extract and aggregate information, such as counting
+//! symbols or collecting enum values, holding the whole syntax tree in memory
+//! is unnecessary. [`parse`] instead hands each node to a callback as soon as
+//! it has been deserialized, and drops it afterward.
+//!
+//! ```no_run
+//! use serde::Deserialize;
+//! use std::fs::File;
+//! use std::io::BufReader;
+//!
+//! #[derive(Deserialize)]
+//! pub enum Clang {
+//!     EnumConstantDecl { name: String },
+//!     Other,
+//! }
+//!
+//! fn main() -> serde_json::Result<()> {
+//!     let reader = BufReader::new(File::open("ast.json").unwrap());
+//!     let mut deserializer = serde_json::Deserializer::from_reader(reader);
+//!     let mut count = 0;
+//!     clang_ast::stream::parse(&mut deserializer, |event| {
+//!         if let Clang::EnumConstantDecl { .. } = event.kind {
+//!             count += 1;
+//!         }
+//!     })?;
+//!     println!("{} enumerators", count);
+//!     Ok(())
+//! }
+//! ```
+
+use crate::deserializer::{self, Inner};
+use crate::{ancestry, intern, Id};
+use serde::de::{Deserialize, DeserializeSeed, Deserializer, MapAccess, SeqAccess, Visitor};
+use std::fmt;
+#[cfg(feature = "reader")]
+use std::io::{BufReader, Read};
+use std::marker::PhantomData;
+
+/// One deserialized node, without its children.
+///
+/// Events are delivered in post-order: the event for a node comes after the
+/// events for all of its descendants, and the root comes last. The `parent`
+/// id can be used to associate nodes with their enclosing node.
+#[derive(Debug)]
+pub struct Event<T> {
+    pub id: Id,
+    pub kind: T,
+    /// Id of the enclosing node, or `None` for the root.
+    pub parent: Option<Id>,
+    /// Number of nodes enclosing this one. The root has depth 0.
+    pub depth: usize,
+}
+
+/// Deserialize a syntax tree, invoking `f` once per node instead of
+/// accumulating the nodes into a tree.
+pub fn parse<'de, D, T, F>(deserializer: D, mut f: F) -> Result<(), D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+    F: FnMut(Event<T>),
+{
+    let _intern = intern::activate();
+    let _ancestry = ancestry::activate();
+    deserializer.deserialize_map(StreamVisitor {
+        f: &mut f,
+        marker: PhantomData,
+    })
+}
+
+/// Deserialize a syntax tree from JSON as it arrives from `reader`, such as
+/// the standard output of a Clang process or a pipe, invoking `f` once per
+/// node.
+///
+/// Reads are buffered internally, and events begin to be delivered as soon
+/// as the first node is complete, long before the end of the input. Nothing
+/// other than whitespace may follow the tree.
+///
+/// ```no_run
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// pub enum Clang {
+///     FunctionDecl { name: String },
+///     Other,
+/// }
+///
+/// // clang++ -Xclang -ast-dump=json -fsyntax-only main.cc | my-tool
+/// fn main() -> serde_json::Result<()> {
+///     clang_ast::stream::from_reader(std::io::stdin(), |event| {
+///         if let Clang::FunctionDecl { name } = event.kind {
+///             println!("{}", name);
+///         }
+///     })
+/// }
+/// ```
+#[cfg(feature = "reader")]
+pub fn from_reader<R, T, F>(reader: R, f: F) -> serde_json::Result<()>
+where
+    R: Read,
+    T: for<'de> Deserialize<'de>,
+    F: FnMut(Event<T>),
+{
+    const CAPACITY: usize = 64 * 1024;
+    let reader = BufReader::with_capacity(CAPACITY, reader);
+    let mut deserializer = serde_json::Deserializer::from_reader(reader);
+    parse(&mut deserializer, f)?;
+    deserializer.end()
+}
+
+struct StreamVisitor<'a, T, F> {
+    f: &'a mut F,
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<'de, 'a, T, F> Visitor<'de> for StreamVisitor<'a, T, F>
+where
+    T: Deserialize<'de>,
+    F: FnMut(Event<T>),
+{
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("clang syntax tree node")
+    }
+
+    fn visit_map<M>(self, map: M) -> Result<Self::Value, M::Error>
+    where
+        M: MapAccess<'de>,
+    {
+        let mut inner = StreamInner {
+            f: &mut *self.f,
+            marker: PhantomData,
+        };
+        let (id, kind) = deserializer::visit_node(map, &mut inner)?;
+        let (parent, depth) = ancestry::current();
+        (self.f)(Event {
+            id,
+            kind,
+            parent,
+            depth,
+        });
+        Ok(())
+    }
+}
+
+impl<'de, 'a, T, F> DeserializeSeed<'de> for StreamVisitor<'a, T, F>
+where
+    T: Deserialize<'de>,
+    F: FnMut(Event<T>),
+{
+    type Value = ();
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_map(self)
+    }
+}
+
+struct StreamInner<'a, T, F> {
+    f: &'a mut F,
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<'de, 'a, T, F> Inner<'de> for StreamInner<'a, T, F>
+where
+    T: Deserialize<'de>,
+    F: FnMut(Event<T>),
+{
+    fn deserialize_inner<D>(&mut self, deserializer: D) -> Result<(), D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_seq(StreamInnerVisitor {
+            f: &mut *self.f,
+            marker: PhantomData,
+        })
+    }
+}
+
+struct StreamInnerVisitor<'a, T, F> {
+    f: &'a mut F,
+    marker: PhantomData<fn() -> T>,
+}
+
+impl<'de, 'a, T, F> Visitor<'de> for StreamInnerVisitor<'a, T, F>
+where
+    T: Deserialize<'de>,
+    F: FnMut(Event<T>),
+{
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("array of clang syntax tree nodes")
+    }
+
+    fn visit_seq<S>(self, mut seq: S) -> Result<Self::Value, S::Error>
+    where
+        S: SeqAccess<'de>,
+    {
+        while let Some(()) = seq.next_element_seed(StreamVisitor {
+            f: &mut *self.f,
+            marker: PhantomData,
+        })? {}
+        Ok(())
+    }
+}
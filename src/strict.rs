@@ -0,0 +1,97 @@
+use crate::{ancestry, context};
+use serde::de::{Deserialize, Deserializer};
+use serde::ser::{Serialize, Serializer};
+use std::mem;
+use std::ops::{Deref, DerefMut};
+
+/// Kind of a node whose deserialization fails on kinds which would otherwise
+/// fall into the `Other` or `Unknown` variant.
+///
+/// When auditing whether a tool handles every kind of node that occurs in a
+/// codebase, the catch-all variant silently absorbs whatever was missed.
+/// Deserializing `Node<Strict<Clang>>` in place of `Node<Clang>` instead
+/// reports the first such node, naming its kind and id.
+///
+/// ```
+/// use clang_ast::{Node, Strict};
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// pub enum Clang {
+///     TranslationUnitDecl,
+///     FunctionDecl { name: String },
+///     Other,
+/// }
+///
+/// let json = r#"
+///     {
+///       "id": "0x1",
+///       "kind": "TranslationUnitDecl",
+///       "inner": [
+///         { "id": "0x2", "kind": "VarDecl", "name": "x" }
+///       ]
+///     }
+/// "#;
+/// let error = serde_json::from_str::<Node<Strict<Clang>>>(json).err().unwrap();
+/// assert!(error.to_string().starts_with("unknown node kind `VarDecl` with id 0x2"));
+/// ```
+///
+/// This applies to every node nested within a strict one, including nodes
+/// deserialized as part of some field's value rather than as children.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Strict<T>(pub T);
+
+pub(crate) fn is_active() -> bool {
+    context::with(|context| context.strict)
+}
+
+impl<'de, T> Deserialize<'de> for Strict<T>
+where
+    T: Deserialize<'de>,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct Restore(bool);
+
+        impl Drop for Restore {
+            fn drop(&mut self) {
+                let prev = self.0;
+                context::with(|context| context.strict = prev);
+            }
+        }
+
+        let _restore = Restore(context::with(|context| {
+            mem::replace(&mut context.strict, true)
+        }));
+        let _ancestry = ancestry::activate_in_node();
+        T::deserialize(deserializer).map(Strict)
+    }
+}
+
+impl<T> Serialize for Strict<T>
+where
+    T: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        self.0.serialize(serializer)
+    }
+}
+
+impl<T> Deref for Strict<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T> DerefMut for Strict<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
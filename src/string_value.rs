@@ -0,0 +1,336 @@
+use serde::de::{Deserialize, Deserializer, Error, Unexpected, Visitor};
+use serde::ser::{Serialize, Serializer};
+use std::char;
+use std::convert::TryFrom;
+use std::fmt::{self, Display};
+use std::str::Chars;
+
+/// The `"value"` of a `StringLiteral`, decoded from the quoted and escaped
+/// form in which Clang writes it.
+///
+/// ```
+/// use clang_ast::{StringEncoding, StringValue};
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// pub enum Clang {
+///     StringLiteral { value: StringValue },
+///     Other,
+/// }
+/// #
+/// # let json = r#"{"id":"0x1","kind":"StringLiteral","value":"u8\"caf\\303\\251\\n\""}"#;
+/// # let node: clang_ast::Node<Clang> = serde_json::from_str(json).unwrap();
+/// # match node.kind {
+/// #     Clang::StringLiteral { value } => {
+/// #         assert_eq!(value.encoding(), StringEncoding::Utf8);
+/// #         assert_eq!(value.decode().unwrap(), "café\n");
+/// #     }
+/// #     Clang::Other => panic!(),
+/// # }
+/// ```
+///
+/// The content is held as code units of the literal's encoding, without the
+/// implicit null terminator. Ordinary strings are taken to be in UTF-8, and
+/// wide strings to have 32-bit code units, which is what Clang uses on
+/// every target other than Windows.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct StringValue {
+    encoding: StringEncoding,
+    units: Vec<u32>,
+    text: Box<str>,
+}
+
+/// The prefix of a string literal, which determines its encoding.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum StringEncoding {
+    /// `"..."`
+    Ordinary,
+    /// `L"..."`
+    Wide,
+    /// `u8"..."`
+    Utf8,
+    /// `u"..."`
+    Utf16,
+    /// `U"..."`
+    Utf32,
+}
+
+impl StringEncoding {
+    /// The width of one code unit, in bits.
+    pub fn code_unit_bits(self) -> u32 {
+        match self {
+            StringEncoding::Ordinary | StringEncoding::Utf8 => 8,
+            StringEncoding::Utf16 => 16,
+            StringEncoding::Wide | StringEncoding::Utf32 => 32,
+        }
+    }
+
+    fn prefix(self) -> &'static str {
+        match self {
+            StringEncoding::Ordinary => "",
+            StringEncoding::Wide => "L",
+            StringEncoding::Utf8 => "u8",
+            StringEncoding::Utf16 => "u",
+            StringEncoding::Utf32 => "U",
+        }
+    }
+}
+
+impl StringValue {
+    /// Decodes a string literal as Clang or C source spells it, including
+    /// the quotes and any prefix. Adjacent literals like `"\x1" "2"` are
+    /// concatenated, as Clang itself writes them to end a hex escape.
+    pub fn parse(text: &str) -> Option<Self> {
+        let encoding = [
+            StringEncoding::Utf8,
+            StringEncoding::Wide,
+            StringEncoding::Utf16,
+            StringEncoding::Utf32,
+        ]
+        .iter()
+        .copied()
+        .find(|encoding| {
+            text.starts_with(encoding.prefix()) && text[encoding.prefix().len()..].starts_with('"')
+        })
+        .unwrap_or(StringEncoding::Ordinary);
+
+        let mut parser = Parser {
+            encoding,
+            units: Vec::new(),
+        };
+        let mut rest = text[encoding.prefix().len()..].strip_prefix('"')?;
+        loop {
+            rest = parser.segment(rest)?.trim_start();
+            if rest.is_empty() {
+                break;
+            }
+            rest = rest.strip_prefix(encoding.prefix()).unwrap_or(rest);
+            rest = rest.strip_prefix('"')?;
+        }
+
+        Some(StringValue {
+            encoding,
+            units: parser.units,
+            text: Box::from(text),
+        })
+    }
+
+    pub fn encoding(&self) -> StringEncoding {
+        self.encoding
+    }
+
+    /// The content as code units, each of which is at most
+    /// [`code_unit_bits`][StringEncoding::code_unit_bits] wide.
+    pub fn code_units(&self) -> &[u32] {
+        &self.units
+    }
+
+    /// The content of an ordinary or UTF-8 string as bytes.
+    pub fn to_bytes(&self) -> Option<Vec<u8>> {
+        if self.encoding.code_unit_bits() != 8 {
+            return None;
+        }
+        #[allow(clippy::cast_possible_truncation)] // checked in Parser::escape
+        Some(self.units.iter().map(|&unit| unit as u8).collect())
+    }
+
+    /// The content as Unicode text, or `None` if it is not valid in the
+    /// literal's encoding, as with a string of arbitrary bytes.
+    pub fn decode(&self) -> Option<String> {
+        match self.encoding {
+            StringEncoding::Ordinary | StringEncoding::Utf8 => {
+                String::from_utf8(self.to_bytes()?).ok()
+            }
+            StringEncoding::Utf16 => decode_utf16(&self.units),
+            // A wide string containing surrogates comes from a target with
+            // 16-bit wchar_t.
+            StringEncoding::Wide if self.units.iter().any(|&unit| is_surrogate(unit)) => {
+                decode_utf16(&self.units)
+            }
+            StringEncoding::Wide | StringEncoding::Utf32 => self
+                .units
+                .iter()
+                .map(|&unit| char::from_u32(unit))
+                .collect(),
+        }
+    }
+
+    /// The literal as Clang wrote it.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+}
+
+fn is_surrogate(unit: u32) -> bool {
+    (0xD800..=0xDFFF).contains(&unit)
+}
+
+fn decode_utf16(units: &[u32]) -> Option<String> {
+    let wide = units
+        .iter()
+        .map(|&unit| u16::try_from(unit).ok())
+        .collect::<Option<Vec<u16>>>()?;
+    char::decode_utf16(wide).collect::<Result<_, _>>().ok()
+}
+
+struct Parser {
+    encoding: StringEncoding,
+    units: Vec<u32>,
+}
+
+impl Parser {
+    // Consumes the body of one quoted segment, returning what follows its
+    // closing quote.
+    fn segment<'a>(&mut self, body: &'a str) -> Option<&'a str> {
+        let mut chars = body.chars();
+        loop {
+            match chars.next()? {
+                '"' => return Some(chars.as_str()),
+                '\\' => self.escape(&mut chars)?,
+                '\n' => return None,
+                ch => self.push_char(ch),
+            }
+        }
+    }
+
+    fn escape(&mut self, chars: &mut Chars) -> Option<()> {
+        let unit = match chars.next()? {
+            ch if ['\'', '"', '?', '\\'].contains(&ch) => u32::from(ch),
+            'a' => 0x07,
+            'b' => 0x08,
+            'e' | 'E' => 0x1B,
+            'f' => 0x0C,
+            'n' => 0x0A,
+            'r' => 0x0D,
+            't' => 0x09,
+            'v' => 0x0B,
+            first @ '0'..='7' => {
+                let (rest, count) = digits(chars, 8, 2).unwrap_or((0, 0));
+                first.to_digit(8).unwrap() << (3 * count) | rest
+            }
+            'o' if peek(chars) == Some('{') => delimited(chars, 8)?,
+            'x' if peek(chars) == Some('{') => delimited(chars, 16)?,
+            'x' => digits(chars, 16, usize::MAX)?.0,
+            'u' if peek(chars) == Some('{') => return self.push_code(delimited(chars, 16)?),
+            'u' => return self.push_code(fixed(chars, 4)?),
+            'U' => return self.push_code(fixed(chars, 8)?),
+            _ => return None,
+        };
+        let bits = self.encoding.code_unit_bits();
+        if bits < 32 && unit >> bits != 0 {
+            return None;
+        }
+        self.units.push(unit);
+        Some(())
+    }
+
+    fn push_code(&mut self, code: u32) -> Option<()> {
+        self.push_char(char::from_u32(code)?);
+        Some(())
+    }
+
+    fn push_char(&mut self, ch: char) {
+        match self.encoding.code_unit_bits() {
+            8 => {
+                let mut buf = [0; 4];
+                let units = ch.encode_utf8(&mut buf).bytes().map(u32::from);
+                self.units.extend(units);
+            }
+            16 => {
+                let mut buf = [0; 2];
+                let units = ch
+                    .encode_utf16(&mut buf)
+                    .iter()
+                    .map(|&unit| u32::from(unit));
+                self.units.extend(units);
+            }
+            _ => self.units.push(u32::from(ch)),
+        }
+    }
+}
+
+fn peek(chars: &Chars) -> Option<char> {
+    chars.clone().next()
+}
+
+// Up to max digits of the given radix, at least one. Returns the value and
+// the number of digits.
+fn digits(chars: &mut Chars, radix: u32, max: usize) -> Option<(u32, usize)> {
+    let mut value: u32 = 0;
+    let mut count = 0;
+    while count < max {
+        let digit = match peek(chars).and_then(|ch| ch.to_digit(radix)) {
+            Some(digit) => digit,
+            None => break,
+        };
+        chars.next();
+        value = value.checked_mul(radix)?.checked_add(digit)?;
+        count += 1;
+    }
+    if count == 0 {
+        None
+    } else {
+        Some((value, count))
+    }
+}
+
+// Exactly len hex digits, as in \uXXXX.
+fn fixed(chars: &mut Chars, len: usize) -> Option<u32> {
+    match digits(chars, 16, len)? {
+        (value, count) if count == len => Some(value),
+        _ => None,
+    }
+}
+
+// Digits between braces, as in \x{...}, after the opening brace.
+fn delimited(chars: &mut Chars, radix: u32) -> Option<u32> {
+    chars.next();
+    let (value, _) = digits(chars, radix, usize::MAX)?;
+    if chars.next()? == '}' {
+        Some(value)
+    } else {
+        None
+    }
+}
+
+impl Display for StringValue {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str(&self.text)
+    }
+}
+
+impl<'de> Deserialize<'de> for StringValue {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct StringValueVisitor;
+
+        impl<'de> Visitor<'de> for StringValueVisitor {
+            type Value = StringValue;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("string literal")
+            }
+
+            fn visit_str<E>(self, text: &str) -> Result<Self::Value, E>
+            where
+                E: Error,
+            {
+                StringValue::parse(text)
+                    .ok_or_else(|| E::invalid_value(Unexpected::Str(text), &self))
+            }
+        }
+
+        deserializer.deserialize_str(StringValueVisitor)
+    }
+}
+
+impl Serialize for StringValue {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.text)
+    }
+}
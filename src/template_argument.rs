@@ -0,0 +1,307 @@
+use crate::{Id, Type};
+use serde::de::{Deserialize, Deserializer, Error, IgnoredAny, MapAccess, Visitor};
+use serde::ser::{Serialize, SerializeMap, Serializer};
+use std::fmt;
+
+/// The fields of a `TemplateArgument` node, as found in the `"inner"` of
+/// template specializations and of types like `TemplateSpecializationType`.
+///
+/// ```
+/// use clang_ast::TemplateArgument;
+/// use serde::Deserialize;
+///
+/// pub type Node = clang_ast::Node<Clang>;
+///
+/// #[derive(Deserialize)]
+/// pub enum Clang {
+///     TemplateArgument(TemplateArgument),
+///     Other,
+/// }
+///
+/// fn print_arguments(specialization: &Node) {
+///     for arg in &specialization.inner {
+///         match &arg.kind {
+///             Clang::TemplateArgument(TemplateArgument::Type(ty)) => {
+///                 println!("type {}", ty.qual_type);
+///             }
+///             Clang::TemplateArgument(TemplateArgument::Integral(value)) => {
+///                 println!("value {}", value);
+///             }
+///             Clang::TemplateArgument(TemplateArgument::Pack) => {
+///                 println!("pack of {} arguments", arg.inner.len());
+///             }
+///             _ => {}
+///         }
+///     }
+/// }
+/// #
+/// # let json = r#"{"id":"0x1","kind":"TemplateArgument","type":{"qualType":"int"}}"#;
+/// # let node: Node = serde_json::from_str(json).unwrap();
+/// # print_arguments(&node);
+/// ```
+///
+/// Beyond the fields read here, the node's `"inner"` holds the expression
+/// of an `Expression` argument, the elements of a `Pack`, and for a `Type`
+/// argument the type's own node.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum TemplateArgument {
+    /// An empty argument, which Clang uses for arguments not yet deduced.
+    Null,
+    /// A type, like `int` in `std::vector<int>`.
+    ///
+    /// A pack expansion like `Ts...` is a type argument whose type is a
+    /// `PackExpansionType`.
+    Type(Type),
+    /// A pointer or reference to a declaration, as a non-type argument. The
+    /// id is that of the referenced declaration.
+    Declaration(Id),
+    /// A null pointer value, as a non-type argument.
+    NullPtr,
+    /// An integer value, as a non-type argument.
+    Integral(i64),
+    /// A template, like `std::vector` in `Container<std::vector>`, or a pack
+    /// expansion of templates. Clang writes no further detail about these.
+    Template,
+    /// An expression whose value is not yet known, as a non-type argument in
+    /// a dependent context.
+    Expression,
+    /// An argument pack, like the `int, char` bound to `Ts...`.
+    Pack,
+}
+
+impl TemplateArgument {
+    /// The type of a `Type` argument.
+    pub fn ty(&self) -> Option<&Type> {
+        match self {
+            TemplateArgument::Type(ty) => Some(ty),
+            _ => None,
+        }
+    }
+
+    /// The value of an `Integral` argument.
+    pub fn integral(&self) -> Option<i64> {
+        match self {
+            TemplateArgument::Integral(value) => Some(*value),
+            _ => None,
+        }
+    }
+}
+
+enum TemplateArgumentField {
+    IsNull,
+    Type,
+    Decl,
+    IsNullptr,
+    Value,
+    IsExpr,
+    IsPack,
+    Other,
+}
+
+impl<'de> Deserialize<'de> for TemplateArgumentField {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct TemplateArgumentFieldVisitor;
+
+        impl<'de> Visitor<'de> for TemplateArgumentFieldVisitor {
+            type Value = TemplateArgumentField;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("field identifier")
+            }
+
+            fn visit_str<E>(self, field: &str) -> Result<Self::Value, E> {
+                Ok(match field {
+                    "isNull" => TemplateArgumentField::IsNull,
+                    "type" => TemplateArgumentField::Type,
+                    "decl" => TemplateArgumentField::Decl,
+                    "isNullptr" => TemplateArgumentField::IsNullptr,
+                    "value" => TemplateArgumentField::Value,
+                    "isExpr" => TemplateArgumentField::IsExpr,
+                    "isPack" => TemplateArgumentField::IsPack,
+                    _ => TemplateArgumentField::Other,
+                })
+            }
+        }
+
+        deserializer.deserialize_identifier(TemplateArgumentFieldVisitor)
+    }
+}
+
+// The "decl" of a declaration argument is a bare reference to the
+// declaration: its id, kind, name and type.
+struct DeclRef(Id);
+
+enum DeclRefField {
+    Id,
+    Other,
+}
+
+impl<'de> Deserialize<'de> for DeclRefField {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct DeclRefFieldVisitor;
+
+        impl<'de> Visitor<'de> for DeclRefFieldVisitor {
+            type Value = DeclRefField;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("field identifier")
+            }
+
+            fn visit_str<E>(self, field: &str) -> Result<Self::Value, E> {
+                Ok(match field {
+                    "id" => DeclRefField::Id,
+                    _ => DeclRefField::Other,
+                })
+            }
+        }
+
+        deserializer.deserialize_identifier(DeclRefFieldVisitor)
+    }
+}
+
+impl<'de> Deserialize<'de> for DeclRef {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct DeclRefVisitor;
+
+        impl<'de> Visitor<'de> for DeclRefVisitor {
+            type Value = DeclRef;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("declaration reference")
+            }
+
+            fn visit_map<M>(self, mut map: M) -> Result<Self::Value, M::Error>
+            where
+                M: MapAccess<'de>,
+            {
+                let mut id = None;
+                while let Some(field) = map.next_key()? {
+                    match field {
+                        DeclRefField::Id => {
+                            if id.is_some() {
+                                return Err(Error::duplicate_field("id"));
+                            }
+                            id = Some(map.next_value()?);
+                        }
+                        DeclRefField::Other => {
+                            let IgnoredAny = map.next_value()?;
+                        }
+                    }
+                }
+                id.map(DeclRef).ok_or_else(|| Error::missing_field("id"))
+            }
+        }
+
+        deserializer.deserialize_map(DeclRefVisitor)
+    }
+}
+
+impl<'de> Deserialize<'de> for TemplateArgument {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct TemplateArgumentVisitor;
+
+        impl<'de> Visitor<'de> for TemplateArgumentVisitor {
+            type Value = TemplateArgument;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("template argument")
+            }
+
+            fn visit_map<M>(self, mut map: M) -> Result<Self::Value, M::Error>
+            where
+                M: MapAccess<'de>,
+            {
+                let mut arg = None;
+                while let Some(field) = map.next_key()? {
+                    let next = match field {
+                        TemplateArgumentField::IsNull => {
+                            let IgnoredAny = map.next_value()?;
+                            TemplateArgument::Null
+                        }
+                        TemplateArgumentField::Type => TemplateArgument::Type(map.next_value()?),
+                        TemplateArgumentField::Decl => {
+                            let DeclRef(id) = map.next_value()?;
+                            TemplateArgument::Declaration(id)
+                        }
+                        TemplateArgumentField::IsNullptr => {
+                            let IgnoredAny = map.next_value()?;
+                            TemplateArgument::NullPtr
+                        }
+                        TemplateArgumentField::Value => {
+                            TemplateArgument::Integral(map.next_value()?)
+                        }
+                        TemplateArgumentField::IsExpr => {
+                            let IgnoredAny = map.next_value()?;
+                            TemplateArgument::Expression
+                        }
+                        TemplateArgumentField::IsPack => {
+                            let IgnoredAny = map.next_value()?;
+                            TemplateArgument::Pack
+                        }
+                        TemplateArgumentField::Other => {
+                            let IgnoredAny = map.next_value()?;
+                            continue;
+                        }
+                    };
+                    if arg.is_some() {
+                        return Err(Error::custom(
+                            "template argument has more than one kind of value",
+                        ));
+                    }
+                    arg = Some(next);
+                }
+                // Clang writes nothing about template template arguments.
+                Ok(arg.unwrap_or(TemplateArgument::Template))
+            }
+        }
+
+        deserializer.deserialize_map(TemplateArgumentVisitor)
+    }
+}
+
+impl Serialize for TemplateArgument {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        struct SerializeDeclRef(Id);
+
+        impl Serialize for SerializeDeclRef {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: Serializer,
+            {
+                let mut map = serializer.serialize_map(Some(1))?;
+                map.serialize_entry("id", &self.0)?;
+                map.end()
+            }
+        }
+
+        let mut map = serializer.serialize_map(None)?;
+        match self {
+            TemplateArgument::Null => map.serialize_entry("isNull", &true)?,
+            TemplateArgument::Type(ty) => map.serialize_entry("type", ty)?,
+            TemplateArgument::Declaration(id) => {
+                map.serialize_entry("decl", &SerializeDeclRef(*id))?;
+            }
+            TemplateArgument::NullPtr => map.serialize_entry("isNullptr", &true)?,
+            TemplateArgument::Integral(value) => map.serialize_entry("value", value)?,
+            TemplateArgument::Template => {}
+            TemplateArgument::Expression => map.serialize_entry("isExpr", &true)?,
+            TemplateArgument::Pack => map.serialize_entry("isPack", &true)?,
+        }
+        map.end()
+    }
+}
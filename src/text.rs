@@ -0,0 +1,459 @@
+//! Best-effort parsing of Clang's plain-text `-ast-dump` format.
+//!
+//! Some toolchains only provide the indented text dump rather than JSON.
+//! [`from_str`] reads the text dump into the same [`Node`] structure as
+//! deserializing JSON would, so that code written against the JSON shape can
+//! consume either one.
+//!
+//! ```
+//! use clang_ast::{Kind, SourceRange};
+//! use serde::Deserialize;
+//!
+//! #[derive(Deserialize)]
+//! pub struct Clang {
+//!     pub kind: Kind,
+//!     pub name: Option<String>,
+//!     pub range: Option<SourceRange>,
+//! }
+//!
+//! let dump = "\
+//! TranslationUnitDecl 0x1 <<invalid sloc>> <invalid sloc>
+//! `-FunctionDecl 0x2 <main.cc:1:1, line:3:1> line:1:5 main 'int ()'
+//! ";
+//! let node: clang_ast::Node<Clang> = clang_ast::text::from_str(dump).unwrap();
+//! assert_eq!(node.inner[0].kind.name.as_deref(), Some("main"));
+//! ```
+//!
+//! The text format carries less information than JSON, so only the
+//! following fields are produced, when present in the dump:
+//!
+//! - `"id"`, `"kind"`, and `"inner"`
+//! - `"range"` and, for declarations, `"loc"`; the text format has no byte
+//!   offsets or token lengths, so those are always 0
+//! - `"previousDecl"`
+//! - `"isImplicit"`, `"isUsed"`, `"isReferenced"`, `"isInvalid"`
+//! - `"name"` of declarations
+//! - `"type"`, with `"qualType"` and `"desugaredQualType"`
+//! - `"valueCategory"` of expressions
+//! - `"castKind"` of casts
+//! - `"value"` of integer and string literals
+//!
+//! Lines which do not begin with a kind followed by a node id, such as
+//! `TemplateArgument` or `DefinitionData`, are skipped along with everything
+//! nested beneath them.
+
+use crate::Node;
+use serde::de::value::{MapDeserializer, SeqDeserializer};
+use serde::de::{self, DeserializeOwned, Deserializer, IntoDeserializer, Visitor};
+use serde::forward_to_deserialize_any;
+
+pub use serde::de::value::Error;
+
+/// Parse a syntax tree from the output of `clang -Xclang -ast-dump`.
+pub fn from_str<T>(text: &str) -> Result<Node<T>, Error>
+where
+    T: DeserializeOwned,
+{
+    let root = parse(text)?;
+    de::Deserialize::deserialize(root)
+}
+
+fn parse(text: &str) -> Result<Value, Error> {
+    struct Open {
+        depth: usize,
+        fields: Vec<(&'static str, Value)>,
+        inner: Vec<Value>,
+    }
+
+    fn close(open: Open) -> Value {
+        let mut fields = open.fields;
+        if !open.inner.is_empty() {
+            fields.push(("inner", Value::Seq(open.inner)));
+        }
+        Value::Map(fields)
+    }
+
+    let mut last_loc = LastLoc::default();
+    let mut stack: Vec<Open> = Vec::new();
+    let mut root = None;
+    let mut skip_below = None;
+
+    for (i, line) in text.lines().enumerate() {
+        let body = line.trim_start_matches(&['|', '`', '-', ' '][..]);
+        if body.is_empty() {
+            continue;
+        }
+        let depth = (line.len() - body.len()) / 2;
+        if let Some(skip) = skip_below {
+            if depth > skip {
+                continue;
+            }
+            skip_below = None;
+        }
+        let fields = if let Some(fields) = parse_line(body, &mut last_loc) {
+            fields
+        } else {
+            skip_below = Some(depth);
+            continue;
+        };
+        while stack.last().map_or(false, |open| open.depth >= depth) {
+            let value = close(stack.pop().unwrap());
+            match stack.last_mut() {
+                Some(parent) => parent.inner.push(value),
+                None => root = Some(value),
+            }
+        }
+        if stack.is_empty() && root.is_some() {
+            let msg = format!("line {}: text dump has more than one root node", i + 1);
+            return Err(de::Error::custom(msg));
+        }
+        stack.push(Open {
+            depth,
+            fields,
+            inner: Vec::new(),
+        });
+    }
+
+    while let Some(open) = stack.pop() {
+        let value = close(open);
+        match stack.last_mut() {
+            Some(parent) => parent.inner.push(value),
+            None => root = Some(value),
+        }
+    }
+    root.ok_or_else(|| de::Error::custom("text dump contains no nodes"))
+}
+
+// File and line of the most recent location, which later locations omit if
+// unchanged.
+#[derive(Default)]
+struct LastLoc {
+    file: String,
+    line: u64,
+}
+
+fn parse_line(body: &str, last_loc: &mut LastLoc) -> Option<Vec<(&'static str, Value)>> {
+    let mut tokens = tokenize(body).into_iter().peekable();
+    let kind = match tokens.next()? {
+        Token::Word(kind) if kind.starts_with(|ch: char| ch.is_ascii_alphabetic()) => kind,
+        _ => return None,
+    };
+    let id = match tokens.next()? {
+        Token::Word(id) if id.starts_with("0x") => id,
+        _ => return None,
+    };
+
+    let is_decl = kind.ends_with("Decl");
+    let is_expr = kind.ends_with("Expr") || kind.ends_with("Literal") || kind.ends_with("Operator");
+    let mut fields = vec![("id", Value::Str(id)), ("kind", Value::Str(kind.clone()))];
+
+    while let Some(Token::Word(word)) = tokens.peek() {
+        let key = match word.as_str() {
+            "prev" => "previousDecl",
+            "parent" => "parentDeclContextId",
+            _ => break,
+        };
+        tokens.next();
+        if let Some(Token::Word(id)) = tokens.next() {
+            fields.push((key, Value::Str(id)));
+        }
+    }
+
+    if let Some(Token::Angle(range)) = tokens.peek() {
+        let range = parse_range(range, last_loc);
+        fields.push(("range", range));
+        tokens.next();
+    }
+
+    if is_decl {
+        let loc = match tokens.peek() {
+            Some(Token::Angle(loc)) if loc == "invalid sloc" => Some(Value::Map(Vec::new())),
+            Some(Token::Word(loc)) => parse_loc(loc, last_loc),
+            _ => None,
+        };
+        if let Some(loc) = loc {
+            fields.push(("loc", loc));
+            tokens.next();
+        }
+    }
+
+    let mut name = None;
+    let mut qual_type = None;
+    let mut value_category = None;
+    let mut cast_kind = None;
+    let mut value = None;
+    for token in tokens {
+        match token {
+            Token::Word(word) => match word.as_str() {
+                "implicit" => fields.push(("isImplicit", Value::Bool(true))),
+                "used" => fields.push(("isUsed", Value::Bool(true))),
+                "referenced" => fields.push(("isReferenced", Value::Bool(true))),
+                "invalid" => fields.push(("isInvalid", Value::Bool(true))),
+                "lvalue" | "xvalue" if is_expr => value_category = Some(word),
+                _ if is_decl && name.is_none() && qual_type.is_none() => name = Some(word),
+                _ if kind == "IntegerLiteral" && value.is_none() => value = Some(word),
+                _ => {}
+            },
+            Token::Quoted(qual, desugared) if qual_type.is_none() => {
+                qual_type = Some((qual, desugared));
+            }
+            Token::Angle(angle) if kind.ends_with("CastExpr") && cast_kind.is_none() => {
+                cast_kind = Some(angle);
+            }
+            Token::Str(literal) if kind == "StringLiteral" && value.is_none() => {
+                value = Some(literal);
+            }
+            _ => {}
+        }
+    }
+
+    if let Some(name) = name {
+        fields.push(("name", Value::Str(name)));
+    }
+    if let Some((qual, desugared)) = qual_type {
+        let mut ty = vec![("qualType", Value::Str(qual))];
+        if let Some(desugared) = desugared {
+            ty.push(("desugaredQualType", Value::Str(desugared)));
+        }
+        fields.push(("type", Value::Map(ty)));
+        if is_expr {
+            let category = value_category.unwrap_or_else(|| "prvalue".to_owned());
+            fields.push(("valueCategory", Value::Str(category)));
+        }
+    }
+    if let Some(cast_kind) = cast_kind {
+        fields.push(("castKind", Value::Str(cast_kind)));
+    }
+    if let Some(value) = value {
+        fields.push(("value", Value::Str(value)));
+    }
+    Some(fields)
+}
+
+fn parse_range(range: &str, last_loc: &mut LastLoc) -> Value {
+    // Locations inside of macro expansions are followed by the spelling
+    // location in the form `<Spelling=...>`.
+    let mut stripped = String::new();
+    let mut rest = range;
+    while let Some(start) = rest.find(" <Spelling=") {
+        stripped.push_str(&rest[..start]);
+        rest = match rest[start..].find('>') {
+            Some(end) => &rest[start + end + 1..],
+            None => "",
+        };
+    }
+    stripped.push_str(rest);
+
+    let mut locs = stripped.splitn(2, ", ");
+    let begin = locs.next().unwrap_or("");
+    let begin = parse_loc(begin, last_loc).unwrap_or_else(|| Value::Map(Vec::new()));
+    let end = match locs.next() {
+        Some(end) => parse_loc(end, last_loc).unwrap_or_else(|| Value::Map(Vec::new())),
+        None => begin.clone(),
+    };
+    Value::Map(vec![("begin", begin), ("end", end)])
+}
+
+fn parse_loc(loc: &str, last_loc: &mut LastLoc) -> Option<Value> {
+    if loc == "<invalid sloc>" {
+        return Some(Value::Map(Vec::new()));
+    }
+    let mut parts = loc.rsplitn(3, ':');
+    let last = parts.next()?.parse::<u64>().ok()?;
+    let (line, col) = match (parts.next(), parts.next()) {
+        (Some("col"), None) => (last_loc.line, last),
+        (Some(line), Some("line")) => (line.parse().ok()?, last),
+        (Some(line), Some(file)) => {
+            let line = line.parse().ok()?;
+            last_loc.file.clear();
+            last_loc.file.push_str(file);
+            (line, last)
+        }
+        _ => return None,
+    };
+    last_loc.line = line;
+    Some(Value::Map(vec![
+        ("offset", Value::U64(0)),
+        ("file", Value::Str(last_loc.file.clone())),
+        ("line", Value::U64(line)),
+        ("col", Value::U64(col)),
+        ("tokLen", Value::U64(0)),
+    ]))
+}
+
+enum Token {
+    Word(String),
+    // Contents of `<...>`, which may nest.
+    Angle(String),
+    // Type in the form `'int'` or `'size_t':'unsigned long'`.
+    Quoted(String, Option<String>),
+    // String literal including its double quotes, with escapes left in place.
+    Str(String),
+}
+
+fn tokenize(body: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut chars = body.char_indices().peekable();
+    while let Some((start, ch)) = chars.next() {
+        match ch {
+            ' ' => {}
+            '<' => {
+                let mut depth = 1;
+                let mut end = body.len();
+                for (i, ch) in &mut chars {
+                    match ch {
+                        '<' => depth += 1,
+                        '>' => depth -= 1,
+                        _ => {}
+                    }
+                    if depth == 0 {
+                        end = i;
+                        break;
+                    }
+                }
+                tokens.push(Token::Angle(body[start + 1..end].to_owned()));
+            }
+            '\'' => {
+                let quoted = take_quoted(body, start, &mut chars);
+                let after = body.get(start + quoted.len() + 2..).unwrap_or("");
+                let desugared = if after.starts_with(":'") {
+                    chars.next();
+                    let (start, _) = chars.next().unwrap();
+                    Some(take_quoted(body, start, &mut chars))
+                } else {
+                    None
+                };
+                tokens.push(Token::Quoted(quoted, desugared));
+            }
+            '"' => {
+                let mut end = body.len();
+                let mut escaped = false;
+                for (i, ch) in &mut chars {
+                    match ch {
+                        '\\' if !escaped => escaped = true,
+                        '"' if !escaped => {
+                            end = i;
+                            break;
+                        }
+                        _ => escaped = false,
+                    }
+                }
+                let end = (end + 1).min(body.len());
+                tokens.push(Token::Str(body[start..end].to_owned()));
+            }
+            _ => {
+                let mut end = body.len();
+                while let Some(&(i, ch)) = chars.peek() {
+                    if ch == ' ' {
+                        end = i;
+                        break;
+                    }
+                    chars.next();
+                }
+                tokens.push(Token::Word(body[start..end].to_owned()));
+            }
+        }
+    }
+    tokens
+}
+
+// Read up to the closing quote of a quote that begins at `start`.
+fn take_quoted(
+    body: &str,
+    start: usize,
+    chars: &mut std::iter::Peekable<std::str::CharIndices>,
+) -> String {
+    let mut end = body.len();
+    for (i, ch) in chars {
+        if ch == '\'' {
+            end = i;
+            break;
+        }
+    }
+    body[start + 1..end].to_owned()
+}
+
+#[derive(Clone)]
+enum Value {
+    Bool(bool),
+    U64(u64),
+    Str(String),
+    Seq(Vec<Value>),
+    Map(Vec<(&'static str, Value)>),
+}
+
+impl<'de> IntoDeserializer<'de, Error> for Value {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self::Deserializer {
+        self
+    }
+}
+
+impl<'de> Deserializer<'de> for Value {
+    type Error = Error;
+
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        match self {
+            Value::Bool(b) => visitor.visit_bool(b),
+            Value::U64(n) => visitor.visit_u64(n),
+            Value::Str(s) => visitor.visit_string(s),
+            Value::Seq(seq) => {
+                let mut seq = SeqDeserializer::new(seq.into_iter());
+                let value = visitor.visit_seq(&mut seq)?;
+                seq.end()?;
+                Ok(value)
+            }
+            Value::Map(map) => {
+                let mut map = MapDeserializer::new(map.into_iter());
+                let value = visitor.visit_map(&mut map)?;
+                map.end()?;
+                Ok(value)
+            }
+        }
+    }
+
+    fn deserialize_option<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_newtype_struct<V>(
+        self,
+        name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        let _ = name;
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        let _ = (name, variants);
+        match self {
+            Value::Str(s) => visitor.visit_enum(s.into_deserializer()),
+            other => other.deserialize_any(visitor),
+        }
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple tuple_struct map struct
+        identifier ignored_any
+    }
+}
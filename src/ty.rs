@@ -0,0 +1,178 @@
+use crate::{Id, ParseTypeError, ParsedType};
+use serde::de::{Deserialize, Deserializer, Error, IgnoredAny, MapAccess, Visitor};
+use serde::ser::{Serialize, SerializeMap, Serializer};
+use std::fmt;
+
+// String type of the fields of Type.
+#[cfg(feature = "compact-str")]
+use compact_str::CompactString as TypeStr;
+#[cfg(not(feature = "compact-str"))]
+use std::string::String as TypeStr;
+
+/// The `"type"` of a declaration or expression.
+///
+/// The strings are of type `compact_str::CompactString` rather than `String`
+/// if the `"compact-str"` feature is enabled, which stores strings of up to 24
+/// bytes inline.
+///
+/// ```
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// pub enum Clang {
+///     VarDecl {
+///         name: String,
+///         #[serde(rename = "type")]
+///         ty: clang_ast::Type,
+///     },
+///     Other,
+/// }
+/// #
+/// # let json = r#"{"id":"0x1","kind":"VarDecl","name":"x","type":{"qualType":"size_t","desugaredQualType":"unsigned long","typeAliasDeclId":"0x2"}}"#;
+/// # let node: clang_ast::Node<Clang> = serde_json::from_str(json).unwrap();
+/// # match node.kind {
+/// #     Clang::VarDecl { ty, .. } => assert_eq!(ty.desugared(), "unsigned long"),
+/// #     Clang::Other => panic!(),
+/// # }
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Type {
+    /// The type as written, such as `size_t`.
+    pub qual_type: TypeStr,
+    /// The type with typedefs and other sugar removed, such as
+    /// `unsigned long`, if it differs from `qual_type`.
+    pub desugared_qual_type: Option<TypeStr>,
+    /// The typedef or alias declaration named by `qual_type`, if any.
+    pub type_alias_decl_id: Option<Id>,
+}
+
+impl Type {
+    /// The desugared type, falling back to the type as written when Clang
+    /// did not write a separate desugared spelling.
+    pub fn desugared(&self) -> &str {
+        self.desugared_qual_type
+            .as_deref()
+            .unwrap_or(&self.qual_type)
+    }
+
+    /// Parse the structure of the type as written.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `qual_type` uses syntax which the parser does not
+    /// understand. See [`ParsedType`].
+    pub fn parse(&self) -> Result<ParsedType, ParseTypeError> {
+        self.qual_type.parse()
+    }
+}
+
+enum TypeField {
+    QualType,
+    DesugaredQualType,
+    TypeAliasDeclId,
+    Other,
+}
+
+impl<'de> Deserialize<'de> for TypeField {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct TypeFieldVisitor;
+
+        impl<'de> Visitor<'de> for TypeFieldVisitor {
+            type Value = TypeField;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("field identifier")
+            }
+
+            fn visit_str<E>(self, field: &str) -> Result<Self::Value, E> {
+                Ok(match field {
+                    "qualType" => TypeField::QualType,
+                    "desugaredQualType" => TypeField::DesugaredQualType,
+                    "typeAliasDeclId" => TypeField::TypeAliasDeclId,
+                    _ => TypeField::Other,
+                })
+            }
+        }
+
+        deserializer.deserialize_identifier(TypeFieldVisitor)
+    }
+}
+
+impl<'de> Deserialize<'de> for Type {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct TypeVisitor;
+
+        impl<'de> Visitor<'de> for TypeVisitor {
+            type Value = Type;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("clang type")
+            }
+
+            fn visit_map<M>(self, mut map: M) -> Result<Self::Value, M::Error>
+            where
+                M: MapAccess<'de>,
+            {
+                let mut qual_type = None;
+                let mut desugared_qual_type = None;
+                let mut type_alias_decl_id = None;
+                while let Some(field) = map.next_key()? {
+                    match field {
+                        TypeField::QualType => {
+                            if qual_type.is_some() {
+                                return Err(Error::duplicate_field("qualType"));
+                            }
+                            qual_type = Some(map.next_value()?);
+                        }
+                        TypeField::DesugaredQualType => {
+                            if desugared_qual_type.is_some() {
+                                return Err(Error::duplicate_field("desugaredQualType"));
+                            }
+                            desugared_qual_type = Some(map.next_value()?);
+                        }
+                        TypeField::TypeAliasDeclId => {
+                            if type_alias_decl_id.is_some() {
+                                return Err(Error::duplicate_field("typeAliasDeclId"));
+                            }
+                            type_alias_decl_id = Some(map.next_value()?);
+                        }
+                        TypeField::Other => {
+                            let IgnoredAny = map.next_value()?;
+                        }
+                    }
+                }
+                let qual_type = qual_type.ok_or_else(|| Error::missing_field("qualType"))?;
+                Ok(Type {
+                    qual_type,
+                    desugared_qual_type,
+                    type_alias_decl_id,
+                })
+            }
+        }
+
+        deserializer.deserialize_map(TypeVisitor)
+    }
+}
+
+impl Serialize for Type {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry("qualType", &self.qual_type)?;
+        if let Some(desugared_qual_type) = &self.desugared_qual_type {
+            map.serialize_entry("desugaredQualType", desugared_qual_type)?;
+        }
+        if let Some(type_alias_decl_id) = &self.type_alias_decl_id {
+            map.serialize_entry("typeAliasDeclId", type_alias_decl_id)?;
+        }
+        map.end()
+    }
+}
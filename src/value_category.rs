@@ -0,0 +1,105 @@
+use serde::de::{Deserialize, Deserializer, Error, Visitor};
+use serde::ser::{Serialize, Serializer};
+use std::fmt;
+
+/// The `"valueCategory"` of an expression.
+///
+/// ```
+/// use clang_ast::ValueCategory;
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// pub enum Clang {
+///     DeclRefExpr {
+///         #[serde(rename = "valueCategory")]
+///         value_category: ValueCategory,
+///     },
+///     Other,
+/// }
+/// #
+/// # let json = r#"{"id":"0x1","kind":"DeclRefExpr","valueCategory":"lvalue"}"#;
+/// # let node: clang_ast::Node<Clang> = serde_json::from_str(json).unwrap();
+/// # match node.kind {
+/// #     Clang::DeclRefExpr { value_category } => {
+/// #         assert!(value_category.is_glvalue());
+/// #     }
+/// #     Clang::Other => panic!(),
+/// # }
+/// ```
+///
+/// Clang 12 and older write `"rvalue"` for what is now `"prvalue"`; both
+/// deserialize to `ValueCategory::PRValue`, which serializes as
+/// `"prvalue"`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ValueCategory {
+    LValue,
+    XValue,
+    PRValue,
+}
+
+impl ValueCategory {
+    /// An lvalue or xvalue.
+    pub fn is_glvalue(self) -> bool {
+        self != ValueCategory::PRValue
+    }
+
+    /// A prvalue or xvalue.
+    pub fn is_rvalue(self) -> bool {
+        self != ValueCategory::LValue
+    }
+
+    fn from_str(category: &str) -> Option<Self> {
+        match category {
+            "lvalue" => Some(ValueCategory::LValue),
+            "xvalue" => Some(ValueCategory::XValue),
+            "prvalue" | "rvalue" => Some(ValueCategory::PRValue),
+            _ => None,
+        }
+    }
+
+    fn as_str(self) -> &'static str {
+        match self {
+            ValueCategory::LValue => "lvalue",
+            ValueCategory::XValue => "xvalue",
+            ValueCategory::PRValue => "prvalue",
+        }
+    }
+}
+
+const VALUE_CATEGORY: &[&str] = &["lvalue", "xvalue", "prvalue", "rvalue"];
+
+impl<'de> Deserialize<'de> for ValueCategory {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct ValueCategoryVisitor;
+
+        impl<'de> Visitor<'de> for ValueCategoryVisitor {
+            type Value = ValueCategory;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("value category")
+            }
+
+            fn visit_str<E>(self, category: &str) -> Result<Self::Value, E>
+            where
+                E: Error,
+            {
+                ValueCategory::from_str(category)
+                    .ok_or_else(|| E::unknown_variant(category, VALUE_CATEGORY))
+            }
+        }
+
+        deserializer.deserialize_str(ValueCategoryVisitor)
+    }
+}
+
+impl Serialize for ValueCategory {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(self.as_str())
+    }
+}
@@ -0,0 +1,226 @@
+use serde::de::{Deserialize, DeserializeSeed, Deserializer, MapAccess, SeqAccess, Visitor};
+use std::fmt;
+
+/// Range of Clang major versions which could have produced an AST dump,
+/// inferred from details of the dump's format.
+///
+/// Deserializing a `ClangVersion` walks the entire dump looking for node
+/// kinds and field values which were introduced or removed in a particular
+/// release. Either bound is `None` if nothing in the dump constrains it.
+///
+/// ```
+/// use clang_ast::ClangVersion;
+///
+/// let json = r#"
+///     {
+///       "id": "0x1",
+///       "kind": "ImplicitCastExpr",
+///       "valueCategory": "prvalue",
+///       "castKind": "LValueToRValue"
+///     }
+/// "#;
+/// let version: ClangVersion = serde_json::from_str(json).unwrap();
+/// assert_eq!(version.min, Some(13));
+/// assert_eq!(version.max, None);
+/// ```
+///
+/// Dumps are the same shape across many releases, so the range is typically
+/// much wider than one version. Small dumps which happen to use none of the
+/// distinguishing features leave both bounds unknown.
+///
+/// # Compatibility
+///
+/// The only rename between releases which this crate papers over is the
+/// `"rvalue"` value category of Clang 12 and older becoming `"prvalue"` in
+/// Clang 13: [`ValueCategory`] deserializes both to
+/// [`ValueCategory::PRValue`]. Source locations and ranges need no shim, as
+/// Clang 11 through 19 all use the same field names for them, differing only
+/// in optional fields which this crate accepts when present. Nothing else
+/// about a dump is translated between releases; a field renamed in some
+/// other release reaches the caller's node types under whichever name that
+/// release wrote.
+///
+/// [`ValueCategory`]: crate::ValueCategory
+/// [`ValueCategory::PRValue`]: crate::ValueCategory::PRValue
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct ClangVersion {
+    /// Oldest Clang release able to produce the dump.
+    pub min: Option<u32>,
+    /// Newest Clang release able to produce the dump.
+    pub max: Option<u32>,
+}
+
+impl ClangVersion {
+    /// Whether `major` lies within the range.
+    pub fn contains(&self, major: u32) -> bool {
+        self.min.map_or(true, |min| min <= major) && self.max.map_or(true, |max| major <= max)
+    }
+
+    fn at_least(&mut self, major: u32) {
+        self.min = Some(self.min.map_or(major, |min| min.max(major)));
+    }
+
+    fn at_most(&mut self, major: u32) {
+        self.max = Some(self.max.map_or(major, |max| max.min(major)));
+    }
+
+    fn kind(&mut self, kind: &str) {
+        match kind {
+            "RecoveryExpr" => self.at_least(11),
+            "UnnamedGlobalConstantDecl" => self.at_least(15),
+            "CXXParenListInitExpr" | "ImplicitConceptSpecializationDecl" => self.at_least(16),
+            "OMPArraySectionExpr" => self.at_most(18),
+            "ArraySectionExpr" | "EmbedExpr" | "PackIndexingExpr" => self.at_least(19),
+            _ => {}
+        }
+    }
+
+    fn value_category(&mut self, category: &str) {
+        // Renamed in Clang 13.
+        match category {
+            "rvalue" => self.at_most(12),
+            "prvalue" => self.at_least(13),
+            _ => {}
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for ClangVersion {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let mut version = ClangVersion::default();
+        Sniff {
+            version: &mut version,
+            field: Field::Other,
+        }
+        .deserialize(deserializer)?;
+        Ok(version)
+    }
+}
+
+#[derive(Copy, Clone)]
+enum Field {
+    Kind,
+    ValueCategory,
+    Other,
+}
+
+struct Sniff<'a> {
+    version: &'a mut ClangVersion,
+    // Key under which the value being visited appears.
+    field: Field,
+}
+
+impl<'de, 'a> DeserializeSeed<'de> for Sniff<'a> {
+    type Value = ();
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_any(self)
+    }
+}
+
+impl<'de, 'a> Visitor<'de> for Sniff<'a> {
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("clang syntax tree")
+    }
+
+    fn visit_bool<E>(self, _: bool) -> Result<Self::Value, E> {
+        Ok(())
+    }
+
+    fn visit_i64<E>(self, _: i64) -> Result<Self::Value, E> {
+        Ok(())
+    }
+
+    fn visit_u64<E>(self, _: u64) -> Result<Self::Value, E> {
+        Ok(())
+    }
+
+    fn visit_f64<E>(self, _: f64) -> Result<Self::Value, E> {
+        Ok(())
+    }
+
+    fn visit_str<E>(self, value: &str) -> Result<Self::Value, E> {
+        match self.field {
+            Field::Kind => self.version.kind(value),
+            Field::ValueCategory => self.version.value_category(value),
+            Field::Other => {}
+        }
+        Ok(())
+    }
+
+    fn visit_none<E>(self) -> Result<Self::Value, E> {
+        Ok(())
+    }
+
+    fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        self.deserialize(deserializer)
+    }
+
+    fn visit_unit<E>(self) -> Result<Self::Value, E> {
+        Ok(())
+    }
+
+    fn visit_seq<S>(self, mut seq: S) -> Result<Self::Value, S::Error>
+    where
+        S: SeqAccess<'de>,
+    {
+        while let Some(()) = seq.next_element_seed(Sniff {
+            version: &mut *self.version,
+            field: Field::Other,
+        })? {}
+        Ok(())
+    }
+
+    fn visit_map<M>(self, mut map: M) -> Result<Self::Value, M::Error>
+    where
+        M: MapAccess<'de>,
+    {
+        while let Some(field) = map.next_key_seed(FieldSeed)? {
+            map.next_value_seed(Sniff {
+                version: &mut *self.version,
+                field,
+            })?;
+        }
+        Ok(())
+    }
+}
+
+struct FieldSeed;
+
+impl<'de> DeserializeSeed<'de> for FieldSeed {
+    type Value = Field;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_identifier(self)
+    }
+}
+
+impl<'de> Visitor<'de> for FieldSeed {
+    type Value = Field;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("field identifier")
+    }
+
+    fn visit_str<E>(self, field: &str) -> Result<Self::Value, E> {
+        Ok(match field {
+            "kind" => Field::Kind,
+            "valueCategory" => Field::ValueCategory,
+            _ => Field::Other,
+        })
+    }
+}
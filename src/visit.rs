@@ -0,0 +1,259 @@
+use crate::{InnerIntoIter, InnerVec, Node};
+use std::mem;
+
+/// Callbacks invoked by [`Node::visit`] while walking a syntax tree.
+///
+/// For every node, `enter_node` is called before any of its children are
+/// visited and `exit_node` is called after all of them have been. This
+/// matches the shape of Clang's `RecursiveASTVisitor` and makes it
+/// straightforward to maintain scoped state such as a stack of enclosing
+/// namespaces.
+///
+/// The value returned by `enter_node` decides how the walk proceeds; see
+/// [`Control`].
+///
+/// ```
+/// use clang_ast::{Control, Node, Visit};
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// pub enum Clang {
+///     NamespaceDecl { name: Option<String> },
+///     EnumDecl { name: Option<String> },
+///     Other,
+/// }
+///
+/// #[derive(Default)]
+/// struct EnumPaths<'ast> {
+///     namespaces: Vec<&'ast str>,
+///     paths: Vec<String>,
+/// }
+///
+/// impl<'ast> Visit<'ast, Clang> for EnumPaths<'ast> {
+///     fn enter_node(&mut self, node: &'ast Node<Clang>) -> Control {
+///         match &node.kind {
+///             Clang::NamespaceDecl { name } => {
+///                 self.namespaces.push(name.as_deref().unwrap_or("(anonymous)"));
+///             }
+///             Clang::EnumDecl { name: Some(name) } => {
+///                 let mut path = self.namespaces.join("::");
+///                 path += "::";
+///                 path += name;
+///                 self.paths.push(path);
+///             }
+///             _ => {}
+///         }
+///         Control::Continue
+///     }
+///
+///     fn exit_node(&mut self, node: &'ast Node<Clang>) {
+///         if let Clang::NamespaceDecl { .. } = node.kind {
+///             self.namespaces.pop();
+///         }
+///     }
+/// }
+/// #
+/// # let json = r#"{"id":"0x1","kind":"NamespaceDecl","name":"ns","inner":[{"id":"0x2","kind":"EnumDecl","name":"E"}]}"#;
+/// # let node: Node<Clang> = serde_json::from_str(json).unwrap();
+/// # let mut visitor = EnumPaths::default();
+/// # node.visit(&mut visitor);
+/// # assert_eq!(visitor.paths, ["ns::E"]);
+/// ```
+pub trait Visit<'ast, T> {
+    fn enter_node(&mut self, node: &'ast Node<T>) -> Control {
+        let _ = node;
+        Control::Continue
+    }
+
+    fn exit_node(&mut self, node: &'ast Node<T>) {
+        let _ = node;
+    }
+}
+
+/// Callbacks invoked by [`Node::visit_mut`] while walking a syntax tree,
+/// with the ability to modify it in place.
+///
+/// The children of a node are walked after `enter_node` returns, so any
+/// children added or rearranged by `enter_node` are the ones that get visited.
+/// `exit_node` sees the node after all of its children have been visited and
+/// may replace the node outright.
+///
+/// ```
+/// use clang_ast::{Node, VisitMut};
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// pub enum Clang {
+///     ImplicitCastExpr,
+///     Other,
+/// }
+///
+/// struct StripImplicitCasts;
+///
+/// impl VisitMut<Clang> for StripImplicitCasts {
+///     fn exit_node(&mut self, node: &mut Node<Clang>) {
+///         if let Clang::ImplicitCastExpr = node.kind {
+///             if node.inner.len() == 1 {
+///                 *node = node.inner.pop().unwrap();
+///             }
+///         }
+///     }
+/// }
+/// #
+/// # let json = r#"{"id":"0x1","kind":"ImplicitCastExpr","inner":[{"id":"0x2","kind":"ImplicitCastExpr","inner":[{"id":"0x3","kind":"DeclRefExpr"}]}]}"#;
+/// # let mut node: Node<Clang> = serde_json::from_str(json).unwrap();
+/// # node.visit_mut(&mut StripImplicitCasts);
+/// # assert_eq!(node.id.to_string(), "0x3");
+/// ```
+pub trait VisitMut<T> {
+    fn enter_node(&mut self, node: &mut Node<T>) -> Control {
+        let _ = node;
+        Control::Continue
+    }
+
+    fn exit_node(&mut self, node: &mut Node<T>) {
+        let _ = node;
+    }
+}
+
+/// Returned from a visitor callback to steer the traversal.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Control {
+    /// Proceed into this node's children.
+    Continue,
+    /// Do not visit this node's children. Its `exit_node` callback is still
+    /// invoked.
+    SkipChildren,
+    /// End the traversal immediately. No further callbacks are invoked, not
+    /// even `exit_node` for nodes that have already been entered.
+    Stop,
+}
+
+pub(crate) fn visit<'ast, T, V>(node: &'ast Node<T>, visitor: &mut V)
+where
+    V: ?Sized + Visit<'ast, T>,
+{
+    let children = match visitor.enter_node(node) {
+        Control::Continue => node.inner.iter(),
+        Control::SkipChildren => [].iter(),
+        Control::Stop => return,
+    };
+    let mut stack = vec![(node, children)];
+    while let Some((_node, children)) = stack.last_mut() {
+        if let Some(child) = children.next() {
+            let children = match visitor.enter_node(child) {
+                Control::Continue => child.inner.iter(),
+                Control::SkipChildren => [].iter(),
+                Control::Stop => return,
+            };
+            stack.push((child, children));
+        } else {
+            let (node, _children) = stack.pop().unwrap();
+            visitor.exit_node(node);
+        }
+    }
+}
+
+pub(crate) fn visit_with_ancestors<'ast, T, F>(node: &'ast Node<T>, mut f: F)
+where
+    F: FnMut(&[&'ast Node<T>], &'ast Node<T>) -> Control,
+{
+    let children = match f(&[], node) {
+        Control::Continue => node.inner.iter(),
+        Control::SkipChildren | Control::Stop => return,
+    };
+    let mut ancestors = vec![node];
+    let mut stack = vec![children];
+    while let Some(children) = stack.last_mut() {
+        if let Some(child) = children.next() {
+            match f(&ancestors, child) {
+                Control::Continue => {
+                    ancestors.push(child);
+                    stack.push(child.inner.iter());
+                }
+                Control::SkipChildren => {}
+                Control::Stop => return,
+            }
+        } else {
+            ancestors.pop();
+            stack.pop();
+        }
+    }
+}
+
+// A node whose children are being walked by visit_mut. The children are moved
+// out of the node for the duration, and moved back in before its exit_node.
+struct Frame<T> {
+    // None for the root, which stays in place behind the caller's reference.
+    node: Option<Node<T>>,
+    pending: InnerIntoIter<Node<T>>,
+    done: InnerVec<Node<T>>,
+}
+
+impl<T> Frame<T> {
+    fn new(node: Option<Node<T>>, inner: InnerVec<Node<T>>) -> Self {
+        let done = InnerVec::with_capacity(inner.len());
+        let pending = inner.into_iter();
+        Frame {
+            node,
+            pending,
+            done,
+        }
+    }
+}
+
+pub(crate) fn visit_mut<T, V>(root: &mut Node<T>, visitor: &mut V)
+where
+    V: ?Sized + VisitMut<T>,
+{
+    match visitor.enter_node(root) {
+        Control::Continue => {}
+        Control::SkipChildren => {
+            visitor.exit_node(root);
+            return;
+        }
+        Control::Stop => return,
+    }
+    let mut stack = vec![Frame::new(None, mem::take(&mut root.inner))];
+    while let Some(frame) = stack.last_mut() {
+        if let Some(mut child) = frame.pending.next() {
+            match visitor.enter_node(&mut child) {
+                Control::Continue => {
+                    let inner = mem::take(&mut child.inner);
+                    stack.push(Frame::new(Some(child), inner));
+                }
+                Control::SkipChildren => {
+                    visitor.exit_node(&mut child);
+                    frame.done.push(child);
+                }
+                Control::Stop => {
+                    frame.done.push(child);
+                    break;
+                }
+            }
+        } else {
+            let frame = stack.pop().unwrap();
+            if let Some(mut node) = frame.node {
+                node.inner = frame.done;
+                visitor.exit_node(&mut node);
+                stack.last_mut().unwrap().done.push(node);
+            } else {
+                root.inner = frame.done;
+                visitor.exit_node(root);
+            }
+        }
+    }
+    // Stopped early. Put back together whatever had been taken apart, without
+    // any more callbacks.
+    while let Some(frame) = stack.pop() {
+        let mut inner = frame.done;
+        inner.extend(frame.pending);
+        match frame.node {
+            Some(mut node) => {
+                node.inner = inner;
+                stack.last_mut().unwrap().done.push(node);
+            }
+            None => root.inner = inner,
+        }
+    }
+}
@@ -0,0 +1,70 @@
+use clang_ast::{Filter, Kind, WithDepth, WithParent};
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+pub struct Clang {
+    pub kind: Kind,
+}
+
+const JSON: &str = r#"
+    {
+      "id": "0x1",
+      "kind": "TranslationUnitDecl",
+      "inner": [
+        {
+          "id": "0x2",
+          "kind": "NamespaceDecl",
+          "inner": [
+            { "id": "0x3", "kind": "EnumDecl" }
+          ]
+        },
+        { "id": "0x4", "kind": "FunctionDecl" }
+      ]
+    }
+"#;
+
+#[test]
+fn test_with_parent() {
+    let node: clang_ast::Node<WithParent<Clang>> = serde_json::from_str(JSON).unwrap();
+    assert_eq!(node.kind.parent, None);
+    assert_eq!(node.inner[0].kind.parent, Some(node.id));
+    assert_eq!(node.inner[0].inner[0].kind.parent, Some(node.inner[0].id));
+    assert_eq!(node.inner[1].kind.parent, Some(node.id));
+    assert_eq!(node.inner[1].kind.kind.kind, Kind::FunctionDecl);
+}
+
+#[test]
+fn test_with_depth() {
+    let node: clang_ast::Node<WithDepth<Clang>> = serde_json::from_str(JSON).unwrap();
+    assert_eq!(node.kind.depth, 0);
+    assert_eq!(node.inner[0].kind.depth, 1);
+    assert_eq!(node.inner[0].inner[0].kind.depth, 2);
+    assert_eq!(node.inner[1].kind.depth, 1);
+}
+
+#[test]
+fn test_combined() {
+    type Node = clang_ast::Node<WithDepth<WithParent<Clang>>>;
+    let node: Node = serde_json::from_str(JSON).unwrap();
+    let enumeration = &node.inner[0].inner[0].kind;
+    assert_eq!(enumeration.depth, 2);
+    assert_eq!(enumeration.kind.parent, Some(node.inner[0].id));
+    assert_eq!(enumeration.kind.kind.kind, Kind::EnumDecl);
+}
+
+#[test]
+fn test_filtered() {
+    let filter = Filter::new();
+    let mut deserializer = serde_json::Deserializer::from_str(JSON);
+    let node = filter
+        .deserialize::<WithParent<Clang>, _>(&mut deserializer)
+        .unwrap();
+    assert_eq!(node.kind.parent, None);
+    assert_eq!(node.inner[0].inner[0].kind.parent, Some(node.inner[0].id));
+    assert_eq!(node.inner[1].kind.parent, Some(node.id));
+
+    // Nothing is left behind for the next tree.
+    let node: clang_ast::Node<WithDepth<Clang>> = serde_json::from_str(JSON).unwrap();
+    assert_eq!(node.kind.depth, 0);
+    assert_eq!(node.inner[0].inner[0].kind.depth, 2);
+}
@@ -0,0 +1,82 @@
+use clang_ast::{Ast, AstNode, Kind, NodeId};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::mem;
+
+pub type Node = clang_ast::Node<Clang>;
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct Clang {
+    pub kind: Kind,
+}
+
+const JSON: &str = r#"
+    {
+      "id": "0x1",
+      "kind": "TranslationUnitDecl",
+      "inner": [
+        {
+          "id": "0x2",
+          "kind": "NamespaceDecl",
+          "inner": [
+            { "id": "0x3", "kind": "EnumDecl" },
+            { "id": "0x4", "kind": "CXXRecordDecl" }
+          ]
+        },
+        { "id": "0x5", "kind": "FunctionDecl" }
+      ]
+    }
+"#;
+
+fn check(ast: &Ast<Clang>) {
+    assert_eq!(ast.len(), 5);
+
+    let root = ast.root();
+    assert_eq!(ast[root].id.to_string(), "0x1");
+    assert_eq!(ast.parent(root), None);
+
+    let children: Vec<String> = ast
+        .children(root)
+        .iter()
+        .map(|&child| ast[child].id.to_string())
+        .collect();
+    assert_eq!(children, ["0x2", "0x5"]);
+
+    let namespace = ast.children(root)[0];
+    let record = ast.children(namespace)[1];
+    assert_eq!(ast[record].kind.kind, Kind::CXXRecordDecl);
+    assert_eq!(ast[record].parent(), Some(namespace));
+    assert!(ast.children(record).is_empty());
+
+    let ancestors: Vec<NodeId> = ast.ancestors(record).collect();
+    assert_eq!(ancestors, [namespace, root]);
+
+    let post_order: Vec<String> = ast.iter().map(|(_id, node)| node.id.to_string()).collect();
+    assert_eq!(post_order, ["0x3", "0x4", "0x2", "0x5", "0x1"]);
+}
+
+#[test]
+fn test_deserialize() {
+    let ast: Ast<Clang> = serde_json::from_str(JSON).unwrap();
+    check(&ast);
+}
+
+#[test]
+fn test_from_node() {
+    let node: Node = serde_json::from_str(JSON).unwrap();
+    check(&Ast::from(node));
+}
+
+#[test]
+fn test_serialize() {
+    let ast: Ast<Clang> = serde_json::from_str(JSON).unwrap();
+    let expected: Value = serde_json::from_str(JSON).unwrap();
+    assert_eq!(serde_json::to_value(&ast).unwrap(), expected);
+}
+
+#[test]
+fn test_size() {
+    assert_eq!(mem::size_of::<AstNode<()>>(), 16);
+    let node_size = if cfg!(feature = "thin-inner") { 16 } else { 32 };
+    assert_eq!(mem::size_of::<clang_ast::Node<()>>(), node_size);
+}
@@ -0,0 +1,92 @@
+use clang_ast::{Ast, Filter, Kind, SourceRange};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+pub type Node = clang_ast::Node<Clang>;
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct Clang {
+    pub kind: Kind,
+    #[serde(default)]
+    pub range: SourceRange,
+}
+
+const JSON: &str = r#"
+    {
+      "id": "0x1",
+      "kind": "TranslationUnitDecl",
+      "inner": [
+        {
+          "id": "0x2",
+          "kind": "FunctionDecl",
+          "isImplicit": true,
+          "range": {
+            "begin": { "offset": 0, "file": "a.cc", "line": 1, "col": 1, "tokLen": 4 },
+            "end": { "offset": 12, "col": 13, "tokLen": 1 }
+          }
+        },
+        {
+          "id": "0x3",
+          "kind": "NotYetKnownDecl",
+          "range": {
+            "begin": { "offset": 15, "line": 2, "col": 1, "tokLen": 4 },
+            "end": { "offset": 0, "file": "b.h", "line": 1, "col": 5, "tokLen": 1 }
+          },
+          "inner": [
+            {
+              "id": "0x4",
+              "kind": "ParmVarDecl",
+              "range": {
+                "begin": { "offset": 3, "col": 4, "tokLen": 1 },
+                "end": { "offset": 5, "line": 2, "col": 2, "tokLen": 1 }
+              }
+            }
+          ]
+        }
+      ]
+    }
+"#;
+
+fn reserialize<T: Serialize>(value: &T) -> Value {
+    serde_json::to_value(value).unwrap()
+}
+
+#[test]
+fn test_transient_strings() {
+    // Reading from io::Read, no string is borrowed from the input.
+    let expected: Node = serde_json::from_str(JSON).unwrap();
+    let node: Node = serde_json::from_reader(JSON.as_bytes()).unwrap();
+    assert_eq!(reserialize(&node), reserialize(&expected));
+
+    // The location omitting "file" and "line" picks them up from the
+    // previous location, including across sibling and parent nodes.
+    let parm = &node.inner[1].inner[0].kind.range;
+    let begin = parm.begin.expansion().unwrap();
+    assert_eq!((&*begin.file, begin.line), ("b.h", 1));
+    let end = parm.end.expansion().unwrap();
+    assert_eq!((&*end.file, end.line), ("b.h", 2));
+    assert_eq!(node.inner[1].kind.kind.as_str(), "NotYetKnownDecl");
+
+    let ast: Ast<Clang> = serde_json::from_reader(JSON.as_bytes()).unwrap();
+    assert_eq!(ast.len(), 4);
+
+    let mut filter = Filter::new();
+    filter
+        .exclude_implicit()
+        .exclude_file(|path| path == "a.cc");
+    let mut deserializer = serde_json::Deserializer::from_reader(JSON.as_bytes());
+    let node: Node = filter.deserialize(&mut deserializer).unwrap();
+    assert!(node.inner.is_empty());
+}
+
+#[test]
+fn test_reordered_fields() {
+    // Without serde_json's "preserve_order" feature, a Value sorts the
+    // fields of every object by key.
+    let value: Value = serde_json::from_str(JSON).unwrap();
+    let err = Node::deserialize(&value).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "node field `inner` comes before `kind`; deserialize nodes from a format that preserves the order of fields",
+    );
+}
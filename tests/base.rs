@@ -0,0 +1,29 @@
+use clang_ast::{Access, Base};
+
+#[test]
+fn test_base() {
+    let json =
+        r#"{"access":"public","isVirtual":true,"type":{"qualType":"B"},"writtenAccess":"public"}"#;
+    let base: Base = serde_json::from_str(json).unwrap();
+    assert_eq!(base.access, Access::Public);
+    assert_eq!(base.written_access, Some(Access::Public));
+    assert!(base.is_virtual);
+    assert!(!base.is_pack_expansion);
+    assert_eq!(base.ty.qual_type, "B");
+    assert_eq!(serde_json::to_string(&base).unwrap(), json);
+
+    let json = r#"{"access":"private","isPackExpansion":true,"type":{"qualType":"Ts"},"writtenAccess":"none"}"#;
+    let base: Base = serde_json::from_str(json).unwrap();
+    assert_eq!(base.access, Access::Private);
+    assert_eq!(base.written_access, None);
+    assert!(!base.is_virtual);
+    assert!(base.is_pack_expansion);
+    assert_eq!(serde_json::to_string(&base).unwrap(), json);
+
+    let error = serde_json::from_str::<Base>(r#"{"type":{"qualType":"B"}}"#).unwrap_err();
+    assert!(error.to_string().starts_with("missing field `access`"));
+
+    let json = r#"{"access":"none","type":{"qualType":"B"}}"#;
+    let error = serde_json::from_str::<Base>(json).unwrap_err();
+    assert!(error.to_string().starts_with("unknown variant `none`"));
+}
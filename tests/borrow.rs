@@ -0,0 +1,79 @@
+use clang_ast::{Ast, Filter};
+use serde::Deserialize;
+use std::borrow::Cow;
+
+#[derive(Deserialize)]
+pub enum Clang<'a> {
+    NamespaceDecl {
+        name: &'a str,
+    },
+    FunctionDecl {
+        #[serde(borrow)]
+        name: Cow<'a, str>,
+    },
+    Other,
+}
+
+const JSON: &str = r#"
+    {
+      "id": "0x1",
+      "kind": "TranslationUnitDecl",
+      "inner": [
+        {
+          "id": "0x2",
+          "kind": "NamespaceDecl",
+          "name": "ns",
+          "inner": [
+            { "id": "0x3", "kind": "FunctionDecl", "name": "f" },
+            { "id": "0x4", "kind": "FunctionDecl", "name": "operator\"\"_x" }
+          ]
+        }
+      ]
+    }
+"#;
+
+fn check(kinds: &[&Clang]) {
+    match kinds[1] {
+        Clang::NamespaceDecl { name } => assert_eq!(*name, "ns"),
+        _ => panic!(),
+    }
+    match kinds[2] {
+        Clang::FunctionDecl {
+            name: Cow::Borrowed(name),
+        } => assert_eq!(*name, "f"),
+        _ => panic!(),
+    }
+    match kinds[3] {
+        Clang::FunctionDecl {
+            name: Cow::Owned(name),
+        } => assert_eq!(name, "operator\"\"_x"),
+        _ => panic!(),
+    }
+}
+
+#[test]
+fn test_borrow_node() {
+    let node: clang_ast::Node<Clang> = serde_json::from_str(JSON).unwrap();
+    let kinds: Vec<_> = node.descendants().map(|node| &node.kind).collect();
+    check(&kinds);
+}
+
+#[test]
+fn test_borrow_ast() {
+    let ast: Ast<Clang> = serde_json::from_str(JSON).unwrap();
+    let mut kinds: Vec<_> = ast.iter().map(|(_id, node)| &node.kind).collect();
+    // Post-order to pre-order for this particular tree shape.
+    kinds.reverse();
+    kinds.swap(2, 3);
+    check(&kinds);
+}
+
+#[test]
+fn test_borrow_filter() {
+    let mut filter = Filter::new();
+    filter.exclude_file(|_| true).exclude_implicit();
+    let mut deserializer = serde_json::Deserializer::from_str(JSON);
+    let node: clang_ast::Node<Clang> = filter.deserialize(&mut deserializer).unwrap();
+    let kinds: Vec<_> = node.descendants().map(|node| &node.kind).collect();
+    check(&kinds);
+}
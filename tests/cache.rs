@@ -0,0 +1,159 @@
+#![cfg(feature = "cache")]
+
+use clang_ast::{Kind, SourceRange};
+use serde::{Deserialize, Serialize};
+
+pub type Node<'a> = clang_ast::Node<Clang<'a>>;
+
+#[derive(Deserialize, Serialize, Debug)]
+pub struct Clang<'a> {
+    pub kind: Kind,
+    pub range: Option<SourceRange>,
+    pub name: Option<&'a str>,
+    pub extra: Option<Extra>,
+}
+
+#[derive(Deserialize, Serialize, Debug, PartialEq)]
+pub enum Extra {
+    Unit,
+    Newtype(i64),
+    Tuple(u128, char),
+    Struct { ratio: f64, bytes: Vec<u8> },
+}
+
+const JSON: &str = r#"
+    {
+      "id": "0x1",
+      "kind": "TranslationUnitDecl",
+      "inner": [
+        {
+          "id": "0x2",
+          "kind": "FunctionDecl",
+          "name": "f",
+          "range": {
+            "begin": { "offset": 0, "file": "a.cc", "line": 1, "col": 1, "tokLen": 4 },
+            "end": { "offset": 12, "col": 13, "tokLen": 1 }
+          },
+          "extra": "Unit"
+        },
+        {
+          "id": "0x3",
+          "kind": "FunctionDecl",
+          "name": "g",
+          "range": {
+            "begin": { "offset": 15, "line": 2, "col": 1, "tokLen": 4 },
+            "end": { "offset": 0, "file": "b.h", "line": 1, "col": 5, "tokLen": 1 }
+          },
+          "extra": { "Newtype": -7 }
+        },
+        {
+          "id": "0x4",
+          "kind": "HypotheticalFutureDecl",
+          "extra": { "Tuple": [340282366920938463463374607431768211455, "c"] }
+        },
+        {
+          "id": "0x5",
+          "kind": "VarDecl",
+          "extra": { "Struct": { "ratio": 0.5, "bytes": [1, 2, 3] } }
+        }
+      ]
+    }
+"#;
+
+fn locs(node: &Node) -> Vec<(String, usize, usize)> {
+    node.descendants()
+        .filter_map(|node| node.kind.range.as_ref())
+        .flat_map(|range| vec![&range.begin, &range.end])
+        .filter_map(|loc| loc.expansion_loc.as_ref())
+        .map(|loc| (loc.file.to_string(), loc.line, loc.col))
+        .collect()
+}
+
+#[test]
+fn test_round_trip() {
+    let node: Node = serde_json::from_str(JSON).unwrap();
+    let bytes = clang_ast::cache::to_vec(&node).unwrap();
+    assert!(bytes.len() < serde_json::to_string(&node).unwrap().len());
+
+    let cached: Node = clang_ast::cache::from_slice(&bytes).unwrap();
+    assert_eq!(locs(&cached), locs(&node));
+    assert_eq!(
+        locs(&cached),
+        [
+            ("a.cc".to_owned(), 1, 1),
+            ("a.cc".to_owned(), 1, 13),
+            ("a.cc".to_owned(), 2, 1),
+            ("b.h".to_owned(), 1, 5),
+        ],
+    );
+    let ids: Vec<_> = cached.descendants().map(|node| node.id).collect();
+    let expected: Vec<_> = node.descendants().map(|node| node.id).collect();
+    assert_eq!(ids, expected);
+
+    let kinds: Vec<_> = cached.inner.iter().map(|node| &node.kind.kind).collect();
+    assert_eq!(kinds[0], &Kind::FunctionDecl);
+    assert_eq!(kinds[2], &Kind::Other(Box::from("HypotheticalFutureDecl")));
+    assert_eq!(cached.inner[1].kind.name, Some("g"));
+
+    let extras: Vec<_> = cached
+        .inner
+        .iter()
+        .map(|node| node.kind.extra.as_ref().unwrap())
+        .collect();
+    assert_eq!(*extras[0], Extra::Unit);
+    assert_eq!(*extras[1], Extra::Newtype(-7));
+    assert_eq!(*extras[2], Extra::Tuple(u128::MAX, 'c'));
+    assert_eq!(
+        *extras[3],
+        Extra::Struct {
+            ratio: 0.5,
+            bytes: vec![1, 2, 3],
+        },
+    );
+    assert!(cached.kind.extra.is_none());
+}
+
+#[test]
+fn test_ignored() {
+    #[derive(Deserialize)]
+    pub enum Clang {
+        FunctionDecl { name: String },
+        Other,
+    }
+
+    let node: Node = serde_json::from_str(JSON).unwrap();
+    let bytes = clang_ast::cache::to_vec(&node).unwrap();
+    let cached: clang_ast::Node<Clang> = clang_ast::cache::from_slice(&bytes).unwrap();
+    let names: Vec<_> = cached
+        .inner
+        .iter()
+        .filter_map(|node| match &node.kind {
+            Clang::FunctionDecl { name } => Some(name.as_str()),
+            Clang::Other => None,
+        })
+        .collect();
+    assert_eq!(names, ["f", "g"]);
+}
+
+#[test]
+fn test_header() {
+    let node: Node = serde_json::from_str(JSON).unwrap();
+    let bytes = clang_ast::cache::to_vec(&node).unwrap();
+
+    let error = clang_ast::cache::from_slice::<Clang>(b"{\"id\":\"0x1\"}").unwrap_err();
+    assert_eq!(error.to_string(), "not a clang-ast cache");
+
+    let mut other_version = bytes.clone();
+    other_version[8] = 0xff;
+    let error = clang_ast::cache::from_slice::<Clang>(&other_version).unwrap_err();
+    assert!(error.to_string().starts_with("cache format version 255 "));
+
+    let truncated = &bytes[..bytes.len() - 1];
+    let error = clang_ast::cache::from_slice::<Clang>(truncated).unwrap_err();
+    assert_eq!(error.to_string(), "unexpected end of cache");
+
+    let mut trailing = bytes;
+    trailing.push(0);
+    let error = clang_ast::cache::from_slice::<Clang>(&trailing).unwrap_err();
+    assert_eq!(error.to_string(), "trailing bytes after cached syntax tree");
+}
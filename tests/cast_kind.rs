@@ -0,0 +1,62 @@
+use clang_ast::CastKind;
+use serde::Deserialize;
+
+pub type Node = clang_ast::Node<Clang>;
+
+#[derive(Deserialize)]
+pub enum Clang {
+    ImplicitCastExpr {
+        #[serde(rename = "castKind")]
+        cast_kind: CastKind,
+    },
+    CXXStaticCastExpr {
+        #[serde(rename = "castKind")]
+        cast_kind: CastKind,
+    },
+    Other,
+}
+
+#[test]
+fn test_cast_kind() {
+    let json = r#"
+        {
+          "id": "0x1",
+          "kind": "CXXStaticCastExpr",
+          "castKind": "NoOp",
+          "inner": [
+            { "id": "0x2", "kind": "ImplicitCastExpr", "castKind": "LValueToRValue" },
+            { "id": "0x3", "kind": "ImplicitCastExpr", "castKind": "FutureCast" }
+          ]
+        }
+    "#;
+    let node: Node = serde_json::from_str(json).unwrap();
+    let kinds: Vec<&CastKind> = std::iter::once(&node)
+        .chain(&node.inner)
+        .map(|node| match &node.kind {
+            Clang::ImplicitCastExpr { cast_kind } | Clang::CXXStaticCastExpr { cast_kind } => {
+                cast_kind
+            }
+            Clang::Other => panic!("expected cast"),
+        })
+        .collect();
+    assert_eq!(*kinds[0], CastKind::NoOp);
+    assert_eq!(*kinds[1], CastKind::LValueToRValue);
+    assert_eq!(*kinds[2], CastKind::Other(Box::from("FutureCast")));
+    assert_eq!(kinds[2].as_str(), "FutureCast");
+}
+
+#[test]
+fn test_round_trip() {
+    for name in &[
+        "ArrayToPointerDecay",
+        "ARCProduceObject",
+        "HLSLVectorTruncation",
+        "UncheckedDerivedToBase",
+        "SomethingNew",
+    ] {
+        let json = serde_json::to_string(name).unwrap();
+        let kind: CastKind = serde_json::from_str(&json).unwrap();
+        assert_eq!(kind.to_string(), *name);
+        assert_eq!(serde_json::to_string(&kind).unwrap(), json);
+    }
+}
@@ -0,0 +1,187 @@
+use clang_ast::{Access, ClassHierarchy, Id};
+
+const JSON: &str = r#"
+    {
+      "id": "0x1",
+      "kind": "TranslationUnitDecl",
+      "inner": [
+        {
+          "id": "0x2",
+          "kind": "NamespaceDecl",
+          "name": "ns",
+          "inner": [
+            {
+              "id": "0x10",
+              "kind": "CXXRecordDecl",
+              "name": "Base",
+              "tagUsed": "struct",
+              "completeDefinition": true,
+              "inner": [
+                { "id": "0x11", "kind": "CXXRecordDecl", "isImplicit": true, "name": "Base", "tagUsed": "struct" }
+              ]
+            },
+            {
+              "id": "0x12",
+              "kind": "ClassTemplateDecl",
+              "name": "Mixin",
+              "inner": [
+                {
+                  "id": "0x13",
+                  "kind": "CXXRecordDecl",
+                  "name": "Mixin",
+                  "tagUsed": "class",
+                  "completeDefinition": true
+                }
+              ]
+            }
+          ]
+        },
+        { "id": "0x20", "kind": "CXXRecordDecl", "name": "Left", "tagUsed": "struct" },
+        {
+          "id": "0x21",
+          "kind": "CXXRecordDecl",
+          "name": "Left",
+          "tagUsed": "struct",
+          "completeDefinition": true,
+          "bases": [
+            {
+              "access": "public",
+              "type": { "qualType": "ns::Base" },
+              "writtenAccess": "none",
+              "isVirtual": true
+            }
+          ]
+        },
+        {
+          "id": "0x22",
+          "kind": "CXXRecordDecl",
+          "name": "Right",
+          "tagUsed": "class",
+          "completeDefinition": true,
+          "bases": [
+            {
+              "access": "protected",
+              "type": { "qualType": "Base", "desugaredQualType": "ns::Base" },
+              "writtenAccess": "protected",
+              "isVirtual": true
+            },
+            {
+              "access": "private",
+              "type": { "qualType": "ns::Mixin<int>" },
+              "writtenAccess": "none"
+            }
+          ]
+        },
+        {
+          "id": "0x23",
+          "kind": "CXXRecordDecl",
+          "name": "Bottom",
+          "tagUsed": "struct",
+          "completeDefinition": true,
+          "bases": [
+            { "access": "public", "type": { "qualType": "Left" }, "writtenAccess": "none" },
+            { "access": "public", "type": { "qualType": "Right" }, "writtenAccess": "public" },
+            { "access": "public", "type": { "qualType": "std::exception" }, "writtenAccess": "none" }
+          ]
+        },
+        {
+          "id": "0x30",
+          "kind": "FunctionDecl",
+          "name": "f",
+          "inner": [
+            {
+              "id": "0x31",
+              "kind": "CompoundStmt",
+              "inner": [
+                {
+                  "id": "0x32",
+                  "kind": "DeclStmt",
+                  "inner": [
+                    {
+                      "id": "0x33",
+                      "kind": "CXXRecordDecl",
+                      "name": "Base",
+                      "tagUsed": "struct",
+                      "completeDefinition": true
+                    }
+                  ]
+                }
+              ]
+            }
+          ]
+        }
+      ]
+    }
+"#;
+
+fn names(classes: Vec<&clang_ast::Class>) -> Vec<&str> {
+    classes.into_iter().map(|class| &*class.name).collect()
+}
+
+fn id(id: u64) -> Id {
+    Id::from_u64(id).unwrap()
+}
+
+#[test]
+fn test_class_hierarchy() {
+    let hierarchy: ClassHierarchy = serde_json::from_str(JSON).unwrap();
+    let all: Vec<&str> = hierarchy
+        .classes()
+        .iter()
+        .map(|class| &*class.name)
+        .collect();
+    assert_eq!(
+        all,
+        ["ns::Base", "ns::Mixin", "Left", "Right", "Bottom", "Base"]
+    );
+
+    let base = hierarchy.find("ns::Base").unwrap();
+    assert_eq!(base.id, id(0x10));
+    assert_eq!(hierarchy.find("::ns::Base").unwrap().id, base.id);
+    assert!(hierarchy.find("Base").is_none());
+    assert!(hierarchy.get(id(0x33)).is_some());
+
+    let right = hierarchy.bases(id(0x22));
+    assert_eq!(right.len(), 2);
+    assert_eq!(right[0].class, Some(base.id));
+    assert_eq!(right[0].access, Access::Protected);
+    assert!(right[0].is_virtual);
+    assert_eq!(right[1].class, Some(id(0x13)));
+    assert_eq!(right[1].access, Access::Private);
+    assert!(!right[1].is_virtual);
+
+    let bottom = hierarchy.bases(id(0x23));
+    assert_eq!(bottom[2].class, None);
+    assert_eq!(bottom[2].ty.qual_type, "std::exception");
+
+    let derived: Vec<&str> = hierarchy
+        .derived_of(base.id)
+        .map(|class| &*class.name)
+        .collect();
+    assert_eq!(derived, ["Left", "Right"]);
+
+    assert_eq!(
+        names(hierarchy.all_bases(id(0x23))),
+        ["Left", "Right", "ns::Base", "ns::Mixin"],
+    );
+    assert_eq!(
+        names(hierarchy.all_subclasses(base.id)),
+        ["Left", "Right", "Bottom"],
+    );
+    assert!(hierarchy.all_bases(id(0x99)).is_empty());
+}
+
+#[test]
+fn test_bad_access() {
+    let json = r#"
+        {
+          "id": "0x1",
+          "kind": "CXXRecordDecl",
+          "name": "S",
+          "completeDefinition": true,
+          "bases": [{ "access": "friendly", "type": { "qualType": "T" } }]
+        }
+    "#;
+    let error = serde_json::from_str::<ClassHierarchy>(json).unwrap_err();
+    assert!(error.to_string().starts_with("unknown variant `friendly`"));
+}
@@ -0,0 +1,96 @@
+#[allow(dead_code, clippy::large_enum_variant)]
+#[path = "codegen/clang.rs"]
+mod clang;
+
+use clang_ast::Schema;
+
+const JSON: &str = r#"
+    {
+      "id": "0x1",
+      "kind": "TranslationUnitDecl",
+      "inner": [
+        {
+          "id": "0x2",
+          "kind": "FunctionDecl",
+          "loc": {
+            "offset": 5, "file": "main.c", "line": 1, "col": 6, "tokLen": 4
+          },
+          "range": {
+            "begin": { "offset": 0, "col": 1, "tokLen": 3 },
+            "end": { "offset": 19, "col": 20, "tokLen": 1 }
+          },
+          "name": "main",
+          "type": { "qualType": "int (void)" },
+          "inner": [
+            {
+              "id": "0x3",
+              "kind": "IntegerLiteral",
+              "valueCategory": "prvalue",
+              "value": "0"
+            }
+          ]
+        },
+        {
+          "id": "0x4",
+          "kind": "FunctionDecl",
+          "loc": {},
+          "range": { "begin": {}, "end": {} },
+          "isImplicit": true,
+          "name": "__builtin_va_start",
+          "type": { "qualType": "void (__builtin_va_list &, ...)" },
+          "storageClass": "extern"
+        },
+        {
+          "id": "0x5",
+          "kind": "CXXRecordDecl",
+          "name": "S",
+          "tagUsed": "struct",
+          "definitionData": { "isAggregate": true, "copyCtor": { "simple": true, "trivial": true } },
+          "bases": [
+            { "access": "public", "type": { "qualType": "B" }, "writtenAccess": "none" }
+          ]
+        }
+      ]
+    }
+"#;
+
+#[test]
+fn test_to_rust() {
+    let schema: Schema = serde_json::from_str(JSON).unwrap();
+    assert_eq!(schema.to_rust(), include_str!("codegen/clang.rs"));
+}
+
+#[test]
+fn test_generated() {
+    let node: clang::Node = serde_json::from_str(JSON).unwrap();
+    assert!(matches!(node.kind, clang::Clang::TranslationUnitDecl));
+    let main = match &node.inner[0].kind {
+        clang::Clang::FunctionDecl(decl) => decl,
+        _ => panic!("expected FunctionDecl"),
+    };
+    assert_eq!(main.name, "main");
+    assert!(!main.is_implicit);
+    assert_eq!(main.storage_class, None);
+    assert_eq!(main.loc.spelling_loc.as_ref().unwrap().line, 1);
+    match &node.inner[0].inner[0].kind {
+        clang::Clang::IntegerLiteral(literal) => {
+            assert_eq!(literal.value_category, clang_ast::ValueCategory::PRValue);
+        }
+        _ => panic!("expected IntegerLiteral"),
+    }
+    let builtin = match &node.inner[1].kind {
+        clang::Clang::FunctionDecl(decl) => decl,
+        _ => panic!("expected FunctionDecl"),
+    };
+    assert!(builtin.is_implicit);
+    assert_eq!(builtin.storage_class, Some(clang_ast::StorageClass::Extern));
+    let record = match &node.inner[2].kind {
+        clang::Clang::CXXRecordDecl(decl) => decl,
+        _ => panic!("expected CXXRecordDecl"),
+    };
+    assert_eq!(record.tag_used, clang_ast::TagKind::Struct);
+    assert_eq!(record.bases[0].access, clang_ast::Access::Public);
+    assert_eq!(record.bases[0].ty.qual_type, "B");
+    assert!(record.definition_data.is_aggregate);
+    assert!(record.definition_data.copy_ctor.unwrap().trivial);
+}
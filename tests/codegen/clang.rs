@@ -0,0 +1,42 @@
+use serde::Deserialize;
+
+pub type Node = clang_ast::Node<Clang>;
+
+#[derive(Deserialize, Debug)]
+pub enum Clang {
+    CXXRecordDecl(CXXRecordDecl),
+    FunctionDecl(FunctionDecl),
+    IntegerLiteral(IntegerLiteral),
+    TranslationUnitDecl,
+    Other,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct CXXRecordDecl {
+    pub bases: Vec<clang_ast::Base>,
+    #[serde(rename = "definitionData")]
+    pub definition_data: clang_ast::DefinitionData,
+    pub name: String,
+    #[serde(rename = "tagUsed")]
+    pub tag_used: clang_ast::TagKind,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct FunctionDecl {
+    #[serde(rename = "isImplicit")]
+    #[serde(default)]
+    pub is_implicit: bool,
+    pub loc: clang_ast::SourceLocation,
+    pub name: String,
+    pub range: clang_ast::SourceRange,
+    #[serde(rename = "storageClass")]
+    pub storage_class: Option<clang_ast::StorageClass>,
+    pub r#type: clang_ast::Type,
+}
+
+#[derive(Deserialize, Debug)]
+pub struct IntegerLiteral {
+    pub value: String,
+    #[serde(rename = "valueCategory")]
+    pub value_category: clang_ast::ValueCategory,
+}
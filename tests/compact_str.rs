@@ -0,0 +1,21 @@
+#![cfg(feature = "compact-str")]
+
+use clang_ast::Type;
+use compact_str::CompactString;
+
+#[test]
+fn test_compact_str() {
+    let json =
+        r#"{"qualType":"std::size_t","desugaredQualType":"std::vector<std::basic_string<char>>"}"#;
+    let ty: Type = serde_json::from_str(json).unwrap();
+
+    let qual_type: &CompactString = &ty.qual_type;
+    assert_eq!(qual_type, "std::size_t");
+    assert!(!qual_type.is_heap_allocated());
+
+    let desugared = ty.desugared_qual_type.as_ref().unwrap();
+    assert_eq!(desugared, "std::vector<std::basic_string<char>>");
+    assert!(desugared.is_heap_allocated());
+
+    assert_eq!(serde_json::to_string(&ty).unwrap(), json);
+}
@@ -0,0 +1,112 @@
+#![cfg(all(feature = "compile-commands", unix))]
+
+use clang_ast::compile_commands::{self, CompileCommand, Driver, Error};
+use clang_ast::Kind;
+use serde::Deserialize;
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::PathBuf;
+
+#[derive(Deserialize)]
+pub struct Clang {
+    pub kind: Kind,
+    pub name: Option<String>,
+}
+
+#[test]
+fn test_parse() {
+    let json = r#"[
+        {
+          "directory": "/build",
+          "file": "a.cc",
+          "arguments": ["clang++", "-c", "a.cc", "-o", "a.o"],
+          "output": "a.o"
+        },
+        {
+          "directory": "/build",
+          "file": "b.cc",
+          "command": "/usr/bin/c++ -DNAME=\"x y\" -I'dir with space' -c b\\ c.cc"
+        }
+    ]"#;
+    let commands = compile_commands::parse(json.as_bytes()).unwrap();
+    assert_eq!(
+        commands[0],
+        CompileCommand {
+            directory: PathBuf::from("/build"),
+            file: PathBuf::from("a.cc"),
+            arguments: vec![
+                "clang++".to_owned(),
+                "-c".to_owned(),
+                "a.cc".to_owned(),
+                "-o".to_owned(),
+                "a.o".to_owned(),
+            ],
+            output: Some(PathBuf::from("a.o")),
+        },
+    );
+    assert_eq!(
+        commands[1].arguments,
+        [
+            "/usr/bin/c++",
+            "-DNAME=x y",
+            "-Idir with space",
+            "-c",
+            "b c.cc"
+        ],
+    );
+
+    let missing = compile_commands::parse(br#"[{"directory": "/build"}]"#);
+    assert!(missing.is_err());
+}
+
+// Stands in for clang: dumps a FunctionDecl named after the source file, or
+// fails with a diagnostic for bad.c. Checks that the dump flags come last.
+const FAKE_CLANG: &str = r#"#!/bin/sh
+if [ "$2 $3 $4" != "-Xclang -ast-dump=json -fsyntax-only" ]; then
+    echo "unexpected arguments: $*" >&2
+    exit 2
+fi
+if [ "$1" = bad.c ]; then
+    echo "bad.c:1:1: error: unknown type name 'x'" >&2
+    exit 1
+fi
+echo "{\"id\":\"0x1\",\"kind\":\"FunctionDecl\",\"name\":\"$1\"}"
+"#;
+
+#[test]
+fn test_driver() {
+    let dir = std::env::temp_dir().join(format!("clang-ast-compdb-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let clang = dir.join("fake-clang");
+    fs::write(&clang, FAKE_CLANG).unwrap();
+    fs::set_permissions(&clang, fs::Permissions::from_mode(0o755)).unwrap();
+
+    let commands: Vec<CompileCommand> = ["a.c", "bad.c", "b.c"]
+        .iter()
+        .map(|file| CompileCommand {
+            directory: dir.clone(),
+            file: PathBuf::from(file),
+            arguments: vec!["gcc".to_owned(), (*file).to_owned()],
+            output: None,
+        })
+        .collect();
+    let mut driver = Driver::new();
+    driver.clang(&clang).jobs(2);
+    let mut results: Vec<_> = driver.run::<Clang>(commands).collect();
+    fs::remove_dir_all(&dir).unwrap();
+
+    results.sort_by_key(|(i, _)| *i);
+    assert_eq!(results.len(), 3);
+    let a = results[0].1.as_ref().unwrap();
+    assert_eq!(a.kind.kind, Kind::FunctionDecl);
+    assert_eq!(a.kind.name.as_deref(), Some("a.c"));
+    match &results[1].1 {
+        Err(Error::Clang { status, stderr }) => {
+            assert_eq!(status.code(), Some(1));
+            assert!(stderr.contains("unknown type name"));
+        }
+        _ => panic!("expected clang to fail"),
+    }
+    let b = results[2].1.as_ref().unwrap();
+    assert_eq!(b.kind.name.as_deref(), Some("b.c"));
+}
@@ -0,0 +1,99 @@
+use clang_ast::ConstantValue;
+
+fn parse(text: &str) -> ConstantValue {
+    ConstantValue::parse(text).unwrap()
+}
+
+#[test]
+fn test_scalars() {
+    assert_eq!(parse("-42").as_integer().unwrap().as_i64(), Some(-42));
+    let wide = parse("340282366920938463463374607431768211455");
+    assert_eq!(wide.as_integer().unwrap().as_u128(), Some(u128::MAX));
+    assert_eq!(parse("true").as_bool(), Some(true));
+    assert_eq!(parse("false").as_bool(), Some(false));
+    assert_eq!(parse("1.500000e+00").as_f64(), Some(1.5));
+    assert_eq!(parse("inf").as_f64(), Some(f64::INFINITY));
+    assert_eq!(parse("&global"), ConstantValue::Other("&global".to_owned()));
+    assert_eq!(parse("'a'"), ConstantValue::Other("'a'".to_owned()));
+}
+
+#[test]
+fn test_aggregates() {
+    let value = parse("{1, {2.000000e+00, true}, &arr[1], {}}");
+    let elements = value.elements().unwrap();
+    assert_eq!(elements.len(), 4);
+    assert_eq!(elements[0].as_integer().unwrap().as_i64(), Some(1));
+    let inner = elements[1].elements().unwrap();
+    assert_eq!(inner[0].as_f64(), Some(2.0));
+    assert_eq!(inner[1].as_bool(), Some(true));
+    assert_eq!(elements[2], ConstantValue::Other("&arr[1]".to_owned()));
+    assert_eq!(elements[3], ConstantValue::Aggregate(Vec::new()));
+
+    let truncated = parse("{0, 1, 2, ...}");
+    assert_eq!(truncated.elements().unwrap()[3], ConstantValue::Elided);
+
+    let quoted = parse(r#"{&"a, }"[0], 'b'}"#);
+    assert_eq!(quoted.elements().unwrap().len(), 2);
+
+    let template = parse("{&S<1, 2>::value, 3}");
+    assert_eq!(template.elements().unwrap().len(), 2);
+}
+
+#[test]
+fn test_union() {
+    let value = parse("{.f = {1, 2}}");
+    match &value {
+        ConstantValue::Union(field, value) => {
+            assert_eq!(field, "f");
+            assert_eq!(value.elements().unwrap().len(), 2);
+        }
+        _ => panic!("expected union"),
+    }
+}
+
+#[test]
+fn test_unbalanced() {
+    for text in &["{1} + {2}", "{1, }", "{.f}", "{"] {
+        assert_eq!(
+            parse(text),
+            ConstantValue::Other((*text).to_owned()),
+            "{}",
+            text
+        );
+    }
+}
+
+#[test]
+fn test_round_trip() {
+    for text in &["{1, {2, 3}, ...}", "{.x = -1}", "&x", "1.500000e+00", "{}"] {
+        let value: ConstantValue =
+            serde_json::from_str(&serde_json::to_string(text).unwrap()).unwrap();
+        assert_eq!(value.to_string(), *text);
+        assert_eq!(
+            serde_json::to_string(&value).unwrap(),
+            serde_json::to_string(text).unwrap()
+        );
+    }
+}
+
+#[test]
+fn test_recursion_limit() {
+    let nested = |n: usize| format!("{}1{}", "{".repeat(n), "}".repeat(n));
+
+    let mut value = &parse(&nested(128));
+    for _ in 0..128 {
+        value = &value.elements().unwrap()[0];
+    }
+    assert_eq!(value.as_integer().unwrap().as_i64(), Some(1));
+
+    assert_eq!(ConstantValue::parse(&nested(129)), None);
+    assert_eq!(ConstantValue::parse(&nested(100_000)), None);
+    let union = format!("{}1{}", "{.x = ".repeat(100_000), "}".repeat(100_000));
+    assert_eq!(ConstantValue::parse(&union), None);
+
+    let json = serde_json::to_string(&nested(100_000)).unwrap();
+    let error = serde_json::from_str::<ConstantValue>(&json).unwrap_err();
+    assert!(error
+        .to_string()
+        .starts_with("constant value nested more than 128 levels deep"));
+}
@@ -0,0 +1,119 @@
+use clang_ast::{DeclContexts, DeclInfo, Id, Node};
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+pub enum Clang {
+    TranslationUnitDecl,
+    NamespaceDecl,
+    CXXRecordDecl,
+    CXXMethodDecl {
+        #[serde(rename = "parentDeclContextId")]
+        parent_decl_context_id: Option<Id>,
+    },
+    CompoundStmt,
+    Other,
+}
+
+const JSON: &str = r#"
+    {
+      "id": "0x1",
+      "kind": "TranslationUnitDecl",
+      "inner": [
+        {
+          "id": "0x2",
+          "kind": "NamespaceDecl",
+          "inner": [
+            {
+              "id": "0x3",
+              "kind": "CXXRecordDecl",
+              "inner": [
+                { "id": "0x4", "kind": "CXXMethodDecl" }
+              ]
+            }
+          ]
+        },
+        {
+          "id": "0x5",
+          "kind": "CXXMethodDecl",
+          "parentDeclContextId": "0x3",
+          "inner": [
+            {
+              "id": "0x6",
+              "kind": "CompoundStmt",
+              "inner": [
+                {
+                  "id": "0x7",
+                  "kind": "DeclStmt",
+                  "inner": [
+                    { "id": "0x8", "kind": "VarDecl" }
+                  ]
+                }
+              ]
+            }
+          ]
+        }
+      ]
+    }
+"#;
+
+fn decl_info(kind: &Clang) -> DeclInfo {
+    match kind {
+        Clang::CXXMethodDecl {
+            parent_decl_context_id,
+        } => DeclInfo {
+            is_context: true,
+            parent_decl_context_id: *parent_decl_context_id,
+        },
+        Clang::Other => DeclInfo::default(),
+        _ => DeclInfo {
+            is_context: true,
+            parent_decl_context_id: None,
+        },
+    }
+}
+
+fn id(id: u64) -> Id {
+    Id::from_u64(id).unwrap()
+}
+
+fn ids(ids: &[u64]) -> Vec<Id> {
+    ids.iter().copied().map(id).collect()
+}
+
+#[test]
+fn test_decl_contexts() {
+    let node: Node<Clang> = serde_json::from_str(JSON).unwrap();
+    let contexts = DeclContexts::new(&node, decl_info);
+
+    assert_eq!(contexts.parent(id(0x1)), None);
+    assert_eq!(contexts.parent(id(0x4)), Some(id(0x3)));
+    assert_eq!(contexts.parent(id(0x5)), Some(id(0x3)));
+    assert_eq!(contexts.parent(id(0x7)), Some(id(0x6)));
+
+    let enclosing: Vec<Id> = contexts.enclosing(id(0x8)).collect();
+    assert_eq!(enclosing, ids(&[0x6, 0x5, 0x3, 0x2, 0x1]));
+
+    assert_eq!(contexts.children(id(0x1)), ids(&[0x2]));
+    assert_eq!(contexts.children(id(0x3)), ids(&[0x4, 0x5]));
+    assert!(contexts.children(id(0x4)).is_empty());
+
+    assert!(contexts.is_context(id(0x6)));
+    assert!(!contexts.is_context(id(0x7)));
+}
+
+#[test]
+fn test_cycle() {
+    let json = r#"
+        {
+          "id": "0x1",
+          "kind": "TranslationUnitDecl",
+          "inner": [
+            { "id": "0x2", "kind": "CXXMethodDecl", "parentDeclContextId": "0x3" },
+            { "id": "0x3", "kind": "CXXMethodDecl", "parentDeclContextId": "0x2" }
+          ]
+        }
+    "#;
+    let node: Node<Clang> = serde_json::from_str(json).unwrap();
+    let contexts = DeclContexts::new(&node, decl_info);
+    assert_eq!(contexts.enclosing(id(0x2)).count(), 3);
+}
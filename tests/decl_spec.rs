@@ -0,0 +1,91 @@
+use clang_ast::{InitStyle, StorageClass, TagKind};
+use serde::Deserialize;
+
+pub type Node = clang_ast::Node<Clang>;
+
+#[derive(Deserialize)]
+pub enum Clang {
+    VarDecl {
+        #[serde(rename = "storageClass")]
+        storage_class: Option<StorageClass>,
+        init: Option<InitStyle>,
+    },
+    CXXRecordDecl {
+        #[serde(rename = "tagUsed")]
+        tag_used: TagKind,
+    },
+    Other,
+}
+
+const JSON: &str = r#"
+    {
+      "id": "0x1",
+      "kind": "TranslationUnitDecl",
+      "inner": [
+        { "id": "0x2", "kind": "VarDecl", "storageClass": "static", "init": "c" },
+        { "id": "0x3", "kind": "VarDecl", "init": "list" },
+        { "id": "0x4", "kind": "VarDecl", "storageClass": "__private_extern__" },
+        { "id": "0x5", "kind": "CXXRecordDecl", "tagUsed": "class" },
+        { "id": "0x6", "kind": "CXXRecordDecl", "tagUsed": "__interface" },
+        { "id": "0x7", "kind": "VarDecl", "storageClass": "thread_local", "init": "designated" }
+      ]
+    }
+"#;
+
+#[test]
+fn test_decl_spec() {
+    let node: Node = serde_json::from_str(JSON).unwrap();
+    let mut vars = Vec::new();
+    let mut tags = Vec::new();
+    for node in &node.inner {
+        match &node.kind {
+            Clang::VarDecl {
+                storage_class,
+                init,
+            } => vars.push((storage_class, init)),
+            Clang::CXXRecordDecl { tag_used } => tags.push(tag_used),
+            Clang::Other => panic!("unexpected node"),
+        }
+    }
+    assert_eq!(*vars[0].0, Some(StorageClass::Static));
+    assert_eq!(*vars[0].1, Some(InitStyle::C));
+    assert_eq!(*vars[1].0, None);
+    assert_eq!(*vars[1].1, Some(InitStyle::List));
+    assert_eq!(*vars[2].0, Some(StorageClass::PrivateExtern));
+    assert_eq!(
+        *vars[3].0,
+        Some(StorageClass::Other(Box::from("thread_local")))
+    );
+    assert_eq!(*vars[3].1, Some(InitStyle::Other(Box::from("designated"))));
+    assert_eq!(*tags[0], TagKind::Class);
+    assert_eq!(*tags[1], TagKind::Interface);
+}
+
+#[test]
+fn test_round_trip() {
+    for spelling in &[
+        "extern",
+        "static",
+        "__private_extern__",
+        "auto",
+        "register",
+        "other",
+    ] {
+        let json = serde_json::to_string(spelling).unwrap();
+        let storage_class: StorageClass = serde_json::from_str(&json).unwrap();
+        assert_eq!(storage_class.as_str(), *spelling);
+        assert_eq!(serde_json::to_string(&storage_class).unwrap(), json);
+    }
+    for spelling in &["struct", "__interface", "union", "class", "enum"] {
+        let json = serde_json::to_string(spelling).unwrap();
+        let tag: TagKind = serde_json::from_str(&json).unwrap();
+        assert!(!matches!(tag, TagKind::Other(_)));
+        assert_eq!(tag.to_string(), *spelling);
+    }
+    for spelling in &["c", "call", "list", "paren-list"] {
+        let json = serde_json::to_string(spelling).unwrap();
+        let init: InitStyle = serde_json::from_str(&json).unwrap();
+        assert!(!matches!(init, InitStyle::Other(_)));
+        assert_eq!(init.to_string(), *spelling);
+    }
+}
@@ -0,0 +1,108 @@
+#![cfg(feature = "reader")]
+
+use clang_ast::{Compression, Decoders, Kind};
+use serde::de::IgnoredAny;
+use serde::Deserialize;
+use std::convert::TryFrom;
+use std::io::{self, BufRead, Read};
+
+pub type Node = clang_ast::Node<Clang>;
+
+#[derive(Deserialize, Debug)]
+pub struct Clang {
+    pub kind: Kind,
+}
+
+const JSON: &str =
+    r#"{"id":"0x1","kind":"TranslationUnitDecl","inner":[{"id":"0x2","kind":"FunctionDecl"}]}"#;
+
+// A gzip member holding one uncompressed ("stored") deflate block.
+fn gzip(data: &[u8]) -> Vec<u8> {
+    let mut gzip = vec![0x1f, 0x8b, 8, 0, 0, 0, 0, 0, 0, 255];
+    let len = u16::try_from(data.len()).unwrap();
+    gzip.push(1);
+    gzip.extend_from_slice(&len.to_le_bytes());
+    gzip.extend_from_slice(&(!len).to_le_bytes());
+    gzip.extend_from_slice(data);
+    gzip.extend_from_slice(&[0; 8]); // crc32 and size, unchecked here
+    gzip
+}
+
+// Decoder for exactly what the function above produces.
+fn gunzip(mut input: Box<dyn BufRead>) -> io::Result<io::Cursor<Vec<u8>>> {
+    let mut header = [0; 15];
+    input.read_exact(&mut header)?;
+    let len = u16::from_le_bytes([header[11], header[12]]);
+    let mut data = vec![0; len as usize];
+    input.read_exact(&mut data)?;
+    Ok(io::Cursor::new(data))
+}
+
+// Hands out one byte per read.
+struct Trickle<R>(R);
+
+impl<R: Read> Read for Trickle<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let len = buf.len().min(1);
+        self.0.read(&mut buf[..len])
+    }
+}
+
+#[test]
+fn test_detect() {
+    assert_eq!(Compression::detect(&gzip(b"{}")), Compression::Gzip);
+    assert_eq!(
+        Compression::detect(&[0x28, 0xb5, 0x2f, 0xfd]),
+        Compression::Zstd,
+    );
+    assert_eq!(Compression::detect(JSON.as_bytes()), Compression::None);
+    assert_eq!(Compression::detect(&[0x28, 0xb5]), Compression::None);
+    assert_eq!(Compression::detect(&[]), Compression::None);
+}
+
+#[test]
+fn test_uncompressed() {
+    let node: Node = clang_ast::from_reader(JSON.as_bytes()).unwrap();
+    assert_eq!(node.inner[0].kind.kind, Kind::FunctionDecl);
+
+    let node: Node = clang_ast::from_reader(Trickle(JSON.as_bytes())).unwrap();
+    assert_eq!(node.inner.len(), 1);
+
+    // Shorter than the magic number of any compression format.
+    let node: clang_ast::Node<IgnoredAny> = clang_ast::from_reader(&b"{}"[..]).unwrap();
+    assert!(node.inner.is_empty());
+}
+
+#[test]
+fn test_gzip() {
+    let compressed = gzip(JSON.as_bytes());
+
+    let err = clang_ast::from_reader::<Clang, _>(io::Cursor::new(compressed.clone())).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "gzip compressed input, but no gzip decoder",
+    );
+
+    let mut decoders = Decoders::new();
+    decoders.gzip(gunzip);
+    let node: Node = decoders
+        .from_reader(Trickle(io::Cursor::new(compressed)))
+        .unwrap();
+    assert_eq!(node.inner[0].kind.kind, Kind::FunctionDecl);
+}
+
+#[test]
+fn test_stream() {
+    let mut decoders = Decoders::new();
+    decoders.gzip(gunzip);
+    let input = decoders
+        .decode(io::Cursor::new(gzip(JSON.as_bytes())))
+        .unwrap();
+    let mut kinds = Vec::new();
+    clang_ast::stream::from_reader(input, |event| {
+        let clang: Clang = event.kind;
+        kinds.push(clang.kind);
+    })
+    .unwrap();
+    assert_eq!(kinds, [Kind::FunctionDecl, Kind::TranslationUnitDecl]);
+}
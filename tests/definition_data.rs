@@ -0,0 +1,86 @@
+use clang_ast::{DefinitionData, SpecialMember};
+
+const JSON: &str = r#"
+    {
+      "canPassInRegisters": true,
+      "copyAssign": {
+        "hasConstParam": true,
+        "implicitHasConstParam": true,
+        "needsImplicit": true,
+        "simple": true,
+        "trivial": true
+      },
+      "copyCtor": {
+        "hasConstParam": true,
+        "implicitHasConstParam": true,
+        "needsImplicit": true,
+        "simple": true,
+        "trivial": true
+      },
+      "defaultCtor": {
+        "defaultedIsConstexpr": true,
+        "exists": true,
+        "isConstexpr": true,
+        "needsImplicit": true,
+        "trivial": true
+      },
+      "dtor": {
+        "irrelevant": true,
+        "needsImplicit": true,
+        "simple": true,
+        "trivial": true
+      },
+      "isAggregate": true,
+      "isLiteral": true,
+      "isPOD": true,
+      "isStandardLayout": true,
+      "isTrivial": true,
+      "isTriviallyCopyable": true,
+      "hasSomethingFromTheFuture": { "nested": [1, 2, 3] }
+    }
+"#;
+
+#[test]
+fn test_definition_data() {
+    let data: DefinitionData = serde_json::from_str(JSON).unwrap();
+    assert!(data.is_pod);
+    assert!(data.is_trivial);
+    assert!(data.is_aggregate);
+    assert!(data.can_pass_in_registers);
+    assert!(!data.is_empty);
+    assert!(!data.is_polymorphic);
+
+    let default_ctor = data.default_ctor.unwrap();
+    assert!(default_ctor.exists);
+    assert!(default_ctor.is_constexpr);
+    assert!(!default_ctor.user_provided);
+
+    let copy_ctor = data.copy_ctor.unwrap();
+    assert!(copy_ctor.trivial);
+    assert!(copy_ctor.has_const_param);
+    assert!(!copy_ctor.non_trivial);
+
+    assert!(data.dtor.unwrap().irrelevant);
+    assert_eq!(data.move_ctor, None);
+    assert_eq!(data.move_assign, None);
+}
+
+#[test]
+fn test_round_trip() {
+    let data: DefinitionData = serde_json::from_str(JSON).unwrap();
+    let json = serde_json::to_string(&data).unwrap();
+    let again: DefinitionData = serde_json::from_str(&json).unwrap();
+    assert_eq!(again, data);
+    assert!(!json.contains("false"));
+    assert!(!json.contains("hasSomethingFromTheFuture"));
+
+    let empty = serde_json::to_string(&SpecialMember::default()).unwrap();
+    assert_eq!(empty, "{}");
+}
+
+#[test]
+fn test_duplicate() {
+    let json = r#"{ "isPOD": true, "isPOD": false }"#;
+    let error = serde_json::from_str::<DefinitionData>(json).unwrap_err();
+    assert!(error.to_string().starts_with("duplicate field `isPOD`"));
+}
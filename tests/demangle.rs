@@ -0,0 +1,72 @@
+#![cfg(feature = "demangle")]
+
+use clang_ast::demangle;
+
+#[test]
+fn test_demangle() {
+    // Expected output as printed by c++filt.
+    let cases = [
+        ("_ZN3foo3barEPKc", "foo::bar(char const*)"),
+        ("_ZL6helperi", "helper(int)"),
+        ("_ZN12_GLOBAL__N_11fEv", "(anonymous namespace)::f()"),
+        ("_ZN1AC2ERKS_", "A::A(A const&)"),
+        ("_ZN1AD0Ev", "A::~A()"),
+        ("_ZN1AcviEv", "A::operator int()"),
+        ("_ZN1AltIiEEbRKS_", "bool A::operator< <int>(A const&)"),
+        ("_ZN1A1fB5cxx11Ev", "A::f[abi:cxx11]()"),
+        ("_Z1fPFviE", "f(void (*)(int))"),
+        ("_Z1fPA10_i", "f(int (*) [10])"),
+        ("_Z1fPFPFivEvE", "f(int (*(*)())())"),
+        ("_Z1fM1AKFvvE", "f(void (A::*)() const)"),
+        ("_Z1fIiEvT_", "void f<int>(int)"),
+        ("_Z1fILb1ELc97ELj5EEvv", "void f<true, (char)97, 5u>()"),
+        (
+            "_Z1fSt6vectorIiSaIiEE",
+            "f(std::vector<int, std::allocator<int> >)",
+        ),
+        (
+            "_ZNSsC1Ev",
+            "std::basic_string<char, std::char_traits<char>, std::allocator<char> >::basic_string()",
+        ),
+        (
+            "_ZN4llvm10make_errorINS_11StringErrorEJRA42_KcSt10error_codeEEENS_5ErrorEDpOT0_",
+            "llvm::Error llvm::make_error<llvm::StringError, char const (&) [42], std::error_code>(char const (&) [42], std::error_code&&)",
+        ),
+        ("_ZZ1fvENKUlvE_clEv", "f()::{lambda()#1}::operator()() const"),
+        (
+            "_Z1fIiEDTcl1gfp_EET_",
+            "decltype (g({parm#1})) f<int>(int)",
+        ),
+        ("_Z1fIiEvPDTstT_E", "void f<int>(decltype (sizeof (int))*)"),
+        ("_ZGVZ1fvE1x", "guard variable for f()::x"),
+        ("_ZTV1A", "vtable for A"),
+        ("_ZTh8_N1A1fEv", "non-virtual thunk to A::f()"),
+        ("_Z1fv.cold", "f() [clone .cold]"),
+        // Darwin.
+        ("__ZN3foo3barEv", "foo::bar()"),
+    ];
+    for &(mangled, demangled) in &cases {
+        assert_eq!(demangle(mangled).as_deref(), Some(demangled), "{}", mangled);
+    }
+}
+
+#[test]
+fn test_unsupported() {
+    // C linkage.
+    assert_eq!(demangle("main"), None);
+    assert_eq!(demangle("_Z"), None);
+    assert_eq!(demangle("_Z1fvX"), None);
+
+    let deep = format!("_Z1f{}i", "P".repeat(100_000));
+    assert_eq!(demangle(&deep), None);
+}
+
+#[test]
+fn test_corpus() {
+    // Symbols from libLLVM and other system libraries, each followed by a tab
+    // and the output of c++filt.
+    for line in include_str!("demangle/corpus.txt").lines() {
+        let (mangled, demangled) = line.split_once('\t').unwrap();
+        assert_eq!(demangle(mangled).as_deref(), Some(demangled), "{}", mangled);
+    }
+}
@@ -0,0 +1,185 @@
+#![cfg(feature = "derive")]
+
+use clang_ast::{ClangKind, ClangVisitor, Control, Strict};
+use serde::Deserialize;
+
+const JSON: &str = r#"
+    {
+      "id": "0x1",
+      "kind": "TranslationUnitDecl",
+      "inner": [
+        {
+          "id": "0x2",
+          "kind": "FunctionDecl",
+          "name": "f",
+          "inner": [
+            { "id": "0x3", "kind": "CompoundStmt" }
+          ]
+        },
+        {
+          "id": "0x4",
+          "kind": "VarDecl",
+          "name": "x",
+          "inner": [
+            { "id": "0x5", "kind": "IntegerLiteral", "value": "0" }
+          ]
+        }
+      ]
+    }
+"#;
+
+#[derive(Deserialize, Debug)]
+pub struct FunctionDecl {
+    pub name: String,
+}
+
+#[test]
+fn test_named_fallback() {
+    #[derive(ClangKind, Debug)]
+    pub enum Clang {
+        TranslationUnitDecl,
+        FunctionDecl(FunctionDecl),
+        #[clang(other)]
+        Unrecognized(String),
+    }
+
+    let node: clang_ast::Node<Clang> = serde_json::from_str(JSON).unwrap();
+    assert!(matches!(node.kind, Clang::TranslationUnitDecl));
+    match &node.inner[0].kind {
+        Clang::FunctionDecl(decl) => assert_eq!(decl.name, "f"),
+        other => panic!("unexpected {:?}", other),
+    }
+    match &node.inner[0].inner[0].kind {
+        Clang::Unrecognized(kind) => assert_eq!(kind, "CompoundStmt"),
+        other => panic!("unexpected {:?}", other),
+    }
+    match &node.inner[1].kind {
+        Clang::Unrecognized(kind) => assert_eq!(kind, "VarDecl"),
+        other => panic!("unexpected {:?}", other),
+    }
+    // Children of an unrecognized node are still deserialized.
+    match &node.inner[1].inner[0].kind {
+        Clang::Unrecognized(kind) => assert_eq!(kind, "IntegerLiteral"),
+        other => panic!("unexpected {:?}", other),
+    }
+}
+
+#[test]
+fn test_unit_fallback() {
+    #[derive(ClangKind, Debug)]
+    pub enum Clang {
+        FunctionDecl(FunctionDecl),
+        Other,
+    }
+
+    let node: clang_ast::Node<Clang> = serde_json::from_str(JSON).unwrap();
+    assert!(matches!(node.kind, Clang::Other));
+    match &node.inner[0].kind {
+        Clang::FunctionDecl(decl) => assert_eq!(decl.name, "f"),
+        Clang::Other => panic!("unexpected Other"),
+    }
+    assert!(matches!(node.inner[1].kind, Clang::Other));
+    assert_eq!(node.inner[1].inner.len(), 1);
+}
+
+#[test]
+fn test_no_fallback() {
+    #[derive(ClangKind, Debug)]
+    pub enum Clang {
+        TranslationUnitDecl,
+        FunctionDecl,
+    }
+
+    let error = serde_json::from_str::<clang_ast::Node<Clang>>(JSON).unwrap_err();
+    assert!(error.to_string().starts_with(
+        "unknown variant `CompoundStmt`, expected `TranslationUnitDecl` or `FunctionDecl`"
+    ));
+}
+
+#[test]
+fn test_strict() {
+    #[derive(ClangKind, Debug)]
+    pub enum Clang {
+        TranslationUnitDecl,
+        FunctionDecl,
+        #[clang(other)]
+        Unrecognized,
+    }
+
+    let error = serde_json::from_str::<clang_ast::Node<Strict<Clang>>>(JSON).unwrap_err();
+    assert!(error
+        .to_string()
+        .starts_with("unknown node kind `CompoundStmt` with id 0x3"));
+}
+
+#[test]
+fn test_unchecked() {
+    #[derive(ClangKind, Debug)]
+    pub enum Clang {
+        #[clang(unchecked)]
+        HypotheticalDecl,
+        Unknown,
+    }
+
+    let json = r#"{ "id": "0x1", "kind": "HypotheticalDecl" }"#;
+    let node: clang_ast::Node<Clang> = serde_json::from_str(json).unwrap();
+    assert!(matches!(node.kind, Clang::HypotheticalDecl));
+}
+
+#[test]
+fn test_visitor() {
+    #[derive(ClangKind, ClangVisitor, Debug)]
+    pub enum Clang {
+        TranslationUnitDecl,
+        FunctionDecl(FunctionDecl),
+        CompoundStmt,
+        Other,
+    }
+
+    #[derive(Default)]
+    struct Events {
+        events: Vec<String>,
+    }
+
+    impl<'ast> ClangVisitor<'ast> for Events {
+        fn visit_function_decl(
+            &mut self,
+            node: &'ast clang_ast::Node<Clang>,
+            decl: &'ast FunctionDecl,
+        ) -> Control {
+            self.events
+                .push(format!("function {} {}", decl.name, node.id));
+            Control::Continue
+        }
+
+        fn visit_compound_stmt(&mut self, node: &'ast clang_ast::Node<Clang>) -> Control {
+            self.events.push(format!("compound {}", node.id));
+            Control::Continue
+        }
+
+        fn visit_other(&mut self, _node: &'ast clang_ast::Node<Clang>) -> Control {
+            Control::SkipChildren
+        }
+
+        fn exit_node(&mut self, node: &'ast clang_ast::Node<Clang>) {
+            self.events.push(format!("exit {}", node.id));
+        }
+    }
+
+    let node: clang_ast::Node<Clang> = serde_json::from_str(JSON).unwrap();
+    let mut visitor = Events::default();
+    visitor.walk(&node);
+    let expected = [
+        "function f 0x2",
+        "compound 0x3",
+        "exit 0x3",
+        "exit 0x2",
+        "exit 0x4",
+        "exit 0x1",
+    ];
+    assert_eq!(visitor.events, expected);
+
+    let mut visitor = Events::default();
+    assert_eq!(visitor.dispatch(&node.inner[0]), Control::Continue);
+    assert_eq!(visitor.events, ["function f 0x2"]);
+}
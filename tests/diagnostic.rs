@@ -0,0 +1,190 @@
+use clang_ast::{Diagnostic, SourceManager, SourceRange};
+use std::io::ErrorKind;
+
+fn range(json: &str) -> SourceRange {
+    serde_json::from_str(json).unwrap()
+}
+
+fn sources() -> SourceManager {
+    let mut sources = SourceManager::new();
+    sources.insert("s.h", "struct S {\n\tint x;\n};\n".to_owned());
+    let mut main = "#include \"s.h\"\r\n".to_owned();
+    for _ in 0..8 {
+        main.push_str("\r\n");
+    }
+    main.push_str("int f(struct S s) {\r\n  return s.x +\r\n    s.y;\r\n}\r\n");
+    sources.insert("main.c", main);
+    sources
+}
+
+// Labels in two files, one of them spanning two lines.
+fn no_member() -> Diagnostic {
+    // `s.x +\n    s.y` on lines 11 and 12 of main.c.
+    let expr = range(
+        r#"{
+          "begin": { "offset": 62, "file": "main.c", "line": 11, "col": 10, "tokLen": 1 },
+          "end": { "offset": 75, "line": 12, "col": 7, "tokLen": 1 }
+        }"#,
+    );
+    // `s` on line 10.
+    let param = range(
+        r#"{
+          "begin": { "offset": 47, "file": "main.c", "line": 10, "col": 16, "tokLen": 1 },
+          "end": { "offset": 47, "col": 16, "tokLen": 1 }
+        }"#,
+    );
+    // `int x` in s.h, after a tab.
+    let field = range(
+        r#"{
+          "begin": { "offset": 12, "file": "s.h", "line": 2, "col": 2, "tokLen": 3 },
+          "end": { "offset": 16, "col": 6, "tokLen": 1 }
+        }"#,
+    );
+
+    let mut diagnostic = Diagnostic::error("no member named 'y' in 'struct S'");
+    diagnostic
+        .secondary(&field, "struct S has only x")
+        .primary(&expr, "in this expression")
+        .secondary(&param, "")
+        .note("did you mean 'x'?");
+    diagnostic
+}
+
+#[test]
+fn test_render() {
+    let sources = sources();
+    let diagnostic = no_member();
+    let expected = "\
+error: no member named 'y' in 'struct S'
+  --> main.c:11:10
+   |
+10 | int f(struct S s) {
+   |                -
+11 |   return s.x +
+   |          ^^^^^
+12 |     s.y;
+   |     ^^^ in this expression
+  ::: s.h:2:2
+   |
+2  |     int x;
+   |     ----- struct S has only x
+   |
+   = note: did you mean 'x'?
+";
+    assert_eq!(diagnostic.render(&sources).unwrap(), expected);
+}
+
+#[test]
+fn test_gap() {
+    let sources = sources();
+    let include = range(
+        r#"{
+          "begin": { "offset": 0, "file": "main.c", "line": 1, "col": 1, "tokLen": 1 },
+          "end": { "offset": 9, "col": 10, "tokLen": 5 }
+        }"#,
+    );
+    let close = range(
+        r#"{
+          "begin": { "offset": 79, "file": "main.c", "line": 13, "col": 1, "tokLen": 1 },
+          "end": { "offset": 79, "col": 1, "tokLen": 1 }
+        }"#,
+    );
+    let rendered = Diagnostic::warning("far apart")
+        .primary(&close, "here")
+        .secondary(&include, "and here")
+        .render(&sources)
+        .unwrap();
+    let expected = "\
+warning: far apart
+  --> main.c:13:1
+   |
+1  | #include \"s.h\"
+   | -------------- and here
+...
+13 | }
+   | ^ here
+";
+    assert_eq!(rendered, expected);
+}
+
+#[test]
+fn test_error() {
+    let sources = sources();
+
+    let error = Diagnostic::error("nowhere")
+        .primary(&SourceRange::default(), "")
+        .render(&sources)
+        .unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::InvalidInput);
+
+    let past_end = range(
+        r#"{
+          "begin": { "offset": 100, "file": "s.h", "line": 9, "col": 1, "tokLen": 1 },
+          "end": { "offset": 100, "col": 1, "tokLen": 1 }
+        }"#,
+    );
+    let error = Diagnostic::error("out of bounds")
+        .primary(&past_end, "")
+        .render(&sources)
+        .unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::InvalidInput);
+}
+
+#[cfg(feature = "codespan-reporting")]
+#[test]
+fn test_codespan() {
+    use codespan_reporting::term;
+
+    let sources = sources();
+    let diagnostic = no_member();
+    let config = term::Config::default();
+    let rendered = term::emit_into_string(&config, &sources, &diagnostic.to_codespan()).unwrap();
+    let expected = "\
+error: no member named 'y' in 'struct S'
+   ┌─ main.c:11:10
+   │  
+10 │   int f(struct S s) {
+   │                  -
+11 │     return s.x +
+   │ ╭──────────^
+12 │ │     s.y;
+   │ ╰───────^ in this expression
+   │  
+   ┌─ s.h:2:2
+   │
+ 2 │     int x;
+   │     ----- struct S has only x
+   │
+   = did you mean 'x'?
+
+";
+    assert_eq!(rendered, expected);
+}
+
+#[cfg(feature = "ariadne")]
+#[test]
+fn test_ariadne() {
+    let sources = sources();
+    let diagnostic = no_member();
+    let mut rendered = Vec::new();
+    diagnostic
+        .to_ariadne()
+        .write(diagnostic.ariadne_sources(&sources).unwrap(), &mut rendered)
+        .unwrap();
+    let rendered = String::from_utf8(rendered).unwrap();
+
+    // Colored, so only check for the pieces in the right order.
+    let pieces = [
+        "no member named 'y' in 'struct S'",
+        "main.c:11:10",
+        "in this expression",
+        "s.h:2:2",
+        "struct S has only x",
+        "did you mean 'x'?",
+    ];
+    let positions: Vec<usize> = pieces
+        .iter()
+        .map(|piece| rendered.find(piece).unwrap())
+        .collect();
+    assert!(positions.windows(2).all(|pair| pair[0] < pair[1]));
+}
@@ -0,0 +1,97 @@
+use clang_ast::diff::{self, Edit};
+use clang_ast::Kind;
+use serde::Deserialize;
+
+pub type Node = clang_ast::Node<Clang>;
+
+#[derive(Deserialize, PartialEq, Debug)]
+pub struct Clang {
+    pub kind: Kind,
+    pub name: Option<String>,
+    #[serde(rename = "type")]
+    pub ty: Option<Type>,
+}
+
+#[derive(Deserialize, PartialEq, Debug)]
+pub struct Type {
+    #[serde(rename = "qualType")]
+    pub qual_type: String,
+}
+
+const OLD: &str = r#"
+    {
+      "id": "0x1",
+      "kind": "TranslationUnitDecl",
+      "inner": [
+        { "id": "0x2", "kind": "FunctionDecl", "name": "a", "type": { "qualType": "void ()" } },
+        { "id": "0x3", "kind": "FunctionDecl", "name": "b", "type": { "qualType": "void ()" } },
+        { "id": "0x4", "kind": "FunctionDecl", "name": "c", "type": { "qualType": "void ()" } },
+        {
+          "id": "0x5",
+          "kind": "CXXRecordDecl",
+          "name": "S",
+          "inner": [
+            { "id": "0x6", "kind": "FieldDecl", "name": "x", "type": { "qualType": "int" } }
+          ]
+        }
+      ]
+    }
+"#;
+
+const NEW: &str = r#"
+    {
+      "id": "0x11",
+      "kind": "TranslationUnitDecl",
+      "inner": [
+        { "id": "0x14", "kind": "FunctionDecl", "name": "c", "type": { "qualType": "void ()" } },
+        { "id": "0x12", "kind": "FunctionDecl", "name": "a", "type": { "qualType": "void ()" } },
+        {
+          "id": "0x15",
+          "kind": "CXXRecordDecl",
+          "name": "S",
+          "inner": [
+            { "id": "0x16", "kind": "FieldDecl", "name": "x", "type": { "qualType": "long" } },
+            { "id": "0x17", "kind": "FieldDecl", "name": "y", "type": { "qualType": "int" } }
+          ]
+        }
+      ]
+    }
+"#;
+
+fn describe(edit: &Edit<Clang>) -> String {
+    let name = |node: &Node| node.kind.name.clone().unwrap_or_default();
+    match edit {
+        Edit::Insert { parent, node } => format!("insert {} in {}", name(node), parent.id),
+        Edit::Remove { parent, node } => format!("remove {} from {}", name(node), parent.id),
+        Edit::Move { old, new } => format!("move {} {}->{}", name(old), old.id, new.id),
+        Edit::Update { old, new } => format!("update {} {}->{}", name(old), old.id, new.id),
+    }
+}
+
+#[test]
+fn test_diff() {
+    let old: Node = serde_json::from_str(OLD).unwrap();
+    let new: Node = serde_json::from_str(NEW).unwrap();
+    let edits = diff::diff(&old, &new, |clang| {
+        (clang.kind.clone(), clang.name.as_deref())
+    });
+    let edits: Vec<String> = edits.iter().map(describe).collect();
+    let expected = [
+        "remove b from 0x1",
+        "move c 0x4->0x14",
+        "insert y in 0x15",
+        "update x 0x6->0x16",
+    ];
+    assert_eq!(edits, expected);
+}
+
+#[test]
+fn test_diff_identical() {
+    let old: Node = serde_json::from_str(OLD).unwrap();
+    let new: Node = serde_json::from_str(OLD).unwrap();
+    let edits = diff::diff(&old, &new, |clang| clang.kind.clone());
+    assert!(edits.is_empty());
+
+    let edits = diff::diff_by(&old, &new, |clang| clang.kind.clone(), |_, _| false);
+    assert_eq!(edits.len(), 6);
+}
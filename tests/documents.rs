@@ -0,0 +1,68 @@
+#![cfg(feature = "documents")]
+
+use clang_ast::Kind;
+use serde::Deserialize;
+
+pub type Node = clang_ast::Node<Clang>;
+
+#[derive(Deserialize)]
+pub struct Clang {
+    pub kind: Kind,
+    pub name: Option<String>,
+}
+
+#[test]
+fn test_documents() {
+    let output = r#"Dumping f:
+{
+  "id": "0x1",
+  "kind": "FunctionDecl",
+  "name": "f",
+  "inner": [
+    { "id": "0x2", "kind": "CompoundStmt" }
+  ]
+}
+Dumping ns::f:
+{
+  "id": "0x3",
+  "kind": "FunctionDecl",
+  "name": "f"
+}
+"#;
+    let nodes = clang_ast::documents::<Clang>(output)
+        .collect::<serde_json::Result<Vec<Node>>>()
+        .unwrap();
+    assert_eq!(nodes.len(), 2);
+    assert_eq!(nodes[0].inner[0].kind.kind, Kind::CompoundStmt);
+    assert_eq!(nodes[1].kind.name.as_deref(), Some("f"));
+}
+
+#[test]
+fn test_error() {
+    let output =
+        "Dumping f:\n{\"id\":\"0x1\"}\nDumping g:\n{\"id\":\"0x2\",\"kind\":\"FunctionDecl\"}\n";
+    let mut documents = clang_ast::documents::<Clang>(output);
+    assert!(documents.next().unwrap().is_err());
+    assert!(documents.next().is_none());
+}
+
+#[derive(Deserialize)]
+pub struct Located {
+    pub loc: clang_ast::SourceLocation,
+}
+
+#[test]
+fn test_concatenated() {
+    let input = r#"
+        {"id":"0x1","kind":"VarDecl","loc":{"offset":4,"file":"a.h","line":1,"col":5,"tokLen":1}}
+        {"id":"0x2","kind":"VarDecl","loc":{"offset":4,"line":1,"col":5,"tokLen":1}}
+    "#;
+    let deserializer = serde_json::Deserializer::from_str(input);
+    let mut stream = clang_ast::Concatenated::<_, Located>::new(deserializer);
+    let first = stream.next().unwrap().unwrap();
+    assert_eq!(&*first.kind.loc.expansion_loc.unwrap().file, "a.h");
+    let second = stream.next().unwrap().unwrap();
+    assert_eq!(&*second.kind.loc.expansion_loc.unwrap().file, "");
+    assert!(stream.next().is_none());
+    assert_eq!(stream.byte_offset(), input.len());
+}
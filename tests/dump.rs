@@ -0,0 +1,98 @@
+use clang_ast::{Kind, SourceRange, TextDump};
+use serde::Deserialize;
+
+pub type Node = clang_ast::Node<Clang>;
+
+#[derive(Deserialize)]
+pub struct Clang {
+    pub kind: Kind,
+    pub name: Option<String>,
+    pub range: Option<SourceRange>,
+}
+
+const JSON: &str = r#"
+    {
+      "id": "0x1",
+      "kind": "TranslationUnitDecl",
+      "range": { "begin": {}, "end": {} },
+      "inner": [
+        {
+          "id": "0x2",
+          "kind": "FunctionDecl",
+          "name": "main",
+          "range": {
+            "begin": { "offset": 0, "file": "main.cc", "line": 1, "col": 1, "tokLen": 3 },
+            "end": { "offset": 30, "line": 3, "col": 1, "tokLen": 1 }
+          },
+          "inner": [
+            {
+              "id": "0x3",
+              "kind": "CompoundStmt",
+              "range": {
+                "begin": { "offset": 11, "line": 1, "col": 12, "tokLen": 1 },
+                "end": { "offset": 30, "line": 3, "col": 1, "tokLen": 1 }
+              },
+              "inner": [
+                {
+                  "id": "0x4",
+                  "kind": "ReturnStmt",
+                  "range": {
+                    "begin": { "offset": 15, "line": 2, "col": 3, "tokLen": 6 },
+                    "end": { "offset": 22, "col": 10, "tokLen": 1 }
+                  }
+                }
+              ]
+            }
+          ]
+        },
+        {
+          "id": "0x5",
+          "kind": "VarDecl",
+          "name": "x",
+          "range": {
+            "begin": { "offset": 0, "file": "x.h", "line": 1, "col": 5, "tokLen": 1 },
+            "end": { "offset": 0, "col": 5, "tokLen": 1 }
+          }
+        }
+      ]
+    }
+"#;
+
+#[test]
+fn test_to_text() {
+    let node: Node = serde_json::from_str(JSON).unwrap();
+    let mut dump = TextDump::new(|clang: &Clang| clang.kind.to_string());
+    dump.range(|clang| clang.range.as_ref());
+    dump.details(|clang| clang.name.clone().unwrap_or_default());
+    let expected = "\
+TranslationUnitDecl 0x1 <<invalid sloc>>
+|-FunctionDecl 0x2 <main.cc:1:1, line:3:1> main
+| `-CompoundStmt 0x3 <line:1:12, line:3:1>
+|   `-ReturnStmt 0x4 <line:2:3, col:10>
+`-VarDecl 0x5 <x.h:1:5> x
+";
+    assert_eq!(node.to_text(&dump), expected);
+}
+
+#[test]
+fn test_to_text_color() {
+    let json = r#"
+        {
+          "id": "0x1",
+          "kind": "FunctionDecl",
+          "name": "f",
+          "inner": [
+            { "id": "0x2", "kind": "CompoundStmt" }
+          ]
+        }
+    "#;
+    let node: Node = serde_json::from_str(json).unwrap();
+    let mut dump = TextDump::new(|clang: &Clang| clang.kind.to_string());
+    dump.details(|clang| clang.name.clone().unwrap_or_default());
+    dump.color(true);
+    let expected = "\
+\x1b[0;34m\x1b[1;32mFunctionDecl\x1b[0m \x1b[0;33m0x1\x1b[0m \x1b[1;36mf\x1b[0m
+\x1b[0;34m`-\x1b[1;35mCompoundStmt\x1b[0m \x1b[0;33m0x2\x1b[0m
+";
+    assert_eq!(node.to_text(&dump), expected);
+}
@@ -0,0 +1,60 @@
+#![cfg(all(feature = "exec", unix))]
+
+use clang_ast::exec::{Error, Exec};
+use clang_ast::Kind;
+use serde::Deserialize;
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+
+#[derive(Deserialize)]
+pub struct Clang {
+    pub kind: Kind,
+    pub name: Option<String>,
+}
+
+// Stands in for clang: expects one flag, then the dump flags, then the
+// source file, and dumps a decl named after the source file.
+const FAKE_CLANG: &str = r#"#!/bin/sh
+if [ "$1 $2 $3 $4" != "-std=c++17 -Xclang -ast-dump=json -fsyntax-only" ]; then
+    echo "unexpected arguments: $*" >&2
+    exit 2
+fi
+if [ ! -f "$5" ]; then
+    echo "clang++: error: no such file or directory: '$5'" >&2
+    exit 1
+fi
+echo "{\"id\":\"0x1\",\"kind\":\"TranslationUnitDecl\",\"name\":\"$5\"}"
+"#;
+
+#[test]
+fn test_exec() {
+    let dir = std::env::temp_dir().join(format!("clang-ast-exec-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let clang = dir.join("fake-clang");
+    fs::write(&clang, FAKE_CLANG).unwrap();
+    fs::set_permissions(&clang, fs::Permissions::from_mode(0o755)).unwrap();
+    fs::write(dir.join("main.cc"), "").unwrap();
+
+    let mut exec = Exec::new("main.cc");
+    exec.program(&clang).arg("-std=c++17").current_dir(&dir);
+    let node = exec.run::<Clang>();
+    let missing = Exec::new("missing.cc")
+        .program(&clang)
+        .arg("-std=c++17")
+        .current_dir(&dir)
+        .run::<Clang>();
+    fs::remove_dir_all(&dir).unwrap();
+
+    let node = node.unwrap();
+    assert_eq!(node.kind.kind, Kind::TranslationUnitDecl);
+    assert_eq!(node.kind.name.as_deref(), Some("main.cc"));
+
+    let error = missing.err().unwrap();
+    if let Error::Clang { status, stderr } = &error {
+        assert_eq!(status.code(), Some(1));
+        assert!(stderr.contains("no such file"));
+    } else {
+        panic!("expected clang to fail");
+    }
+    assert!(error.to_string().contains("no such file"));
+}
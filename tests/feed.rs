@@ -0,0 +1,107 @@
+#![cfg(feature = "feed-thread")]
+
+use clang_ast::Kind;
+use serde::Deserialize;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll, Wake, Waker};
+use std::thread::{self, Thread};
+
+pub type Node = clang_ast::Node<Clang>;
+
+#[derive(Deserialize, Debug)]
+pub struct Clang {
+    pub kind: Kind,
+}
+
+struct Unpark(Thread);
+
+impl Wake for Unpark {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+fn block_on<F: Future>(future: F) -> F::Output {
+    let mut future = Box::pin(future);
+    let waker = Waker::from(Arc::new(Unpark(thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    loop {
+        match Pin::as_mut(&mut future).poll(&mut cx) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => thread::park(),
+        }
+    }
+}
+
+fn json(functions: usize) -> String {
+    let mut json = String::from(r#"{"id":"0x1","kind":"TranslationUnitDecl","inner":["#);
+    for i in 0..functions {
+        if i > 0 {
+            json.push(',');
+        }
+        json.push_str(r#"{"id":"0x2","kind":"FunctionDecl","name":"f"}"#);
+    }
+    json.push_str("]}");
+    json
+}
+
+#[test]
+fn test_feed() {
+    let json = json(3);
+    let node = block_on(async {
+        let (mut feed, parsing) = clang_ast::feed();
+        for chunk in json.as_bytes().chunks(7) {
+            feed.send(chunk).await;
+        }
+        feed.send(Vec::new()).await;
+        drop(feed);
+        parsing.await
+    });
+    let node: Node = node.unwrap();
+    assert_eq!(node.kind.kind, Kind::TranslationUnitDecl);
+    assert_eq!(node.inner.len(), 3);
+}
+
+#[test]
+fn test_backpressure() {
+    // Several times more than the feed buffers at once.
+    let json = json(200_000);
+    assert!(json.len() > 8 * 1024 * 1024);
+    let node = block_on(async {
+        let (mut feed, parsing) = clang_ast::feed();
+        for chunk in json.as_bytes().chunks(100_000) {
+            feed.send(chunk).await;
+        }
+        drop(feed);
+        parsing.await
+    });
+    let node: Node = node.unwrap();
+    assert_eq!(node.inner.len(), 200_000);
+}
+
+#[test]
+fn test_invalid() {
+    let err = block_on(async {
+        let (mut feed, parsing) = clang_ast::feed::<Clang>();
+        feed.send(&b"{\"id\":\"0x1\",]"[..]).await;
+        let err = parsing.await.unwrap_err();
+        // Input after the error is discarded.
+        feed.send(vec![b' '; 4 * 1024 * 1024]).await;
+        feed.send(vec![b' '; 4 * 1024 * 1024]).await;
+        err
+    });
+    assert!(err.is_syntax());
+}
+
+#[test]
+fn test_truncated() {
+    let err = block_on(async {
+        let (mut feed, parsing) = clang_ast::feed::<Clang>();
+        feed.send(&b"{\"id\":\"0x1\","[..]).await;
+        drop(feed);
+        parsing.await.unwrap_err()
+    });
+    assert!(err.is_eof());
+}
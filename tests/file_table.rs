@@ -0,0 +1,68 @@
+use clang_ast::{FileTable, Node, SourceLocation};
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+pub struct Clang {
+    pub loc: Option<SourceLocation>,
+}
+
+const JSON: &str = r#"
+    {
+      "id": "0x1",
+      "kind": "TranslationUnitDecl",
+      "inner": [
+        {
+          "id": "0x2",
+          "kind": "VarDecl",
+          "loc": {
+            "offset": 4, "file": "a.h", "line": 1, "col": 5, "tokLen": 1,
+            "includedFrom": { "file": "main.c" }
+          }
+        },
+        {
+          "id": "0x3",
+          "kind": "VarDecl",
+          "loc": {
+            "spellingLoc": {
+              "offset": 10, "file": "b.h", "line": 1, "col": 5, "tokLen": 1,
+              "includedFrom": { "file": "a.h" }
+            },
+            "expansionLoc": {
+              "offset": 40, "file": "main.c", "line": 3, "col": 5, "tokLen": 1
+            }
+          }
+        },
+        {
+          "id": "0x4",
+          "kind": "VarDecl",
+          "loc": { "offset": 50, "line": 4, "col": 5, "tokLen": 1 }
+        }
+      ]
+    }
+"#;
+
+#[test]
+fn test_file_table() {
+    let node: Node<Clang> = serde_json::from_str(JSON).unwrap();
+    let table = FileTable::new(&node, |kind| kind.loc.as_ref());
+
+    let files: Vec<(&str, usize)> = table
+        .files()
+        .iter()
+        .map(|file| (&*file.path, file.nodes))
+        .collect();
+    assert_eq!(files, [("a.h", 1), ("main.c", 2), ("b.h", 0)]);
+    assert_eq!(table.index("b.h"), Some(2));
+    assert_eq!(table.index("c.h"), None);
+    assert_eq!(table.len(), 3);
+
+    // Shares the allocation of the paths in the tree.
+    let loc = node.inner[0]
+        .kind
+        .loc
+        .as_ref()
+        .unwrap()
+        .expansion()
+        .unwrap();
+    assert_eq!(table.get(0).unwrap().path.as_ptr(), loc.file.as_ptr());
+}
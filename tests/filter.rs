@@ -0,0 +1,234 @@
+use clang_ast::{Filter, Kind, SourceLocation};
+use serde::Deserialize;
+
+pub type Node = clang_ast::Node<Clang>;
+
+#[derive(Deserialize)]
+pub struct Clang {
+    pub kind: Kind,
+    pub loc: Option<SourceLocation>,
+}
+
+fn file(node: &Node) -> &str {
+    let loc = node.kind.loc.as_ref().unwrap();
+    &loc.expansion_loc.as_ref().unwrap().file
+}
+
+const JSON: &str = r#"
+    {
+      "id": "0x1",
+      "kind": "TranslationUnitDecl",
+      "inner": [
+        {
+          "id": "0x2",
+          "kind": "FunctionDecl",
+          "loc": { "offset": 4, "file": "a.cc", "line": 1, "col": 5, "tokLen": 1 },
+          "inner": [
+            {
+              "id": "0x3",
+              "kind": "CompoundStmt",
+              "inner": [
+                {
+                  "id": "0x4",
+                  "kind": "DeclStmt",
+                  "inner": [
+                    {
+                      "id": "0x5",
+                      "kind": "VarDecl",
+                      "loc": {
+                        "spellingLoc": { "offset": 0, "file": "b.h", "line": 9, "col": 1, "tokLen": 1 },
+                        "expansionLoc": { "offset": 12, "file": "a.cc", "line": 2, "col": 3, "tokLen": 1 }
+                      }
+                    }
+                  ]
+                },
+                {
+                  "id": "0x6",
+                  "kind": "NullStmt",
+                  "loc": { "offset": 20, "file": "c.h", "line": 3, "col": 1, "tokLen": 1 }
+                }
+              ]
+            }
+          ]
+        },
+        {
+          "id": "0x7",
+          "kind": "VarDecl",
+          "loc": { "offset": 30, "col": 1, "tokLen": 1 }
+        }
+      ]
+    }
+"#;
+
+#[test]
+fn test_skip_inner() {
+    let mut filter = Filter::new();
+    filter.skip_inner(Kind::CompoundStmt);
+
+    let mut deserializer = serde_json::Deserializer::from_str(JSON);
+    let node: Node = filter.deserialize(&mut deserializer).unwrap();
+    assert_eq!(node.descendants().count(), 4);
+
+    let body = &node.inner[0].inner[0];
+    assert_eq!(body.kind.kind, Kind::CompoundStmt);
+    assert!(body.inner.is_empty());
+
+    // The last loc omits "file", meaning it is in the same file as the loc
+    // inside the skipped body.
+    assert_eq!(file(&node.inner[1]), "c.h");
+}
+
+#[test]
+fn test_default_keeps_everything() {
+    let mut deserializer = serde_json::Deserializer::from_str(JSON);
+    let node: Node = Filter::new().deserialize(&mut deserializer).unwrap();
+    assert_eq!(node.descendants().count(), 7);
+    assert_eq!(file(&node.inner[1]), "c.h");
+}
+
+#[derive(Deserialize)]
+struct Bare {
+    kind: Kind,
+}
+
+#[test]
+fn test_exclude_file() {
+    let mut filter = Filter::new();
+    filter.exclude_file(|path| path != "a.cc");
+
+    let mut deserializer = serde_json::Deserializer::from_str(JSON);
+    let node: Node = filter.deserialize(&mut deserializer).unwrap();
+    let ids: Vec<_> = node.descendants().map(|node| node.id.to_string()).collect();
+    // 0x5 is spelled in b.h but expanded in a.cc, so it is kept. 0x7 is in
+    // the same file as the loc before it, c.h.
+    assert_eq!(ids, ["0x1", "0x2", "0x3", "0x4", "0x5"]);
+
+    // Same result when the kind does not deserialize locations at all.
+    let mut filter = Filter::new();
+    filter.exclude_file(|path| path == "c.h");
+    let mut deserializer = serde_json::Deserializer::from_str(JSON);
+    let node: clang_ast::Node<Bare> = filter.deserialize(&mut deserializer).unwrap();
+    assert_eq!(node.inner.len(), 1);
+    let body = &node.inner[0].inner[0];
+    assert_eq!(body.inner.len(), 1);
+    assert_eq!(body.inner[0].kind.kind, Kind::DeclStmt);
+}
+
+const IMPLICIT: &str = r#"
+    {
+      "id": "0x1",
+      "kind": "TranslationUnitDecl",
+      "inner": [
+        {
+          "id": "0x2",
+          "kind": "CXXRecordDecl",
+          "loc": { "offset": 7, "file": "a.cc", "line": 1, "col": 8, "tokLen": 1 },
+          "inner": [
+            {
+              "id": "0x3",
+              "kind": "CXXRecordDecl",
+              "loc": { "offset": 7, "col": 8, "tokLen": 1 },
+              "isImplicit": true
+            },
+            {
+              "id": "0x4",
+              "kind": "CXXConstructorDecl",
+              "loc": { "offset": 7, "col": 8, "tokLen": 1 },
+              "isImplicit": true,
+              "inner": [
+                { "id": "0x5", "kind": "ParmVarDecl" }
+              ]
+            },
+            {
+              "id": "0x6",
+              "kind": "FieldDecl",
+              "loc": { "offset": 15, "col": 9, "tokLen": 1 },
+              "isImplicit": false
+            }
+          ]
+        }
+      ]
+    }
+"#;
+
+#[derive(Deserialize)]
+struct WithImplicit {
+    #[serde(rename = "isImplicit", default)]
+    is_implicit: bool,
+}
+
+#[test]
+fn test_exclude_implicit() {
+    let mut filter = Filter::new();
+    filter.exclude_implicit();
+    let mut deserializer = serde_json::Deserializer::from_str(IMPLICIT);
+    let node: clang_ast::Node<Bare> = filter.deserialize(&mut deserializer).unwrap();
+    let ids: Vec<_> = node.descendants().map(|node| node.id.to_string()).collect();
+    assert_eq!(ids, ["0x1", "0x2", "0x6"]);
+    assert_eq!(node.inner[0].inner[0].kind.kind, Kind::FieldDecl);
+
+    // The kind still sees isImplicit if it asks for it.
+    let mut filter = Filter::new();
+    filter.hoist_implicit();
+    let mut deserializer = serde_json::Deserializer::from_str(IMPLICIT);
+    let node: clang_ast::Node<WithImplicit> = filter.deserialize(&mut deserializer).unwrap();
+    let ids: Vec<_> = node.descendants().map(|node| node.id.to_string()).collect();
+    assert_eq!(ids, ["0x1", "0x2", "0x5", "0x6"]);
+    assert!(node.descendants().all(|node| !node.kind.is_implicit));
+}
+
+#[test]
+fn test_max_depth() {
+    let mut filter = Filter::new();
+    filter.max_depth(2);
+
+    let mut deserializer = serde_json::Deserializer::from_str(JSON);
+    let node: Node = filter.deserialize(&mut deserializer).unwrap();
+    let ids: Vec<_> = node.descendants().map(|node| node.id.to_string()).collect();
+    assert_eq!(ids, ["0x1", "0x2", "0x3", "0x7"]);
+    assert_eq!(file(&node.inner[1]), "c.h");
+
+    let mut filter = Filter::new();
+    filter.max_depth(0);
+    let mut deserializer = serde_json::Deserializer::from_str(JSON);
+    let node: Node = filter.deserialize(&mut deserializer).unwrap();
+    assert!(node.inner.is_empty());
+}
+
+#[test]
+fn test_recursion_limit() {
+    let depth = 1000;
+    let mut json = String::new();
+    for _ in 0..depth {
+        json.push_str(r#"{"id":"0x1","kind":"ParenExpr","inner":["#);
+    }
+    json.push_str(r#"{"id":"0x1","kind":"IntegerLiteral"}"#);
+    for _ in 0..depth {
+        json.push_str("]}");
+    }
+
+    let thread = std::thread::Builder::new().stack_size(256 << 20);
+    let handle =
+        thread.spawn(move || {
+            let mut filter = Filter::new();
+            filter.recursion_limit(500);
+            let mut deserializer = serde_json::Deserializer::from_str(&json);
+            deserializer.disable_recursion_limit();
+            let result = filter.deserialize::<Clang, _>(&mut deserializer);
+            let error = result.err().unwrap().to_string();
+            assert!(
+                error.starts_with("syntax tree nested more deeply than the recursion limit of 500")
+            );
+
+            filter.recursion_limit(depth);
+            let mut deserializer = serde_json::Deserializer::from_str(&json);
+            deserializer.disable_recursion_limit();
+            let node: Node = filter.deserialize(&mut deserializer).unwrap();
+            let count = node.descendants().count();
+
+            // The default recursive drop would overflow.
+            drop(node.into_flattened());
+            count
+        });
+    assert_eq!(handle.unwrap().join().unwrap(), depth + 1);
+}
@@ -0,0 +1,62 @@
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug)]
+pub enum Clang {
+    CXXRecordDecl { name: String },
+    Other,
+}
+
+fn named(want: &'static str) -> impl FnMut(&Clang) -> bool {
+    move |kind| match kind {
+        Clang::CXXRecordDecl { name } => name == want,
+        Clang::Other => false,
+    }
+}
+
+#[test]
+fn test_find() {
+    // Everything after the match is malformed, which proves that it is never
+    // parsed.
+    let json = r#"
+        {
+          "id": "0x1",
+          "kind": "TranslationUnitDecl",
+          "inner": [
+            { "id": "0x2", "kind": "CXXRecordDecl", "name": "Bar" },
+            {
+              "id": "0x3",
+              "kind": "CXXRecordDecl",
+              "name": "Foo",
+              "inner": [
+                { "id": "0x4", "kind": "FieldDecl" }
+              ]
+            },
+            !!!
+    "#;
+
+    let mut deserializer = serde_json::Deserializer::from_str(json);
+    let found = clang_ast::find(&mut deserializer, named("Foo")).unwrap();
+    let found = found.unwrap();
+    assert_eq!(found.id.to_string(), "0x3");
+    assert_eq!(found.inner.len(), 1);
+}
+
+#[test]
+fn test_not_found() {
+    let json = r#"
+        {
+          "id": "0x1",
+          "kind": "TranslationUnitDecl",
+          "inner": [
+            { "id": "0x2", "kind": "CXXRecordDecl", "name": "Bar" }
+          ]
+        }
+    "#;
+
+    let mut deserializer = serde_json::Deserializer::from_str(json);
+    let found = clang_ast::find(&mut deserializer, named("Foo")).unwrap();
+    assert!(found.is_none());
+
+    let mut deserializer = serde_json::Deserializer::from_str(&json[..40]);
+    assert!(clang_ast::find(&mut deserializer, named("Foo")).is_err());
+}
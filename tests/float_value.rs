@@ -0,0 +1,61 @@
+#![allow(clippy::float_cmp)]
+
+use clang_ast::FloatValue;
+
+fn value(text: &str) -> f64 {
+    FloatValue::parse(text).unwrap().value()
+}
+
+#[test]
+fn test_decimal() {
+    assert_eq!(value("1.5"), 1.5);
+    assert_eq!(value("1.0E+10"), 1e10);
+    assert_eq!(value("-2.5E-3"), -2.5e-3);
+    assert_eq!(value("3.4028234663852886E+38"), f64::from(f32::MAX));
+    assert_eq!(value(".5"), 0.5);
+    assert_eq!(value("7"), 7.0);
+    assert!(value("-0.0").is_sign_negative());
+}
+
+#[test]
+fn test_special() {
+    assert_eq!(value("+Inf"), f64::INFINITY);
+    assert_eq!(value("-Inf"), f64::NEG_INFINITY);
+    assert_eq!(value("infinity"), f64::INFINITY);
+    assert!(value("NaN").is_nan());
+    assert!(value("-nan").is_nan());
+}
+
+#[test]
+fn test_hex() {
+    assert_eq!(value("0x1.8p3"), 12.0);
+    assert_eq!(value("0X1P-2"), 0.25);
+    assert_eq!(value("-0x.8p1"), -1.0);
+    assert_eq!(value("0x1.fffffffffffffp1023"), f64::MAX);
+    assert_eq!(value("0x1p-1074"), 5e-324);
+    assert_eq!(value("0x1p-1075"), 0.0);
+    assert_eq!(value("0x1p1024"), f64::INFINITY);
+    assert_eq!(value("0x0p99999999999999999999"), 0.0);
+    // Rounds to even, where an approach dropping excess digits would not.
+    assert_eq!(value("0x1.00000000000008p0"), 1.0);
+    assert_eq!(value("0x1.000000000000080001p0"), 1.0 + f64::EPSILON);
+    assert_eq!(value("0x0000000000000000000001p0"), 1.0);
+}
+
+#[test]
+fn test_invalid() {
+    for text in &[
+        "", "-", "abc", "1.5f", "0x1.8", "0xp1", "0x1p", "0x1.8.p1", "--1", "Infx",
+    ] {
+        assert!(FloatValue::parse(text).is_none(), "{}", text);
+    }
+}
+
+#[test]
+fn test_serde() {
+    let value: FloatValue = serde_json::from_str(r#""0x1.8p3""#).unwrap();
+    assert_eq!(value.value(), 12.0);
+    assert_eq!(value.text(), "0x1.8p3");
+    assert_eq!(serde_json::to_string(&value).unwrap(), r#""0x1.8p3""#);
+    assert!(serde_json::from_str::<FloatValue>(r#""1.5f""#).is_err());
+}
@@ -0,0 +1,83 @@
+use clang_ast::{Graph, Id, Kind};
+use serde::Deserialize;
+
+pub type Node = clang_ast::Node<Clang>;
+
+#[derive(Deserialize)]
+pub struct Clang {
+    pub kind: Kind,
+    pub name: Option<String>,
+    #[serde(rename = "referencedDecl")]
+    pub referenced_decl: Option<Decl>,
+}
+
+#[derive(Deserialize)]
+pub struct Decl {
+    pub id: Id,
+}
+
+const JSON: &str = r#"
+    {
+      "id": "0x1",
+      "kind": "FunctionDecl",
+      "name": "f",
+      "inner": [
+        { "id": "0x2", "kind": "ParmVarDecl", "name": "\"x\"" },
+        {
+          "id": "0x3",
+          "kind": "CompoundStmt",
+          "inner": [
+            { "id": "0x4", "kind": "DeclRefExpr", "referencedDecl": { "id": "0x2" } },
+            { "id": "0x5", "kind": "DeclRefExpr", "referencedDecl": { "id": "0x99" } }
+          ]
+        }
+      ]
+    }
+"#;
+
+fn graph<'a>() -> Graph<'a, Clang> {
+    let mut graph = Graph::new(|clang: &Clang| match &clang.name {
+        Some(name) => format!("{} {}", clang.kind, name),
+        None => clang.kind.to_string(),
+    });
+    graph.references(|clang| clang.referenced_decl.iter().map(|decl| decl.id).collect());
+    graph
+}
+
+#[test]
+fn test_to_dot() {
+    let node: Node = serde_json::from_str(JSON).unwrap();
+    let expected = r#"digraph ast {
+    node [shape=box];
+    n0 [label="FunctionDecl f"];
+    n1 [label="ParmVarDecl \"x\""];
+    n2 [label="CompoundStmt"];
+    n3 [label="DeclRefExpr"];
+    n4 [label="DeclRefExpr"];
+    n0 -> n1;
+    n0 -> n2;
+    n2 -> n3;
+    n2 -> n4;
+    n3 -> n1 [style=dashed];
+}
+"#;
+    assert_eq!(node.to_dot(&graph()), expected);
+}
+
+#[test]
+fn test_to_mermaid() {
+    let node: Node = serde_json::from_str(JSON).unwrap();
+    let expected = r#"flowchart TD
+    n0["FunctionDecl f"]
+    n1["ParmVarDecl #quot;x#quot;"]
+    n2["CompoundStmt"]
+    n3["DeclRefExpr"]
+    n4["DeclRefExpr"]
+    n0 --> n1
+    n0 --> n2
+    n2 --> n3
+    n2 --> n4
+    n3 -.-> n1
+"#;
+    assert_eq!(node.to_mermaid(&graph()), expected);
+}
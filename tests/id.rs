@@ -0,0 +1,157 @@
+use clang_ast::Id;
+use serde::de::{Deserialize, Deserializer, Error, Visitor};
+use serde::forward_to_deserialize_any;
+
+#[test]
+fn test_u64() {
+    let id: Id = serde_json::from_str("\"0x55d0f9a3c1e8\"").unwrap();
+    assert_eq!(id.as_u64(), 0x55d0_f9a3_c1e8);
+    assert_eq!(Id::from_u64(0x55d0_f9a3_c1e8), Some(id));
+    assert_eq!(Id::from_u64(0), Some(Id::NULL));
+    assert_eq!(Id::from_u64(u64::MAX), None);
+}
+
+#[test]
+fn test_fmt() {
+    let id = Id::from_u64(0x55d0_f9a3_c1e8).unwrap();
+    assert_eq!(id.to_string(), "0x55d0f9a3c1e8");
+    assert_eq!(format!("{:x}", id), "55d0f9a3c1e8");
+    assert_eq!(format!("{:#x}", id), "0x55d0f9a3c1e8");
+    assert_eq!(format!("{:016x}", id), "000055d0f9a3c1e8");
+    assert_eq!(format!("{:?}", id), "Id(0x55d0f9a3c1e8)");
+}
+
+#[test]
+fn test_size() {
+    assert_eq!(std::mem::size_of::<Id>(), 8);
+    assert_eq!(std::mem::size_of::<Option<Id>>(), 8);
+}
+
+#[test]
+fn test_ord() {
+    let mut ids = [
+        Id::from_u64(0x20).unwrap(),
+        Id::NULL,
+        Id::from_u64(0x3).unwrap(),
+        Id::from_u64(0x100).unwrap(),
+    ];
+    ids.sort();
+    assert_eq!(
+        ids,
+        [
+            Id::NULL,
+            Id::from_u64(0x3).unwrap(),
+            Id::from_u64(0x20).unwrap(),
+            Id::from_u64(0x100).unwrap(),
+        ],
+    );
+    assert_eq!(Id::default(), Id::NULL);
+    assert_eq!(Id::NULL.as_u64(), 0);
+}
+
+#[test]
+fn test_max() {
+    let error = serde_json::from_str::<Id>("\"0xffffffffffffffff\"").unwrap_err();
+    assert!(error.to_string().starts_with("invalid value"));
+}
+
+#[test]
+fn test_integer() {
+    #[derive(serde::Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Clang {
+        previous_decl: Option<Id>,
+    }
+
+    let id: Id = serde_json::from_str("93879385555432").unwrap();
+    assert_eq!(id, Id::from_u64(0x5561_ff6d_81e8).unwrap());
+    assert_eq!(serde_json::from_str::<Id>("0").unwrap(), Id::NULL);
+    assert!(serde_json::from_str::<Id>("-1").is_err());
+    assert!(serde_json::from_str::<Id>("18446744073709551615").is_err());
+
+    let json = r#"
+        {
+          "id": 1,
+          "kind": "TranslationUnitDecl",
+          "inner": [
+            { "id": 2, "kind": "FunctionDecl", "previousDecl": 3 }
+          ]
+        }
+    "#;
+    let node: clang_ast::Node<Clang> = serde_json::from_str(json).unwrap();
+    assert_eq!(node.id, Id::from_u64(1).unwrap());
+    assert_eq!(node.inner[0].id, Id::from_u64(2).unwrap());
+    assert_eq!(node.inner[0].kind.previous_decl, Id::from_u64(3));
+}
+
+// Like bincode, which cannot deserialize_any because nothing in its encoding
+// says what type the next value is.
+struct Compact<'a>(&'a str);
+
+impl<'de, 'a> Deserializer<'de> for Compact<'a> {
+    type Error = serde::de::value::Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Self::Error> {
+        Err(Error::custom("deserialize_any is not supported"))
+    }
+
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        visitor.visit_str(self.0)
+    }
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+#[test]
+fn test_not_self_describing() {
+    let id = Id::deserialize(Compact("0x55d0f9a3c1e8")).unwrap();
+    assert_eq!(id, Id::from_u64(0x55d0_f9a3_c1e8).unwrap());
+}
+
+#[test]
+fn test_id_map() {
+    use clang_ast::IdMap;
+
+    #[derive(serde::Deserialize)]
+    struct Clang;
+
+    let json = r#"
+        {
+          "id": "0x30",
+          "kind": "TranslationUnitDecl",
+          "inner": [
+            { "id": "0x10", "kind": "FunctionDecl" },
+            { "id": "0x0", "kind": "NullStmt" },
+            { "id": "0x20", "kind": "VarDecl" }
+          ]
+        }
+    "#;
+    let node: clang_ast::Node<Clang> = serde_json::from_str(json).unwrap();
+    let mut map = IdMap::from_tree(&node);
+    assert_eq!(
+        map.ids(),
+        [
+            Id::from_u64(0x30).unwrap(),
+            Id::from_u64(0x10).unwrap(),
+            Id::from_u64(0x20).unwrap()
+        ],
+    );
+    assert_eq!(map.get(Id::from_u64(0x20).unwrap()), Some(2));
+    assert_eq!(map.get(Id::NULL), None);
+    assert_eq!(map.id(3), None);
+
+    assert_eq!(map.insert(Id::from_u64(0x10).unwrap()), 1);
+    assert_eq!(map.insert(Id::from_u64(0x40).unwrap()), 3);
+    assert_eq!(map.len(), 4);
+
+    let collected: IdMap = map.ids().iter().rev().copied().collect();
+    assert_eq!(collected.get(Id::from_u64(0x40).unwrap()), Some(0));
+}
@@ -0,0 +1,73 @@
+use clang_ast::IncludeGraph;
+
+const JSON: &str = r#"
+    {
+      "id": "0x1",
+      "kind": "TranslationUnitDecl",
+      "loc": {},
+      "range": { "begin": {}, "end": {} },
+      "inner": [
+        {
+          "id": "0x2",
+          "kind": "TypedefDecl",
+          "loc": {
+            "offset": 4, "file": "<built-in>", "line": 1, "col": 5, "tokLen": 1
+          }
+        },
+        {
+          "id": "0x3",
+          "kind": "VarDecl",
+          "loc": {
+            "offset": 4, "file": "a.h", "line": 1, "col": 5, "tokLen": 1,
+            "includedFrom": { "file": "main.c" }
+          },
+          "range": {
+            "begin": {
+              "spellingLoc": {
+                "offset": 10, "file": "b.h", "line": 1, "col": 5, "tokLen": 1,
+                "includedFrom": { "file": "a.h" }
+              },
+              "expansionLoc": {
+                "offset": 4, "file": "a.h", "line": 1, "col": 5, "tokLen": 1,
+                "includedFrom": { "file": "main.c" }
+              }
+            },
+            "end": {
+              "offset": 4, "col": 5, "tokLen": 1,
+              "includedFrom": { "file": "main.c" }
+            }
+          }
+        },
+        {
+          "id": "0x4",
+          "kind": "VarDecl",
+          "loc": {
+            "offset": 4, "file": "c.h", "line": 1, "col": 5, "tokLen": 1,
+            "includedFrom": { "file": "main.c" }
+          }
+        },
+        {
+          "id": "0x5",
+          "kind": "VarDecl",
+          "loc": { "offset": 40, "file": "main.c", "line": 3, "col": 5, "tokLen": 1 }
+        }
+      ]
+    }
+"#;
+
+#[test]
+fn test_include_graph() {
+    let graph: IncludeGraph = serde_json::from_str(JSON).unwrap();
+    assert_eq!(
+        graph.files().collect::<Vec<_>>(),
+        ["<built-in>", "a.h", "main.c", "b.h", "c.h"],
+    );
+    assert_eq!(
+        graph.edges().collect::<Vec<_>>(),
+        [("main.c", "a.h"), ("a.h", "b.h"), ("main.c", "c.h")],
+    );
+    assert_eq!(graph.includes("main.c").collect::<Vec<_>>(), ["a.h", "c.h"]);
+    assert_eq!(graph.included_by("b.h").collect::<Vec<_>>(), ["a.h"]);
+    assert_eq!(graph.includes("missing.h").count(), 0);
+    assert_eq!(graph.roots().collect::<Vec<_>>(), ["<built-in>", "main.c"]);
+}
@@ -0,0 +1,83 @@
+use clang_ast::{InternedStr, Interner, SourceLocation};
+use serde::Deserialize;
+
+pub type Node = clang_ast::Node<Clang>;
+
+#[derive(Deserialize)]
+pub struct Clang {
+    pub loc: SourceLocation,
+}
+
+fn json(id: &str) -> String {
+    format!(
+        r#"
+        {{
+          "id": "{}",
+          "kind": "TranslationUnitDecl",
+          "loc": {{ "offset": 1, "file": "/usr/include/stdio.h", "line": 1, "col": 1, "tokLen": 1 }}
+        }}
+        "#,
+        id,
+    )
+}
+
+fn file_ptr(node: &Node) -> *const u8 {
+    node.kind.loc.expansion_loc.as_ref().unwrap().file.as_ptr()
+}
+
+#[test]
+fn test_interner() {
+    let interner = Interner::new();
+    let a: Node = interner
+        .scope(|| serde_json::from_str(&json("0x1")))
+        .unwrap();
+    let b: Node = interner
+        .scope(|| serde_json::from_str(&json("0x2")))
+        .unwrap();
+    assert_eq!(file_ptr(&a), file_ptr(&b));
+    assert_eq!(interner.len(), 1);
+    assert_eq!(
+        interner.intern("/usr/include/stdio.h").as_ptr(),
+        file_ptr(&a),
+    );
+
+    // Outside of a scope, each tree interns separately.
+    let c: Node = serde_json::from_str(&json("0x3")).unwrap();
+    assert_ne!(file_ptr(&a), file_ptr(&c));
+    assert_eq!(interner.len(), 1);
+}
+
+#[test]
+fn test_interned_str() {
+    #[derive(Deserialize)]
+    pub struct Decl {
+        pub name: InternedStr,
+        #[serde(rename = "type")]
+        pub ty: Type,
+    }
+
+    #[derive(Deserialize)]
+    pub struct Type {
+        #[serde(rename = "qualType")]
+        pub qual_type: InternedStr,
+    }
+
+    let json = r#"
+        {
+          "id": "0x1",
+          "kind": "TranslationUnitDecl",
+          "name": "",
+          "type": { "qualType": "void" },
+          "inner": [
+            { "id": "0x2", "kind": "VarDecl", "name": "v", "type": { "qualType": "std::vector<int>" } },
+            { "id": "0x3", "kind": "VarDecl", "name": "w", "type": { "qualType": "std::vector<int>" } }
+          ]
+        }
+    "#;
+    let node: clang_ast::Node<Decl> = serde_json::from_str(json).unwrap();
+    let v = &node.inner[0].kind;
+    let w = &node.inner[1].kind;
+    assert_eq!(v.name, "v");
+    assert_eq!(w.ty.qual_type, "std::vector<int>");
+    assert_eq!(v.ty.qual_type.as_ptr(), w.ty.qual_type.as_ptr());
+}
@@ -0,0 +1,103 @@
+use clang_ast::Kind;
+
+#[test]
+fn test_all() {
+    assert!(!Kind::ALL.contains(&Kind::null));
+    assert!(Kind::ALL.windows(2).all(|pair| pair[0] < pair[1]));
+    assert!(Kind::ALL
+        .windows(2)
+        .all(|pair| pair[0].as_str() < pair[1].as_str()));
+    for kind in Kind::ALL {
+        assert_eq!(kind.as_str().parse::<Kind>().unwrap(), *kind);
+    }
+    assert!(Kind::iter().eq(Kind::ALL.iter().cloned()));
+}
+
+#[test]
+fn test_from_str() {
+    let kind: Kind = "CXXMethodDecl".parse().unwrap();
+    assert_eq!(kind, Kind::CXXMethodDecl);
+    assert_eq!(kind.to_string(), "CXXMethodDecl");
+
+    let error: clang_ast::ParseKindError = "CxxMethodDecl".parse::<Kind>().unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "unrecognized clang syntax tree node kind",
+    );
+}
+
+#[test]
+fn test_other() {
+    let kind: Kind = serde_json::from_str("\"HypotheticalFutureDecl\"").unwrap();
+    assert_eq!(kind, Kind::Other(Box::from("HypotheticalFutureDecl")));
+    assert_eq!(kind.as_str(), "HypotheticalFutureDecl");
+    assert_eq!(kind.known_str(), None);
+    assert_eq!(Kind::CXXRecordDecl.known_str(), Some("CXXRecordDecl"));
+    assert_eq!(
+        serde_json::to_string(&kind).unwrap(),
+        "\"HypotheticalFutureDecl\""
+    );
+    assert!("HypotheticalFutureDecl".parse::<Kind>().is_err());
+}
+
+#[test]
+fn test_from_name() {
+    assert_eq!(Kind::from_name("CXXRecordDecl"), Kind::CXXRecordDecl);
+    assert_eq!(
+        Kind::from_name("HypotheticalFutureDecl"),
+        Kind::Other(Box::from("HypotheticalFutureDecl")),
+    );
+    assert_ne!(Kind::Other(Box::from("CXXRecordDecl")), Kind::CXXRecordDecl,);
+}
+
+#[test]
+fn test_other_in_node() {
+    #[derive(serde::Deserialize)]
+    struct Clang {
+        kind: Kind,
+    }
+
+    let json = r#"
+        {
+          "id": "0x1",
+          "kind": "TranslationUnitDecl",
+          "inner": [
+            { "id": "0x2", "kind": "HypotheticalFutureStmt" }
+          ]
+        }
+    "#;
+    let node: clang_ast::Node<Clang> = serde_json::from_str(json).unwrap();
+    assert_eq!(node.kind.kind, Kind::TranslationUnitDecl);
+    assert_eq!(
+        node.inner[0].kind.kind,
+        Kind::Other(Box::from("HypotheticalFutureStmt"))
+    );
+}
+
+#[test]
+fn test_other_borrowed() {
+    #[derive(serde::Deserialize)]
+    enum Clang<'a> {
+        TranslationUnitDecl,
+        Other {
+            #[serde(borrow)]
+            kind: &'a str,
+        },
+    }
+
+    let json = r#"
+        {
+          "id": "0x1",
+          "kind": "TranslationUnitDecl",
+          "inner": [
+            { "id": "0x2", "kind": "HypotheticalFutureStmt" }
+          ]
+        }
+    "#;
+    let node: clang_ast::Node<Clang> = serde_json::from_str(json).unwrap();
+    assert!(matches!(node.kind, Clang::TranslationUnitDecl));
+    match node.inner[0].kind {
+        Clang::Other { kind } => assert_eq!(kind, "HypotheticalFutureStmt"),
+        Clang::TranslationUnitDecl => panic!(),
+    }
+}
@@ -0,0 +1,65 @@
+#![cfg(feature = "lazy")]
+
+use clang_ast::{Kind, LazyNode, SourceLocation};
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+pub struct Clang {
+    pub kind: Kind,
+    pub loc: Option<SourceLocation>,
+}
+
+fn file(node: &LazyNode<Clang>) -> &str {
+    let loc = node.kind.loc.as_ref().unwrap();
+    &loc.expansion_loc.as_ref().unwrap().file
+}
+
+#[test]
+fn test_lazy() {
+    let json = r#"
+        {
+          "id": "0x1",
+          "kind": "TranslationUnitDecl",
+          "inner": [
+            {
+              "id": "0x2",
+              "kind": "NamespaceDecl",
+              "loc": { "offset": 10, "file": "a.h", "line": 1, "col": 11, "tokLen": 2 },
+              "inner": [
+                {
+                  "id": "0x3",
+                  "kind": "VarDecl",
+                  "loc": { "offset": 20, "line": 2, "col": 5, "tokLen": 1 }
+                },
+                {
+                  "id": "0x4",
+                  "kind": "VarDecl",
+                  "loc": { "offset": 30, "file": "b.h", "line": 7, "col": 5, "tokLen": 1 }
+                }
+              ]
+            },
+            {
+              "id": "0x5",
+              "kind": "VarDecl",
+              "loc": { "offset": 40, "col": 5, "tokLen": 1 }
+            }
+          ]
+        }
+    "#;
+
+    let root: LazyNode<Clang> = serde_json::from_str(json).unwrap();
+    assert_eq!(root.kind.kind, Kind::TranslationUnitDecl);
+
+    let children = root.children().unwrap();
+    assert_eq!(children.len(), 2);
+    assert!(children[0].inner_json().is_some());
+    assert!(children[1].inner_json().is_none());
+    assert_eq!(file(&children[0]), "a.h");
+    assert_eq!(file(&children[1]), "b.h");
+
+    let grandchildren = children[0].children().unwrap();
+    assert_eq!(grandchildren.len(), 2);
+    assert_eq!(file(&grandchildren[0]), "a.h");
+    assert_eq!(file(&grandchildren[1]), "b.h");
+    assert!(grandchildren[0].children().unwrap().is_empty());
+}
@@ -0,0 +1,53 @@
+#![cfg(feature = "legacy")]
+
+use clang_ast::{Kind, SourceLocation, SourceRange};
+use serde::Deserialize;
+
+pub type Node = clang_ast::Node<Clang>;
+
+#[derive(Deserialize)]
+pub struct Clang {
+    pub kind: Kind,
+    pub loc: Option<SourceLocation>,
+    pub range: Option<SourceRange>,
+}
+
+#[test]
+fn test_legacy_locations() {
+    let json = r#"
+        {
+          "id": "0x1",
+          "kind": "TranslationUnitDecl",
+          "inner": [
+            {
+              "id": "0x2",
+              "kind": "VarDecl",
+              "loc": { "file": "a.h", "line": 3, "col": 5, "tokLen": 1 },
+              "range": {
+                "begin": {
+                  "expansionLoc": { "line": 4, "col": 1, "tokLen": 6 },
+                  "spellingLoc": { "file": "b.h", "line": 1, "col": 9, "tokLen": 6 }
+                },
+                "end": { "col": 7, "tokLen": 1 }
+              }
+            }
+          ]
+        }
+    "#;
+    let node: Node = serde_json::from_str(json).unwrap();
+    let var = &node.inner[0].kind;
+
+    let loc = var.loc.as_ref().unwrap().expansion_loc.as_ref().unwrap();
+    assert_eq!(
+        (&*loc.file, loc.line, loc.col, loc.offset),
+        ("a.h", 3, 5, 0)
+    );
+
+    let range = var.range.as_ref().unwrap();
+    let expansion = range.begin.expansion_loc.as_ref().unwrap();
+    assert_eq!((&*expansion.file, expansion.line), ("a.h", 4));
+    let spelling = range.begin.spelling_loc.as_ref().unwrap();
+    assert_eq!((&*spelling.file, spelling.line), ("b.h", 1));
+    let end = range.end.expansion_loc.as_ref().unwrap();
+    assert_eq!((&*end.file, end.line, end.col), ("b.h", 1, 7));
+}
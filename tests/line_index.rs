@@ -0,0 +1,55 @@
+use clang_ast::{LineIndex, Position, PositionEncoding};
+
+#[test]
+fn test_position() {
+    // The emoji is 4 bytes, 2 UTF-16 code units, and 1 char.
+    let text = "a\u{1F600}b\r\n\nc";
+    let index = LineIndex::new(text);
+    assert_eq!(index.line_count(), 3);
+    assert_eq!(index.line_range(0), Some(0..7));
+    assert_eq!(index.line_range(1), Some(8..8));
+    assert_eq!(index.line_range(2), Some(9..10));
+    assert_eq!(index.line_range(3), None);
+
+    let b = text.find('b').unwrap();
+    let cases = [
+        (PositionEncoding::Utf8, 5),
+        (PositionEncoding::Utf16, 3),
+        (PositionEncoding::Utf32, 2),
+    ];
+    for &(encoding, col) in &cases {
+        let position = Position { line: 0, col };
+        assert_eq!(index.position(b, encoding), Some(position));
+        assert_eq!(index.offset(position, encoding), Some(b));
+    }
+
+    let c = Position { line: 2, col: 0 };
+    assert_eq!(index.position(9, PositionEncoding::Utf16), Some(c));
+    assert_eq!(index.offset(c, PositionEncoding::Utf16), Some(9));
+    let end = Position { line: 2, col: 1 };
+    assert_eq!(
+        index.position(text.len(), PositionEncoding::Utf8),
+        Some(end)
+    );
+    assert_eq!(index.offset(end, PositionEncoding::Utf32), Some(text.len()));
+}
+
+#[test]
+fn test_invalid() {
+    let text = "a\u{1F600}b\n";
+    let index = LineIndex::new(text);
+
+    // Inside the emoji.
+    assert_eq!(index.position(2, PositionEncoding::Utf8), None);
+    let inside = Position { line: 0, col: 2 };
+    assert_eq!(index.offset(inside, PositionEncoding::Utf8), None);
+    assert_eq!(index.offset(inside, PositionEncoding::Utf16), None);
+
+    assert_eq!(index.position(text.len() + 1, PositionEncoding::Utf8), None);
+    let past_end = Position { line: 0, col: 4 };
+    assert_eq!(index.offset(past_end, PositionEncoding::Utf32), None);
+    let past_end = Position { line: 0, col: 5 };
+    assert_eq!(index.offset(past_end, PositionEncoding::Utf16), None);
+    let no_line = Position { line: 2, col: 0 };
+    assert_eq!(index.offset(no_line, PositionEncoding::Utf8), None);
+}
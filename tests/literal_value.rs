@@ -0,0 +1,78 @@
+use clang_ast::LiteralValue;
+
+fn parse(json: &str) -> LiteralValue {
+    serde_json::from_str(json).unwrap()
+}
+
+#[test]
+fn test_small() {
+    let value = parse(r#""42""#);
+    assert!(!value.is_negative());
+    assert_eq!(value.as_i64(), Some(42));
+    assert_eq!(value.as_u64(), Some(42));
+    assert_eq!(value.to_string(), "42");
+
+    let value = parse(r#""-9223372036854775808""#);
+    assert!(value.is_negative());
+    assert_eq!(value.as_i64(), Some(i64::MIN));
+    assert_eq!(value.as_u64(), None);
+
+    assert_eq!(parse(r#""-0""#), parse("0"));
+    assert_eq!(parse("-7").as_i64(), Some(-7));
+}
+
+#[test]
+fn test_wide() {
+    let value = parse(r#""18446744073709551615""#);
+    assert_eq!(value.as_i64(), None);
+    assert_eq!(value.as_u64(), Some(u64::MAX));
+
+    let value = parse(r#""340282366920938463463374607431768211455""#);
+    assert_eq!(value.as_u64(), None);
+    assert_eq!(value.as_i128(), None);
+    assert_eq!(value.as_u128(), Some(u128::MAX));
+
+    let value = parse(r#""-170141183460469231731687303715884105728""#);
+    assert_eq!(value.as_i128(), Some(i128::MIN));
+    assert_eq!(value.as_u128(), None);
+
+    let digits = "-1000000000000000000000000000000000000000000";
+    let value = parse(&format!("\"{}\"", digits));
+    assert!(value.is_wide());
+    assert!(value.is_negative());
+    assert_eq!(value.as_i128(), None);
+    assert_eq!(value.to_bits(128), None);
+    assert_eq!(value.to_string(), digits);
+}
+
+#[test]
+fn test_to_bits() {
+    assert_eq!(parse(r#""-1""#).to_bits(8), Some(0xff));
+    assert_eq!(parse(r#""255""#).to_bits(8), Some(0xff));
+    assert_eq!(parse(r#""-128""#).to_bits(8), Some(0x80));
+    assert_eq!(parse(r#""-129""#).to_bits(8), None);
+    assert_eq!(parse(r#""256""#).to_bits(8), None);
+    assert_eq!(parse(r#""-1""#).to_bits(128), Some(u128::MAX));
+    assert_eq!(parse(r#""1""#).to_bits(0), None);
+}
+
+#[test]
+fn test_serialize() {
+    let value = parse(r#""-12""#);
+    assert_eq!(serde_json::to_string(&value).unwrap(), r#""-12""#);
+    assert_eq!(
+        LiteralValue::from(u128::MAX).to_string(),
+        u128::MAX.to_string()
+    );
+}
+
+#[test]
+fn test_invalid() {
+    for json in &[r#""""#, r#""-""#, r#""0x10""#, r#""1.5""#, "true"] {
+        assert!(
+            serde_json::from_str::<LiteralValue>(json).is_err(),
+            "{}",
+            json
+        );
+    }
+}
@@ -0,0 +1,390 @@
+use clang_ast::{IncludedFrom, SourceLocation};
+
+#[derive(serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Clang {
+    loc: SourceLocation,
+}
+
+#[test]
+fn test_spelling_expansion() {
+    let json = r#"
+        {
+          "id": "0x1",
+          "kind": "VarDecl",
+          "loc": {
+            "offset": 40,
+            "file": "main.c",
+            "line": 4,
+            "col": 5,
+            "tokLen": 1
+          },
+          "inner": [
+            {
+              "id": "0x2",
+              "kind": "IntegerLiteral",
+              "loc": {
+                "spellingLoc": {
+                  "offset": 20, "line": 1, "col": 17, "tokLen": 1
+                },
+                "expansionLoc": {
+                  "offset": 44, "line": 4, "col": 9, "tokLen": 4
+                }
+              }
+            }
+          ]
+        }
+    "#;
+    let node: clang_ast::Node<Clang> = serde_json::from_str(json).unwrap();
+
+    let loc = &node.kind.loc;
+    assert_eq!(loc.spelling().unwrap().offset, 40);
+    assert_eq!(loc.expansion().unwrap().offset, 40);
+
+    let loc = &node.inner[0].kind.loc;
+    let spelling = loc.spelling().unwrap();
+    assert_eq!((spelling.line, spelling.col), (1, 17));
+    assert_eq!(&*spelling.file, "main.c");
+    let expansion = loc.expansion().unwrap();
+    assert_eq!((expansion.line, expansion.col), (4, 9));
+    assert_eq!(&*expansion.file, "main.c");
+
+    assert!(SourceLocation::default().spelling().is_none());
+}
+
+#[test]
+fn test_macro_expansion() {
+    // #define ZERO 0
+    // #define ID(x) x
+    // int a = ZERO, b = ID(1), c = 2;
+    let json = r#"
+        {
+          "id": "0x1",
+          "kind": "TranslationUnitDecl",
+          "loc": {},
+          "inner": [
+            {
+              "id": "0x2",
+              "kind": "IntegerLiteral",
+              "loc": {
+                "spellingLoc": {
+                  "offset": 13, "file": "main.c", "line": 1, "col": 14, "tokLen": 1
+                },
+                "expansionLoc": {
+                  "offset": 42, "line": 3, "col": 9, "tokLen": 4
+                }
+              }
+            },
+            {
+              "id": "0x3",
+              "kind": "IntegerLiteral",
+              "loc": {
+                "spellingLoc": {
+                  "offset": 54, "col": 21, "tokLen": 1
+                },
+                "expansionLoc": {
+                  "offset": 51, "col": 18, "tokLen": 2, "isMacroArgExpansion": true
+                }
+              }
+            },
+            {
+              "id": "0x4",
+              "kind": "IntegerLiteral",
+              "loc": {
+                "offset": 62, "col": 29, "tokLen": 1
+              }
+            }
+          ]
+        }
+    "#;
+    let node: clang_ast::Node<Clang> = serde_json::from_str(json).unwrap();
+    assert!(!node.kind.loc.is_macro_expansion());
+
+    let body = &node.inner[0].kind.loc;
+    assert!(body.is_macro_expansion());
+    assert!(!body.is_macro_arg_expansion());
+    assert!(body.is_macro_body_expansion());
+
+    let arg = &node.inner[1].kind.loc;
+    assert!(arg.is_macro_expansion());
+    assert!(arg.is_macro_arg_expansion());
+    assert!(!arg.is_macro_body_expansion());
+
+    let literal = &node.inner[2].kind.loc;
+    assert!(!literal.is_macro_expansion());
+    assert!(!literal.is_macro_arg_expansion());
+    assert!(!literal.is_macro_body_expansion());
+}
+
+#[test]
+fn test_presumed() {
+    // parser.cc, generated from parser.y:
+    //
+    //     #line 40 "parser.y"
+    //     int a = 1; int b;
+    //     int c;
+    //     #line 6 "parser.cc"
+    //     int d;
+    let json = r#"
+        {
+          "id": "0x1",
+          "kind": "TranslationUnitDecl",
+          "loc": {},
+          "inner": [
+            {
+              "id": "0x2",
+              "kind": "VarDecl",
+              "loc": {
+                "offset": 24, "file": "parser.cc", "line": 2,
+                "presumedFile": "parser.y", "presumedLine": 40,
+                "col": 5, "tokLen": 1
+              }
+            },
+            {
+              "id": "0x3",
+              "kind": "VarDecl",
+              "loc": { "offset": 35, "col": 16, "tokLen": 1 }
+            },
+            {
+              "id": "0x4",
+              "kind": "VarDecl",
+              "loc": { "offset": 42, "line": 3, "presumedLine": 41, "col": 5, "tokLen": 1 }
+            },
+            {
+              "id": "0x5",
+              "kind": "VarDecl",
+              "loc": { "offset": 68, "line": 5, "presumedFile": "parser.cc", "presumedLine": 6, "col": 5, "tokLen": 1 }
+            },
+            {
+              "id": "0x6",
+              "kind": "VarDecl",
+              "loc": { "offset": 70, "col": 7, "tokLen": 1 }
+            }
+          ]
+        }
+    "#;
+    let node: clang_ast::Node<Clang> = serde_json::from_str(json).unwrap();
+    let presumed: Vec<_> = node
+        .inner
+        .iter()
+        .map(|child| {
+            let loc = child.kind.loc.spelling().unwrap();
+            (loc.presumed_file.as_deref(), loc.presumed_line, loc.line)
+        })
+        .collect();
+    assert_eq!(
+        presumed,
+        [
+            (Some("parser.y"), Some(40), 2),
+            (Some("parser.y"), Some(40), 2),
+            (Some("parser.y"), Some(41), 3),
+            (None, Some(6), 5),
+            (None, Some(6), 5),
+        ],
+    );
+}
+
+fn bare(json: &str) -> clang_ast::BareSourceLocation {
+    serde_json::from_str(json).unwrap()
+}
+
+#[test]
+fn test_cmp() {
+    use std::cmp::Ordering;
+
+    let a = bare(r#"{ "offset": 10, "file": "b.h", "line": 1, "col": 11, "tokLen": 3 }"#);
+    let b = bare(r#"{ "offset": 30, "file": "b.h", "line": 2, "col": 5, "tokLen": 1 }"#);
+    let c = bare(r#"{ "offset": 5, "file": "a.h", "line": 1, "col": 6, "tokLen": 1 }"#);
+
+    assert!(a.same_file(&b));
+    assert!(!a.same_file(&c));
+    assert_eq!(a.cmp_in_file(&b), Some(Ordering::Less));
+    assert_eq!(b.cmp_in_file(&a), Some(Ordering::Greater));
+    assert_eq!(a.cmp_in_file(&a), Some(Ordering::Equal));
+    assert_eq!(a.cmp_in_file(&c), None);
+
+    let mut locs = [b, c, a];
+    locs.sort_by(clang_ast::BareSourceLocation::cmp_by_file);
+    let order: Vec<_> = locs.iter().map(|loc| (&*loc.file, loc.offset)).collect();
+    assert_eq!(order, [("a.h", 5), ("b.h", 10), ("b.h", 30)]);
+}
+
+fn range(json: &str) -> clang_ast::SourceRange {
+    serde_json::from_str(json).unwrap()
+}
+
+#[test]
+fn test_contains() {
+    // int f() { return 0; }
+    let function = range(
+        r#"{
+          "begin": { "offset": 0, "file": "main.c", "line": 1, "col": 1, "tokLen": 3 },
+          "end": { "offset": 20, "col": 21, "tokLen": 1 }
+        }"#,
+    );
+    let body = range(
+        r#"{
+          "begin": { "offset": 8, "col": 9, "tokLen": 1 },
+          "end": { "offset": 20, "col": 21, "tokLen": 1 }
+        }"#,
+    );
+    let other_file = range(
+        r#"{
+          "begin": { "offset": 8, "file": "other.c", "line": 1, "col": 9, "tokLen": 1 },
+          "end": { "offset": 9, "col": 10, "tokLen": 1 }
+        }"#,
+    );
+    let zero = bare(r#"{ "offset": 17, "file": "main.c", "line": 1, "col": 18, "tokLen": 1 }"#);
+    let name = bare(r#"{ "offset": 4, "col": 5, "tokLen": 1 }"#);
+    let past_end = bare(r#"{ "offset": 21, "col": 22, "tokLen": 1 }"#);
+
+    assert!(function.contains(&zero));
+    assert!(body.contains(&zero));
+    assert!(function.contains(&name));
+    assert!(!body.contains(&name));
+    assert!(!function.contains(&past_end));
+    assert!(!other_file.contains(&zero));
+
+    assert!(function.contains_range(&body));
+    assert!(function.contains_range(&function));
+    assert!(!body.contains_range(&function));
+    assert!(!function.contains_range(&other_file));
+
+    assert!(function.overlaps(&body));
+    assert!(body.overlaps(&function));
+    assert!(!function.overlaps(&other_file));
+
+    let before = range(
+        r#"{
+          "begin": { "offset": 0, "file": "main.c", "line": 1, "col": 1, "tokLen": 3 },
+          "end": { "offset": 4, "col": 5, "tokLen": 1 }
+        }"#,
+    );
+    assert!(!before.overlaps(&body));
+    assert!(before.overlaps(&function));
+}
+
+#[test]
+fn test_byte_range() {
+    let loc = bare(r#"{ "offset": 8, "file": "main.c", "line": 1, "col": 9, "tokLen": 6 }"#);
+    assert_eq!(loc.end_offset(), 14);
+
+    let token = range(
+        r#"{
+          "begin": { "offset": 8, "col": 9, "tokLen": 6 },
+          "end": { "offset": 20, "col": 21, "tokLen": 1 }
+        }"#,
+    );
+    assert_eq!(token.byte_range(), Some(8..21));
+
+    let split = range(
+        r#"{
+          "begin": { "offset": 8, "file": "a.h", "line": 1, "col": 9, "tokLen": 1 },
+          "end": { "offset": 2, "file": "b.h", "line": 1, "col": 3, "tokLen": 1 }
+        }"#,
+    );
+    assert_eq!(split.byte_range(), None);
+    assert_eq!(clang_ast::SourceRange::default().byte_range(), None);
+}
+
+#[test]
+fn test_include_chain() {
+    let json = r#"
+        {
+          "id": "0x1",
+          "kind": "TranslationUnitDecl",
+          "loc": {},
+          "inner": [
+            {
+              "id": "0x2",
+              "kind": "VarDecl",
+              "loc": {
+                "offset": 4, "file": "a.h", "line": 1, "col": 5, "tokLen": 1,
+                "includedFrom": { "file": "main.c" }
+              }
+            },
+            {
+              "id": "0x3",
+              "kind": "VarDecl",
+              "loc": {
+                "offset": 4, "file": "b.h", "line": 1, "col": 5, "tokLen": 1,
+                "includedFrom": { "file": "a.h" }
+              }
+            },
+            {
+              "id": "0x4",
+              "kind": "VarDecl",
+              "loc": {
+                "offset": 20, "line": 2, "col": 5, "tokLen": 1,
+                "includedFrom": { "file": "a.h" }
+              }
+            },
+            {
+              "id": "0x5",
+              "kind": "VarDecl",
+              "loc": { "offset": 40, "file": "main.c", "line": 3, "col": 5, "tokLen": 1 }
+            }
+          ]
+        }
+    "#;
+    let chain = |node: &clang_ast::Node<Clang>, i: usize| -> Vec<String> {
+        let loc = node.inner[i].kind.loc.spelling().unwrap();
+        loc.included_from
+            .iter()
+            .flat_map(IncludedFrom::chain)
+            .map(|inc| inc.file.to_string())
+            .collect()
+    };
+
+    // Only the direct includer unless asked for the whole chain.
+    let node: clang_ast::Node<Clang> = serde_json::from_str(json).unwrap();
+    assert_eq!(chain(&node, 1), ["a.h"]);
+
+    let node: clang_ast::Node<Clang> =
+        IncludedFrom::with_chains(|| serde_json::from_str(json)).unwrap();
+    let chain = |i| chain(&node, i);
+    assert_eq!(chain(0), ["main.c"]);
+    assert_eq!(chain(1), ["a.h", "main.c"]);
+    assert_eq!(chain(2), ["a.h", "main.c"]);
+    assert!(chain(3).is_empty());
+
+    // Serialized the way Clang writes it, naming only the direct includer.
+    let json = serde_json::to_string(&node.inner[1].kind).unwrap();
+    assert!(
+        json.contains(r#""includedFrom":{"file":"a.h"}"#),
+        "{}",
+        json
+    );
+}
+
+#[test]
+fn test_origin() {
+    use clang_ast::Origin;
+
+    let loc = |json| -> SourceLocation { serde_json::from_str(json).unwrap() };
+    let main = loc(r#"{ "offset": 0, "file": "src/main.c", "line": 1, "col": 1, "tokLen": 1 }"#);
+    let project = loc(r#"{ "offset": 0, "file": "src/util.h", "line": 1, "col": 1, "tokLen": 1 }"#);
+    let system =
+        loc(r#"{ "offset": 0, "file": "/usr/include/stdio.h", "line": 1, "col": 1, "tokLen": 1 }"#);
+    let not_system =
+        loc(r#"{ "offset": 0, "file": "/usr/includes/x.h", "line": 1, "col": 1, "tokLen": 1 }"#);
+    let scratch =
+        loc(r#"{ "offset": 0, "file": "<scratch space>", "line": 1, "col": 1, "tokLen": 1 }"#);
+    let macro_in_main = loc(r#"{
+          "spellingLoc": { "offset": 0, "file": "/usr/include/stdio.h", "line": 1, "col": 1, "tokLen": 1 },
+          "expansionLoc": { "offset": 0, "file": "src/main.c", "line": 1, "col": 1, "tokLen": 1 }
+        }"#);
+
+    let system_dirs = ["/usr/include"];
+    let origin = |loc: &SourceLocation| loc.origin("src/main.c", &system_dirs);
+    assert_eq!(origin(&main), Some(Origin::MainFile));
+    assert_eq!(origin(&project), Some(Origin::ProjectHeader));
+    assert_eq!(origin(&system), Some(Origin::SystemHeader));
+    assert_eq!(origin(&not_system), Some(Origin::ProjectHeader));
+    assert_eq!(origin(&scratch), None);
+    assert_eq!(origin(&SourceLocation::default()), None);
+    assert_eq!(origin(&macro_in_main), Some(Origin::MainFile));
+    assert!(system.is_in_system_header(&system_dirs));
+    assert!(!main.is_in_system_header(&system_dirs));
+    assert!(macro_in_main.is_in_main_file("src/main.c"));
+}
@@ -0,0 +1,81 @@
+use clang_ast::{Kind, Node, SourceRange};
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+pub struct Clang {
+    pub kind: Kind,
+    pub range: Option<SourceRange>,
+}
+
+// int f() {
+//   return 42;
+// }
+const JSON: &str = r#"
+    {
+      "id": "0x1",
+      "kind": "TranslationUnitDecl",
+      "inner": [
+        {
+          "id": "0x2",
+          "kind": "TypedefDecl",
+          "range": { "begin": {}, "end": {} }
+        },
+        {
+          "id": "0x3",
+          "kind": "FunctionDecl",
+          "range": {
+            "begin": { "offset": 0, "file": "main.c", "line": 1, "col": 1, "tokLen": 3 },
+            "end": { "offset": 24, "line": 3, "col": 1, "tokLen": 1 }
+          },
+          "inner": [
+            {
+              "id": "0x4",
+              "kind": "CompoundStmt",
+              "range": {
+                "begin": { "offset": 8, "line": 1, "col": 9, "tokLen": 1 },
+                "end": { "offset": 24, "line": 3, "col": 1, "tokLen": 1 }
+              },
+              "inner": [
+                {
+                  "id": "0x5",
+                  "kind": "ReturnStmt",
+                  "range": {
+                    "begin": { "offset": 12, "line": 2, "col": 3, "tokLen": 6 },
+                    "end": { "offset": 19, "col": 10, "tokLen": 2 }
+                  },
+                  "inner": [
+                    {
+                      "id": "0x6",
+                      "kind": "IntegerLiteral",
+                      "range": {
+                        "begin": { "offset": 19, "col": 10, "tokLen": 2 },
+                        "end": { "offset": 19, "col": 10, "tokLen": 2 }
+                      }
+                    }
+                  ]
+                }
+              ]
+            }
+          ]
+        }
+      ]
+    }
+"#;
+
+#[test]
+fn test_node_at() {
+    let node: Node<Clang> = serde_json::from_str(JSON).unwrap();
+    let at = |file, line, col| {
+        node.node_at(file, line, col, |kind| kind.range.as_ref())
+            .map(|node| node.kind.kind.clone())
+    };
+
+    assert_eq!(at("main.c", 1, 1), Some(Kind::FunctionDecl));
+    assert_eq!(at("main.c", 1, 9), Some(Kind::CompoundStmt));
+    assert_eq!(at("main.c", 2, 3), Some(Kind::ReturnStmt));
+    assert_eq!(at("main.c", 2, 11), Some(Kind::IntegerLiteral));
+    assert_eq!(at("main.c", 2, 12), Some(Kind::CompoundStmt));
+    assert_eq!(at("main.c", 3, 1), Some(Kind::CompoundStmt));
+    assert_eq!(at("main.c", 3, 2), None);
+    assert_eq!(at("other.c", 2, 3), None);
+}
@@ -0,0 +1,94 @@
+use clang_ast::lsp::{self, Position, Range};
+use clang_ast::{PositionEncoding, SourceManager, SourceRange};
+use std::io::ErrorKind;
+
+fn range(json: &str) -> SourceRange {
+    serde_json::from_str(json).unwrap()
+}
+
+#[test]
+fn test_location() {
+    let mut sources = SourceManager::new();
+    sources.root("/work");
+    // The emoji is 4 bytes and 2 UTF-16 code units.
+    sources.insert("src/a b.c", "s = \"\u{1F600}\"; x;\n".to_owned());
+
+    // `x`, at byte 12.
+    let x = range(
+        r#"{
+          "begin": { "offset": 12, "file": "src/a b.c", "line": 1, "col": 13, "tokLen": 1 },
+          "end": { "offset": 12, "col": 13, "tokLen": 1 }
+        }"#,
+    );
+    let utf16 = Range {
+        start: Position {
+            line: 0,
+            character: 10,
+        },
+        end: Position {
+            line: 0,
+            character: 11,
+        },
+    };
+    assert_eq!(
+        lsp::range(&sources, &x, PositionEncoding::Utf16).unwrap(),
+        utf16
+    );
+    let utf8 = lsp::range(&sources, &x, PositionEncoding::Utf8).unwrap();
+    assert_eq!((utf8.start.character, utf8.end.character), (12, 13));
+
+    let location = lsp::location(&sources, &x, PositionEncoding::Utf16).unwrap();
+    assert_eq!(location.range, utf16);
+    if cfg!(unix) {
+        assert_eq!(location.uri, "file:///work/src/a%20b.c");
+    }
+    assert_eq!(
+        serde_json::to_value(location.range).unwrap(),
+        serde_json::json!({
+            "start": { "line": 0, "character": 10 },
+            "end": { "line": 0, "character": 11 },
+        }),
+    );
+}
+
+#[test]
+fn test_error() {
+    let mut sources = SourceManager::new();
+    sources.insert("a.c", "int x;\n".to_owned());
+
+    let error = lsp::range(&sources, &SourceRange::default(), PositionEncoding::Utf16).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::InvalidInput);
+
+    let past_end = range(
+        r#"{
+          "begin": { "offset": 100, "file": "a.c", "line": 9, "col": 1, "tokLen": 1 },
+          "end": { "offset": 100, "col": 1, "tokLen": 1 }
+        }"#,
+    );
+    let error = lsp::location(&sources, &past_end, PositionEncoding::Utf16).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::InvalidInput);
+}
+
+#[cfg(feature = "lsp-types")]
+#[test]
+fn test_lsp_types() {
+    let mut sources = SourceManager::new();
+    sources.root("/work");
+    sources.insert("src/a b.c", "int x;\n".to_owned());
+
+    let x = range(
+        r#"{
+          "begin": { "offset": 4, "file": "src/a b.c", "line": 1, "col": 5, "tokLen": 1 },
+          "end": { "offset": 4, "col": 5, "tokLen": 1 }
+        }"#,
+    );
+    let location = lsp::location(&sources, &x, PositionEncoding::Utf16).unwrap();
+    let converted = lsp_types::Location::from(location.clone());
+    assert_eq!(converted.range.start, lsp_types::Position::new(0, 4));
+    assert_eq!(converted.range.end, lsp_types::Position::new(0, 5));
+    assert_eq!(converted.uri.as_str(), location.uri);
+    assert_eq!(
+        serde_json::to_value(&converted).unwrap(),
+        serde_json::to_value(&location).unwrap(),
+    );
+}
@@ -0,0 +1,52 @@
+#![cfg(feature = "mmap")]
+
+use clang_ast::Kind;
+use serde::Deserialize;
+use std::fs;
+
+pub type Node = clang_ast::Node<Clang>;
+
+#[derive(Deserialize)]
+pub struct Clang {
+    pub kind: Kind,
+}
+
+#[test]
+fn test_from_path() {
+    let json = r#"
+        {
+          "id": "0x1",
+          "kind": "TranslationUnitDecl",
+          "inner": [
+            { "id": "0x2", "kind": "FunctionDecl" }
+          ]
+        }
+    "#;
+    let dir = std::env::temp_dir();
+    let path = dir.join(format!("clang-ast-mmap-{}.json", std::process::id()));
+    fs::write(&path, json).unwrap();
+    let result = clang_ast::from_path::<Clang, _>(&path);
+    fs::remove_file(&path).unwrap();
+
+    let node: Node = result.unwrap();
+    assert_eq!(node.kind.kind, Kind::TranslationUnitDecl);
+    assert_eq!(node.inner[0].kind.kind, Kind::FunctionDecl);
+}
+
+#[test]
+fn test_from_path_empty() {
+    let dir = std::env::temp_dir();
+    let path = dir.join(format!("clang-ast-empty-{}.json", std::process::id()));
+    fs::write(&path, "").unwrap();
+    let result = clang_ast::from_path::<Clang, _>(&path);
+    fs::remove_file(&path).unwrap();
+
+    assert!(result.err().unwrap().is_eof());
+}
+
+#[test]
+fn test_from_path_missing() {
+    let path = std::env::temp_dir().join("clang-ast-does-not-exist.json");
+    let err = clang_ast::from_path::<Clang, _>(path).err().unwrap();
+    assert!(err.is_io());
+}
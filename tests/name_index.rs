@@ -0,0 +1,61 @@
+use clang_ast::{DeclName, Id, NameIndex, Node, QualifiedNames};
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+pub struct Clang {
+    pub name: Option<String>,
+    #[serde(rename = "mangledName")]
+    pub mangled_name: Option<String>,
+}
+
+const JSON: &str = r#"
+    {
+      "id": "0x1",
+      "kind": "TranslationUnitDecl",
+      "inner": [
+        {
+          "id": "0x2",
+          "kind": "NamespaceDecl",
+          "name": "a",
+          "inner": [
+            { "id": "0x3", "kind": "FunctionDecl", "name": "f", "mangledName": "_ZN1a1fEv" }
+          ]
+        },
+        {
+          "id": "0x4",
+          "kind": "NamespaceDecl",
+          "name": "b",
+          "inner": [
+            { "id": "0x5", "kind": "FunctionDecl", "name": "f", "mangledName": "_ZN1b1fEv" }
+          ]
+        },
+        { "id": "0x6", "kind": "FunctionDecl", "name": "f", "mangledName": "_Z1fv" }
+      ]
+    }
+"#;
+
+fn id(id: u64) -> Id {
+    Id::from_u64(id).unwrap()
+}
+
+#[test]
+fn test_name_index() {
+    let node: Node<Clang> = serde_json::from_str(JSON).unwrap();
+
+    let index = NameIndex::new(&node, |kind| kind.name.as_deref());
+    assert_eq!(index.len(), 3);
+    assert_eq!(index.get("f"), [id(0x3), id(0x5), id(0x6)],);
+    assert_eq!(index.get("a"), [id(0x2)]);
+    let mut names: Vec<&str> = index.names().collect();
+    names.sort_unstable();
+    assert_eq!(names, ["a", "b", "f"]);
+
+    let index = NameIndex::new(&node, |kind| kind.mangled_name.as_deref());
+    assert_eq!(index.get("_ZN1b1fEv"), [id(0x5)]);
+    assert!(index.get("f").is_empty());
+
+    let names = QualifiedNames::new(&node, |kind| kind.name.as_deref().map(DeclName::Named));
+    let index = NameIndex::qualified(&node, &names);
+    assert_eq!(index.get("a::f"), [id(0x3)]);
+    assert_eq!(index.get("f"), [id(0x6)]);
+}
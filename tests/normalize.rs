@@ -0,0 +1,92 @@
+use clang_ast::{Kind, Normalize, Ref, SourceRange};
+use serde::{Deserialize, Serialize};
+
+pub type Node = clang_ast::Node<Clang>;
+
+#[derive(Deserialize, Serialize)]
+pub struct Clang {
+    pub kind: Kind,
+    pub range: Option<SourceRange>,
+    #[serde(rename = "previousDecl", skip_serializing_if = "Option::is_none")]
+    pub previous_decl: Option<Ref<()>>,
+}
+
+fn dump(base: &str, first: u64, offset: usize) -> String {
+    format!(
+        r#"
+        {{
+          "id": "0x{first:x}",
+          "kind": "TranslationUnitDecl",
+          "inner": [
+            {{
+              "id": "0x{second:x}",
+              "kind": "FunctionDecl",
+              "range": {{
+                "begin": {{ "offset": {offset}, "file": "{base}/a.h", "line": 3, "col": 1, "tokLen": 4 }},
+                "end": {{ "offset": {end}, "col": 12, "tokLen": 1 }}
+              }}
+            }},
+            {{
+              "id": "0x{third:x}",
+              "kind": "FunctionDecl",
+              "previousDecl": "0x{second:x}",
+              "range": {{
+                "begin": {{ "offset": 0, "file": "/usr/include/stdio.h", "line": 3, "col": 1, "tokLen": 4 }},
+                "end": {{ "offset": 11, "col": 12, "tokLen": 1 }}
+              }}
+            }}
+          ]
+        }}
+        "#,
+        first = first,
+        second = first + 0x40,
+        third = first + 0x80,
+        base = base,
+        offset = offset,
+        end = offset + 11,
+    )
+}
+
+fn snapshot(json: &str) -> String {
+    let node: Node = serde_json::from_str(json).unwrap();
+    let mut normalize = Normalize::new();
+    normalize
+        .renumber_ids()
+        .zero_offsets()
+        .relative_to("/home/ci");
+    let mut out = Vec::new();
+    normalize
+        .serialize(&node, &mut serde_json::Serializer::new(&mut out))
+        .unwrap();
+    String::from_utf8(out).unwrap()
+}
+
+#[test]
+fn test_normalize() {
+    let a = snapshot(&dump("/home/ci/src", 0x7f00_0000, 100));
+    let b = snapshot(&dump("/home/ci/src", 0x5500_0000, 250));
+    assert_eq!(a, b);
+
+    let node: serde_json::Value = serde_json::from_str(&a).unwrap();
+    assert_eq!(node["id"], "0x1");
+    assert_eq!(node["inner"][0]["id"], "0x2");
+    assert_eq!(node["inner"][1]["id"], "0x3");
+    assert_eq!(node["inner"][1]["previousDecl"], "0x2");
+
+    let node: Node = serde_json::from_str(&a).unwrap();
+    let begin = |node: &Node| {
+        let range = node.kind.range.as_ref().unwrap();
+        let loc = range.begin.expansion_loc.as_ref().unwrap();
+        (loc.file.to_string(), loc.offset)
+    };
+    assert_eq!(begin(&node.inner[0]), ("src/a.h".to_owned(), 0));
+    assert_eq!(
+        begin(&node.inner[1]),
+        ("/usr/include/stdio.h".to_owned(), 0),
+    );
+
+    // Plain serialization is unaffected.
+    let node: Node = serde_json::from_str(&dump("/home/ci/src", 0x7f00_0000, 100)).unwrap();
+    let plain: serde_json::Value = serde_json::to_value(&node).unwrap();
+    assert_eq!(plain["id"], "0x7f000000");
+}
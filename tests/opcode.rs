@@ -0,0 +1,90 @@
+use clang_ast::{BinaryOpcode, UnaryOpcode};
+
+const BINARY: &[&str] = &[
+    ".*", "->*", "*", "/", "%", "+", "-", "<<", ">>", "<=>", "<", ">", "<=", ">=", "==", "!=", "&",
+    "^", "|", "&&", "||", "=", "*=", "/=", "%=", "+=", "-=", "<<=", ">>=", "&=", "^=", "|=", ",",
+];
+
+const UNARY: &[&str] = &[
+    "++",
+    "--",
+    "&",
+    "*",
+    "+",
+    "-",
+    "~",
+    "!",
+    "__real",
+    "__imag",
+    "__extension__",
+    "co_await",
+];
+
+fn binary(opcode: &str) -> BinaryOpcode {
+    serde_json::from_str(&serde_json::to_string(opcode).unwrap()).unwrap()
+}
+
+fn unary(opcode: &str) -> UnaryOpcode {
+    serde_json::from_str(&serde_json::to_string(opcode).unwrap()).unwrap()
+}
+
+#[test]
+fn test_round_trip() {
+    for opcode in BINARY {
+        assert_eq!(binary(opcode).as_str(), *opcode);
+        assert_eq!(binary(opcode).to_string(), *opcode);
+        let json = serde_json::to_string(&binary(opcode)).unwrap();
+        assert_eq!(json, serde_json::to_string(opcode).unwrap());
+    }
+    for opcode in UNARY {
+        assert_eq!(unary(opcode).as_str(), *opcode);
+    }
+}
+
+#[test]
+fn test_binary_classification() {
+    let comparisons: Vec<&str> = BINARY
+        .iter()
+        .copied()
+        .filter(|opcode| binary(opcode).is_comparison())
+        .collect();
+    assert_eq!(comparisons, ["<=>", "<", ">", "<=", ">=", "==", "!="]);
+
+    let assignments = BINARY
+        .iter()
+        .filter(|opcode| binary(opcode).is_assignment())
+        .count();
+    assert_eq!(assignments, 11);
+    assert!(!BinaryOpcode::Assign.is_compound_assignment());
+    assert_eq!(
+        BinaryOpcode::XorAssign.op_for_compound_assignment(),
+        Some(BinaryOpcode::Xor),
+    );
+    assert_eq!(BinaryOpcode::Xor.op_for_compound_assignment(), None);
+
+    assert!(BinaryOpcode::LAnd.is_logical());
+    assert!(!BinaryOpcode::And.is_logical());
+    assert!(BinaryOpcode::And.is_bitwise());
+    assert!(BinaryOpcode::Shr.is_shift());
+    assert!(BinaryOpcode::Rem.is_multiplicative());
+    assert!(BinaryOpcode::Sub.is_additive());
+    assert!(BinaryOpcode::PtrMemI.is_pointer_to_member());
+    assert!(!BinaryOpcode::Comma.is_comparison());
+}
+
+#[test]
+fn test_unary_classification() {
+    assert!(UnaryOpcode::Inc.is_increment_decrement());
+    assert!(!UnaryOpcode::Deref.is_increment_decrement());
+    assert!(UnaryOpcode::Not.is_arithmetic());
+    assert!(!UnaryOpcode::AddrOf.is_arithmetic());
+    assert!(UnaryOpcode::LNot.is_logical());
+    assert!(!UnaryOpcode::Not.is_logical());
+}
+
+#[test]
+fn test_unknown() {
+    let error = serde_json::from_str::<BinaryOpcode>(r#""**""#).unwrap_err();
+    assert!(error.to_string().starts_with("unknown variant `**`"));
+    assert!(serde_json::from_str::<UnaryOpcode>(r#""++x""#).is_err());
+}
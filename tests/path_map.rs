@@ -0,0 +1,103 @@
+use clang_ast::{Node, PathMap, PathStyle, SourceLocation};
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+pub struct Clang {
+    pub loc: Option<SourceLocation>,
+}
+
+const JSON: &str = r#"
+    {
+      "id": "0x1",
+      "kind": "TranslationUnitDecl",
+      "inner": [
+        {
+          "id": "0x2",
+          "kind": "VarDecl",
+          "loc": { "offset": 4, "file": "./main.c", "line": 1, "col": 5, "tokLen": 1 }
+        },
+        {
+          "id": "0x3",
+          "kind": "VarDecl",
+          "loc": { "offset": 4, "file": "/usr/include/stdio.h", "line": 1, "col": 5, "tokLen": 1 }
+        },
+        {
+          "id": "0x4",
+          "kind": "VarDecl",
+          "loc": { "offset": 4, "file": "<scratch space>", "line": 1, "col": 5, "tokLen": 1 }
+        }
+      ]
+    }
+"#;
+
+fn files(node: &Node<Clang>) -> Vec<&str> {
+    node.inner
+        .iter()
+        .map(|node| &*node.kind.loc.as_ref().unwrap().expansion().unwrap().file)
+        .collect()
+}
+
+#[test]
+fn test_path_map() {
+    let mut paths = PathMap::new();
+    paths
+        .absolute("/home/ci/build/")
+        .strip_prefix("/home/ci")
+        .remap("/usr", "/sysroot/usr");
+    let node: Node<Clang> = paths.scope(|| serde_json::from_str(JSON)).unwrap();
+    assert_eq!(
+        files(&node),
+        [
+            "build/main.c",
+            "/sysroot/usr/include/stdio.h",
+            "<scratch space>"
+        ],
+    );
+
+    let node: Node<Clang> = serde_json::from_str(JSON).unwrap();
+    assert_eq!(
+        files(&node),
+        ["./main.c", "/usr/include/stdio.h", "<scratch space>"],
+    );
+}
+
+#[test]
+fn test_apply() {
+    let mut paths = PathMap::new();
+    paths.remap("/src", "/build").remap("/", "/root/");
+    assert_eq!(paths.apply("/src/main.c").as_deref(), Some("/build/main.c"));
+    assert_eq!(paths.apply("/src").as_deref(), Some("/build"));
+    assert_eq!(
+        paths.apply("/srcs/main.c").as_deref(),
+        Some("/root/srcs/main.c")
+    );
+    assert_eq!(paths.apply("main.c"), None);
+}
+
+#[test]
+fn test_windows() {
+    let style = PathStyle::Windows;
+    assert!(style.same_path(r"C:\src\Main.c", "c:/src/main.c"));
+    assert!(!style.same_path(r"C:\src\main.c", "C:/src/main.h"));
+    assert!(style.has_prefix(r"C:\src\main.c", "C:/src/"));
+    assert!(!style.has_prefix(r"C:\srcs\main.c", "C:/src"));
+    assert!(style.is_absolute(r"D:\include\stdio.h"));
+    assert!(style.is_absolute(r"\\server\share\a.h"));
+    assert!(!style.is_absolute(r"src\main.c"));
+    assert!(!PathStyle::Posix.same_path(r"C:\src\main.c", "C:/src/main.c"));
+
+    let mut paths = PathMap::new();
+    paths
+        .style(PathStyle::Windows)
+        .absolute(r"C:\build")
+        .strip_prefix("c:/build");
+    assert_eq!(paths.apply(r".\src\main.c").as_deref(), Some(r"src\main.c"));
+    assert_eq!(paths.apply(r"C:\include\a.h"), None);
+
+    let mut paths = PathMap::new();
+    paths.style(PathStyle::Windows).remap(r"C:\src", "/src");
+    assert_eq!(
+        paths.apply(r"c:\SRC\main.c").as_deref(),
+        Some(r"/src\main.c")
+    );
+}
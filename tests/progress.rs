@@ -0,0 +1,31 @@
+use clang_ast::{Kind, Progress};
+use serde::Deserialize;
+use std::io::BufReader;
+
+pub type Node = clang_ast::Node<Clang>;
+
+#[derive(Deserialize)]
+pub struct Clang {
+    pub kind: Kind,
+}
+
+#[test]
+fn test_progress() {
+    let mut json = String::from(r#"{"id":"0x1","kind":"TranslationUnitDecl","inner":["#);
+    for i in 0..60_000 {
+        if i > 0 {
+            json.push(',');
+        }
+        json += r#"{"id":"0x2","kind":"VarDecl"}"#;
+    }
+    json += "]}";
+
+    let mut reports = Vec::new();
+    let progress = Progress::new(json.as_bytes(), |consumed| reports.push(consumed));
+    let node: Node = serde_json::from_reader(BufReader::new(progress)).unwrap();
+    assert_eq!(node.inner.len(), 60_000);
+
+    assert!(reports.len() > 1);
+    assert!(reports.windows(2).all(|pair| pair[0] < pair[1]));
+    assert_eq!(*reports.last().unwrap(), json.len() as u64);
+}
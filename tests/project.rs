@@ -0,0 +1,69 @@
+#![cfg(feature = "project")]
+
+use clang_ast::{Kind, Project, SourceLocation};
+use serde::Deserialize;
+use std::fs;
+use std::path::PathBuf;
+
+#[derive(Deserialize)]
+pub struct Clang {
+    pub kind: Kind,
+    pub loc: Option<SourceLocation>,
+}
+
+fn tempdir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("clang-ast-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(dir.join("sub")).unwrap();
+    dir
+}
+
+const TU: &str = r#"{
+  "id": "0x1",
+  "kind": "TranslationUnitDecl",
+  "inner": [
+    {
+      "id": "0x2",
+      "kind": "VarDecl",
+      "loc": { "offset": 4, "file": "common.h", "line": 1, "col": 5, "tokLen": 1 }
+    }
+  ]
+}"#;
+
+fn header_ptr(unit: &clang_ast::TranslationUnit<Clang>) -> *const u8 {
+    let loc = unit.ast.inner[0].kind.loc.as_ref().unwrap();
+    loc.expansion_loc.as_ref().unwrap().file.as_ptr()
+}
+
+#[test]
+fn test_load() {
+    let dir = tempdir("project");
+    fs::write(dir.join("a.json"), TU).unwrap();
+    fs::write(dir.join("sub/b.json"), TU).unwrap();
+    fs::write(dir.join("notes.txt"), "not json").unwrap();
+
+    let mut project = Project::<Clang>::new();
+    assert_eq!(project.load(&dir).unwrap(), 2);
+    assert_eq!(project.load(dir.join("*.json")).unwrap(), 1);
+    let missing = project.load(dir.join("missing.json")).unwrap_err();
+    fs::remove_dir_all(&dir).unwrap();
+
+    assert_eq!(missing.path(), dir.join("missing.json"));
+    assert!(missing.into_inner().is_io());
+
+    let paths: Vec<_> = project.iter().map(|unit| unit.path.clone()).collect();
+    assert_eq!(
+        paths,
+        [
+            dir.join("a.json"),
+            dir.join("sub/b.json"),
+            dir.join("a.json")
+        ],
+    );
+    assert_eq!(project.units()[0].len, TU.len() as u64);
+    assert_eq!(
+        header_ptr(&project.units()[0]),
+        header_ptr(&project.units()[1]),
+    );
+    assert_eq!(project.interner().len(), 1);
+}
@@ -0,0 +1,199 @@
+use clang_ast::{FunctionType, ParsedType, Qualifiers, TemplateArg};
+
+fn parse(qual_type: &str) -> ParsedType {
+    match qual_type.parse() {
+        Ok(ty) => ty,
+        Err(error) => panic!("{}: {}", qual_type, error),
+    }
+}
+
+fn named(ty: &ParsedType) -> String {
+    ty.name().unwrap().to_string()
+}
+
+fn function(ty: &ParsedType) -> &FunctionType {
+    match ty.unqualified() {
+        ParsedType::Function(function) => function,
+        _ => panic!("not a function: {:?}", ty),
+    }
+}
+
+#[test]
+fn test_builtin() {
+    let ty = parse("unsigned long long");
+    assert_eq!(named(&ty), "unsigned long long");
+
+    let ty = parse("const volatile int");
+    let qualifiers = ty.qualifiers();
+    assert!(qualifiers.is_const && qualifiers.is_volatile && !qualifiers.is_restrict);
+    assert_eq!(named(&ty), "int");
+
+    let ty = parse("int const");
+    assert!(ty.qualifiers().is_const);
+    assert_eq!(named(&ty), "int");
+}
+
+#[test]
+fn test_pointers_and_references() {
+    let ty = parse("const char *const");
+    assert!(ty.qualifiers().is_const);
+    let pointee = ty.pointee().unwrap();
+    assert!(pointee.qualifiers().is_const);
+    assert_eq!(named(pointee), "char");
+
+    let ty = parse("int *__restrict");
+    assert!(matches!(
+        ty,
+        ParsedType::Qualified(
+            Qualifiers {
+                is_restrict: true,
+                ..
+            },
+            _
+        )
+    ));
+
+    let ty = parse("std::string &&");
+    assert!(matches!(ty, ParsedType::RValueReference(_)));
+    assert_eq!(named(ty.pointee().unwrap()), "std::string");
+
+    let ty = parse("void *_Nullable");
+    assert!(matches!(ty, ParsedType::Pointer(_)));
+}
+
+#[test]
+fn test_names() {
+    let ty = parse("const std::vector<int> &");
+    assert!(matches!(ty, ParsedType::LValueReference(_)));
+    let referent = ty.pointee().unwrap();
+    assert!(referent.qualifiers().is_const);
+    let name = referent.name().unwrap();
+    assert_eq!(name.segments.len(), 2);
+    assert_eq!(name.segments[0].name, "std");
+    assert_eq!(name.segments[1].name, "vector");
+
+    let ty = parse("struct point");
+    let name = ty.name().unwrap();
+    assert_eq!(name.keyword.as_deref(), Some("struct"));
+    assert_eq!(name.to_string(), "struct point");
+
+    let ty = parse("std::map<std::string, std::vector<int>>::iterator");
+    assert_eq!(
+        named(&ty),
+        "std::map<std::string, std::vector<int>>::iterator",
+    );
+
+    let ty = parse("std::array<float, 3>");
+    let args = ty.name().unwrap().segments[1].args.as_ref().unwrap();
+    assert_eq!(args[1], TemplateArg::Expr("3".to_owned()));
+
+    let ty = parse("Foo<-1, 'c', 2U>");
+    let args = ty.name().unwrap().segments[0].args.as_ref().unwrap();
+    assert_eq!(args[0], TemplateArg::Expr("-1".to_owned()));
+    assert_eq!(args[1], TemplateArg::Expr("'c'".to_owned()));
+    assert_eq!(args[2], TemplateArg::Expr("2U".to_owned()));
+
+    let ty = parse("(anonymous namespace)::Widget *");
+    let name = ty.pointee().unwrap().name().unwrap();
+    assert_eq!(name.segments[0].name, "(anonymous namespace)");
+
+    let ty = parse("struct (unnamed struct at a.c:1:1)");
+    assert_eq!(named(&ty), "struct (unnamed struct at a.c:1:1)");
+
+    let ty = parse("type-parameter-0-0 &");
+    assert_eq!(named(ty.pointee().unwrap()), "type-parameter-0-0");
+
+    let ty = parse("decltype(nullptr)");
+    assert_eq!(named(&ty), "decltype(nullptr)");
+}
+
+#[test]
+fn test_arrays() {
+    let ty = parse("int [2][3]");
+    let (element, size) = match &ty {
+        ParsedType::Array { element, size } => (element, size),
+        _ => panic!(),
+    };
+    assert_eq!(size.as_deref(), Some("2"));
+    assert!(
+        matches!(**element, ParsedType::Array { ref size, .. } if size.as_deref() == Some("3"))
+    );
+
+    let ty = parse("char []");
+    assert!(matches!(ty, ParsedType::Array { size: None, .. }));
+
+    let ty = parse("char (&)[4]");
+    let referent = ty.pointee().unwrap();
+    assert!(matches!(referent, ParsedType::Array { .. }));
+
+    let ty = parse("int *[4]");
+    let element = match &ty {
+        ParsedType::Array { element, .. } => element,
+        _ => panic!(),
+    };
+    assert!(matches!(**element, ParsedType::Pointer(_)));
+}
+
+#[test]
+fn test_functions() {
+    let ty = parse("int (int, char)");
+    let f = function(&ty);
+    assert_eq!(named(&f.ret), "int");
+    assert_eq!(f.params.len(), 2);
+    assert_eq!(named(&f.params[1]), "char");
+    assert!(!f.variadic);
+
+    let ty = parse("int (*)(const char *, ...)");
+    let f = function(ty.pointee().unwrap());
+    assert_eq!(f.params.len(), 1);
+    assert!(f.variadic);
+
+    let ty = parse("void (void)");
+    assert!(function(&ty).params.is_empty());
+
+    let ty = parse("void (int) const noexcept");
+    assert_eq!(function(&ty).trailing, ["const", "noexcept"]);
+
+    let ty = parse("auto () -> int");
+    assert_eq!(named(&function(&ty).ret), "int");
+
+    let ty = parse("void (^)(int)");
+    assert!(matches!(ty, ParsedType::BlockPointer(_)));
+
+    let ty = parse("void (*(*)(int))(char)");
+    let inner = function(ty.pointee().unwrap());
+    assert_eq!(named(&inner.params[0]), "int");
+    let outer = function(inner.ret.pointee().unwrap());
+    assert_eq!(named(&outer.params[0]), "char");
+
+    let ty = parse("void () __attribute__((noreturn))");
+    assert!(function(&ty).trailing.is_empty());
+}
+
+#[test]
+fn test_errors() {
+    for qual_type in &["", "int (", "std::vector<int", "int Foo::*"] {
+        assert!(qual_type.parse::<ParsedType>().is_err(), "{}", qual_type);
+    }
+}
+
+#[test]
+fn test_recursion_limit() {
+    let nested = |open: &str, inner: &str, close: &str, n: usize| {
+        format!("{}{}{}", open.repeat(n), inner, close.repeat(n))
+    };
+
+    let ty = parse(&nested("A<", "int", ">", 50));
+    assert!(ty.name().is_some());
+
+    for qual_type in &[
+        nested("A<", "int", ">", 100_000),
+        nested("", "int", "*", 100_000),
+        nested("int (", "", ")", 100_000),
+        nested("void (*", "", ")(int)", 100_000),
+        nested("void (", "int", ")", 100_000),
+        format!("int{}", "[1]".repeat(100_000)),
+    ] {
+        assert!(qual_type.parse::<ParsedType>().is_err());
+    }
+}
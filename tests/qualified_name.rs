@@ -0,0 +1,136 @@
+use clang_ast::{DeclName, Id, Node, QualifiedNames};
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+pub enum Clang {
+    NamespaceDecl {
+        name: Option<String>,
+        #[serde(rename = "isInline", default)]
+        is_inline: bool,
+    },
+    CXXRecordDecl {
+        name: Option<String>,
+        #[serde(rename = "tagUsed")]
+        tag_used: String,
+    },
+    ClassTemplateDecl {
+        name: String,
+    },
+    FunctionDecl {
+        name: String,
+    },
+    FieldDecl {
+        name: String,
+    },
+    Other,
+}
+
+const JSON: &str = r#"
+    {
+      "id": "0x1",
+      "kind": "TranslationUnitDecl",
+      "inner": [
+        {
+          "id": "0x2",
+          "kind": "NamespaceDecl",
+          "name": "std",
+          "inner": [
+            {
+              "id": "0x3",
+              "kind": "NamespaceDecl",
+              "name": "__1",
+              "isInline": true,
+              "inner": [
+                {
+                  "id": "0x4",
+                  "kind": "ClassTemplateDecl",
+                  "name": "vector",
+                  "inner": [
+                    {
+                      "id": "0x5",
+                      "kind": "CXXRecordDecl",
+                      "name": "vector",
+                      "tagUsed": "class",
+                      "inner": [
+                        { "id": "0x6", "kind": "FunctionDecl", "name": "size" }
+                      ]
+                    }
+                  ]
+                }
+              ]
+            }
+          ]
+        },
+        {
+          "id": "0x7",
+          "kind": "NamespaceDecl",
+          "inner": [
+            {
+              "id": "0x8",
+              "kind": "CXXRecordDecl",
+              "name": "S",
+              "tagUsed": "struct",
+              "inner": [
+                {
+                  "id": "0x9",
+                  "kind": "CXXRecordDecl",
+                  "tagUsed": "union",
+                  "inner": [
+                    { "id": "0xa", "kind": "FieldDecl", "name": "x" }
+                  ]
+                }
+              ]
+            }
+          ]
+        },
+        { "id": "0xb", "kind": "FunctionDecl", "name": "main" }
+      ]
+    }
+"#;
+
+fn decl_name(kind: &Clang, inline_namespaces: bool) -> Option<DeclName<'_>> {
+    match kind {
+        Clang::NamespaceDecl {
+            is_inline: true, ..
+        } if !inline_namespaces => None,
+        Clang::NamespaceDecl { name: None, .. } => Some(DeclName::AnonymousNamespace),
+        Clang::CXXRecordDecl {
+            name: None,
+            tag_used,
+        } => Some(DeclName::AnonymousTag(tag_used)),
+        Clang::NamespaceDecl {
+            name: Some(name), ..
+        }
+        | Clang::CXXRecordDecl {
+            name: Some(name), ..
+        }
+        | Clang::FunctionDecl { name }
+        | Clang::FieldDecl { name } => Some(DeclName::Named(name)),
+        Clang::ClassTemplateDecl { .. } | Clang::Other => None,
+    }
+}
+
+fn id(id: u64) -> Id {
+    Id::from_u64(id).unwrap()
+}
+
+#[test]
+fn test_qualified_names() {
+    let node: Node<Clang> = serde_json::from_str(JSON).unwrap();
+    let names = QualifiedNames::new(&node, |kind| decl_name(kind, true));
+    assert_eq!(names.len(), 9);
+    assert_eq!(names.get(id(0x1)), None);
+    assert_eq!(names.get(id(0x3)), Some("std::__1"));
+    assert_eq!(names.get(id(0x4)), None);
+    assert_eq!(names.get(id(0x6)), Some("std::__1::vector::size"));
+    assert_eq!(names.get(id(0x8)), Some("(anonymous namespace)::S"),);
+    assert_eq!(
+        names.get(id(0xa)),
+        Some("(anonymous namespace)::S::(anonymous union)::x"),
+    );
+    assert_eq!(names.get(id(0xb)), Some("main"));
+
+    let names = QualifiedNames::new(&node, |kind| decl_name(kind, false));
+    assert_eq!(names.get(id(0x3)), None);
+    assert_eq!(names.get(id(0x6)), Some("std::vector::size"));
+}
@@ -0,0 +1,117 @@
+use clang_ast::{Kind, Node, RangeIndex, SourceRange};
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+pub struct Clang {
+    pub kind: Kind,
+    pub range: Option<SourceRange>,
+}
+
+// int f() {
+//   return 42;
+// }
+// int g;
+const JSON: &str = r#"
+    {
+      "id": "0x1",
+      "kind": "TranslationUnitDecl",
+      "inner": [
+        {
+          "id": "0x2",
+          "kind": "TypedefDecl",
+          "range": { "begin": {}, "end": {} }
+        },
+        {
+          "id": "0x3",
+          "kind": "FunctionDecl",
+          "range": {
+            "begin": { "offset": 0, "file": "main.c", "line": 1, "col": 1, "tokLen": 3 },
+            "end": { "offset": 24, "line": 3, "col": 1, "tokLen": 1 }
+          },
+          "inner": [
+            {
+              "id": "0x4",
+              "kind": "CompoundStmt",
+              "range": {
+                "begin": { "offset": 8, "line": 1, "col": 9, "tokLen": 1 },
+                "end": { "offset": 24, "line": 3, "col": 1, "tokLen": 1 }
+              },
+              "inner": [
+                {
+                  "id": "0x5",
+                  "kind": "ReturnStmt",
+                  "range": {
+                    "begin": { "offset": 12, "line": 2, "col": 3, "tokLen": 6 },
+                    "end": { "offset": 19, "col": 10, "tokLen": 2 }
+                  },
+                  "inner": [
+                    {
+                      "id": "0x6",
+                      "kind": "IntegerLiteral",
+                      "range": {
+                        "begin": { "offset": 19, "col": 10, "tokLen": 2 },
+                        "end": { "offset": 19, "col": 10, "tokLen": 2 }
+                      }
+                    }
+                  ]
+                }
+              ]
+            }
+          ]
+        },
+        {
+          "id": "0x7",
+          "kind": "VarDecl",
+          "range": {
+            "begin": { "offset": 26, "line": 4, "col": 1, "tokLen": 3 },
+            "end": { "offset": 30, "col": 5, "tokLen": 1 }
+          }
+        },
+        {
+          "id": "0x8",
+          "kind": "VarDecl",
+          "range": {
+            "begin": { "offset": 0, "file": "other.c", "line": 1, "col": 1, "tokLen": 3 },
+            "end": { "offset": 4, "col": 5, "tokLen": 1 }
+          }
+        }
+      ]
+    }
+"#;
+
+fn kinds(nodes: Vec<&Node<Clang>>) -> Vec<Kind> {
+    nodes
+        .into_iter()
+        .map(|node| node.kind.kind.clone())
+        .collect()
+}
+
+#[test]
+fn test_range_index() {
+    let node: Node<Clang> = serde_json::from_str(JSON).unwrap();
+    let index = RangeIndex::new(&node, |kind| kind.range.as_ref());
+    assert_eq!(index.len(), 6);
+
+    assert_eq!(
+        kinds(index.nodes_at("main.c", 20)),
+        [
+            Kind::FunctionDecl,
+            Kind::CompoundStmt,
+            Kind::ReturnStmt,
+            Kind::IntegerLiteral,
+        ],
+    );
+    assert_eq!(
+        index.node_at("main.c", 12).map(|node| node.id.to_string()),
+        Some("0x5".to_owned()),
+    );
+    assert!(index.node_at("main.c", 25).is_none());
+    assert_eq!(kinds(index.nodes_at("other.c", 0)), [Kind::VarDecl]);
+    assert_eq!(kinds(index.nodes_at("missing.c", 0)), []);
+
+    assert_eq!(
+        kinds(index.overlapping("main.c", 22..28)),
+        [Kind::FunctionDecl, Kind::CompoundStmt, Kind::VarDecl],
+    );
+    assert_eq!(kinds(index.overlapping("main.c", 25..26)), []);
+}
@@ -0,0 +1,47 @@
+use clang_ast::{NodeIndex, Ref};
+use serde::Deserialize;
+
+pub type Node = clang_ast::Node<Clang>;
+
+#[derive(Deserialize)]
+pub enum Clang {
+    CXXRecordDecl {
+        name: String,
+        #[serde(rename = "previousDecl")]
+        previous_decl: Option<Ref<Clang>>,
+    },
+    Other,
+}
+
+#[test]
+fn test_resolve() {
+    let json = r#"
+        {
+          "id": "0x1",
+          "kind": "TranslationUnitDecl",
+          "inner": [
+            { "id": "0x2", "kind": "CXXRecordDecl", "name": "S" },
+            { "id": "0x3", "kind": "CXXRecordDecl", "name": "S", "previousDecl": "0x2" },
+            { "id": "0x4", "kind": "CXXRecordDecl", "name": "T", "previousDecl": "0x99" }
+          ]
+        }
+    "#;
+
+    let node: Node = serde_json::from_str(json).unwrap();
+    let index = NodeIndex::new(&node);
+    assert_eq!(index.len(), 4);
+
+    let previous = |node: &Node| match &node.kind {
+        Clang::CXXRecordDecl { previous_decl, .. } => *previous_decl,
+        Clang::Other => None,
+    };
+
+    assert!(previous(&node.inner[0]).is_none());
+
+    let resolved = previous(&node.inner[1]).unwrap().resolve(&index).unwrap();
+    assert_eq!(resolved.id, node.inner[0].id);
+
+    let dangling = previous(&node.inner[2]).unwrap();
+    assert_eq!(format!("{:?}", dangling), "Ref(0x99)");
+    assert!(dangling.resolve(&index).is_none());
+}
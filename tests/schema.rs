@@ -0,0 +1,61 @@
+use clang_ast::{Schema, ValueType};
+
+const JSON: &str = r#"
+    {
+      "id": "0x1",
+      "kind": "TranslationUnitDecl",
+      "inner": [
+        {
+          "id": "0x2",
+          "kind": "FunctionDecl",
+          "name": "f",
+          "type": { "qualType": "void ()" },
+          "inner": [
+            {
+              "id": "0x3",
+              "kind": "DeclRefExpr",
+              "referencedDecl": { "id": "0x4", "kind": "VarDecl", "name": "x" }
+            }
+          ]
+        },
+        { "id": "0x4", "kind": "VarDecl", "name": "x", "init": null },
+        { "id": "0x5", "kind": "VarDecl", "name": 1.5 },
+        {}
+      ]
+    }
+"#;
+
+#[test]
+fn test_schema() {
+    let schema: Schema = serde_json::from_str(JSON).unwrap();
+    let expected = "\
+DeclRefExpr (1)
+    referencedDecl: object
+FunctionDecl (1)
+    inner: array
+    name: string
+    type: object
+TranslationUnitDecl (1)
+    inner: array
+VarDecl (2)
+    init: null (1 of 2)
+    name: float | string
+null (1)
+";
+    assert_eq!(schema.to_string(), expected);
+
+    let var = &schema.kinds["VarDecl"];
+    assert!(var.fields["name"].is_required(var));
+    assert!(var.fields["name"].types.contains(&ValueType::Float));
+}
+
+#[test]
+fn test_merge() {
+    let mut schema: Schema = serde_json::from_str(JSON).unwrap();
+    let other = r#"{"id": "0x9", "kind": "VarDecl", "isUsed": true}"#;
+    schema.merge(serde_json::from_str(other).unwrap());
+    let var = &schema.kinds["VarDecl"];
+    assert_eq!(var.count, 3);
+    assert_eq!(var.fields["name"].count, 2);
+    assert_eq!(var.fields["isUsed"].count, 1);
+}
@@ -0,0 +1,67 @@
+use clang_ast::{Kind, SourceRange};
+use serde::{Deserialize, Serialize};
+
+pub type Node = clang_ast::Node<Clang>;
+
+#[derive(Deserialize, Serialize)]
+pub struct Clang {
+    pub kind: Kind,
+    pub range: SourceRange,
+}
+
+// Locations omit "file" and "line" when they are the same as in the
+// previous location, so serialization must put back exactly the ones that
+// a reader will need.
+const JSON: &str = r#"
+    {
+      "id": "0x1",
+      "kind": "TranslationUnitDecl",
+      "range": {
+        "begin": {},
+        "end": {}
+      },
+      "inner": [
+        {
+          "id": "0x2",
+          "kind": "FunctionDecl",
+          "range": {
+            "begin": { "offset": 0, "file": "a.cc", "line": 1, "col": 1, "tokLen": 4 },
+            "end": { "offset": 12, "col": 13, "tokLen": 1 }
+          }
+        },
+        {
+          "id": "0x3",
+          "kind": "FunctionDecl",
+          "range": {
+            "begin": { "offset": 15, "line": 2, "col": 1, "tokLen": 4 },
+            "end": { "offset": 0, "file": "b.h", "line": 1, "col": 5, "tokLen": 1 }
+          }
+        }
+      ]
+    }
+"#;
+
+fn locs(node: &Node) -> Vec<(String, usize, usize)> {
+    node.descendants()
+        .flat_map(|node| vec![&node.kind.range.begin, &node.kind.range.end])
+        .filter_map(|loc| loc.expansion_loc.as_ref())
+        .map(|loc| (loc.file.to_string(), loc.line, loc.col))
+        .collect()
+}
+
+#[test]
+fn test_round_trip() {
+    let node: Node = serde_json::from_str(JSON).unwrap();
+    let expected = [
+        ("a.cc".to_owned(), 1, 1),
+        ("a.cc".to_owned(), 1, 13),
+        ("a.cc".to_owned(), 2, 1),
+        ("b.h".to_owned(), 1, 5),
+    ];
+    assert_eq!(locs(&node), expected);
+
+    let json = serde_json::to_string(&node).unwrap();
+    let node: Node = serde_json::from_str(&json).unwrap();
+    assert_eq!(locs(&node), expected);
+    assert_eq!(node.inner[1].kind.kind, Kind::FunctionDecl);
+}
@@ -0,0 +1,167 @@
+use clang_ast::{Interner, Session, SourceLocation};
+use serde::de::{DeserializeSeed, Deserializer};
+use serde::Deserialize;
+use std::panic::{self, AssertUnwindSafe};
+
+pub type Node = clang_ast::Node<Clang>;
+
+#[derive(Deserialize)]
+pub struct Clang {
+    pub loc: Option<SourceLocation>,
+}
+
+const FIRST: &str = r#"
+    {
+      "id": "0x1",
+      "kind": "VarDecl",
+      "loc": { "offset": 0, "file": "/usr/include/stdio.h", "line": 3, "col": 5, "tokLen": 1 }
+    }
+"#;
+
+const SECOND: &str = r#"
+    {
+      "id": "0x2",
+      "kind": "VarDecl",
+      "loc": { "offset": 8, "col": 6, "tokLen": 1 }
+    }
+"#;
+
+fn parse(session: &mut Session, json: &str) -> Node {
+    let mut deserializer = serde_json::Deserializer::from_str(json);
+    session.seed().deserialize(&mut deserializer).unwrap()
+}
+
+fn file_and_line(node: &Node) -> (&str, usize) {
+    let loc = node
+        .kind
+        .loc
+        .as_ref()
+        .unwrap()
+        .expansion_loc
+        .as_ref()
+        .unwrap();
+    (&loc.file, loc.line)
+}
+
+#[test]
+fn test_session() {
+    let mut session = Session::new();
+    let first = parse(&mut session, FIRST);
+    let second = parse(&mut session, SECOND);
+    assert_eq!(file_and_line(&second), ("/usr/include/stdio.h", 3));
+    assert_eq!(session.interner().len(), 1);
+
+    let first_file = &first
+        .kind
+        .loc
+        .as_ref()
+        .unwrap()
+        .expansion_loc
+        .as_ref()
+        .unwrap()
+        .file;
+    let second_file = &second
+        .kind
+        .loc
+        .as_ref()
+        .unwrap()
+        .expansion_loc
+        .as_ref()
+        .unwrap()
+        .file;
+    assert_eq!(first_file.as_ptr(), second_file.as_ptr());
+
+    // Without the session, nothing carries over.
+    let alone: Node = serde_json::from_str(SECOND).unwrap();
+    assert_eq!(file_and_line(&alone), ("", 0));
+}
+
+#[test]
+fn test_shared_interner() {
+    let interner = Interner::new();
+    let mut a = Session::with_interner(interner.clone());
+    let mut b = Session::with_interner(interner.clone());
+    parse(&mut a, FIRST);
+    parse(&mut b, FIRST);
+    assert_eq!(interner.len(), 1);
+
+    // Each session still has its own locations.
+    let node = parse(&mut Session::with_interner(interner), SECOND);
+    assert_eq!(file_and_line(&node), ("", 0));
+}
+
+#[cfg(not(feature = "rc"))]
+#[test]
+fn test_across_threads() {
+    let mut session = Session::new();
+    parse(&mut session, FIRST);
+    let (session, file, line) = std::thread::spawn(move || {
+        let node = parse(&mut session, SECOND);
+        let (file, line) = file_and_line(&node);
+        (session, file.to_owned(), line)
+    })
+    .join()
+    .unwrap();
+    assert_eq!((file.as_str(), line), ("/usr/include/stdio.h", 3));
+    assert_eq!(session.interner().len(), 1);
+}
+
+#[test]
+fn test_panic_in_nested_parse() {
+    #[derive(Deserialize)]
+    pub struct Outer {
+        pub loc: Option<SourceLocation>,
+        #[serde(default, rename = "nested", deserialize_with = "nested")]
+        pub _nested: (),
+    }
+
+    #[derive(Deserialize)]
+    pub struct Panics {
+        #[serde(rename = "loc")]
+        pub _loc: Option<SourceLocation>,
+        #[serde(rename = "panics", deserialize_with = "panics")]
+        pub _panics: (),
+    }
+
+    // Parse a tree in a session of its own while the outer tree is in the
+    // middle of being parsed, and panic partway through.
+    fn nested<'de, D: Deserializer<'de>>(deserializer: D) -> Result<(), D::Error> {
+        <()>::deserialize(deserializer)?;
+        let json = r#"{"id":"0x9","kind":"VarDecl","loc":{"offset":0,"file":"b.h","line":9,"col":1,"tokLen":1},"panics":null}"#;
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            let mut deserializer = serde_json::Deserializer::from_str(json);
+            let _: serde_json::Result<clang_ast::Node<Panics>> =
+                Session::new().seed().deserialize(&mut deserializer);
+        }));
+        assert!(result.is_err());
+        Ok(())
+    }
+
+    fn panics<'de, D: Deserializer<'de>>(_deserializer: D) -> Result<(), D::Error> {
+        panic!("nested parse panicked");
+    }
+
+    let json = r#"
+        {
+          "id": "0x1",
+          "kind": "TranslationUnitDecl",
+          "inner": [
+            {
+              "id": "0x2",
+              "kind": "VarDecl",
+              "loc": { "offset": 0, "file": "a.h", "line": 3, "col": 5, "tokLen": 1 }
+            },
+            { "id": "0x3", "kind": "VarDecl", "nested": null },
+            {
+              "id": "0x4",
+              "kind": "VarDecl",
+              "loc": { "offset": 8, "col": 6, "tokLen": 1 }
+            }
+          ]
+        }
+    "#;
+    let node: clang_ast::Node<Outer> = serde_json::from_str(json).unwrap();
+    let loc = node.inner[2].kind.loc.as_ref().unwrap();
+    let loc = loc.expansion_loc.as_ref().unwrap();
+    assert_eq!((&*loc.file, loc.line), ("a.h", 3));
+}
@@ -0,0 +1,55 @@
+use clang_ast::{SourceManager, SourceRange};
+use std::io::ErrorKind;
+
+fn range(json: &str) -> SourceRange {
+    serde_json::from_str(json).unwrap()
+}
+
+#[test]
+fn test_text() {
+    let mut sources = SourceManager::new();
+    sources.insert("main.c", "int f() {\n  return 42;\n}\n".to_owned());
+
+    let ret = range(
+        r#"{
+          "begin": { "offset": 12, "file": "main.c", "line": 2, "col": 3, "tokLen": 6 },
+          "end": { "offset": 19, "col": 10, "tokLen": 2 }
+        }"#,
+    );
+    assert_eq!(sources.text(&ret).unwrap(), "return 42");
+
+    let past_end = range(
+        r#"{
+          "begin": { "offset": 24, "file": "main.c", "line": 3, "col": 1, "tokLen": 1 },
+          "end": { "offset": 30, "col": 7, "tokLen": 1 }
+        }"#,
+    );
+    let error = sources.text(&past_end).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::InvalidInput);
+
+    let error = sources.text(&SourceRange::default()).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::InvalidInput);
+
+    let missing = range(
+        r#"{
+          "begin": { "offset": 0, "file": "missing.c", "line": 1, "col": 1, "tokLen": 1 },
+          "end": { "offset": 0, "col": 1, "tokLen": 1 }
+        }"#,
+    );
+    let error = sources.text(&missing).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::NotFound);
+}
+
+#[test]
+fn test_read_file() {
+    let mut sources = SourceManager::new();
+    sources.root(env!("CARGO_MANIFEST_DIR"));
+    let manifest = range(
+        r#"{
+          "begin": { "offset": 0, "file": "Cargo.toml", "line": 1, "col": 1, "tokLen": 1 },
+          "end": { "offset": 8, "col": 9, "tokLen": 1 }
+        }"#,
+    );
+    assert_eq!(sources.text(&manifest).unwrap(), "[package]");
+    assert!(sources.file("Cargo.toml").unwrap().contains("clang-ast"));
+}
@@ -0,0 +1,70 @@
+use clang_ast::{ArenaStr, Ast, StrArena};
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug)]
+pub enum Clang {
+    FunctionDecl { name: ArenaStr },
+    Other,
+}
+
+const JSON: &str = r#"
+    {
+      "id": "0x1",
+      "kind": "TranslationUnitDecl",
+      "inner": [
+        { "id": "0x2", "kind": "FunctionDecl", "name": "first" },
+        { "id": "0x3", "kind": "FunctionDecl", "name": "second" }
+      ]
+    }
+"#;
+
+fn names(ast: &Ast<Clang>, arena: &StrArena) -> Vec<String> {
+    let mut names = Vec::new();
+    for (_id, node) in ast.iter() {
+        if let Clang::FunctionDecl { name } = &node.kind {
+            names.push(arena[*name].to_owned());
+        }
+    }
+    names
+}
+
+#[test]
+fn test_str_arena() {
+    let mut arena = StrArena::new();
+    let ast: Ast<Clang> = arena.scope(|| serde_json::from_str(JSON)).unwrap();
+    assert_eq!(names(&ast, &arena), ["first", "second"]);
+    assert_eq!(arena.len(), "firstsecond".len());
+
+    // Strings of a second tree are appended after those of the first.
+    let again: Ast<Clang> = arena.scope(|| serde_json::from_str(JSON)).unwrap();
+    assert_eq!(names(&again, &arena), ["first", "second"]);
+    assert_eq!(names(&ast, &arena), ["first", "second"]);
+    assert_eq!(arena.len(), 2 * "firstsecond".len());
+
+    arena.clear();
+    assert!(arena.is_empty());
+}
+
+#[test]
+fn test_outside_scope() {
+    let err = serde_json::from_str::<Ast<Clang>>(JSON).unwrap_err();
+    assert!(err
+        .to_string()
+        .contains("ArenaStr deserialized outside of StrArena::scope"));
+}
+
+#[test]
+fn test_nested_scope() {
+    let mut outer = StrArena::new();
+    let mut inner = StrArena::new();
+    let (a, b, c) = outer.scope(|| {
+        let a: ArenaStr = serde_json::from_str(r#""a""#).unwrap();
+        let b: ArenaStr = inner.scope(|| serde_json::from_str(r#""bb""#).unwrap());
+        let c: ArenaStr = serde_json::from_str(r#""ccc""#).unwrap();
+        (a, b, c)
+    });
+    assert_eq!(&outer[a], "a");
+    assert_eq!(&inner[b], "bb");
+    assert_eq!(&outer[c], "ccc");
+    assert_eq!(inner.len(), 2);
+}
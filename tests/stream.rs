@@ -0,0 +1,74 @@
+use clang_ast::stream::{self, Event};
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug)]
+pub enum Clang {
+    EnumDecl { name: String },
+    EnumConstantDecl { name: String },
+    Other,
+}
+
+#[test]
+fn test_parse() {
+    let json = r#"
+        {
+          "id": "0x1",
+          "kind": "TranslationUnitDecl",
+          "inner": [
+            {
+              "id": "0x2",
+              "kind": "EnumDecl",
+              "name": "E",
+              "inner": [
+                { "id": "0x3", "kind": "EnumConstantDecl", "name": "A" },
+                { "id": "0x4", "kind": "EnumConstantDecl", "name": "B" }
+              ]
+            }
+          ]
+        }
+    "#;
+
+    let mut events = Vec::new();
+    let mut deserializer = serde_json::Deserializer::from_reader(json.as_bytes());
+    stream::parse(&mut deserializer, |event: Event<Clang>| {
+        let name = match event.kind {
+            Clang::EnumDecl { name } | Clang::EnumConstantDecl { name } => name,
+            Clang::Other => "-".to_owned(),
+        };
+        let parent = event.parent.map_or_else(String::new, |id| id.to_string());
+        events.push(format!("{} {} {} {}", event.id, name, parent, event.depth));
+    })
+    .unwrap();
+
+    let expected = ["0x3 A 0x2 2", "0x4 B 0x2 2", "0x2 E 0x1 1", "0x1 -  0"];
+    assert_eq!(events, expected);
+}
+
+#[cfg(feature = "reader")]
+#[test]
+fn test_from_reader_before_eof() {
+    use std::io::{self, Read};
+
+    // Produces a truncated dump and then fails, like a compiler that crashed
+    // partway through writing its output.
+    struct Truncated<'a>(&'a [u8]);
+
+    impl<'a> Read for Truncated<'a> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            if self.0.is_empty() {
+                return Err(io::Error::new(io::ErrorKind::BrokenPipe, "crashed"));
+            }
+            self.0.read(buf)
+        }
+    }
+
+    let json = r#"{"id":"0x1","kind":"EnumDecl","name":"E","inner":[{"id":"0x2","kind":"EnumConstantDecl","name":"A"},"#;
+    let mut names = Vec::new();
+    let result = stream::from_reader(Truncated(json.as_bytes()), |event: Event<Clang>| {
+        if let Clang::EnumConstantDecl { name } = event.kind {
+            names.push(name);
+        }
+    });
+    assert!(result.unwrap_err().is_io());
+    assert_eq!(names, ["A"]);
+}
@@ -0,0 +1,58 @@
+use clang_ast::Strict;
+use serde::Deserialize;
+
+pub type Node = clang_ast::Node<Strict<Clang>>;
+
+#[derive(Deserialize, Debug)]
+pub enum Clang {
+    TranslationUnitDecl,
+    FunctionDecl { name: String },
+    Other,
+}
+
+#[test]
+fn test_covered() {
+    let json = r#"
+        {
+          "id": "0x1",
+          "kind": "TranslationUnitDecl",
+          "inner": [
+            { "id": "0x2", "kind": "FunctionDecl", "name": "f" }
+          ]
+        }
+    "#;
+    let node: Node = serde_json::from_str(json).unwrap();
+    match &*node.inner[0].kind {
+        Clang::FunctionDecl { name } => assert_eq!(name, "f"),
+        other => panic!("unexpected {:?}", other),
+    }
+}
+
+#[test]
+fn test_uncovered() {
+    let json = r#"
+        {
+          "id": "0x1",
+          "kind": "TranslationUnitDecl",
+          "inner": [
+            {
+              "id": "0x2",
+              "kind": "FunctionDecl",
+              "name": "f",
+              "inner": [
+                { "id": "0x3", "kind": "CompoundStmt" }
+              ]
+            }
+          ]
+        }
+    "#;
+    let error = serde_json::from_str::<Node>(json).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "unknown node kind `CompoundStmt` with id 0x3 at line 11 column 55",
+    );
+
+    // Without Strict, the same node is Other.
+    let node: clang_ast::Node<Clang> = serde_json::from_str(json).unwrap();
+    assert!(matches!(node.inner[0].inner[0].kind, Clang::Other));
+}
@@ -0,0 +1,101 @@
+use clang_ast::{StringEncoding, StringValue};
+
+fn parse(text: &str) -> StringValue {
+    StringValue::parse(text).unwrap()
+}
+
+#[test]
+fn test_ordinary() {
+    let value = parse(r#""hello, \"world\"\n""#);
+    assert_eq!(value.encoding(), StringEncoding::Ordinary);
+    assert_eq!(value.decode().unwrap(), "hello, \"world\"\n");
+    assert_eq!(value.text(), r#""hello, \"world\"\n""#);
+
+    let value = parse(r#""\a\b\f\r\t\v\e\?\'\\""#);
+    assert_eq!(value.to_bytes().unwrap(), b"\x07\x08\x0c\r\t\x0b\x1b?'\\");
+
+    assert_eq!(parse(r#""""#).code_units(), &[] as &[u32]);
+}
+
+#[test]
+fn test_numeric_escapes() {
+    // Clang writes unprintable bytes as three octal digits.
+    let value = parse(r#""\000\377\1234""#);
+    assert_eq!(value.to_bytes().unwrap(), [0, 0xff, 0o123, b'4']);
+    assert_eq!(value.decode(), None);
+
+    assert_eq!(parse(r#""\x41\x{42}\o{103}""#).decode().unwrap(), "ABC");
+    assert!(StringValue::parse(r#""\x100""#).is_none());
+    assert!(StringValue::parse(r#""\400""#).is_none());
+}
+
+#[test]
+fn test_utf8() {
+    let value = parse(r#"u8"caf\303\251 \u00e9 ü""#);
+    assert_eq!(value.encoding(), StringEncoding::Utf8);
+    assert_eq!(value.decode().unwrap(), "café é ü");
+    assert_eq!(parse(r#""\U0001F600""#).decode().unwrap(), "\u{1F600}");
+}
+
+#[test]
+fn test_wide() {
+    // Clang closes a \x escape with an adjacent literal when the next
+    // character is a hex digit.
+    let value = parse(r#"L"\x1F600""1""#);
+    assert_eq!(value.encoding(), StringEncoding::Wide);
+    assert_eq!(value.code_units(), [0x1F600, u32::from('1')]);
+    assert_eq!(value.decode().unwrap(), "\u{1F600}1");
+    assert_eq!(value.to_bytes(), None);
+
+    let windows = parse(r#"L"\xD83D\xDE00""#);
+    assert_eq!(windows.decode().unwrap(), "\u{1F600}");
+
+    let value = parse(r#"U"\U0001F600x""#);
+    assert_eq!(value.encoding(), StringEncoding::Utf32);
+    assert_eq!(value.code_units(), [0x1F600, u32::from('x')]);
+}
+
+#[test]
+fn test_utf16() {
+    let value = parse(r#"u"\U0001F600é""#);
+    assert_eq!(value.encoding(), StringEncoding::Utf16);
+    assert_eq!(value.code_units(), [0xD83D, 0xDE00, 0xE9]);
+    assert_eq!(value.decode().unwrap(), "\u{1F600}é");
+
+    let lone = parse(r#"u"\xD800""#);
+    assert_eq!(lone.code_units(), [0xD800]);
+    assert_eq!(lone.decode(), None);
+}
+
+#[test]
+fn test_concatenation() {
+    assert_eq!(parse(r#""ab" "cd""ef""#).decode().unwrap(), "abcdef");
+    assert_eq!(parse(r#"u8"ab" u8"cd""#).decode().unwrap(), "abcd");
+}
+
+#[test]
+fn test_invalid() {
+    for text in &[
+        "",
+        "abc",
+        r#"""#,
+        r#""abc"#,
+        r#""\q""#,
+        r#""\u12""#,
+        r#""\uD800""#,
+        r#""\x{41""#,
+        r#""a" b"#,
+        r#"u8"a" L"b""#,
+        "\"a\nb\"",
+    ] {
+        assert!(StringValue::parse(text).is_none(), "{}", text);
+    }
+}
+
+#[test]
+fn test_serde() {
+    let json = r#""L\"\\x1F600\"""#;
+    let value: StringValue = serde_json::from_str(json).unwrap();
+    assert_eq!(value.code_units(), [0x1F600]);
+    assert_eq!(serde_json::to_string(&value).unwrap(), json);
+}
@@ -0,0 +1,112 @@
+use clang_ast::{Kind, SourceRange};
+use serde::Deserialize;
+use std::hash::Hash;
+use std::iter;
+
+pub type Node = clang_ast::Node<Clang>;
+
+#[derive(Deserialize, Hash, PartialEq)]
+pub struct Clang {
+    pub kind: Kind,
+    pub name: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct WithRange {
+    pub kind: Kind,
+    pub range: SourceRange,
+}
+
+fn record(id: u32, field: &str, offset: usize) -> String {
+    format!(
+        r#"
+        {{
+          "id": "0x{:x}",
+          "kind": "CXXRecordDecl",
+          "name": "S",
+          "range": {{
+            "begin": {{ "offset": {}, "file": "a.h", "line": 1, "col": 1, "tokLen": 6 }},
+            "end": {{ "offset": {}, "col": 20, "tokLen": 1 }}
+          }},
+          "inner": [
+            {{
+              "id": "0x{:x}",
+              "kind": "FieldDecl",
+              "name": "{}",
+              "range": {{ "begin": {{}}, "end": {{}} }}
+            }}
+          ]
+        }}
+        "#,
+        id,
+        offset,
+        offset + 19,
+        id + 1,
+        field,
+    )
+}
+
+#[test]
+fn test_structural_hash() {
+    let a: Node = serde_json::from_str(&record(0x10, "x", 0)).unwrap();
+    let b: Node = serde_json::from_str(&record(0x20, "x", 0)).unwrap();
+    let c: Node = serde_json::from_str(&record(0x30, "y", 0)).unwrap();
+    assert_eq!(a.structural_hash(), b.structural_hash());
+    assert_ne!(a.structural_hash(), c.structural_hash());
+
+    // Same nodes, different shape.
+    let leaf = |kind| Node {
+        id: clang_ast::Id::NULL,
+        kind: Clang { kind, name: None },
+        inner: iter::empty().collect(),
+    };
+    let nested = Node {
+        inner: iter::once(Node {
+            inner: iter::once(leaf(Kind::ParenExpr)).collect(),
+            ..leaf(Kind::ParenExpr)
+        })
+        .collect(),
+        ..leaf(Kind::ParenExpr)
+    };
+    let flat = Node {
+        inner: vec![leaf(Kind::ParenExpr), leaf(Kind::ParenExpr)]
+            .into_iter()
+            .collect(),
+        ..leaf(Kind::ParenExpr)
+    };
+    assert_ne!(nested.structural_hash(), flat.structural_hash());
+}
+
+#[test]
+fn test_structural_hash_by() {
+    let a: clang_ast::Node<WithRange> = serde_json::from_str(&record(0x10, "x", 0)).unwrap();
+    let b: clang_ast::Node<WithRange> = serde_json::from_str(&record(0x20, "y", 40)).unwrap();
+    let hash = |node: &clang_ast::Node<WithRange>| {
+        node.structural_hash_by(|kind, hasher| kind.kind.hash(hasher))
+    };
+    assert_eq!(hash(&a), hash(&b));
+}
+
+#[test]
+fn test_structurally_eq() {
+    let a: Node = serde_json::from_str(&record(0x10, "x", 0)).unwrap();
+    let b: Node = serde_json::from_str(&record(0x20, "x", 0)).unwrap();
+    let c: Node = serde_json::from_str(&record(0x30, "y", 0)).unwrap();
+    assert!(a.structurally_eq(&b));
+    assert!(!a.structurally_eq(&c));
+
+    let mut d: Node = serde_json::from_str(&record(0x40, "x", 0)).unwrap();
+    d.inner.clear();
+    assert!(!a.structurally_eq(&d));
+    assert!(!d.structurally_eq(&a));
+}
+
+#[test]
+fn test_structurally_eq_by() {
+    let a: clang_ast::Node<WithRange> = serde_json::from_str(&record(0x10, "x", 0)).unwrap();
+    let b: clang_ast::Node<WithRange> = serde_json::from_str(&record(0x20, "y", 40)).unwrap();
+    assert!(a.structurally_eq_by(&b, |a, b| a.kind == b.kind));
+
+    let c: Node = serde_json::from_str(&record(0x30, "y", 0)).unwrap();
+    assert!(a.structurally_eq_by(&c, |a, c| a.kind == c.kind));
+}
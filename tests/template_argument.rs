@@ -0,0 +1,109 @@
+use clang_ast::{Id, TemplateArgument};
+use serde::Deserialize;
+
+pub type Node = clang_ast::Node<Clang>;
+
+#[derive(Deserialize)]
+pub enum Clang {
+    ClassTemplateSpecializationDecl,
+    TemplateArgument(TemplateArgument),
+    Other,
+}
+
+const JSON: &str = r#"
+    {
+      "id": "0x1",
+      "kind": "ClassTemplateSpecializationDecl",
+      "inner": [
+        {
+          "id": "0x2",
+          "kind": "TemplateArgument",
+          "type": { "qualType": "int" },
+          "inner": [{ "id": "0x3", "kind": "BuiltinType", "type": { "qualType": "int" } }]
+        },
+        { "id": "0x4", "kind": "TemplateArgument", "value": -3 },
+        {
+          "id": "0x5",
+          "kind": "TemplateArgument",
+          "decl": { "id": "0x50", "kind": "VarDecl", "name": "global", "type": { "qualType": "int" } }
+        },
+        { "id": "0x6", "kind": "TemplateArgument", "isNullptr": true },
+        { "id": "0x7", "kind": "TemplateArgument" },
+        {
+          "id": "0x8",
+          "kind": "TemplateArgument",
+          "isPack": true,
+          "inner": [
+            { "id": "0x9", "kind": "TemplateArgument", "type": { "qualType": "char" } },
+            { "id": "0xa", "kind": "TemplateArgument", "type": { "qualType": "Ts..." } }
+          ]
+        },
+        {
+          "id": "0xb",
+          "kind": "TemplateArgument",
+          "range": { "begin": {}, "end": {} },
+          "isExpr": true,
+          "inner": [{ "id": "0xc", "kind": "DeclRefExpr" }]
+        },
+        { "id": "0xd", "kind": "TemplateArgument", "isNull": true }
+      ]
+    }
+"#;
+
+fn arguments(node: &Node) -> Vec<&TemplateArgument> {
+    node.inner
+        .iter()
+        .map(|node| match &node.kind {
+            Clang::TemplateArgument(arg) => arg,
+            _ => panic!("expected TemplateArgument"),
+        })
+        .collect()
+}
+
+fn id(id: u64) -> Id {
+    Id::from_u64(id).unwrap()
+}
+
+#[test]
+fn test_template_arguments() {
+    let node: Node = serde_json::from_str(JSON).unwrap();
+    let args = arguments(&node);
+    assert_eq!(args[0].ty().unwrap().qual_type, "int");
+    assert_eq!(args[1].integral(), Some(-3));
+    assert_eq!(*args[2], TemplateArgument::Declaration(id(0x50)));
+    assert_eq!(*args[3], TemplateArgument::NullPtr);
+    assert_eq!(*args[4], TemplateArgument::Template);
+    assert_eq!(*args[5], TemplateArgument::Pack);
+    assert_eq!(*args[6], TemplateArgument::Expression);
+    assert_eq!(*args[7], TemplateArgument::Null);
+    assert!(args[0].integral().is_none());
+    assert!(args[1].ty().is_none());
+
+    let pack = arguments(&node.inner[5]);
+    assert_eq!(pack[0].ty().unwrap().qual_type, "char");
+    assert_eq!(pack[1].ty().unwrap().qual_type, "Ts...");
+}
+
+#[test]
+fn test_round_trip() {
+    let arguments = [
+        TemplateArgument::Null,
+        TemplateArgument::Declaration(id(0x50)),
+        TemplateArgument::NullPtr,
+        TemplateArgument::Integral(i64::MIN),
+        TemplateArgument::Template,
+        TemplateArgument::Expression,
+        TemplateArgument::Pack,
+    ];
+    for arg in &arguments {
+        let json = serde_json::to_string(arg).unwrap();
+        let again: TemplateArgument = serde_json::from_str(&json).unwrap();
+        assert_eq!(again, *arg);
+    }
+}
+
+#[test]
+fn test_ambiguous() {
+    let json = r#"{ "isPack": true, "value": 1 }"#;
+    assert!(serde_json::from_str::<TemplateArgument>(json).is_err());
+}
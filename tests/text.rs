@@ -0,0 +1,110 @@
+use clang_ast::{Id, Kind, SourceLocation, SourceRange};
+use serde::Deserialize;
+
+pub type Node = clang_ast::Node<Clang>;
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Clang {
+    pub kind: Kind,
+    pub name: Option<String>,
+    pub range: Option<SourceRange>,
+    pub loc: Option<SourceLocation>,
+    pub previous_decl: Option<Id>,
+    #[serde(default)]
+    pub is_used: bool,
+    #[serde(rename = "type")]
+    pub ty: Option<Type>,
+    pub value_category: Option<String>,
+    pub cast_kind: Option<String>,
+    pub value: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct Type {
+    pub qual_type: String,
+    pub desugared_qual_type: Option<String>,
+}
+
+const DUMP: &str = "\
+TranslationUnitDecl 0x55d1f0 <<invalid sloc>> <invalid sloc>
+|-TypedefDecl 0x55d2a0 <<invalid sloc>> <invalid sloc> implicit __int128_t '__int128'
+| `-BuiltinType 0x55d310 '__int128'
+|-FunctionDecl 0x55d400 <main.cc:1:1, col:14> col:5 used f 'int (int)'
+| `-ParmVarDecl 0x55d480 <col:7, col:11> col:11 used x 'int'
+|-FunctionDecl 0x55d500 prev 0x55d400 <line:2:1, line:4:1> line:2:5 used f 'int (int)'
+| |-ParmVarDecl 0x55d580 <col:7, col:11> col:11 used x 'int'
+| `-CompoundStmt 0x55d600 <col:14, line:4:1>
+|   `-ReturnStmt 0x55d680 <line:3:3, col:14>
+|     `-BinaryOperator 0x55d700 <col:10, col:14> 'int' '+'
+|       |-ImplicitCastExpr 0x55d780 <col:10> 'int' <LValueToRValue>
+|       | `-DeclRefExpr 0x55d800 <col:10> 'int' lvalue ParmVar 0x55d580 'x' 'int'
+|       `-IntegerLiteral 0x55d880 <col:14> 'int' 1
+`-VarDecl 0x55d900 <x.h:1:1, col:18> col:8 s 'const char *' cinit
+  |-TemplateArgument type 'int'
+  | `-BuiltinType 0x55d310 'int'
+  `-ImplicitCastExpr 0x55d980 <col:12> 'const char *' <ArrayToPointerDecay>
+    `-StringLiteral 0x55da00 <col:12> 'const char[4]' lvalue \"a b\"
+";
+
+fn expansion(loc: &SourceLocation) -> (&str, usize, usize) {
+    let bare = loc.expansion_loc.as_ref().unwrap();
+    (&bare.file, bare.line, bare.col)
+}
+
+#[test]
+fn test_structure() {
+    let node: Node = clang_ast::text::from_str(DUMP).unwrap();
+    assert_eq!(node.kind.kind, Kind::TranslationUnitDecl);
+    assert_eq!(node.inner.len(), 4);
+
+    let typedef = &node.inner[0];
+    assert_eq!(typedef.kind.name.as_deref(), Some("__int128_t"));
+    assert_eq!(typedef.kind.ty.as_ref().unwrap().qual_type, "__int128");
+    assert!(typedef.kind.loc.as_ref().unwrap().expansion_loc.is_none());
+    assert_eq!(typedef.inner[0].kind.kind, Kind::BuiltinType);
+
+    let decl = &node.inner[1];
+    let def = &node.inner[2];
+    assert_eq!(def.kind.previous_decl, Some(decl.id));
+    assert!(def.kind.is_used);
+    assert_eq!(def.kind.name.as_deref(), Some("f"));
+
+    let param = &def.inner[0];
+    assert_eq!(param.kind.kind, Kind::ParmVarDecl);
+    let range = param.kind.range.as_ref().unwrap();
+    assert_eq!(expansion(&range.begin), ("main.cc", 2, 7));
+    assert_eq!(expansion(&range.end), ("main.cc", 2, 11));
+    let loc = param.kind.loc.as_ref().unwrap();
+    assert_eq!(expansion(loc), ("main.cc", 2, 11));
+
+    let binary = &def.inner[1].inner[0].inner[0];
+    assert_eq!(binary.kind.kind, Kind::BinaryOperator);
+    assert_eq!(binary.kind.value_category.as_deref(), Some("prvalue"));
+    let cast = &binary.inner[0];
+    assert_eq!(cast.kind.cast_kind.as_deref(), Some("LValueToRValue"));
+    assert_eq!(cast.inner[0].kind.value_category.as_deref(), Some("lvalue"));
+    assert_eq!(binary.inner[1].kind.value.as_deref(), Some("1"));
+}
+
+#[test]
+fn test_skipped_lines() {
+    let node: Node = clang_ast::text::from_str(DUMP).unwrap();
+    let var = &node.inner[3];
+    assert_eq!(var.kind.name.as_deref(), Some("s"));
+    let range = var.kind.range.as_ref().unwrap();
+    assert_eq!(expansion(&range.begin), ("x.h", 1, 1));
+    assert_eq!(var.inner.len(), 1);
+    let literal = &var.inner[0].inner[0];
+    assert_eq!(literal.kind.kind, Kind::StringLiteral);
+    assert_eq!(literal.kind.value.as_deref(), Some("\"a b\""));
+}
+
+#[test]
+fn test_errors() {
+    assert!(clang_ast::text::from_str::<Clang>("").is_err());
+    let two_roots = "TranslationUnitDecl 0x1 <<invalid sloc>> <invalid sloc>\n\
+                     TranslationUnitDecl 0x2 <<invalid sloc>> <invalid sloc>\n";
+    assert!(clang_ast::text::from_str::<Clang>(two_roots).is_err());
+}
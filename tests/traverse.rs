@@ -0,0 +1,318 @@
+use clang_ast::{Control, Kind, Visit, VisitMut};
+use serde::Deserialize;
+use std::{iter, mem};
+
+pub type Node = clang_ast::Node<Clang>;
+
+#[derive(Deserialize)]
+pub struct Clang {
+    pub kind: Kind,
+}
+
+const JSON: &str = r#"
+    {
+      "id": "0x1",
+      "kind": "TranslationUnitDecl",
+      "inner": [
+        {
+          "id": "0x2",
+          "kind": "NamespaceDecl",
+          "inner": [
+            {
+              "id": "0x3",
+              "kind": "EnumDecl",
+              "inner": [
+                { "id": "0x4", "kind": "EnumConstantDecl" },
+                { "id": "0x5", "kind": "EnumConstantDecl" }
+              ]
+            },
+            { "id": "0x6", "kind": "CXXRecordDecl" }
+          ]
+        },
+        { "id": "0x7", "kind": "FunctionDecl" }
+      ]
+    }
+"#;
+
+fn ids<'a>(nodes: impl IntoIterator<Item = &'a Node>) -> Vec<String> {
+    nodes.into_iter().map(|node| node.id.to_string()).collect()
+}
+
+#[test]
+fn test_descendants() {
+    let node: Node = serde_json::from_str(JSON).unwrap();
+    let expected = ["0x1", "0x2", "0x3", "0x4", "0x5", "0x6", "0x7"];
+    assert_eq!(ids(node.descendants()), expected);
+
+    let leaf = &node.inner[1];
+    assert_eq!(ids(leaf.descendants()), ["0x7"]);
+}
+
+#[test]
+fn test_descendants_skip_children() {
+    let node: Node = serde_json::from_str(JSON).unwrap();
+    let mut descendants = node.descendants();
+    let mut visited = Vec::new();
+    while let Some(node) = descendants.next() {
+        if node.kind.kind == Kind::EnumDecl {
+            descendants.skip_children();
+        }
+        visited.push(node);
+    }
+    assert_eq!(ids(visited), ["0x1", "0x2", "0x3", "0x6", "0x7"]);
+}
+
+#[test]
+fn test_descendants_deep() {
+    let mut node = Node {
+        id: clang_ast::Id::NULL,
+        kind: Clang {
+            kind: Kind::ParenExpr,
+        },
+        inner: iter::empty().collect(),
+    };
+    for _ in 0..100_000 {
+        node = Node {
+            id: clang_ast::Id::NULL,
+            kind: Clang {
+                kind: Kind::ParenExpr,
+            },
+            inner: iter::once(node).collect(),
+        };
+    }
+    assert_eq!(node.descendants().count(), 100_001);
+
+    // The default recursive drop would overflow.
+    let flattened = node.into_flattened();
+    assert_eq!(flattened.len(), 100_001);
+    assert!(flattened.iter().all(|node| node.inner.is_empty()));
+}
+
+#[test]
+fn test_into_flattened() {
+    let node: Node = serde_json::from_str(JSON).unwrap();
+    let flattened = node.into_flattened();
+    let expected = ["0x1", "0x2", "0x3", "0x4", "0x5", "0x6", "0x7"];
+    assert_eq!(ids(&flattened), expected);
+}
+
+#[test]
+fn test_visit() {
+    struct Trace(Vec<String>);
+
+    impl<'ast> Visit<'ast, Clang> for Trace {
+        fn enter_node(&mut self, node: &'ast Node) -> Control {
+            self.0.push(format!("enter {}", node.id));
+            Control::Continue
+        }
+
+        fn exit_node(&mut self, node: &'ast Node) {
+            self.0.push(format!("exit {}", node.id));
+        }
+    }
+
+    let node: Node = serde_json::from_str(JSON).unwrap();
+    let mut trace = Trace(Vec::new());
+    node.inner[0].visit(&mut trace);
+    let expected = [
+        "enter 0x2",
+        "enter 0x3",
+        "enter 0x4",
+        "exit 0x4",
+        "enter 0x5",
+        "exit 0x5",
+        "exit 0x3",
+        "enter 0x6",
+        "exit 0x6",
+        "exit 0x2",
+    ];
+    assert_eq!(trace.0, expected);
+}
+
+#[test]
+fn test_visit_control() {
+    struct Pruning(Vec<String>);
+
+    impl<'ast> Visit<'ast, Clang> for Pruning {
+        fn enter_node(&mut self, node: &'ast Node) -> Control {
+            self.0.push(format!("enter {}", node.id));
+            match node.kind.kind {
+                Kind::EnumDecl => Control::SkipChildren,
+                Kind::CXXRecordDecl => Control::Stop,
+                _ => Control::Continue,
+            }
+        }
+
+        fn exit_node(&mut self, node: &'ast Node) {
+            self.0.push(format!("exit {}", node.id));
+        }
+    }
+
+    let node: Node = serde_json::from_str(JSON).unwrap();
+    let mut pruning = Pruning(Vec::new());
+    node.visit(&mut pruning);
+    let expected = [
+        "enter 0x1",
+        "enter 0x2",
+        "enter 0x3",
+        "exit 0x3",
+        "enter 0x6",
+    ];
+    assert_eq!(pruning.0, expected);
+}
+
+#[test]
+fn test_visit_mut() {
+    // Flatten every EnumDecl's constants into its parent, dropping the
+    // EnumDecl itself.
+    struct FlattenEnums;
+
+    impl VisitMut<Clang> for FlattenEnums {
+        fn enter_node(&mut self, node: &mut Node) -> Control {
+            let mut inner = Vec::new();
+            for mut child in node.inner.drain(..) {
+                if child.kind.kind == Kind::EnumDecl {
+                    inner.extend(mem::take(&mut child.inner));
+                } else {
+                    inner.push(child);
+                }
+            }
+            node.inner = inner.into_iter().collect();
+            Control::Continue
+        }
+
+        fn exit_node(&mut self, node: &mut Node) {
+            if node.kind.kind == Kind::EnumConstantDecl {
+                node.kind.kind = Kind::VarDecl;
+            }
+        }
+    }
+
+    let mut node: Node = serde_json::from_str(JSON).unwrap();
+    node.visit_mut(&mut FlattenEnums);
+    assert_eq!(
+        ids(node.descendants()),
+        ["0x1", "0x2", "0x4", "0x5", "0x6", "0x7"]
+    );
+    assert_eq!(node.inner[0].inner[0].kind.kind, Kind::VarDecl);
+}
+
+#[test]
+fn test_visit_mut_stop() {
+    struct StopAtRecord(Vec<String>);
+
+    impl VisitMut<Clang> for StopAtRecord {
+        fn enter_node(&mut self, node: &mut Node) -> Control {
+            self.0.push(format!("enter {}", node.id));
+            if node.kind.kind == Kind::CXXRecordDecl {
+                Control::Stop
+            } else {
+                Control::Continue
+            }
+        }
+
+        fn exit_node(&mut self, node: &mut Node) {
+            self.0.push(format!("exit {}", node.id));
+        }
+    }
+
+    let mut node: Node = serde_json::from_str(JSON).unwrap();
+    let mut visitor = StopAtRecord(Vec::new());
+    node.visit_mut(&mut visitor);
+    let expected = [
+        "enter 0x1",
+        "enter 0x2",
+        "enter 0x3",
+        "enter 0x4",
+        "exit 0x4",
+        "enter 0x5",
+        "exit 0x5",
+        "exit 0x3",
+        "enter 0x6",
+    ];
+    assert_eq!(visitor.0, expected);
+
+    // Every node is back where it was.
+    let expected = ["0x1", "0x2", "0x3", "0x4", "0x5", "0x6", "0x7"];
+    assert_eq!(ids(node.descendants()), expected);
+}
+
+#[test]
+fn test_visit_mut_deep() {
+    struct Rename;
+
+    impl VisitMut<Clang> for Rename {
+        fn exit_node(&mut self, node: &mut Node) {
+            node.kind.kind = Kind::ParenListExpr;
+        }
+    }
+
+    let mut node = Node {
+        id: clang_ast::Id::NULL,
+        kind: Clang {
+            kind: Kind::ParenExpr,
+        },
+        inner: iter::empty().collect(),
+    };
+    for _ in 0..100_000 {
+        node = Node {
+            id: clang_ast::Id::NULL,
+            kind: Clang {
+                kind: Kind::ParenExpr,
+            },
+            inner: iter::once(node).collect(),
+        };
+    }
+    node.visit_mut(&mut Rename);
+    assert!(node
+        .descendants()
+        .all(|node| node.kind.kind == Kind::ParenListExpr));
+
+    let flattened = node.into_flattened();
+    assert_eq!(flattened.len(), 100_001);
+}
+
+#[test]
+fn test_cursor() {
+    let node: Node = serde_json::from_str(JSON).unwrap();
+    let root = node.cursor();
+    assert!(root.parent().is_none());
+    assert!(root.next_sibling().is_none());
+
+    let namespace = root.first_child().unwrap();
+    let record = namespace.last_child().unwrap();
+    assert_eq!(record.node().id.to_string(), "0x6");
+    assert_eq!(record.depth(), 2);
+    assert!(record.next_sibling().is_none());
+
+    let enumeration = record.prev_sibling().unwrap();
+    assert_eq!(enumeration.node().id.to_string(), "0x3");
+    assert!(enumeration.prev_sibling().is_none());
+
+    let constants: Vec<_> = enumeration.children().collect();
+    assert_eq!(constants.len(), 2);
+    let second = constants[0].next_sibling().unwrap();
+    assert_eq!(second.node().id.to_string(), "0x5");
+    assert_eq!(ids(second.ancestors()), ["0x3", "0x2", "0x1"]);
+
+    let function = second.parent().unwrap().parent().unwrap().next_sibling();
+    assert_eq!(function.unwrap().node().id.to_string(), "0x7");
+}
+
+#[test]
+fn test_visit_with_ancestors() {
+    let node: Node = serde_json::from_str(JSON).unwrap();
+    let mut paths = Vec::new();
+    node.visit_with_ancestors(|ancestors, node| {
+        let mut path = ids(ancestors.iter().copied());
+        path.push(node.id.to_string());
+        paths.push(path.join("/"));
+        if node.kind.kind == Kind::EnumDecl {
+            Control::SkipChildren
+        } else {
+            Control::Continue
+        }
+    });
+    let expected = ["0x1", "0x1/0x2", "0x1/0x2/0x3", "0x1/0x2/0x6", "0x1/0x7"];
+    assert_eq!(paths, expected);
+}
@@ -0,0 +1,24 @@
+use clang_ast::{Id, Type};
+
+#[test]
+fn test_type() {
+    let json =
+        r#"{"qualType":"size_t","desugaredQualType":"unsigned long","typeAliasDeclId":"0x1a2b"}"#;
+    let ty: Type = serde_json::from_str(json).unwrap();
+    assert_eq!(ty.qual_type, "size_t");
+    assert_eq!(ty.desugared(), "unsigned long");
+    assert_eq!(ty.type_alias_decl_id, Id::from_u64(0x1a2b));
+    assert_eq!(serde_json::to_string(&ty).unwrap(), json);
+
+    let json = r#"{"qualType":"int"}"#;
+    let ty: Type = serde_json::from_str(json).unwrap();
+    assert_eq!(ty.desugared(), "int");
+    assert_eq!(ty.type_alias_decl_id, None);
+    assert_eq!(serde_json::to_string(&ty).unwrap(), json);
+
+    let error = serde_json::from_str::<Type>("{}").unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "missing field `qualType` at line 1 column 2"
+    );
+}
@@ -0,0 +1,36 @@
+use clang_ast::ValueCategory;
+
+fn category(json: &str) -> ValueCategory {
+    serde_json::from_str(json).unwrap()
+}
+
+#[test]
+fn test_value_category() {
+    assert_eq!(category(r#""lvalue""#), ValueCategory::LValue);
+    assert_eq!(category(r#""xvalue""#), ValueCategory::XValue);
+    assert_eq!(category(r#""prvalue""#), ValueCategory::PRValue);
+    assert_eq!(category(r#""rvalue""#), ValueCategory::PRValue);
+
+    assert!(ValueCategory::LValue.is_glvalue());
+    assert!(!ValueCategory::LValue.is_rvalue());
+    assert!(ValueCategory::XValue.is_glvalue());
+    assert!(ValueCategory::XValue.is_rvalue());
+    assert!(!ValueCategory::PRValue.is_glvalue());
+    assert!(ValueCategory::PRValue.is_rvalue());
+}
+
+#[test]
+fn test_serialize() {
+    let json = serde_json::to_string(&category(r#""rvalue""#)).unwrap();
+    assert_eq!(json, r#""prvalue""#);
+    assert_eq!(
+        serde_json::to_string(&ValueCategory::XValue).unwrap(),
+        r#""xvalue""#
+    );
+}
+
+#[test]
+fn test_unknown() {
+    let error = serde_json::from_str::<ValueCategory>(r#""glvalue""#).unwrap_err();
+    assert!(error.to_string().starts_with("unknown variant `glvalue`"));
+}
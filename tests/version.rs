@@ -0,0 +1,40 @@
+use clang_ast::ClangVersion;
+
+#[test]
+fn test_sniff() {
+    let json = r#"
+        {
+          "id": "0x1",
+          "kind": "TranslationUnitDecl",
+          "inner": [
+            {
+              "id": "0x2",
+              "kind": "FunctionDecl",
+              "inner": [
+                { "id": "0x3", "kind": "RecoveryExpr", "valueCategory": "rvalue" },
+                { "id": "0x4", "kind": "OMPArraySectionExpr" }
+              ]
+            }
+          ]
+        }
+    "#;
+    let version: ClangVersion = serde_json::from_str(json).unwrap();
+    assert_eq!(
+        version,
+        ClangVersion {
+            min: Some(11),
+            max: Some(12),
+        },
+    );
+    assert!(version.contains(11));
+    assert!(version.contains(12));
+    assert!(!version.contains(13));
+}
+
+#[test]
+fn test_unknown() {
+    let json = r#"{"id": "0x1", "kind": "TranslationUnitDecl"}"#;
+    let version: ClangVersion = serde_json::from_str(json).unwrap();
+    assert_eq!(version, ClangVersion::default());
+    assert!(version.contains(19));
+}